@@ -0,0 +1,62 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use sha2::{Digest, Sha256};
+
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::CredentialHasher;
+
+/// Argon2id for passwords, SHA-256 for random tokens.
+#[derive(Clone, Default)]
+pub struct Argon2Hasher {
+    argon2: Argon2<'static>,
+}
+
+impl CredentialHasher for Argon2Hasher {
+    fn hash_password(&self, password: &str) -> Result<String, PortError> {
+        let salt = SaltString::generate(&mut OsRng);
+        self.argon2
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| PortError::Persistence(e.to_string()))
+    }
+
+    fn verify_password(&self, password: &str, hash: &str) -> bool {
+        PasswordHash::new(hash).is_ok_and(|parsed| {
+            self.argon2
+                .verify_password(password.as_bytes(), &parsed)
+                .is_ok()
+        })
+    }
+
+    fn digest_token(&self, token: &str) -> String {
+        Sha256::digest(token.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_roundtrip() {
+        let hasher = Argon2Hasher::default();
+        let hash = hasher.hash_password("correct horse").unwrap();
+        assert!(hasher.verify_password("correct horse", &hash));
+        assert!(!hasher.verify_password("wrong horse", &hash));
+        assert!(!hasher.verify_password("correct horse", "not a hash"));
+    }
+
+    #[test]
+    fn token_digest_is_stable_hex() {
+        let hasher = Argon2Hasher::default();
+        let digest = hasher.digest_token("abc");
+        assert_eq!(
+            digest,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -1 +1,4 @@
+pub mod crypto;
+pub mod oidc;
 pub mod persistence;
+pub mod slack;
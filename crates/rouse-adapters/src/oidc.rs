@@ -0,0 +1,423 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use rouse_ports::error::IdentityError;
+use rouse_ports::outbound::IdentityProvider;
+use rouse_ports::types::{AuthorizationRequest, IdentityClaims};
+
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    /// Issuer URL; discovery is fetched from `/.well-known/openid-configuration` under it.
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_url: String,
+    pub scopes: Vec<String>,
+    /// ID token claim that lists the user's groups.
+    pub groups_claim: String,
+}
+
+impl OidcConfig {
+    pub fn new(
+        issuer: impl Into<String>,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+        redirect_url: impl Into<String>,
+    ) -> Self {
+        Self {
+            issuer: issuer.into(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            redirect_url: redirect_url.into(),
+            scopes: vec!["openid".into(), "email".into(), "profile".into()],
+            groups_claim: "groups".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// Authorization-code flow with PKCE against any OIDC provider.
+pub struct OidcProvider {
+    config: OidcConfig,
+    http: reqwest::Client,
+    discovery: Mutex<Option<Discovery>>,
+}
+
+impl OidcProvider {
+    /// Discovery is deferred to the first sign-in so startup does not depend
+    /// on the provider being reachable.
+    pub fn new(config: OidcConfig) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+            discovery: Mutex::new(None),
+        }
+    }
+
+    async fn discovery(&self) -> Result<Discovery, IdentityError> {
+        if let Some(discovery) = self.discovery.lock().unwrap().clone() {
+            return Ok(discovery);
+        }
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            self.config.issuer.trim_end_matches('/')
+        );
+        let discovery: Discovery = self.get_json(&url).await?;
+        if discovery.issuer != self.config.issuer {
+            return Err(IdentityError::Rejected(format!(
+                "discovery issuer {} does not match {}",
+                discovery.issuer, self.config.issuer
+            )));
+        }
+        *self.discovery.lock().unwrap() = Some(discovery.clone());
+        Ok(discovery)
+    }
+
+    async fn get_json<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<T, IdentityError> {
+        self.http
+            .get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| IdentityError::Unavailable(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| IdentityError::Unavailable(e.to_string()))
+    }
+
+    async fn validate(
+        &self,
+        discovery: &Discovery,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<IdentityClaims, IdentityError> {
+        let header = decode_header(id_token).map_err(rejected)?;
+        if !matches!(
+            header.alg,
+            Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512 | Algorithm::ES256
+        ) {
+            return Err(IdentityError::Rejected(format!(
+                "unsupported algorithm {:?}",
+                header.alg
+            )));
+        }
+        // Fetched per sign-in so rotated keys are picked up without a restart.
+        let jwks: JwkSet = self.get_json(&discovery.jwks_uri).await?;
+        let jwk = match &header.kid {
+            Some(kid) => jwks.find(kid),
+            None => jwks.keys.first(),
+        }
+        .ok_or_else(|| IdentityError::Rejected("signing key not found".into()))?;
+        let key = DecodingKey::from_jwk(jwk).map_err(rejected)?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&discovery.issuer]);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims =
+            decode::<serde_json::Map<String, serde_json::Value>>(id_token, &key, &validation)
+                .map_err(rejected)?
+                .claims;
+
+        if claims.get("nonce").and_then(|n| n.as_str()) != Some(nonce) {
+            return Err(IdentityError::Rejected("nonce mismatch".into()));
+        }
+
+        let text = |name: &str| claims.get(name).and_then(|v| v.as_str()).map(String::from);
+        let groups = match claims.get(&self.config.groups_claim) {
+            Some(serde_json::Value::Array(items)) => items
+                .iter()
+                .filter_map(|g| g.as_str().map(String::from))
+                .collect(),
+            Some(serde_json::Value::String(group)) => vec![group.clone()],
+            _ => Vec::new(),
+        };
+        Ok(IdentityClaims {
+            issuer: discovery.issuer.clone(),
+            subject: text("sub").unwrap_or_default(),
+            email: text("email"),
+            email_verified: claims
+                .get("email_verified")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            username: text("preferred_username"),
+            groups,
+        })
+    }
+}
+
+fn rejected(e: jsonwebtoken::errors::Error) -> IdentityError {
+    IdentityError::Rejected(e.to_string())
+}
+
+/// S256 code challenge for a PKCE verifier.
+pub fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+#[async_trait]
+impl IdentityProvider for OidcProvider {
+    async fn authorization_url(
+        &self,
+        request: &AuthorizationRequest,
+    ) -> Result<String, IdentityError> {
+        let discovery = self.discovery().await?;
+        let url = reqwest::Url::parse_with_params(
+            &discovery.authorization_endpoint,
+            [
+                ("response_type", "code"),
+                ("client_id", &self.config.client_id),
+                ("redirect_uri", &self.config.redirect_url),
+                ("scope", &self.config.scopes.join(" ")),
+                ("state", &request.state),
+                ("nonce", &request.nonce),
+                ("code_challenge", &pkce_challenge(&request.pkce_verifier)),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| IdentityError::Unavailable(e.to_string()))?;
+        Ok(url.into())
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        request: &AuthorizationRequest,
+    ) -> Result<IdentityClaims, IdentityError> {
+        let discovery = self.discovery().await?;
+        let response = self
+            .http
+            .post(&discovery.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", &self.config.redirect_url),
+                ("client_id", &self.config.client_id),
+                ("client_secret", &self.config.client_secret),
+                ("code_verifier", &request.pkce_verifier),
+            ])
+            .send()
+            .await
+            .map_err(|e| IdentityError::Unavailable(e.to_string()))?;
+        if response.status().is_client_error() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(IdentityError::Rejected(format!(
+                "token request failed: {body}"
+            )));
+        }
+        let tokens: TokenResponse = response
+            .error_for_status()
+            .map_err(|e| IdentityError::Unavailable(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| IdentityError::Unavailable(e.to_string()))?;
+
+        self.validate(&discovery, &tokens.id_token, &request.nonce)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use axum::extract::{Form, State};
+    use axum::http::StatusCode;
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    /// Test-only signing key and its public half.
+    const KEY_PEM: &str = include_str!("../testdata/oidc_key.pem");
+    const JWK: &str = include_str!("../testdata/oidc_jwk.json");
+
+    /// What the mock IdP puts in the next ID token.
+    struct Idp {
+        issuer: String,
+        audience: String,
+        nonce: String,
+        challenge: Mutex<Option<String>>,
+    }
+
+    async fn token(
+        State(idp): State<Arc<Idp>>,
+        Form(form): Form<HashMap<String, String>>,
+    ) -> (StatusCode, Json<Value>) {
+        let expected = idp.challenge.lock().unwrap().clone();
+        let verifier = form.get("code_verifier").map(|v| pkce_challenge(v));
+        if form.get("code").map(String::as_str) != Some("good-code") || verifier != expected {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "invalid_grant" })),
+            );
+        }
+        let claims = json!({
+            "iss": idp.issuer,
+            "aud": idp.audience,
+            "sub": "u-42",
+            "exp": chrono::Utc::now().timestamp() + 300,
+            "iat": chrono::Utc::now().timestamp(),
+            "nonce": idp.nonce,
+            "email": "alice@corp.example",
+            "email_verified": true,
+            "preferred_username": "alice",
+            "groups": ["sre", "staff"],
+        });
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some("test".into());
+        let key = EncodingKey::from_rsa_pem(KEY_PEM.as_bytes()).unwrap();
+        let id_token = encode(&header, &claims, &key).unwrap();
+        (
+            StatusCode::OK,
+            Json(json!({ "access_token": "at", "token_type": "Bearer", "id_token": id_token })),
+        )
+    }
+
+    /// Starts a mock IdP on a free port and returns its issuer URL.
+    async fn mock_idp(audience: &str, nonce: &str) -> (String, Arc<Idp>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let idp = Arc::new(Idp {
+            issuer: issuer.clone(),
+            audience: audience.into(),
+            nonce: nonce.into(),
+            challenge: Mutex::new(None),
+        });
+        let discovery = json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{issuer}/authorize"),
+            "token_endpoint": format!("{issuer}/token"),
+            "jwks_uri": format!("{issuer}/jwks"),
+        });
+        let app = Router::new()
+            .route(
+                "/.well-known/openid-configuration",
+                get(move || async move { Json(discovery) }),
+            )
+            .route(
+                "/jwks",
+                get(|| async {
+                    let jwk: Value = serde_json::from_str(JWK).unwrap();
+                    Json(json!({ "keys": [jwk] }))
+                }),
+            )
+            .route("/token", post(token))
+            .with_state(idp.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (issuer, idp)
+    }
+
+    fn request() -> AuthorizationRequest {
+        AuthorizationRequest {
+            state: "st".into(),
+            nonce: "n-1".into(),
+            pkce_verifier: "verifier-with-enough-entropy-0123456789".into(),
+        }
+    }
+
+    /// Runs the redirect half of the flow and hands the challenge to the IdP.
+    async fn authorize(
+        provider: &OidcProvider,
+        idp: &Idp,
+        request: &AuthorizationRequest,
+    ) -> String {
+        let url = provider.authorization_url(request).await.unwrap();
+        let parsed = reqwest::Url::parse(&url).unwrap();
+        let params: HashMap<_, _> = parsed.query_pairs().into_owned().collect();
+        *idp.challenge.lock().unwrap() = params.get("code_challenge").cloned();
+        url
+    }
+
+    fn provider(issuer: &str) -> OidcProvider {
+        OidcProvider::new(OidcConfig::new(
+            issuer,
+            "rouse",
+            "secret",
+            "http://rouse.test/api/auth/oidc/callback",
+        ))
+    }
+
+    #[tokio::test]
+    async fn authorization_code_flow_returns_validated_claims() {
+        let (issuer, idp) = mock_idp("rouse", "n-1").await;
+        let provider = provider(&issuer);
+        let request = request();
+
+        let url = authorize(&provider, &idp, &request).await;
+        assert!(url.starts_with(&format!("{issuer}/authorize?")));
+        assert!(url.contains("code_challenge_method=S256"));
+        assert!(url.contains("state=st"));
+
+        let claims = provider.exchange_code("good-code", &request).await.unwrap();
+        assert_eq!(claims.issuer, issuer);
+        assert_eq!(claims.subject, "u-42");
+        assert_eq!(claims.email.as_deref(), Some("alice@corp.example"));
+        assert!(claims.email_verified);
+        assert_eq!(claims.username.as_deref(), Some("alice"));
+        assert_eq!(claims.groups, ["sre", "staff"]);
+    }
+
+    #[tokio::test]
+    async fn wrong_pkce_verifier_is_rejected() {
+        let (issuer, idp) = mock_idp("rouse", "n-1").await;
+        let provider = provider(&issuer);
+        authorize(&provider, &idp, &request()).await;
+
+        let mut tampered = request();
+        tampered.pkce_verifier = "someone-elses-verifier".into();
+        let result = provider.exchange_code("good-code", &tampered).await;
+        assert!(matches!(result, Err(IdentityError::Rejected(_))));
+    }
+
+    #[tokio::test]
+    async fn token_for_another_client_is_rejected() {
+        let (issuer, idp) = mock_idp("other-app", "n-1").await;
+        let provider = provider(&issuer);
+        let request = request();
+        authorize(&provider, &idp, &request).await;
+
+        let result = provider.exchange_code("good-code", &request).await;
+        assert!(matches!(result, Err(IdentityError::Rejected(_))));
+    }
+
+    #[tokio::test]
+    async fn replayed_nonce_is_rejected() {
+        let (issuer, idp) = mock_idp("rouse", "stale-nonce").await;
+        let provider = provider(&issuer);
+        let request = request();
+        authorize(&provider, &idp, &request).await;
+
+        let result = provider.exchange_code("good-code", &request).await;
+        assert!(matches!(result, Err(IdentityError::Rejected(m)) if m == "nonce mismatch"));
+    }
+
+    #[tokio::test]
+    async fn unreachable_provider_is_unavailable() {
+        let provider = provider("http://127.0.0.1:1");
+        let result = provider.authorization_url(&request()).await;
+        assert!(matches!(result, Err(IdentityError::Unavailable(_))));
+    }
+}
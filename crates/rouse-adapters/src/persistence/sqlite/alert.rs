@@ -1,18 +1,20 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
-use rouse_core::alert::Alert;
+use rouse_core::alert::{Alert, Status};
+use rouse_core::ids::AlertId;
 use rouse_ports::error::PortError;
 use rouse_ports::outbound::AlertRepository;
-use rouse_ports::types::AlertFilter;
+use rouse_ports::types::{AlertFilter, AlertOccurrence};
 
-use super::SqliteDb;
+use super::{scoped_write, SqliteDb};
 
 #[async_trait]
 impl AlertRepository for SqliteDb {
     async fn save(&self, alert: &Alert) -> Result<(), PortError> {
         let id = alert.id().to_string();
         let fingerprint = alert.fingerprint().as_str().to_string();
-        let status = format!("{:?}", alert.status());
+        let status = alert.status().name();
         let severity = format!("{:?}", alert.severity());
         let source = alert.source().as_str().to_string();
         let data =
@@ -20,35 +22,38 @@ impl AlertRepository for SqliteDb {
         let created_at = alert.created_at().to_rfc3339();
 
         sqlx::query(
-            "INSERT INTO alerts (id, fingerprint, status, severity, source, data, created_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?)
+            "INSERT INTO alerts (id, tenant_id, fingerprint, status, severity, source, data, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
              ON CONFLICT(id) DO UPDATE SET
                 fingerprint = excluded.fingerprint,
                 status = excluded.status,
                 severity = excluded.severity,
                 source = excluded.source,
-                data = excluded.data",
+                data = excluded.data
+             WHERE tenant_id = excluded.tenant_id",
         )
         .bind(&id)
+        .bind(self.tenant_id())
         .bind(&fingerprint)
-        .bind(&status)
+        .bind(status)
         .bind(&severity)
         .bind(&source)
         .bind(&data)
         .bind(&created_at)
         .execute(&self.pool)
         .await
-        .map_err(|e| PortError::Persistence(e.to_string()))?;
-
-        Ok(())
+        .map_err(|e| PortError::Persistence(e.to_string()))
+        .and_then(scoped_write)
     }
 
     async fn find_by_id(&self, id: &str) -> Result<Option<Alert>, PortError> {
-        let row: Option<(String,)> = sqlx::query_as("SELECT data FROM alerts WHERE id = ?")
-            .bind(id)
-            .fetch_optional(&self.pool)
-            .await
-            .map_err(|e| PortError::Persistence(e.to_string()))?;
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT data FROM alerts WHERE id = ? AND tenant_id = ?")
+                .bind(id)
+                .bind(self.tenant_id())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
 
         match row {
             Some((data,)) => {
@@ -61,12 +66,15 @@ impl AlertRepository for SqliteDb {
     }
 
     async fn find_by_fingerprint(&self, fp: &str) -> Result<Option<Alert>, PortError> {
-        let row: Option<(String,)> =
-            sqlx::query_as("SELECT data FROM alerts WHERE fingerprint = ? LIMIT 1")
-                .bind(fp)
-                .fetch_optional(&self.pool)
-                .await
-                .map_err(|e| PortError::Persistence(e.to_string()))?;
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT data FROM alerts WHERE tenant_id = ? AND fingerprint = ?
+             ORDER BY created_at DESC, rowid DESC LIMIT 1",
+        )
+        .bind(self.tenant_id())
+        .bind(fp)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
 
         match row {
             Some((data,)) => {
@@ -78,26 +86,29 @@ impl AlertRepository for SqliteDb {
         }
     }
 
-    async fn find_by_filter(&self, filter: &AlertFilter) -> Result<Vec<Alert>, PortError> {
-        let mut sql = String::from("SELECT data FROM alerts WHERE 1=1");
-        let mut binds: Vec<String> = Vec::new();
+    async fn find_open_by_fingerprint(&self, fp: &str) -> Result<Option<Alert>, PortError> {
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT data FROM alerts WHERE tenant_id = ? AND fingerprint = ? AND status != 'Resolved'
+             ORDER BY created_at DESC, rowid DESC LIMIT 1",
+        )
+        .bind(self.tenant_id())
+        .bind(fp)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
 
-        if let Some(status) = &filter.status {
-            sql.push_str(" AND status = ?");
-            binds.push(format!("{status:?}"));
-        }
-        if let Some(severity) = &filter.severity {
-            sql.push_str(" AND severity = ?");
-            binds.push(format!("{severity:?}"));
-        }
-        if let Some(source) = &filter.source {
-            sql.push_str(" AND source = ?");
-            binds.push(source.clone());
-        }
-        if let Some(search) = &filter.search {
-            sql.push_str(" AND data LIKE ?");
-            binds.push(format!("%{search}%"));
+        match row {
+            Some((data,)) => {
+                let alert: Alert = serde_json::from_str(&data)
+                    .map_err(|e| PortError::Persistence(e.to_string()))?;
+                Ok(Some(alert))
+            }
+            None => Ok(None),
         }
+    }
+
+    async fn find_by_filter(&self, filter: &AlertFilter) -> Result<Vec<Alert>, PortError> {
+        let (mut sql, binds) = self.filter_query("SELECT data", filter);
 
         sql.push_str(" ORDER BY created_at DESC");
 
@@ -106,7 +117,8 @@ impl AlertRepository for SqliteDb {
         } else {
             filter.per_page
         };
-        let offset = filter.page.saturating_sub(1) * per_page;
+        // Wide enough that no page number overflows; past the end is empty.
+        let offset = u64::from(filter.page.saturating_sub(1)) * u64::from(per_page);
         sql.push_str(&format!(" LIMIT {per_page} OFFSET {offset}"));
 
         let mut query = sqlx::query_as::<_, (String,)>(&sql);
@@ -127,12 +139,154 @@ impl AlertRepository for SqliteDb {
         }
         Ok(alerts)
     }
+
+    async fn count(&self, filter: &AlertFilter) -> Result<u64, PortError> {
+        let (sql, binds) = self.filter_query("SELECT COUNT(*)", filter);
+        let mut query = sqlx::query_as::<_, (i64,)>(&sql);
+        for b in &binds {
+            query = query.bind(b);
+        }
+        let (count,) = query
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
+        Ok(count as u64)
+    }
+
+    async fn find_snoozed_due(&self, now: DateTime<Utc>) -> Result<Vec<Alert>, PortError> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT data FROM alerts WHERE tenant_id = ? AND status = 'Snoozed'")
+                .bind(self.tenant_id())
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        let mut due = Vec::new();
+        for (data,) in rows {
+            let alert: Alert =
+                serde_json::from_str(&data).map_err(|e| PortError::Persistence(e.to_string()))?;
+            if matches!(alert.status(), Status::Snoozed { until } if until <= now) {
+                due.push(alert);
+            }
+        }
+        Ok(due)
+    }
+
+    async fn find_ack_expired(&self, now: DateTime<Utc>) -> Result<Vec<Alert>, PortError> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT data FROM alerts WHERE tenant_id = ? AND status = 'Acknowledged'",
+        )
+        .bind(self.tenant_id())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        let mut expired = Vec::new();
+        for (data,) in rows {
+            let alert: Alert =
+                serde_json::from_str(&data).map_err(|e| PortError::Persistence(e.to_string()))?;
+            if alert.ack_expires_at().is_some_and(|at| at <= now) {
+                expired.push(alert);
+            }
+        }
+        Ok(expired)
+    }
+
+    async fn record_occurrence(
+        &self,
+        occurrence: &AlertOccurrence,
+        keep: usize,
+    ) -> Result<(), PortError> {
+        let alert_id = occurrence.alert_id.to_string();
+        let data =
+            serde_json::to_string(occurrence).map_err(|e| PortError::Persistence(e.to_string()))?;
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        sqlx::query("INSERT INTO alert_occurrences (tenant_id, alert_id, data) VALUES (?, ?, ?)")
+            .bind(self.tenant_id())
+            .bind(&alert_id)
+            .bind(&data)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
+        sqlx::query(
+            "DELETE FROM alert_occurrences WHERE tenant_id = ?1 AND alert_id = ?2
+             AND id NOT IN (
+                SELECT id FROM alert_occurrences WHERE tenant_id = ?1 AND alert_id = ?2
+                ORDER BY id DESC LIMIT ?3
+             )",
+        )
+        .bind(self.tenant_id())
+        .bind(&alert_id)
+        .bind(keep as i64)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))
+    }
+
+    async fn occurrences(&self, alert_id: &AlertId) -> Result<Vec<AlertOccurrence>, PortError> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT data FROM alert_occurrences WHERE tenant_id = ? AND alert_id = ?
+             ORDER BY id DESC",
+        )
+        .bind(self.tenant_id())
+        .bind(alert_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|(data,)| {
+                serde_json::from_str(&data).map_err(|e| PortError::Persistence(e.to_string()))
+            })
+            .collect()
+    }
+}
+
+impl SqliteDb {
+    /// `select` over this tenant's alerts matching `filter`, with its binds.
+    fn filter_query(&self, select: &str, filter: &AlertFilter) -> (String, Vec<String>) {
+        let mut sql = format!("{select} FROM alerts WHERE tenant_id = ?");
+        let mut binds: Vec<String> = vec![self.tenant_id()];
+
+        if let Some(status) = &filter.status {
+            sql.push_str(" AND status = ?");
+            binds.push(status.name().into());
+        }
+        if let Some(severity) = &filter.severity {
+            sql.push_str(" AND severity = ?");
+            binds.push(format!("{severity:?}"));
+        }
+        if let Some(source) = &filter.source {
+            sql.push_str(" AND source = ?");
+            binds.push(source.clone());
+        }
+        if let Some(search) = &filter.search {
+            sql.push_str(" AND data LIKE ?");
+            binds.push(format!("%{search}%"));
+        }
+        if filter.unrouted {
+            sql.push_str(" AND json_extract(data, '$.unrouted') = 1");
+        }
+        (sql, binds)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rouse_core::alert::{Severity, Source, Status};
+    use rouse_core::alert::{Severity, Source};
+    use rouse_core::ids::{PolicyId, TenantId, UserId};
+    use rouse_ports::outbound::TenantScoped;
+    use rouse_ports::types::RawAlert;
     use std::collections::BTreeMap;
 
     fn ts(s: &str) -> chrono::DateTime<chrono::Utc> {
@@ -196,6 +350,50 @@ mod tests {
         assert_eq!(found.id(), alert.id());
     }
 
+    #[tokio::test]
+    async fn find_by_fingerprint_returns_the_newest() {
+        let db = db().await;
+        let mut first = make_alert("payments");
+        first
+            .resolve("operator".into(), ts("2025-01-15T10:05:00Z"))
+            .unwrap();
+        db.save(&first).await.unwrap();
+        let second = make_alert("payments");
+        db.save(&second).await.unwrap();
+        db.save(&first).await.unwrap();
+
+        let found = db
+            .find_by_fingerprint(first.fingerprint().as_str())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id(), second.id());
+    }
+
+    #[tokio::test]
+    async fn find_open_by_fingerprint_skips_resolved() {
+        let db = db().await;
+        let mut open = make_alert("payments");
+        db.save(&open).await.unwrap();
+        let mut resolved = make_alert("payments");
+        resolved
+            .resolve("operator".into(), ts("2025-01-15T10:05:00Z"))
+            .unwrap();
+        db.save(&resolved).await.unwrap();
+
+        let fingerprint = open.fingerprint().clone();
+        let fp = fingerprint.as_str();
+        let found = db.find_open_by_fingerprint(fp).await.unwrap().unwrap();
+        assert_eq!(found.id(), open.id());
+        let newest = db.find_by_fingerprint(fp).await.unwrap().unwrap();
+        assert_eq!(newest.id(), resolved.id());
+
+        open.resolve("operator".into(), ts("2025-01-15T10:06:00Z"))
+            .unwrap();
+        db.save(&open).await.unwrap();
+        assert!(db.find_open_by_fingerprint(fp).await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn save_updates_existing() {
         let db = db().await;
@@ -244,5 +442,166 @@ mod tests {
         };
         let results = db.find_by_filter(&filter).await.unwrap();
         assert!(results.is_empty());
+
+        let filter = AlertFilter {
+            page: u32::MAX,
+            per_page: 1000,
+            ..Default::default()
+        };
+        assert!(db.find_by_filter(&filter).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unrouted_alerts_can_be_listed_and_counted() {
+        let db = db().await;
+        db.save(&make_alert("api")).await.unwrap();
+        let mut lost = make_alert("apu");
+        lost.mark_unrouted(None, ts("2025-01-15T10:00:00Z"));
+        db.save(&lost).await.unwrap();
+
+        let filter = AlertFilter {
+            unrouted: true,
+            ..Default::default()
+        };
+        let results = db.find_by_filter(&filter).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id(), lost.id());
+        assert_eq!(db.count(&filter).await.unwrap(), 1);
+        assert_eq!(db.count(&AlertFilter::default()).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn snoozed_alerts_are_due_once_their_snooze_runs_out() {
+        let db = db().await;
+        let mut alert = make_alert("api");
+        let until = ts("2025-01-15T11:00:00Z");
+        alert
+            .snooze(UserId::new(), until, ts("2025-01-15T10:00:00Z"))
+            .unwrap();
+        db.save(&alert).await.unwrap();
+        db.save(&make_alert("web")).await.unwrap();
+
+        let snoozed = AlertFilter {
+            status: Some(alert.status()),
+            ..Default::default()
+        };
+        assert_eq!(db.count(&snoozed).await.unwrap(), 1);
+        assert!(db
+            .find_snoozed_due(ts("2025-01-15T10:59:00Z"))
+            .await
+            .unwrap()
+            .is_empty());
+        let due = db.find_snoozed_due(until).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].status(), Status::Snoozed { until });
+    }
+
+    #[tokio::test]
+    async fn acks_expire_once_their_timeout_runs_out() {
+        let db = db().await;
+        let mut alert = make_alert("api");
+        alert.record_page(PolicyId::new(), 0, 0, Some(900));
+        alert
+            .acknowledge(UserId::new(), ts("2025-01-15T10:00:00Z"))
+            .unwrap();
+        db.save(&alert).await.unwrap();
+        let mut held = make_alert("web");
+        held.acknowledge(UserId::new(), ts("2025-01-15T10:00:00Z"))
+            .unwrap();
+        db.save(&held).await.unwrap();
+
+        assert!(db
+            .find_ack_expired(ts("2025-01-15T10:14:00Z"))
+            .await
+            .unwrap()
+            .is_empty());
+        let expired = db
+            .find_ack_expired(ts("2025-01-15T10:15:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id(), alert.id());
+    }
+
+    #[tokio::test]
+    async fn occurrence_history_keeps_the_newest() {
+        let db = db().await;
+        let alert = make_alert("api");
+        let raw = |summary: &str| RawAlert {
+            external_id: "ext-1".into(),
+            source: "alertmanager".into(),
+            severity: "critical".into(),
+            labels: alert.labels().clone(),
+            summary: summary.into(),
+            status: "firing".into(),
+        };
+        for (i, summary) in ["first", "second", "third"].into_iter().enumerate() {
+            let at = ts("2025-01-15T10:00:00Z") + chrono::Duration::minutes(i as i64);
+            let occurrence = AlertOccurrence::new(alert.id().clone(), &raw(summary), at);
+            db.record_occurrence(&occurrence, 2).await.unwrap();
+        }
+
+        let history = db.occurrences(alert.id()).await.unwrap();
+        let summaries: Vec<_> = history.iter().map(|o| o.summary.as_str()).collect();
+        assert_eq!(summaries, ["third", "second"]);
+        assert!(db
+            .for_tenant(&TenantId::new())
+            .occurrences(alert.id())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn tenants_never_see_each_others_alerts() {
+        let acme = db().await;
+        let globex = acme.for_tenant(&TenantId::new());
+        let alert = make_alert("api");
+        acme.save(&alert).await.unwrap();
+
+        let id = alert.id().to_string();
+        assert!(globex.find_by_id(&id).await.unwrap().is_none());
+        assert!(globex
+            .find_by_fingerprint(alert.fingerprint().as_str())
+            .await
+            .unwrap()
+            .is_none());
+        let listed = globex
+            .find_by_filter(&AlertFilter::default())
+            .await
+            .unwrap();
+        assert!(listed.is_empty());
+        assert_eq!(
+            acme.find_by_filter(&AlertFilter::default())
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn tenant_cannot_ack_another_tenants_alert_by_id() {
+        let acme = db().await;
+        let globex = acme.for_tenant(&TenantId::new());
+        let alert = make_alert("api");
+        acme.save(&alert).await.unwrap();
+
+        // Even holding a copy, the write does not reach the other tenant's row.
+        let mut stolen = alert.clone();
+        stolen
+            .acknowledge(UserId::new(), ts("2025-01-15T10:05:00Z"))
+            .unwrap();
+        assert!(matches!(
+            globex.save(&stolen).await,
+            Err(PortError::Conflict(_))
+        ));
+
+        let stored = acme
+            .find_by_id(&alert.id().to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status(), Status::Firing);
     }
 }
@@ -0,0 +1,231 @@
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::AuditLog;
+use rouse_ports::types::{AuditEntry, AuditFilter};
+
+use super::SqliteDb;
+
+// Fixed-width so that timestamps compare correctly as text.
+fn timestamp(at: &DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+#[async_trait]
+impl AuditLog for SqliteDb {
+    async fn append(&self, entry: &AuditEntry) -> Result<(), PortError> {
+        let data =
+            serde_json::to_string(entry).map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO audit_log (id, tenant_id, actor_id, source, action, target, data, at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&entry.id)
+        .bind(self.tenant_id())
+        .bind(entry.actor.user_id.as_ref().map(|id| id.to_string()))
+        .bind(format!("{:?}", entry.actor.source))
+        .bind(&entry.action)
+        .bind(&entry.target)
+        .bind(&data)
+        .bind(timestamp(&entry.at))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn query(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>, PortError> {
+        let mut sql = String::from("SELECT data FROM audit_log WHERE tenant_id = ?");
+        let mut binds: Vec<String> = vec![self.tenant_id()];
+
+        if let Some(actor) = &filter.actor {
+            sql.push_str(" AND actor_id = ?");
+            binds.push(actor.to_string());
+        }
+        if let Some(action) = &filter.action {
+            sql.push_str(" AND (action = ? OR action LIKE ?)");
+            binds.push(action.clone());
+            binds.push(format!("{action}.%"));
+        }
+        if let Some(target) = &filter.target {
+            sql.push_str(" AND target = ?");
+            binds.push(target.clone());
+        }
+        if let Some(source) = &filter.source {
+            sql.push_str(" AND source = ?");
+            binds.push(format!("{source:?}"));
+        }
+        if let Some(since) = &filter.since {
+            sql.push_str(" AND at >= ?");
+            binds.push(timestamp(since));
+        }
+        if let Some(until) = &filter.until {
+            sql.push_str(" AND at < ?");
+            binds.push(timestamp(until));
+        }
+
+        let limit = if filter.limit == 0 { 100 } else { filter.limit };
+        sql.push_str(&format!(" ORDER BY at DESC, rowid DESC LIMIT {limit}"));
+
+        let mut query = sqlx::query_as::<_, (String,)>(&sql);
+        for b in &binds {
+            query = query.bind(b);
+        }
+
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        rows.iter()
+            .map(|(data,)| {
+                serde_json::from_str(data).map_err(|e| PortError::Persistence(e.to_string()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use rouse_core::ids::{TenantId, UserId};
+    use rouse_ports::outbound::TenantScoped;
+    use rouse_ports::types::{Actor, AuditChange, AuditSource};
+
+    async fn db() -> SqliteDb {
+        SqliteDb::new("sqlite::memory:").await.unwrap()
+    }
+
+    fn entry(
+        id: &str,
+        user: &UserId,
+        source: AuditSource,
+        action: &str,
+        at: DateTime<Utc>,
+    ) -> AuditEntry {
+        AuditEntry {
+            id: id.into(),
+            actor: Actor {
+                user_id: Some(user.clone()),
+                source,
+                ip: Some("10.0.0.1".into()),
+            },
+            action: action.into(),
+            target: "alert:a1".into(),
+            changes: vec![AuditChange {
+                field: "status".into(),
+                before: Some("Firing".into()),
+                after: Some("Acknowledged".into()),
+            }],
+            at,
+        }
+    }
+
+    #[tokio::test]
+    async fn entries_are_filtered_newest_first() {
+        let db = db().await;
+        let alice = UserId::new();
+        let bob = UserId::new();
+        let now = Utc::now();
+        db.append(&entry(
+            "1",
+            &alice,
+            AuditSource::Api,
+            "alert.acknowledge",
+            now - Duration::hours(2),
+        ))
+        .await
+        .unwrap();
+        db.append(&entry(
+            "2",
+            &bob,
+            AuditSource::Slack,
+            "alert.resolve",
+            now - Duration::hours(1),
+        ))
+        .await
+        .unwrap();
+        db.append(&entry("3", &alice, AuditSource::Ui, "user.update", now))
+            .await
+            .unwrap();
+
+        let all = db.query(&AuditFilter::default()).await.unwrap();
+        let ids: Vec<_> = all.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["3", "2", "1"]);
+        assert_eq!(
+            all[2],
+            entry(
+                "1",
+                &alice,
+                AuditSource::Api,
+                "alert.acknowledge",
+                now - Duration::hours(2)
+            )
+        );
+
+        let by_actor = AuditFilter {
+            actor: Some(alice.clone()),
+            ..Default::default()
+        };
+        assert_eq!(db.query(&by_actor).await.unwrap().len(), 2);
+
+        let by_action = AuditFilter {
+            action: Some("alert".into()),
+            since: Some(now - Duration::minutes(90)),
+            ..Default::default()
+        };
+        let found = db.query(&by_action).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "2");
+
+        let by_source = AuditFilter {
+            source: Some(AuditSource::Ui),
+            limit: 1,
+            ..Default::default()
+        };
+        assert_eq!(db.query(&by_source).await.unwrap()[0].id, "3");
+    }
+
+    #[tokio::test]
+    async fn entries_cannot_be_changed_or_removed() {
+        let db = db().await;
+        db.append(&entry(
+            "1",
+            &UserId::new(),
+            AuditSource::Api,
+            "alert.resolve",
+            Utc::now(),
+        ))
+        .await
+        .unwrap();
+
+        let update = sqlx::query("UPDATE audit_log SET action = 'nothing'")
+            .execute(&db.pool)
+            .await;
+        assert!(update.unwrap_err().to_string().contains("append-only"));
+        let delete = sqlx::query("DELETE FROM audit_log").execute(&db.pool).await;
+        assert!(delete.is_err());
+        assert_eq!(db.query(&AuditFilter::default()).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn entries_stay_within_their_tenant() {
+        let db = db().await;
+        let other = db.for_tenant(&TenantId::new());
+        other
+            .append(&entry(
+                "1",
+                &UserId::new(),
+                AuditSource::Api,
+                "alert.resolve",
+                Utc::now(),
+            ))
+            .await
+            .unwrap();
+        assert!(db.query(&AuditFilter::default()).await.unwrap().is_empty());
+        assert_eq!(other.query(&AuditFilter::default()).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,211 @@
+use async_trait::async_trait;
+
+use rouse_core::ids::PolicyId;
+use rouse_core::routing::Route;
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::{
+    ConfigStore, EscalationRepository, RouteRepository, ScheduleRepository, TeamRepository,
+    UserRepository,
+};
+use rouse_ports::types::{ConfigChanges, ConfigSnapshot};
+
+use super::{escalation, schedule, user, SqliteDb};
+
+#[async_trait]
+impl RouteRepository for SqliteDb {
+    async fn list_all(&self) -> Result<Vec<Route>, PortError> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT data FROM routes WHERE tenant_id = ? ORDER BY position")
+                .bind(self.tenant_id())
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        rows.iter()
+            .map(|(data,)| {
+                serde_json::from_str(data).map_err(|e| PortError::Persistence(e.to_string()))
+            })
+            .collect()
+    }
+
+    async fn default_policy(&self) -> Result<Option<PolicyId>, PortError> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT policy_id FROM default_routes WHERE tenant_id = ?")
+                .bind(self.tenant_id())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+        row.map(|(id,)| PolicyId::parse(&id).map_err(|e| PortError::Persistence(e.to_string())))
+            .transpose()
+    }
+
+    async fn revision(&self) -> Result<u64, PortError> {
+        let row: Option<(i64,)> =
+            sqlx::query_as("SELECT revision FROM route_revisions WHERE tenant_id = ?")
+                .bind(self.tenant_id())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+        Ok(row.map_or(0, |(revision,)| revision as u64))
+    }
+}
+
+#[async_trait]
+impl ConfigStore for SqliteDb {
+    async fn snapshot(&self) -> Result<ConfigSnapshot, PortError> {
+        Ok(ConfigSnapshot {
+            users: UserRepository::list_all(self).await?,
+            teams: TeamRepository::list_all(self).await?,
+            schedules: ScheduleRepository::list_all(self).await?,
+            policies: EscalationRepository::list_all(self).await?,
+            routes: RouteRepository::list_all(self).await?,
+            default_policy_id: self.default_policy().await?,
+        })
+    }
+
+    async fn commit(&self, changes: &ConfigChanges) -> Result<(), PortError> {
+        let tenant_id = self.tenant_id();
+        // Dropped without commit on any error, which rolls everything back.
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        // Deletions first, so a name can move to a new object.
+        for id in &changes.deleted_policies {
+            escalation::delete(&mut *tx, &tenant_id, &id.to_string()).await?;
+        }
+        for id in &changes.deleted_schedules {
+            schedule::delete(&mut *tx, &tenant_id, &id.to_string()).await?;
+        }
+        for id in &changes.deleted_users {
+            user::delete(&mut *tx, &tenant_id, &id.to_string()).await?;
+        }
+        for u in &changes.users {
+            user::upsert(&mut *tx, &tenant_id, u).await?;
+        }
+        for s in &changes.schedules {
+            schedule::upsert(&mut *tx, &tenant_id, s).await?;
+        }
+        for p in &changes.policies {
+            escalation::upsert(&mut *tx, &tenant_id, p).await?;
+        }
+
+        sqlx::query("DELETE FROM routes WHERE tenant_id = ?")
+            .bind(&tenant_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
+        for (position, route) in changes.routes.iter().enumerate() {
+            let data =
+                serde_json::to_string(route).map_err(|e| PortError::Persistence(e.to_string()))?;
+            sqlx::query("INSERT INTO routes (tenant_id, position, data) VALUES (?, ?, ?)")
+                .bind(&tenant_id)
+                .bind(position as i64)
+                .bind(&data)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+        }
+
+        sqlx::query("DELETE FROM default_routes WHERE tenant_id = ?")
+            .bind(&tenant_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
+        if let Some(policy_id) = &changes.default_policy_id {
+            sqlx::query("INSERT INTO default_routes (tenant_id, policy_id) VALUES (?, ?)")
+                .bind(&tenant_id)
+                .bind(policy_id.to_string())
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+        }
+        sqlx::query(
+            "INSERT INTO route_revisions (tenant_id, revision) VALUES (?, 1)
+             ON CONFLICT (tenant_id) DO UPDATE SET revision = revision + 1",
+        )
+        .bind(&tenant_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rouse_core::ids::{PolicyId, TenantId};
+    use rouse_core::routing::Matcher;
+    use rouse_core::user::{Role, User};
+    use rouse_ports::outbound::TenantScoped;
+
+    async fn db() -> SqliteDb {
+        SqliteDb::new("sqlite::memory:").await.unwrap()
+    }
+
+    fn route(service: &str) -> Route {
+        Route::new(vec![Matcher::equal("service", service)], PolicyId::new())
+    }
+
+    #[tokio::test]
+    async fn commit_replaces_routes_and_deletes() {
+        let db = db().await;
+        let alice = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        db.commit(&ConfigChanges {
+            users: vec![alice.clone()],
+            routes: vec![route("api"), route("web")],
+            default_policy_id: Some(PolicyId::new()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let snapshot = db.snapshot().await.unwrap();
+        assert_eq!(snapshot.users.len(), 1);
+        assert_eq!(snapshot.routes[1].matchers[0].value, "web");
+        assert!(snapshot.default_policy_id.is_some());
+
+        db.commit(&ConfigChanges {
+            deleted_users: vec![alice.id().clone()],
+            routes: vec![route("db")],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let snapshot = db.snapshot().await.unwrap();
+        assert!(snapshot.users.is_empty());
+        assert_eq!(snapshot.routes.len(), 1);
+        assert_eq!(snapshot.default_policy_id, None);
+        assert_eq!(db.revision().await.unwrap(), 2);
+        let other = db.for_tenant(&TenantId::new());
+        assert!(other.snapshot().await.unwrap().routes.is_empty());
+        assert_eq!(other.revision().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn failed_commit_writes_nothing() {
+        let db = db().await;
+        let result = db
+            .commit(&ConfigChanges {
+                users: vec![
+                    User::new("alice".into(), "same@test.com".into(), Role::User),
+                    User::new("bob".into(), "same@test.com".into(), Role::User),
+                ],
+                routes: vec![route("api")],
+                ..Default::default()
+            })
+            .await;
+
+        assert!(matches!(result, Err(PortError::Conflict(_))));
+        let snapshot = db.snapshot().await.unwrap();
+        assert!(snapshot.users.is_empty());
+        assert!(snapshot.routes.is_empty());
+        assert_eq!(db.revision().await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,172 @@
+use async_trait::async_trait;
+
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::CredentialRepository;
+use rouse_ports::types::AuthToken;
+
+use super::{scoped_write, SqliteDb};
+
+fn decode(data: &str) -> Result<AuthToken, PortError> {
+    serde_json::from_str(data).map_err(|e| PortError::Persistence(e.to_string()))
+}
+
+#[async_trait]
+impl CredentialRepository for SqliteDb {
+    async fn set_password_hash(&self, user_id: &str, hash: &str) -> Result<(), PortError> {
+        sqlx::query(
+            "INSERT INTO passwords (user_id, tenant_id, hash) VALUES (?, ?, ?)
+             ON CONFLICT(user_id) DO UPDATE SET hash = excluded.hash
+             WHERE tenant_id = excluded.tenant_id",
+        )
+        .bind(user_id)
+        .bind(self.tenant_id())
+        .bind(hash)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))
+        .and_then(scoped_write)
+    }
+
+    async fn password_hash(&self, user_id: &str) -> Result<Option<String>, PortError> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT hash FROM passwords WHERE user_id = ? AND tenant_id = ?")
+                .bind(user_id)
+                .bind(self.tenant_id())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+        Ok(row.map(|(hash,)| hash))
+    }
+
+    async fn save_token(&self, token: &AuthToken) -> Result<(), PortError> {
+        let data =
+            serde_json::to_string(token).map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO auth_tokens (id, tenant_id, user_id, digest, data, created_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&token.id)
+        .bind(self.tenant_id())
+        .bind(token.user_id.to_string())
+        .bind(&token.digest)
+        .bind(&data)
+        .bind(token.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn find_token(&self, digest: &str) -> Result<Option<AuthToken>, PortError> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT data FROM auth_tokens WHERE digest = ?")
+                .bind(digest)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        row.map(|(data,)| decode(&data)).transpose()
+    }
+
+    async fn list_tokens(&self, user_id: &str) -> Result<Vec<AuthToken>, PortError> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT data FROM auth_tokens
+                 WHERE tenant_id = ? AND user_id = ?
+                 ORDER BY created_at",
+        )
+        .bind(self.tenant_id())
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        rows.iter().map(|(data,)| decode(data)).collect()
+    }
+
+    async fn delete_token(&self, id: &str) -> Result<(), PortError> {
+        sqlx::query("DELETE FROM auth_tokens WHERE id = ? AND tenant_id = ?")
+            .bind(id)
+            .bind(self.tenant_id())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rouse_core::ids::{TenantId, UserId};
+    use rouse_ports::outbound::TenantScoped;
+    use rouse_ports::types::TokenKind;
+
+    async fn db() -> SqliteDb {
+        SqliteDb::new("sqlite::memory:").await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn password_hash_is_replaced() {
+        let db = db().await;
+        assert!(db.password_hash("u1").await.unwrap().is_none());
+        db.set_password_hash("u1", "first").await.unwrap();
+        db.set_password_hash("u1", "second").await.unwrap();
+        assert_eq!(
+            db.password_hash("u1").await.unwrap().as_deref(),
+            Some("second")
+        );
+    }
+
+    #[tokio::test]
+    async fn tokens_are_found_by_digest_and_deleted() {
+        let db = db().await;
+        let user_id = UserId::new();
+        let token = AuthToken {
+            id: "t1".into(),
+            tenant_id: TenantId::primary(),
+            user_id: user_id.clone(),
+            kind: TokenKind::Api { name: "ci".into() },
+            digest: "abc".into(),
+            created_at: Utc::now(),
+            expires_at: None,
+        };
+        db.save_token(&token).await.unwrap();
+
+        let found = db.find_token("abc").await.unwrap().unwrap();
+        assert_eq!(found.kind, TokenKind::Api { name: "ci".into() });
+        assert_eq!(db.list_tokens(&user_id.to_string()).await.unwrap().len(), 1);
+
+        db.delete_token("t1").await.unwrap();
+        assert!(db.find_token("abc").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn tokens_resolve_across_tenants_but_list_within_one() {
+        let db = db().await;
+        let tenant = TenantId::new();
+        let other = db.for_tenant(&tenant);
+        let user_id = UserId::new();
+        let token = AuthToken {
+            id: "t1".into(),
+            tenant_id: tenant.clone(),
+            user_id: user_id.clone(),
+            kind: TokenKind::Session,
+            digest: "abc".into(),
+            created_at: Utc::now(),
+            expires_at: None,
+        };
+        other.save_token(&token).await.unwrap();
+
+        let found = db.find_token("abc").await.unwrap().unwrap();
+        assert_eq!(found.tenant_id, tenant);
+        assert!(db
+            .list_tokens(&user_id.to_string())
+            .await
+            .unwrap()
+            .is_empty());
+        db.delete_token("t1").await.unwrap();
+        assert!(other.find_token("abc").await.unwrap().is_some());
+    }
+}
@@ -1,35 +1,58 @@
 use async_trait::async_trait;
+use sqlx::SqliteExecutor;
 
 use rouse_core::escalation::EscalationPolicy;
 use rouse_ports::error::PortError;
 use rouse_ports::outbound::EscalationRepository;
 
-use super::SqliteDb;
+use super::{scoped_write, SqliteDb};
 
-#[async_trait]
-impl EscalationRepository for SqliteDb {
-    async fn save(&self, policy: &EscalationPolicy) -> Result<(), PortError> {
-        let id = policy.id().to_string();
-        let data =
-            serde_json::to_string(policy).map_err(|e| PortError::Persistence(e.to_string()))?;
+pub(super) async fn upsert(
+    executor: impl SqliteExecutor<'_>,
+    tenant_id: &str,
+    policy: &EscalationPolicy,
+) -> Result<(), PortError> {
+    let data = serde_json::to_string(policy).map_err(|e| PortError::Persistence(e.to_string()))?;
 
-        sqlx::query(
-            "INSERT INTO escalation_policies (id, data) VALUES (?, ?)
-             ON CONFLICT(id) DO UPDATE SET data = excluded.data",
-        )
-        .bind(&id)
-        .bind(&data)
-        .execute(&self.pool)
+    sqlx::query(
+        "INSERT INTO escalation_policies (id, tenant_id, data) VALUES (?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET data = excluded.data
+         WHERE tenant_id = excluded.tenant_id",
+    )
+    .bind(policy.id().to_string())
+    .bind(tenant_id)
+    .bind(&data)
+    .execute(executor)
+    .await
+    .map_err(|e| PortError::Persistence(e.to_string()))
+    .and_then(scoped_write)
+}
+
+pub(super) async fn delete(
+    executor: impl SqliteExecutor<'_>,
+    tenant_id: &str,
+    id: &str,
+) -> Result<(), PortError> {
+    sqlx::query("DELETE FROM escalation_policies WHERE id = ? AND tenant_id = ?")
+        .bind(id)
+        .bind(tenant_id)
+        .execute(executor)
         .await
         .map_err(|e| PortError::Persistence(e.to_string()))?;
+    Ok(())
+}
 
-        Ok(())
+#[async_trait]
+impl EscalationRepository for SqliteDb {
+    async fn save(&self, policy: &EscalationPolicy) -> Result<(), PortError> {
+        upsert(&self.pool, &self.tenant_id(), policy).await
     }
 
     async fn find_by_id(&self, id: &str) -> Result<Option<EscalationPolicy>, PortError> {
         let row: Option<(String,)> =
-            sqlx::query_as("SELECT data FROM escalation_policies WHERE id = ?")
+            sqlx::query_as("SELECT data FROM escalation_policies WHERE id = ? AND tenant_id = ?")
                 .bind(id)
+                .bind(self.tenant_id())
                 .fetch_optional(&self.pool)
                 .await
                 .map_err(|e| PortError::Persistence(e.to_string()))?;
@@ -43,6 +66,23 @@ impl EscalationRepository for SqliteDb {
             None => Ok(None),
         }
     }
+
+    async fn list_all(&self) -> Result<Vec<EscalationPolicy>, PortError> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT data FROM escalation_policies WHERE tenant_id = ?")
+                .bind(self.tenant_id())
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        let mut policies = Vec::with_capacity(rows.len());
+        for (data,) in rows {
+            let policy: EscalationPolicy =
+                serde_json::from_str(&data).map_err(|e| PortError::Persistence(e.to_string()))?;
+            policies.push(policy);
+        }
+        Ok(policies)
+    }
 }
 
 #[cfg(test)]
@@ -81,6 +121,7 @@ mod tests {
         let found = db.find_by_id(&id).await.unwrap().unwrap();
         assert_eq!(found.name(), "critical");
         assert_eq!(found.repeat_count(), 1);
+        assert_eq!(db.list_all().await.unwrap().len(), 1);
     }
 
     #[tokio::test]
@@ -13,16 +13,25 @@ impl EscalationQueue for SqliteDb {
         let alert_id = step.alert_id.to_string();
         let policy_id = step.policy_id.to_string();
         let fires_at = step.fires_at.to_rfc3339();
+        let target = step
+            .target
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
 
         sqlx::query(
-            "INSERT INTO escalation_steps (id, alert_id, policy_id, step_order, fires_at, status)
-             VALUES (?, ?, ?, ?, ?, 'pending')",
+            "INSERT INTO escalation_steps (id, tenant_id, alert_id, policy_id, step_order, repetition, fires_at, status, target)
+             VALUES (?, ?, ?, ?, ?, ?, ?, 'pending', ?)",
         )
         .bind(&step.id)
+        .bind(self.tenant_id())
         .bind(&alert_id)
         .bind(&policy_id)
         .bind(step.step_order)
+        .bind(step.repetition)
         .bind(&fires_at)
+        .bind(&target)
         .execute(&self.pool)
         .await
         .map_err(|e| PortError::Persistence(e.to_string()))?;
@@ -32,19 +41,20 @@ impl EscalationQueue for SqliteDb {
 
     async fn poll_due(&self) -> Result<Vec<PendingEscalation>, PortError> {
         let now = Utc::now().to_rfc3339();
-        let rows: Vec<(String, String, String, i32, String, String)> = sqlx::query_as(
-            "SELECT id, alert_id, policy_id, step_order, fires_at, status
+        let rows: Vec<(String, String, String, i32, i32, String, Option<String>)> = sqlx::query_as(
+            "SELECT id, alert_id, policy_id, step_order, repetition, fires_at, target
              FROM escalation_steps
-             WHERE status = 'pending' AND fires_at <= ?
+             WHERE tenant_id = ? AND status = 'pending' AND fires_at <= ?
              ORDER BY fires_at ASC",
         )
+        .bind(self.tenant_id())
         .bind(&now)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| PortError::Persistence(e.to_string()))?;
 
         let mut result = Vec::with_capacity(rows.len());
-        for (id, alert_id, policy_id, step_order, fires_at, _status) in rows {
+        for (id, alert_id, policy_id, step_order, repetition, fires_at, target) in rows {
             result.push(PendingEscalation {
                 id,
                 alert_id: rouse_core::ids::AlertId::parse(&alert_id)
@@ -52,10 +62,15 @@ impl EscalationQueue for SqliteDb {
                 policy_id: rouse_core::ids::PolicyId::parse(&policy_id)
                     .map_err(|e| PortError::Persistence(e.to_string()))?,
                 step_order: step_order as u32,
+                repetition: repetition as u32,
                 fires_at: DateTime::parse_from_rfc3339(&fires_at)
                     .map_err(|e| PortError::Persistence(e.to_string()))?
                     .with_timezone(&Utc),
                 status: QueueStatus::Pending,
+                target: target
+                    .map(|t| serde_json::from_str(&t))
+                    .transpose()
+                    .map_err(|e| PortError::Persistence(e.to_string()))?,
             });
         }
         Ok(result)
@@ -63,8 +78,10 @@ impl EscalationQueue for SqliteDb {
 
     async fn cancel_for_alert(&self, alert_id: &str) -> Result<(), PortError> {
         sqlx::query(
-            "UPDATE escalation_steps SET status = 'cancelled' WHERE alert_id = ? AND status = 'pending'",
+            "UPDATE escalation_steps SET status = 'cancelled'
+             WHERE tenant_id = ? AND alert_id = ? AND status = 'pending'",
         )
+        .bind(self.tenant_id())
         .bind(alert_id)
         .execute(&self.pool)
         .await
@@ -74,8 +91,9 @@ impl EscalationQueue for SqliteDb {
     }
 
     async fn mark_fired(&self, id: &str) -> Result<(), PortError> {
-        sqlx::query("UPDATE escalation_steps SET status = 'fired' WHERE id = ?")
+        sqlx::query("UPDATE escalation_steps SET status = 'fired' WHERE id = ? AND tenant_id = ?")
             .bind(id)
+            .bind(self.tenant_id())
             .execute(&self.pool)
             .await
             .map_err(|e| PortError::Persistence(e.to_string()))?;
@@ -86,7 +104,8 @@ impl EscalationQueue for SqliteDb {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rouse_core::ids::{AlertId, PolicyId};
+    use rouse_core::escalation::EscalationTarget;
+    use rouse_core::ids::{AlertId, PolicyId, TeamId};
 
     async fn db() -> SqliteDb {
         SqliteDb::new("sqlite::memory:").await.unwrap()
@@ -98,8 +117,10 @@ mod tests {
             alert_id: alert_id.clone(),
             policy_id: PolicyId::new(),
             step_order: 0,
+            repetition: 0,
             fires_at: chrono::Utc::now() - chrono::Duration::seconds(10),
             status: QueueStatus::Pending,
+            target: None,
         }
     }
 
@@ -115,6 +136,22 @@ mod tests {
         let due = db.poll_due().await.unwrap();
         assert_eq!(due.len(), 1);
         assert_eq!(due[0].id, step_id);
+        assert_eq!(due[0].target, None);
+    }
+
+    #[tokio::test]
+    async fn handed_over_step_keeps_its_target() {
+        let db = db().await;
+        let mut step = make_step(&AlertId::new());
+        let target = EscalationTarget::Team(TeamId::new());
+        step.target = Some(target.clone());
+        step.repetition = 2;
+
+        db.enqueue_step(step).await.unwrap();
+
+        let due = db.poll_due().await.unwrap();
+        assert_eq!(due[0].target, Some(target));
+        assert_eq!(due[0].repetition, 2);
     }
 
     #[tokio::test]
@@ -15,13 +15,16 @@ impl EventPublisher for SqliteDb {
                 serde_json::to_string(event).map_err(|e| PortError::Persistence(e.to_string()))?;
             let occurred_at = event.occurred_at().to_rfc3339();
 
-            sqlx::query("INSERT INTO events (event_type, data, occurred_at) VALUES (?, ?, ?)")
-                .bind(event_type)
-                .bind(&data)
-                .bind(&occurred_at)
-                .execute(&self.pool)
-                .await
-                .map_err(|e| PortError::Persistence(e.to_string()))?;
+            sqlx::query(
+                "INSERT INTO events (tenant_id, event_type, data, occurred_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(self.tenant_id())
+            .bind(event_type)
+            .bind(&data)
+            .bind(&occurred_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
         }
         Ok(())
     }
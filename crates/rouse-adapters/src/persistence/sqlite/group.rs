@@ -4,7 +4,7 @@ use rouse_core::alert::group::AlertGroup;
 use rouse_ports::error::PortError;
 use rouse_ports::outbound::AlertGroupRepository;
 
-use super::SqliteDb;
+use super::{scoped_write, SqliteDb};
 
 #[async_trait]
 impl AlertGroupRepository for SqliteDb {
@@ -15,30 +15,33 @@ impl AlertGroupRepository for SqliteDb {
         let last_added_at = group.last_added_at().to_rfc3339();
 
         sqlx::query(
-            "INSERT INTO alert_groups (id, grouping_key, data, last_added_at)
-             VALUES (?, ?, ?, ?)
+            "INSERT INTO alert_groups (id, tenant_id, grouping_key, data, last_added_at)
+             VALUES (?, ?, ?, ?, ?)
              ON CONFLICT(id) DO UPDATE SET
                 data = excluded.data,
-                last_added_at = excluded.last_added_at",
+                last_added_at = excluded.last_added_at
+             WHERE tenant_id = excluded.tenant_id",
         )
         .bind(&id)
+        .bind(self.tenant_id())
         .bind(group.grouping_key())
         .bind(&data)
         .bind(&last_added_at)
         .execute(&self.pool)
         .await
-        .map_err(|e| PortError::Persistence(e.to_string()))?;
-
-        Ok(())
+        .map_err(|e| PortError::Persistence(e.to_string()))
+        .and_then(scoped_write)
     }
 
     async fn find_active_by_key(&self, key: &str) -> Result<Option<AlertGroup>, PortError> {
-        let row: Option<(String,)> =
-            sqlx::query_as("SELECT data FROM alert_groups WHERE grouping_key = ? LIMIT 1")
-                .bind(key)
-                .fetch_optional(&self.pool)
-                .await
-                .map_err(|e| PortError::Persistence(e.to_string()))?;
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT data FROM alert_groups WHERE tenant_id = ? AND grouping_key = ? LIMIT 1",
+        )
+        .bind(self.tenant_id())
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
 
         match row {
             Some((data,)) => {
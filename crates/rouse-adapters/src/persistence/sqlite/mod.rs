@@ -1,4 +1,7 @@
 mod alert;
+mod audit;
+mod config;
+mod credential;
 mod escalation;
 mod escalation_queue;
 mod event;
@@ -6,16 +9,43 @@ mod group;
 mod noise;
 mod notification_queue;
 mod schedule;
+mod swap;
+mod team;
+mod user;
+mod verification;
 
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions, SqliteQueryResult};
 
+use rouse_core::ids::TenantId;
 use rouse_ports::error::PortError;
+use rouse_ports::outbound::TenantScoped;
 
+/// Every table carries a `tenant_id`; a handle only reads and writes the
+/// rows of its own tenant. `new` starts on the primary tenant.
 #[derive(Clone)]
 pub struct SqliteDb {
     pool: SqlitePool,
+    tenant: TenantId,
 }
 
+/// Tables that existed before tenancy and may lack the column.
+const TENANT_TABLES: &[&str] = &[
+    "alerts",
+    "schedules",
+    "users",
+    "teams",
+    "passwords",
+    "auth_tokens",
+    "verification_challenges",
+    "swap_requests",
+    "escalation_policies",
+    "notifications",
+    "escalation_steps",
+    "events",
+    "alert_groups",
+    "noise_scores",
+];
+
 impl SqliteDb {
     pub async fn new(url: &str) -> Result<Self, PortError> {
         let pool = SqlitePoolOptions::new()
@@ -24,7 +54,10 @@ impl SqliteDb {
             .await
             .map_err(|e| PortError::Connection(e.to_string()))?;
 
-        let db = Self { pool };
+        let db = Self {
+            pool,
+            tenant: TenantId::primary(),
+        };
         db.init_schema().await?;
         Ok(db)
     }
@@ -33,6 +66,7 @@ impl SqliteDb {
         sqlx::query(
             "CREATE TABLE IF NOT EXISTS alerts (
                 id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
                 fingerprint TEXT NOT NULL,
                 status TEXT NOT NULL,
                 severity TEXT NOT NULL,
@@ -45,14 +79,10 @@ impl SqliteDb {
         .await
         .map_err(|e| PortError::Persistence(e.to_string()))?;
 
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_alerts_fingerprint ON alerts(fingerprint)")
-            .execute(&self.pool)
-            .await
-            .map_err(|e| PortError::Persistence(e.to_string()))?;
-
         sqlx::query(
             "CREATE TABLE IF NOT EXISTS schedules (
                 id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
                 data TEXT NOT NULL
             )",
         )
@@ -61,8 +91,11 @@ impl SqliteDb {
         .map_err(|e| PortError::Persistence(e.to_string()))?;
 
         sqlx::query(
-            "CREATE TABLE IF NOT EXISTS escalation_policies (
+            "CREATE TABLE IF NOT EXISTS users (
                 id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                username TEXT NOT NULL,
+                email TEXT NOT NULL,
                 data TEXT NOT NULL
             )",
         )
@@ -71,15 +104,62 @@ impl SqliteDb {
         .map_err(|e| PortError::Persistence(e.to_string()))?;
 
         sqlx::query(
-            "CREATE TABLE IF NOT EXISTS notifications (
+            "CREATE TABLE IF NOT EXISTS teams (
                 id TEXT PRIMARY KEY,
-                alert_id TEXT NOT NULL,
+                tenant_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                data TEXT NOT NULL
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS passwords (
+                user_id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                hash TEXT NOT NULL
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS auth_tokens (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                digest TEXT NOT NULL UNIQUE,
+                data TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS verification_challenges (
+                user_id TEXT NOT NULL,
+                tenant_id TEXT NOT NULL,
                 channel TEXT NOT NULL,
-                target TEXT NOT NULL,
-                payload TEXT NOT NULL,
-                status TEXT NOT NULL DEFAULT 'pending',
-                next_attempt_at TEXT NOT NULL,
-                retry_count INTEGER NOT NULL DEFAULT 0,
+                data TEXT NOT NULL,
+                PRIMARY KEY (user_id, channel)
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS swap_requests (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                schedule_id TEXT NOT NULL,
+                status TEXT NOT NULL,
+                data TEXT NOT NULL,
                 created_at TEXT NOT NULL
             )",
         )
@@ -88,21 +168,28 @@ impl SqliteDb {
         .map_err(|e| PortError::Persistence(e.to_string()))?;
 
         sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_notifications_pending
-             ON notifications(status, next_attempt_at)",
+            "CREATE TABLE IF NOT EXISTS escalation_policies (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                data TEXT NOT NULL
+            )",
         )
         .execute(&self.pool)
         .await
         .map_err(|e| PortError::Persistence(e.to_string()))?;
 
         sqlx::query(
-            "CREATE TABLE IF NOT EXISTS escalation_steps (
+            "CREATE TABLE IF NOT EXISTS notifications (
                 id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
                 alert_id TEXT NOT NULL,
-                policy_id TEXT NOT NULL,
-                step_order INTEGER NOT NULL,
-                fires_at TEXT NOT NULL,
-                status TEXT NOT NULL DEFAULT 'pending'
+                channel TEXT NOT NULL,
+                target TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                next_attempt_at TEXT NOT NULL,
+                retry_count INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL
             )",
         )
         .execute(&self.pool)
@@ -110,8 +197,17 @@ impl SqliteDb {
         .map_err(|e| PortError::Persistence(e.to_string()))?;
 
         sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_escalation_steps_pending
-             ON escalation_steps(status, fires_at)",
+            "CREATE TABLE IF NOT EXISTS escalation_steps (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                alert_id TEXT NOT NULL,
+                policy_id TEXT NOT NULL,
+                step_order INTEGER NOT NULL,
+                fires_at TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                target TEXT,
+                repetition INTEGER NOT NULL DEFAULT 0
+            )",
         )
         .execute(&self.pool)
         .await
@@ -120,6 +216,7 @@ impl SqliteDb {
         sqlx::query(
             "CREATE TABLE IF NOT EXISTS events (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                tenant_id TEXT NOT NULL,
                 event_type TEXT NOT NULL,
                 data TEXT NOT NULL,
                 occurred_at TEXT NOT NULL
@@ -132,6 +229,7 @@ impl SqliteDb {
         sqlx::query(
             "CREATE TABLE IF NOT EXISTS alert_groups (
                 id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
                 grouping_key TEXT NOT NULL,
                 data TEXT NOT NULL,
                 last_added_at TEXT NOT NULL
@@ -142,29 +240,369 @@ impl SqliteDb {
         .map_err(|e| PortError::Persistence(e.to_string()))?;
 
         sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_alert_groups_key ON alert_groups(grouping_key)",
+            "CREATE TABLE IF NOT EXISTS noise_scores (
+                fingerprint TEXT NOT NULL,
+                tenant_id TEXT NOT NULL,
+                total_fires INTEGER NOT NULL DEFAULT 0,
+                dismissed_count INTEGER NOT NULL DEFAULT 0,
+                acted_on_count INTEGER NOT NULL DEFAULT 0,
+                avg_time_to_ack_secs INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (tenant_id, fingerprint)
+            )",
         )
         .execute(&self.pool)
         .await
         .map_err(|e| PortError::Persistence(e.to_string()))?;
+        self.rekey_noise_scores().await?;
 
+        // Kept apart from `events`: that table feeds the domain, this one
+        // answers "who did what" and must never change once written.
         sqlx::query(
-            "CREATE TABLE IF NOT EXISTS noise_scores (
-                fingerprint TEXT PRIMARY KEY,
-                total_fires INTEGER NOT NULL DEFAULT 0,
-                dismissed_count INTEGER NOT NULL DEFAULT 0,
-                acted_on_count INTEGER NOT NULL DEFAULT 0,
-                avg_time_to_ack_secs INTEGER NOT NULL DEFAULT 0
+            "CREATE TABLE IF NOT EXISTS audit_log (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                actor_id TEXT,
+                source TEXT NOT NULL,
+                action TEXT NOT NULL,
+                target TEXT NOT NULL,
+                data TEXT NOT NULL,
+                at TEXT NOT NULL
             )",
         )
         .execute(&self.pool)
         .await
         .map_err(|e| PortError::Persistence(e.to_string()))?;
 
+        // Routes only come from the config file and are replaced as a whole,
+        // so they are stored in order rather than by id.
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS routes (
+                tenant_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                data TEXT NOT NULL,
+                PRIMARY KEY (tenant_id, position)
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS alert_occurrences (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                tenant_id TEXT NOT NULL,
+                alert_id TEXT NOT NULL,
+                data TEXT NOT NULL
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS default_routes (
+                tenant_id TEXT PRIMARY KEY,
+                policy_id TEXT NOT NULL
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS route_revisions (
+                tenant_id TEXT PRIMARY KEY,
+                revision INTEGER NOT NULL
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        for table in TENANT_TABLES {
+            let tenant = format!("TEXT NOT NULL DEFAULT '{}'", TenantId::primary());
+            self.add_column(table, "tenant_id", &tenant).await?;
+        }
+        self.add_column("escalation_steps", "target", "TEXT")
+            .await?;
+        self.add_column(
+            "escalation_steps",
+            "repetition",
+            "INTEGER NOT NULL DEFAULT 0",
+        )
+        .await?;
+
+        for statement in [
+            // Uniqueness used to be global; it is per tenant now.
+            "DROP INDEX IF EXISTS idx_users_username",
+            "DROP INDEX IF EXISTS idx_users_email",
+            "DROP INDEX IF EXISTS idx_alerts_fingerprint",
+            "DROP INDEX IF EXISTS idx_alert_groups_key",
+            "DROP INDEX IF EXISTS idx_notifications_pending",
+            "DROP INDEX IF EXISTS idx_escalation_steps_pending",
+            "DROP INDEX IF EXISTS idx_swap_requests_schedule",
+            "CREATE INDEX IF NOT EXISTS idx_alerts_tenant_fingerprint
+             ON alerts(tenant_id, fingerprint)",
+            "CREATE INDEX IF NOT EXISTS idx_alerts_tenant_created
+             ON alerts(tenant_id, created_at)",
+            "CREATE INDEX IF NOT EXISTS idx_schedules_tenant ON schedules(tenant_id)",
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_tenant_username
+             ON users(tenant_id, username)",
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_tenant_email ON users(tenant_id, email)",
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_teams_tenant_name ON teams(tenant_id, name)",
+            "CREATE INDEX IF NOT EXISTS idx_auth_tokens_tenant_user
+             ON auth_tokens(tenant_id, user_id)",
+            "CREATE INDEX IF NOT EXISTS idx_swap_requests_tenant_schedule
+             ON swap_requests(tenant_id, schedule_id, created_at)",
+            "CREATE INDEX IF NOT EXISTS idx_escalation_policies_tenant
+             ON escalation_policies(tenant_id)",
+            "CREATE INDEX IF NOT EXISTS idx_notifications_tenant_pending
+             ON notifications(tenant_id, status, next_attempt_at)",
+            "CREATE INDEX IF NOT EXISTS idx_escalation_steps_tenant_pending
+             ON escalation_steps(tenant_id, status, fires_at)",
+            "CREATE INDEX IF NOT EXISTS idx_events_tenant ON events(tenant_id, occurred_at)",
+            "CREATE INDEX IF NOT EXISTS idx_alert_groups_tenant_key
+             ON alert_groups(tenant_id, grouping_key)",
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_noise_scores_tenant_fingerprint
+             ON noise_scores(tenant_id, fingerprint)",
+            "CREATE INDEX IF NOT EXISTS idx_audit_log_tenant_at ON audit_log(tenant_id, at)",
+            "CREATE INDEX IF NOT EXISTS idx_alert_occurrences_tenant_alert
+             ON alert_occurrences(tenant_id, alert_id)",
+            "CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+             BEGIN SELECT RAISE(ABORT, 'audit log is append-only'); END",
+            "CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+             BEGIN SELECT RAISE(ABORT, 'audit log is append-only'); END",
+        ] {
+            sqlx::query(statement)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Older databases key `noise_scores` on the fingerprint alone, which
+    /// an added column can't change. The table is rebuilt with the tenant
+    /// in its key and the existing scores copied across.
+    async fn rekey_noise_scores(&self) -> Result<(), PortError> {
+        let columns: Vec<(String, i64)> =
+            sqlx::query_as("SELECT name, pk FROM pragma_table_info('noise_scores')")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+        if columns
+            .iter()
+            .any(|(name, pk)| name == "tenant_id" && *pk > 0)
+        {
+            return Ok(());
+        }
+        let tenant = if columns.iter().any(|(name, _)| name == "tenant_id") {
+            "tenant_id".to_string()
+        } else {
+            format!("'{}'", TenantId::primary())
+        };
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
+        for statement in [
+            "CREATE TABLE noise_scores_new (
+                fingerprint TEXT NOT NULL,
+                tenant_id TEXT NOT NULL,
+                total_fires INTEGER NOT NULL DEFAULT 0,
+                dismissed_count INTEGER NOT NULL DEFAULT 0,
+                acted_on_count INTEGER NOT NULL DEFAULT 0,
+                avg_time_to_ack_secs INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (tenant_id, fingerprint)
+            )"
+            .to_string(),
+            format!(
+                "INSERT INTO noise_scores_new (fingerprint, tenant_id, total_fires, dismissed_count, acted_on_count, avg_time_to_ack_secs)
+                 SELECT fingerprint, {tenant}, total_fires, dismissed_count, acted_on_count, avg_time_to_ack_secs
+                 FROM noise_scores"
+            ),
+            "DROP TABLE noise_scores".to_string(),
+            "ALTER TABLE noise_scores_new RENAME TO noise_scores".to_string(),
+        ] {
+            sqlx::query(&statement)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))
+    }
+
+    /// Adds a column that older databases lack. Tenancy came this way,
+    /// with every existing row assigned to the primary tenant.
+    async fn add_column(
+        &self,
+        table: &str,
+        column: &str,
+        definition: &str,
+    ) -> Result<(), PortError> {
+        let columns: Vec<(String,)> =
+            sqlx::query_as(&format!("SELECT name FROM pragma_table_info('{table}')"))
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+        if columns.iter().any(|(name,)| name == column) {
+            return Ok(());
+        }
+        sqlx::query(&format!(
+            "ALTER TABLE {table} ADD COLUMN {column} {definition}"
+        ))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
         Ok(())
     }
 
+    fn tenant_id(&self) -> String {
+        self.tenant.to_string()
+    }
+
+    /// Every tenant that has users, for work that runs across tenants.
+    pub async fn tenants(&self) -> Result<Vec<TenantId>, PortError> {
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT DISTINCT tenant_id FROM users")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
+        rows.into_iter()
+            .map(|(id,)| TenantId::parse(&id).map_err(|e| PortError::Persistence(e.to_string())))
+            .collect()
+    }
+
     pub fn pool(&self) -> &SqlitePool {
         &self.pool
     }
 }
+
+impl TenantScoped for SqliteDb {
+    fn tenant(&self) -> &TenantId {
+        &self.tenant
+    }
+
+    fn for_tenant(&self, tenant: &TenantId) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            tenant: tenant.clone(),
+        }
+    }
+}
+
+/// Upserts only update rows of the writing tenant
+/// (`ON CONFLICT ... WHERE tenant_id = excluded.tenant_id`). An id taken by
+/// another tenant leaves nothing written and is reported as a conflict.
+fn scoped_write(result: SqliteQueryResult) -> Result<(), PortError> {
+    if result.rows_affected() == 0 {
+        return Err(PortError::Conflict("id belongs to another tenant".into()));
+    }
+    Ok(())
+}
+
+/// Maps unique-constraint violations to `PortError::Conflict`.
+fn write_error(e: sqlx::Error, conflict: &str) -> PortError {
+    match e.as_database_error() {
+        Some(db) if db.is_unique_violation() => PortError::Conflict(conflict.into()),
+        _ => PortError::Persistence(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn pre_tenancy_rows_move_to_the_primary_tenant() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE schedules (id TEXT PRIMARY KEY, data TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO schedules (id, data) VALUES ('s1', '{}')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let db = SqliteDb {
+            pool,
+            tenant: TenantId::primary(),
+        };
+        db.init_schema().await.unwrap();
+
+        let (tenant,): (String,) =
+            sqlx::query_as("SELECT tenant_id FROM schedules WHERE id = 's1'")
+                .fetch_one(&db.pool)
+                .await
+                .unwrap();
+        assert_eq!(tenant, TenantId::primary().to_string());
+    }
+
+    #[tokio::test]
+    async fn noise_scores_are_rekeyed_by_tenant() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE noise_scores (
+                fingerprint TEXT PRIMARY KEY,
+                total_fires INTEGER NOT NULL DEFAULT 0,
+                dismissed_count INTEGER NOT NULL DEFAULT 0,
+                acted_on_count INTEGER NOT NULL DEFAULT 0,
+                avg_time_to_ack_secs INTEGER NOT NULL DEFAULT 0
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO noise_scores (fingerprint, total_fires) VALUES ('fp', 3)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let db = SqliteDb {
+            pool,
+            tenant: TenantId::primary(),
+        };
+        db.init_schema().await.unwrap();
+        // Re-running against the rebuilt table leaves it alone.
+        db.init_schema().await.unwrap();
+
+        let (tenant, fires): (String, i64) = sqlx::query_as(
+            "SELECT tenant_id, total_fires FROM noise_scores WHERE fingerprint = 'fp'",
+        )
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        assert_eq!(tenant, TenantId::primary().to_string());
+        assert_eq!(fires, 3);
+
+        // The same fingerprint may now be scored in another tenant.
+        let upsert = "INSERT INTO noise_scores (tenant_id, fingerprint, total_fires) VALUES (?, 'fp', 1)
+                      ON CONFLICT(tenant_id, fingerprint) DO UPDATE SET total_fires = total_fires + 1";
+        for tenant in [TenantId::primary(), TenantId::new()] {
+            sqlx::query(upsert)
+                .bind(tenant.to_string())
+                .execute(&db.pool)
+                .await
+                .unwrap();
+        }
+        let (rows, fires): (i64, i64) =
+            sqlx::query_as("SELECT COUNT(*), MAX(total_fires) FROM noise_scores")
+                .fetch_one(&db.pool)
+                .await
+                .unwrap();
+        assert_eq!((rows, fires), (2, 4));
+    }
+}
@@ -11,8 +11,9 @@ impl NoiseRepository for SqliteDb {
     async fn get_or_create(&self, fingerprint: &str) -> Result<NoiseScore, PortError> {
         let row: Option<(String, i64, i64, i64, i64)> = sqlx::query_as(
             "SELECT fingerprint, total_fires, dismissed_count, acted_on_count, avg_time_to_ack_secs
-             FROM noise_scores WHERE fingerprint = ?",
+             FROM noise_scores WHERE tenant_id = ? AND fingerprint = ?",
         )
+        .bind(self.tenant_id())
         .bind(fingerprint)
         .fetch_optional(&self.pool)
         .await
@@ -37,14 +38,15 @@ impl NoiseRepository for SqliteDb {
 
     async fn save(&self, score: &NoiseScore) -> Result<(), PortError> {
         sqlx::query(
-            "INSERT INTO noise_scores (fingerprint, total_fires, dismissed_count, acted_on_count, avg_time_to_ack_secs)
-             VALUES (?, ?, ?, ?, ?)
-             ON CONFLICT(fingerprint) DO UPDATE SET
+            "INSERT INTO noise_scores (tenant_id, fingerprint, total_fires, dismissed_count, acted_on_count, avg_time_to_ack_secs)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(tenant_id, fingerprint) DO UPDATE SET
                 total_fires = excluded.total_fires,
                 dismissed_count = excluded.dismissed_count,
                 acted_on_count = excluded.acted_on_count,
                 avg_time_to_ack_secs = excluded.avg_time_to_ack_secs",
         )
+        .bind(self.tenant_id())
         .bind(score.fingerprint())
         .bind(score.total_fires() as i64)
         .bind(score.dismissed_count() as i64)
@@ -61,9 +63,10 @@ impl NoiseRepository for SqliteDb {
         let rows: Vec<(String, i64, i64, i64, i64)> = sqlx::query_as(
             "SELECT fingerprint, total_fires, dismissed_count, acted_on_count, avg_time_to_ack_secs
              FROM noise_scores
-             WHERE total_fires >= ?
+             WHERE tenant_id = ? AND total_fires >= ?
              ORDER BY CAST(dismissed_count AS REAL) / CAST(total_fires AS REAL) DESC",
         )
+        .bind(self.tenant_id())
         .bind(min_fires as i64)
         .fetch_all(&self.pool)
         .await
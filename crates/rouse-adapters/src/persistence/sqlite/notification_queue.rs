@@ -7,7 +7,7 @@ use rouse_ports::types::{PendingNotification, QueueStatus};
 
 use super::SqliteDb;
 
-fn channel_to_str(ch: &rouse_core::channel::Channel) -> &'static str {
+pub(super) fn channel_to_str(ch: &rouse_core::channel::Channel) -> &'static str {
     match ch {
         rouse_core::channel::Channel::Slack => "slack",
         rouse_core::channel::Channel::Discord => "discord",
@@ -40,6 +40,7 @@ fn status_to_str(s: &QueueStatus) -> &'static str {
         QueueStatus::Sent => "sent",
         QueueStatus::Failed => "failed",
         QueueStatus::Dead => "dead",
+        QueueStatus::Cancelled => "cancelled",
     }
 }
 
@@ -53,10 +54,11 @@ impl NotificationQueue for SqliteDb {
         let created_at = notification.created_at.to_rfc3339();
 
         sqlx::query(
-            "INSERT INTO notifications (id, alert_id, channel, target, payload, status, next_attempt_at, retry_count, created_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO notifications (id, tenant_id, alert_id, channel, target, payload, status, next_attempt_at, retry_count, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(&notification.id)
+        .bind(self.tenant_id())
         .bind(&alert_id)
         .bind(channel)
         .bind(&notification.target)
@@ -72,15 +74,17 @@ impl NotificationQueue for SqliteDb {
         Ok(())
     }
 
+    /// Failed notifications come back once their retry is due.
     async fn poll_pending(&self) -> Result<Vec<PendingNotification>, PortError> {
         let now = Utc::now().to_rfc3339();
         let rows: Vec<(String, String, String, String, String, String, String, i32, String)> =
             sqlx::query_as(
                 "SELECT id, alert_id, channel, target, payload, status, next_attempt_at, retry_count, created_at
                  FROM notifications
-                 WHERE status = 'pending' AND next_attempt_at <= ?
+                 WHERE tenant_id = ? AND status IN ('pending', 'failed') AND next_attempt_at <= ?
                  ORDER BY next_attempt_at ASC",
             )
+            .bind(self.tenant_id())
             .bind(&now)
             .fetch_all(&self.pool)
             .await
@@ -93,7 +97,7 @@ impl NotificationQueue for SqliteDb {
             channel,
             target,
             payload,
-            _status,
+            status,
             next_attempt,
             retry_count,
             created_at,
@@ -106,7 +110,11 @@ impl NotificationQueue for SqliteDb {
                 channel: str_to_channel(&channel)?,
                 target,
                 payload,
-                status: QueueStatus::Pending,
+                status: if status == "failed" {
+                    QueueStatus::Failed
+                } else {
+                    QueueStatus::Pending
+                },
                 next_attempt_at: DateTime::parse_from_rfc3339(&next_attempt)
                     .map_err(|e| PortError::Persistence(e.to_string()))?
                     .with_timezone(&Utc),
@@ -120,8 +128,9 @@ impl NotificationQueue for SqliteDb {
     }
 
     async fn mark_sent(&self, id: &str) -> Result<(), PortError> {
-        sqlx::query("UPDATE notifications SET status = 'sent' WHERE id = ?")
+        sqlx::query("UPDATE notifications SET status = 'sent' WHERE id = ? AND tenant_id = ?")
             .bind(id)
+            .bind(self.tenant_id())
             .execute(&self.pool)
             .await
             .map_err(|e| PortError::Persistence(e.to_string()))?;
@@ -136,10 +145,12 @@ impl NotificationQueue for SqliteDb {
     ) -> Result<(), PortError> {
         let next = next_attempt.to_rfc3339();
         sqlx::query(
-            "UPDATE notifications SET status = 'failed', next_attempt_at = ?, retry_count = retry_count + 1 WHERE id = ?",
+            "UPDATE notifications SET status = 'failed', next_attempt_at = ?, retry_count = retry_count + 1
+             WHERE id = ? AND tenant_id = ?",
         )
         .bind(&next)
         .bind(id)
+        .bind(self.tenant_id())
         .execute(&self.pool)
         .await
         .map_err(|e| PortError::Persistence(e.to_string()))?;
@@ -152,13 +163,27 @@ impl NotificationQueue for SqliteDb {
     }
 
     async fn mark_dead(&self, id: &str) -> Result<(), PortError> {
-        sqlx::query("UPDATE notifications SET status = 'dead' WHERE id = ?")
+        sqlx::query("UPDATE notifications SET status = 'dead' WHERE id = ? AND tenant_id = ?")
             .bind(id)
+            .bind(self.tenant_id())
             .execute(&self.pool)
             .await
             .map_err(|e| PortError::Persistence(e.to_string()))?;
         Ok(())
     }
+
+    async fn cancel_for_alert(&self, alert_id: &str) -> Result<(), PortError> {
+        sqlx::query(
+            "UPDATE notifications SET status = 'cancelled'
+             WHERE tenant_id = ? AND alert_id = ? AND status IN ('pending', 'failed')",
+        )
+        .bind(self.tenant_id())
+        .bind(alert_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -214,6 +239,25 @@ mod tests {
         assert!(pending.is_empty());
     }
 
+    #[tokio::test]
+    async fn failed_notification_is_retried_when_due() {
+        let db = db().await;
+        let notif = make_notification(&AlertId::new());
+        let notif_id = notif.id.clone();
+        db.enqueue(notif).await.unwrap();
+
+        let later = Utc::now() + chrono::Duration::seconds(60);
+        db.mark_failed(&notif_id, "timeout", later).await.unwrap();
+        assert!(db.poll_pending().await.unwrap().is_empty());
+
+        let now = Utc::now() - chrono::Duration::seconds(1);
+        db.mark_failed(&notif_id, "timeout", now).await.unwrap();
+        let pending = db.poll_pending().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].status, QueueStatus::Failed);
+        assert_eq!(pending[0].retry_count, 2);
+    }
+
     #[tokio::test]
     async fn mark_dead_removes_from_pending() {
         let db = db().await;
@@ -227,4 +271,18 @@ mod tests {
         let pending = db.poll_pending().await.unwrap();
         assert!(pending.is_empty());
     }
+
+    #[tokio::test]
+    async fn cancel_for_alert_only_touches_that_alert() {
+        let db = db().await;
+        let (alert_id, other_alert) = (AlertId::new(), AlertId::new());
+        db.enqueue(make_notification(&alert_id)).await.unwrap();
+        db.enqueue(make_notification(&other_alert)).await.unwrap();
+
+        db.cancel_for_alert(&alert_id.to_string()).await.unwrap();
+
+        let pending = db.poll_pending().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].alert_id, other_alert);
+    }
 }
@@ -1,37 +1,62 @@
 use async_trait::async_trait;
+use sqlx::SqliteExecutor;
 
 use rouse_core::schedule::Schedule;
 use rouse_ports::error::PortError;
 use rouse_ports::outbound::ScheduleRepository;
 
-use super::SqliteDb;
-
-#[async_trait]
-impl ScheduleRepository for SqliteDb {
-    async fn save(&self, schedule: &Schedule) -> Result<(), PortError> {
-        let id = schedule.id().to_string();
-        let data =
-            serde_json::to_string(schedule).map_err(|e| PortError::Persistence(e.to_string()))?;
+use super::{scoped_write, SqliteDb};
+
+pub(super) async fn upsert(
+    executor: impl SqliteExecutor<'_>,
+    tenant_id: &str,
+    schedule: &Schedule,
+) -> Result<(), PortError> {
+    let data =
+        serde_json::to_string(schedule).map_err(|e| PortError::Persistence(e.to_string()))?;
+
+    sqlx::query(
+        "INSERT INTO schedules (id, tenant_id, data) VALUES (?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET data = excluded.data
+         WHERE tenant_id = excluded.tenant_id",
+    )
+    .bind(schedule.id().to_string())
+    .bind(tenant_id)
+    .bind(&data)
+    .execute(executor)
+    .await
+    .map_err(|e| PortError::Persistence(e.to_string()))
+    .and_then(scoped_write)
+}
 
-        sqlx::query(
-            "INSERT INTO schedules (id, data) VALUES (?, ?)
-             ON CONFLICT(id) DO UPDATE SET data = excluded.data",
-        )
-        .bind(&id)
-        .bind(&data)
-        .execute(&self.pool)
+pub(super) async fn delete(
+    executor: impl SqliteExecutor<'_>,
+    tenant_id: &str,
+    id: &str,
+) -> Result<(), PortError> {
+    sqlx::query("DELETE FROM schedules WHERE id = ? AND tenant_id = ?")
+        .bind(id)
+        .bind(tenant_id)
+        .execute(executor)
         .await
         .map_err(|e| PortError::Persistence(e.to_string()))?;
+    Ok(())
+}
 
-        Ok(())
+#[async_trait]
+impl ScheduleRepository for SqliteDb {
+    async fn save(&self, schedule: &Schedule) -> Result<(), PortError> {
+        upsert(&self.pool, &self.tenant_id(), schedule).await
     }
 
     async fn find_by_id(&self, id: &str) -> Result<Option<Schedule>, PortError> {
-        let row: Option<(String,)> = sqlx::query_as("SELECT data FROM schedules WHERE id = ?")
-            .bind(id)
-            .fetch_optional(&self.pool)
-            .await
-            .map_err(|e| PortError::Persistence(e.to_string()))?;
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT data FROM schedules WHERE id = ? AND tenant_id = ?")
+                .bind(id)
+                .bind(self.tenant_id())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
 
         match row {
             Some((data,)) => {
@@ -44,7 +69,8 @@ impl ScheduleRepository for SqliteDb {
     }
 
     async fn list_all(&self) -> Result<Vec<Schedule>, PortError> {
-        let rows: Vec<(String,)> = sqlx::query_as("SELECT data FROM schedules")
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT data FROM schedules WHERE tenant_id = ?")
+            .bind(self.tenant_id())
             .fetch_all(&self.pool)
             .await
             .map_err(|e| PortError::Persistence(e.to_string()))?;
@@ -0,0 +1,160 @@
+use async_trait::async_trait;
+
+use rouse_core::schedule::SwapRequest;
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::SwapRequestRepository;
+
+use super::{scoped_write, SqliteDb};
+
+#[async_trait]
+impl SwapRequestRepository for SqliteDb {
+    async fn save(&self, swap: &SwapRequest) -> Result<(), PortError> {
+        let id = swap.id().to_string();
+        let schedule_id = swap.schedule_id().to_string();
+        let status = format!("{:?}", swap.status());
+        let data =
+            serde_json::to_string(swap).map_err(|e| PortError::Persistence(e.to_string()))?;
+        let created_at = swap.created_at().to_rfc3339();
+
+        sqlx::query(
+            "INSERT INTO swap_requests (id, tenant_id, schedule_id, status, data, created_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                status = excluded.status,
+                data = excluded.data
+             WHERE tenant_id = excluded.tenant_id",
+        )
+        .bind(&id)
+        .bind(self.tenant_id())
+        .bind(&schedule_id)
+        .bind(&status)
+        .bind(&data)
+        .bind(&created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))
+        .and_then(scoped_write)
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<SwapRequest>, PortError> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT data FROM swap_requests WHERE id = ? AND tenant_id = ?")
+                .bind(id)
+                .bind(self.tenant_id())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        match row {
+            Some((data,)) => {
+                let swap: SwapRequest = serde_json::from_str(&data)
+                    .map_err(|e| PortError::Persistence(e.to_string()))?;
+                Ok(Some(swap))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn list_by_schedule(&self, schedule_id: &str) -> Result<Vec<SwapRequest>, PortError> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT data FROM swap_requests
+             WHERE tenant_id = ? AND schedule_id = ?
+             ORDER BY created_at DESC",
+        )
+        .bind(self.tenant_id())
+        .bind(schedule_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        let mut swaps = Vec::with_capacity(rows.len());
+        for (data,) in rows {
+            let swap: SwapRequest =
+                serde_json::from_str(&data).map_err(|e| PortError::Persistence(e.to_string()))?;
+            swaps.push(swap);
+        }
+        Ok(swaps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rouse_core::ids::{ScheduleId, UserId};
+    use rouse_core::schedule::{SwapDecision, SwapKind, SwapProposal, SwapStatus};
+
+    fn ts(s: &str) -> chrono::DateTime<chrono::Utc> {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .unwrap()
+            .with_timezone(&chrono::Utc)
+    }
+
+    async fn db() -> SqliteDb {
+        SqliteDb::new("sqlite::memory:").await.unwrap()
+    }
+
+    fn make_swap(schedule_id: &ScheduleId) -> SwapRequest {
+        let (swap, _) = SwapRequest::propose(
+            schedule_id.clone(),
+            SwapProposal {
+                requester: UserId::new(),
+                counterpart: UserId::new(),
+                kind: SwapKind::Cover,
+                start: ts("2025-01-14T00:00:00Z"),
+                end: ts("2025-01-15T00:00:00Z"),
+                reason: None,
+            },
+            ts("2025-01-13T09:00:00Z"),
+        )
+        .unwrap();
+        swap
+    }
+
+    #[tokio::test]
+    async fn save_and_find_by_id() {
+        let db = db().await;
+        let swap = make_swap(&ScheduleId::new());
+        let id = swap.id().to_string();
+
+        db.save(&swap).await.unwrap();
+
+        let found = db.find_by_id(&id).await.unwrap().unwrap();
+        assert_eq!(found.id(), swap.id());
+        assert_eq!(found.status(), SwapStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn save_updates_status() {
+        let db = db().await;
+        let mut swap = make_swap(&ScheduleId::new());
+        db.save(&swap).await.unwrap();
+
+        let counterpart = swap.counterpart().clone();
+        swap.respond(
+            &counterpart,
+            SwapDecision::Accept,
+            ts("2025-01-13T10:00:00Z"),
+        )
+        .unwrap();
+        db.save(&swap).await.unwrap();
+
+        let found = db
+            .find_by_id(&swap.id().to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.status(), SwapStatus::Accepted);
+    }
+
+    #[tokio::test]
+    async fn list_by_schedule_only_returns_that_schedule() {
+        let db = db().await;
+        let schedule_id = ScheduleId::new();
+        db.save(&make_swap(&schedule_id)).await.unwrap();
+        db.save(&make_swap(&schedule_id)).await.unwrap();
+        db.save(&make_swap(&ScheduleId::new())).await.unwrap();
+
+        let swaps = db.list_by_schedule(&schedule_id.to_string()).await.unwrap();
+        assert_eq!(swaps.len(), 2);
+    }
+}
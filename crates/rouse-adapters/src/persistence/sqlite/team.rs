@@ -0,0 +1,110 @@
+use async_trait::async_trait;
+
+use rouse_core::user::Team;
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::TeamRepository;
+
+use super::{scoped_write, write_error, SqliteDb};
+
+fn decode(data: &str) -> Result<Team, PortError> {
+    serde_json::from_str(data).map_err(|e| PortError::Persistence(e.to_string()))
+}
+
+#[async_trait]
+impl TeamRepository for SqliteDb {
+    async fn save(&self, team: &Team) -> Result<(), PortError> {
+        let id = team.id().to_string();
+        let data =
+            serde_json::to_string(team).map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO teams (id, tenant_id, name, data) VALUES (?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                data = excluded.data
+             WHERE tenant_id = excluded.tenant_id",
+        )
+        .bind(&id)
+        .bind(self.tenant_id())
+        .bind(team.name())
+        .bind(&data)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| write_error(e, "team name already in use"))
+        .and_then(scoped_write)
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Team>, PortError> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT data FROM teams WHERE id = ? AND tenant_id = ?")
+                .bind(id)
+                .bind(self.tenant_id())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        row.map(|(data,)| decode(&data)).transpose()
+    }
+
+    async fn list_all(&self) -> Result<Vec<Team>, PortError> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT data FROM teams WHERE tenant_id = ? ORDER BY name")
+                .bind(self.tenant_id())
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        rows.iter().map(|(data,)| decode(data)).collect()
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), PortError> {
+        sqlx::query("DELETE FROM teams WHERE id = ? AND tenant_id = ?")
+            .bind(id)
+            .bind(self.tenant_id())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rouse_core::ids::UserId;
+
+    async fn db() -> SqliteDb {
+        SqliteDb::new("sqlite::memory:").await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn save_update_and_find() {
+        let db = db().await;
+        let mut team = Team::new("backend".into(), vec![UserId::new()]).unwrap();
+        db.save(&team).await.unwrap();
+
+        team.add_member(UserId::new());
+        db.save(&team).await.unwrap();
+
+        let found = db
+            .find_by_id(&team.id().to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.members().len(), 2);
+        assert_eq!(db.list_all().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn duplicate_name_conflicts_and_delete_removes() {
+        let db = db().await;
+        let team = Team::new("backend".into(), vec![UserId::new()]).unwrap();
+        db.save(&team).await.unwrap();
+
+        let other = Team::new("backend".into(), vec![UserId::new()]).unwrap();
+        assert!(matches!(db.save(&other).await, Err(PortError::Conflict(_))));
+
+        db.delete(&team.id().to_string()).await.unwrap();
+        assert!(db.list_all().await.unwrap().is_empty());
+    }
+}
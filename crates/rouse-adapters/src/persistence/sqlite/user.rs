@@ -0,0 +1,207 @@
+use async_trait::async_trait;
+use sqlx::SqliteExecutor;
+
+use rouse_core::user::User;
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::UserRepository;
+
+use super::{scoped_write, write_error, SqliteDb};
+
+fn decode(data: &str) -> Result<User, PortError> {
+    serde_json::from_str(data).map_err(|e| PortError::Persistence(e.to_string()))
+}
+
+pub(super) async fn upsert(
+    executor: impl SqliteExecutor<'_>,
+    tenant_id: &str,
+    user: &User,
+) -> Result<(), PortError> {
+    let data = serde_json::to_string(user).map_err(|e| PortError::Persistence(e.to_string()))?;
+
+    sqlx::query(
+        "INSERT INTO users (id, tenant_id, username, email, data) VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET
+            username = excluded.username,
+            email = excluded.email,
+            data = excluded.data
+         WHERE tenant_id = excluded.tenant_id",
+    )
+    .bind(user.id().to_string())
+    .bind(tenant_id)
+    .bind(user.username())
+    .bind(user.email())
+    .bind(&data)
+    .execute(executor)
+    .await
+    .map_err(|e| write_error(e, "username or email already in use"))
+    .and_then(scoped_write)
+}
+
+pub(super) async fn delete(
+    executor: impl SqliteExecutor<'_>,
+    tenant_id: &str,
+    id: &str,
+) -> Result<(), PortError> {
+    sqlx::query("DELETE FROM users WHERE id = ? AND tenant_id = ?")
+        .bind(id)
+        .bind(tenant_id)
+        .execute(executor)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+    Ok(())
+}
+
+#[async_trait]
+impl UserRepository for SqliteDb {
+    async fn save(&self, user: &User) -> Result<(), PortError> {
+        upsert(&self.pool, &self.tenant_id(), user).await
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<User>, PortError> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT data FROM users WHERE id = ? AND tenant_id = ?")
+                .bind(id)
+                .bind(self.tenant_id())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        row.map(|(data,)| decode(&data)).transpose()
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, PortError> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT data FROM users WHERE tenant_id = ? AND username = ?")
+                .bind(self.tenant_id())
+                .bind(username)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        row.map(|(data,)| decode(&data)).transpose()
+    }
+
+    async fn list_all(&self) -> Result<Vec<User>, PortError> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT data FROM users WHERE tenant_id = ? ORDER BY username")
+                .bind(self.tenant_id())
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        rows.iter().map(|(data,)| decode(data)).collect()
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), PortError> {
+        delete(&self.pool, &self.tenant_id(), id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rouse_core::channel::Channel;
+    use rouse_core::ids::TenantId;
+    use rouse_core::user::Role;
+    use rouse_ports::outbound::TenantScoped;
+
+    async fn db() -> SqliteDb {
+        SqliteDb::new("sqlite::memory:").await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn save_and_find_by_id() {
+        let db = db().await;
+        let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        user.set_slack_id("U12345".into());
+        user.mark_verified(Channel::Slack, chrono::Utc::now())
+            .unwrap();
+
+        db.save(&user).await.unwrap();
+
+        let found = db
+            .find_by_id(&user.id().to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.username(), "alice");
+        assert!(found.can_be_on_call());
+    }
+
+    #[tokio::test]
+    async fn find_by_id_returns_none() {
+        let db = db().await;
+        let found = db
+            .find_by_id("00000000-0000-0000-0000-000000000000")
+            .await
+            .unwrap();
+        assert!(found.is_none());
+    }
+
+    #[tokio::test]
+    async fn duplicate_username_or_email_conflicts() {
+        let db = db().await;
+        let alice = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        db.save(&alice).await.unwrap();
+
+        let same_name = User::new("alice".into(), "other@test.com".into(), Role::User);
+        assert!(matches!(
+            db.save(&same_name).await,
+            Err(PortError::Conflict(_))
+        ));
+
+        let same_email = User::new("bob".into(), "alice@test.com".into(), Role::User);
+        assert!(matches!(
+            db.save(&same_email).await,
+            Err(PortError::Conflict(_))
+        ));
+
+        // Re-saving the same user is an update, not a conflict.
+        db.save(&alice).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn list_find_by_username_and_delete() {
+        let db = db().await;
+        let bob = User::new("bob".into(), "bob@test.com".into(), Role::Admin);
+        let alice = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        db.save(&bob).await.unwrap();
+        db.save(&alice).await.unwrap();
+
+        let names: Vec<_> = db
+            .list_all()
+            .await
+            .unwrap()
+            .iter()
+            .map(|u| u.username().to_string())
+            .collect();
+        assert_eq!(names, ["alice", "bob"]);
+
+        let found = db.find_by_username("bob").await.unwrap().unwrap();
+        assert_eq!(found.id(), bob.id());
+
+        db.delete(&bob.id().to_string()).await.unwrap();
+        assert!(db.find_by_username("bob").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn usernames_are_unique_per_tenant() {
+        let acme = db().await;
+        let globex = acme.for_tenant(&TenantId::new());
+        let alice = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        let other_alice = User::new("alice".into(), "alice@test.com".into(), Role::Admin);
+        acme.save(&alice).await.unwrap();
+        globex.save(&other_alice).await.unwrap();
+
+        let found = globex.find_by_username("alice").await.unwrap().unwrap();
+        assert_eq!(found.id(), other_alice.id());
+        assert_eq!(acme.list_all().await.unwrap().len(), 1);
+
+        globex.delete(&alice.id().to_string()).await.unwrap();
+        assert!(acme
+            .find_by_id(&alice.id().to_string())
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
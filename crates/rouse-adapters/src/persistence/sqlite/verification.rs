@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+
+use rouse_core::channel::Channel;
+use rouse_core::user::VerificationChallenge;
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::VerificationRepository;
+
+use super::notification_queue::channel_to_str;
+use super::{scoped_write, SqliteDb};
+
+#[async_trait]
+impl VerificationRepository for SqliteDb {
+    async fn save(&self, challenge: &VerificationChallenge) -> Result<(), PortError> {
+        let data =
+            serde_json::to_string(challenge).map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO verification_challenges (user_id, tenant_id, channel, data)
+             VALUES (?, ?, ?, ?)
+             ON CONFLICT(user_id, channel) DO UPDATE SET data = excluded.data
+             WHERE tenant_id = excluded.tenant_id",
+        )
+        .bind(challenge.user_id().to_string())
+        .bind(self.tenant_id())
+        .bind(channel_to_str(&challenge.channel()))
+        .bind(&data)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))
+        .and_then(scoped_write)
+    }
+
+    async fn find(
+        &self,
+        user_id: &str,
+        channel: Channel,
+    ) -> Result<Option<VerificationChallenge>, PortError> {
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT data FROM verification_challenges
+             WHERE user_id = ? AND channel = ? AND tenant_id = ?",
+        )
+        .bind(user_id)
+        .bind(channel_to_str(&channel))
+        .bind(self.tenant_id())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| PortError::Persistence(e.to_string()))?;
+
+        row.map(|(data,)| {
+            serde_json::from_str(&data).map_err(|e| PortError::Persistence(e.to_string()))
+        })
+        .transpose()
+    }
+
+    async fn delete(&self, user_id: &str, channel: Channel) -> Result<(), PortError> {
+        sqlx::query(
+            "DELETE FROM verification_challenges WHERE user_id = ? AND channel = ? AND tenant_id = ?",
+        )
+        .bind(user_id)
+        .bind(channel_to_str(&channel))
+        .bind(self.tenant_id())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| PortError::Persistence(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rouse_core::ids::UserId;
+
+    #[tokio::test]
+    async fn save_replaces_and_delete_removes() {
+        let db = SqliteDb::new("sqlite::memory:").await.unwrap();
+        let user_id = UserId::new();
+        for code in ["111111", "222222"] {
+            let challenge = VerificationChallenge::new(
+                user_id.clone(),
+                Channel::Slack,
+                "U123".into(),
+                code.into(),
+                Utc::now(),
+            );
+            db.save(&challenge).await.unwrap();
+        }
+
+        let id = user_id.to_string();
+        let found = db.find(&id, Channel::Slack).await.unwrap().unwrap();
+        assert_eq!(found.code(), "222222");
+        assert!(db.find(&id, Channel::Sms).await.unwrap().is_none());
+
+        db.delete(&id, Channel::Slack).await.unwrap();
+        assert!(db.find(&id, Channel::Slack).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,219 @@
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::json;
+
+use rouse_core::channel::Channel;
+use rouse_ports::error::NotifyError;
+use rouse_ports::outbound::Notifier;
+use rouse_ports::types::{Notification, NotifyResult};
+
+const SLACK_API: &str = "https://slack.com/api";
+
+/// Posts as a Slack app through `chat.postMessage`. Targets are channel or
+/// user ids; a user id lands in the app's direct messages with that user.
+pub struct SlackNotifier {
+    bot_token: String,
+    api_url: String,
+    http: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct PostMessageResponse {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    ts: Option<String>,
+    #[serde(default)]
+    channel: Option<String>,
+}
+
+impl SlackNotifier {
+    pub fn new(bot_token: impl Into<String>) -> Self {
+        Self {
+            bot_token: bot_token.into(),
+            api_url: SLACK_API.into(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Talks to another Web API root, e.g. a test double.
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into();
+        self
+    }
+
+    async fn post_message(&self, channel: &str, text: &str) -> Result<NotifyResult, NotifyError> {
+        let response = self
+            .http
+            .post(format!("{}/chat.postMessage", self.api_url))
+            .bearer_auth(&self.bot_token)
+            .json(&json!({ "channel": channel, "text": text }))
+            .send()
+            .await
+            .map_err(|e| NotifyError::DeliveryFailed(e.to_string()))?;
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            return Err(NotifyError::RateLimited);
+        }
+        let body: PostMessageResponse = response
+            .json()
+            .await
+            .map_err(|e| NotifyError::DeliveryFailed(e.to_string()))?;
+        if !body.ok {
+            let error = body.error.unwrap_or_else(|| "unknown error".into());
+            return Err(match error.as_str() {
+                "ratelimited" => NotifyError::RateLimited,
+                "channel_not_found" | "user_not_found" | "is_archived" | "not_in_channel" => {
+                    NotifyError::InvalidTarget
+                }
+                _ => NotifyError::DeliveryFailed(error),
+            });
+        }
+
+        let mut result = NotifyResult {
+            external_id: body.ts,
+            ..Default::default()
+        };
+        if let Some(channel) = body.channel {
+            result.metadata.insert("channel".into(), channel);
+        }
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<NotifyResult, NotifyError> {
+        let severity = format!("{:?}", notification.severity).to_uppercase();
+        let link = format!(
+            "{}/alerts/{}",
+            notification.base_url.trim_end_matches('/'),
+            notification.alert_id
+        );
+        let text = format!("[{severity}] {}\n<{link}|View alert>", notification.summary);
+        self.post_message(&notification.target, &text).await
+    }
+
+    async fn send_text(&self, target: &str, text: &str) -> Result<NotifyResult, NotifyError> {
+        self.post_message(target, text).await
+    }
+
+    fn channel(&self) -> Channel {
+        Channel::Slack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::Value;
+
+    use rouse_core::alert::Severity;
+    use rouse_core::ids::AlertId;
+
+    use super::*;
+
+    /// What the mock Slack received, and how it answers.
+    struct Slack {
+        requests: Mutex<Vec<(Option<String>, Value)>>,
+        status: StatusCode,
+        reply: Value,
+    }
+
+    async fn post_message(
+        State(slack): State<Arc<Slack>>,
+        headers: HeaderMap,
+        Json(body): Json<Value>,
+    ) -> (StatusCode, Json<Value>) {
+        let auth = headers
+            .get("authorization")
+            .map(|v| v.to_str().unwrap().to_string());
+        slack.requests.lock().unwrap().push((auth, body));
+        (slack.status, Json(slack.reply.clone()))
+    }
+
+    /// Starts a mock Slack Web API on a free port and returns its root.
+    async fn mock_slack(status: StatusCode, reply: Value) -> (String, Arc<Slack>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api", listener.local_addr().unwrap());
+        let slack = Arc::new(Slack {
+            requests: Mutex::new(vec![]),
+            status,
+            reply,
+        });
+        let app = Router::new()
+            .route("/api/chat.postMessage", post(post_message))
+            .with_state(slack.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, slack)
+    }
+
+    fn notification() -> Notification {
+        Notification {
+            alert_id: AlertId::new(),
+            severity: Severity::Critical,
+            summary: "High CPU".into(),
+            labels: BTreeMap::new(),
+            target: "U123".into(),
+            base_url: "https://rouse.example.com/".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn posts_the_alert_to_the_target() {
+        let reply = json!({ "ok": true, "ts": "1700000000.000100", "channel": "D42" });
+        let (url, slack) = mock_slack(StatusCode::OK, reply).await;
+        let notifier = SlackNotifier::new("xoxb-test").with_api_url(url);
+        let notification = notification();
+
+        let result = notifier.notify(&notification).await.unwrap();
+
+        assert_eq!(result.external_id.as_deref(), Some("1700000000.000100"));
+        assert_eq!(result.metadata["channel"], "D42");
+        let requests = slack.requests.lock().unwrap();
+        let (auth, body) = &requests[0];
+        assert_eq!(auth.as_deref(), Some("Bearer xoxb-test"));
+        assert_eq!(body["channel"], "U123");
+        let text = body["text"].as_str().unwrap();
+        assert!(text.starts_with("[CRITICAL] High CPU"));
+        let link = format!("https://rouse.example.com/alerts/{}", notification.alert_id);
+        assert!(text.contains(&link));
+    }
+
+    #[tokio::test]
+    async fn unknown_target_is_invalid() {
+        let reply = json!({ "ok": false, "error": "channel_not_found" });
+        let (url, _) = mock_slack(StatusCode::OK, reply).await;
+        let notifier = SlackNotifier::new("xoxb-test").with_api_url(url);
+
+        let err = notifier.send_text("C404", "hello").await.unwrap_err();
+        assert!(matches!(err, NotifyError::InvalidTarget));
+    }
+
+    #[tokio::test]
+    async fn throttled_requests_are_rate_limited() {
+        let reply = json!({ "ok": false, "error": "ratelimited" });
+        let (url, _) = mock_slack(StatusCode::TOO_MANY_REQUESTS, reply).await;
+        let notifier = SlackNotifier::new("xoxb-test").with_api_url(url);
+
+        let err = notifier.send_text("U123", "hello").await.unwrap_err();
+        assert!(matches!(err, NotifyError::RateLimited));
+    }
+
+    #[tokio::test]
+    async fn other_api_errors_fail_delivery() {
+        let reply = json!({ "ok": false, "error": "invalid_auth" });
+        let (url, _) = mock_slack(StatusCode::OK, reply).await;
+        let notifier = SlackNotifier::new("xoxb-bad").with_api_url(url);
+
+        let err = notifier.send_text("U123", "hello").await.unwrap_err();
+        assert!(matches!(err, NotifyError::DeliveryFailed(e) if e == "invalid_auth"));
+    }
+}
@@ -1,112 +1,198 @@
+use std::sync::{Arc, Mutex};
+
 use chrono::{DateTime, Utc};
 
-use rouse_core::alert::{Alert, Fingerprint, Severity, Source};
-use rouse_core::events::{AlertDeduplicated, DomainEvent};
-use rouse_core::ids::{AlertId, UserId};
+use rouse_core::alert::{Alert, Fingerprint, PagedStep, Severity, Source, Status};
+use rouse_core::error::DomainError;
+use rouse_core::escalation::EscalationTarget;
+use rouse_core::events::{AlertSuppressed, DomainEvent};
+use rouse_core::ids::{AlertId, PolicyId, UserId};
+use rouse_core::routing::AfterSuppression;
 use rouse_ports::error::PortError;
-use rouse_ports::outbound::{AlertRepository, EscalationQueue, EventPublisher};
-use rouse_ports::types::RawAlert;
-
+use rouse_ports::outbound::{
+    AlertRepository, AuditLog, EscalationQueue, EscalationRepository, EventPublisher,
+    NotificationQueue, RouteRepository,
+};
+use rouse_ports::types::{
+    Actor, AlertFilter, AlertOccurrence, PendingEscalation, QueueStatus, RawAlert,
+};
+
+use crate::audit;
 use crate::error::AppError;
-use crate::router::AlertRouter;
+use crate::router::{AlertRouter, RouteMatch};
 
-pub struct AlertService<A, EQ, EP>
+pub struct AlertService<A, EQ, NQ, EP, AU, R, P>
 where
     A: AlertRepository,
     EQ: EscalationQueue,
+    NQ: NotificationQueue,
     EP: EventPublisher,
+    AU: AuditLog,
+    R: RouteRepository,
+    P: EscalationRepository,
 {
     alerts: A,
     escalation_queue: EQ,
+    notifications: NQ,
     events: EP,
-    router: AlertRouter,
+    audit: AU,
+    routes: R,
+    policies: P,
+    /// Compiled at the route revision it was loaded at.
+    router: Mutex<Option<(u64, Arc<AlertRouter>)>>,
+    history_limit: usize,
 }
 
-impl<A, EQ, EP> AlertService<A, EQ, EP>
+/// Payloads kept per alert unless `with_history_limit` says otherwise.
+pub const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+impl<A, EQ, NQ, EP, AU, R, P> AlertService<A, EQ, NQ, EP, AU, R, P>
 where
     A: AlertRepository,
     EQ: EscalationQueue,
+    NQ: NotificationQueue,
     EP: EventPublisher,
+    AU: AuditLog,
+    R: RouteRepository,
+    P: EscalationRepository,
 {
-    pub fn new(alerts: A, escalation_queue: EQ, events: EP, router: AlertRouter) -> Self {
+    pub fn new(
+        alerts: A,
+        escalation_queue: EQ,
+        notifications: NQ,
+        events: EP,
+        audit: AU,
+        routes: R,
+        policies: P,
+    ) -> Self {
         Self {
             alerts,
             escalation_queue,
+            notifications,
             events,
-            router,
+            audit,
+            routes,
+            policies,
+            router: Mutex::new(None),
+            history_limit: DEFAULT_HISTORY_LIMIT,
         }
     }
 
+    /// Keeps the newest `limit` received payloads per alert; 0 keeps none.
+    pub fn with_history_limit(mut self, limit: usize) -> Self {
+        self.history_limit = limit;
+        self
+    }
+
+    pub async fn get(&self, alert_id: &AlertId) -> Result<Alert, AppError> {
+        self.load(alert_id).await
+    }
+
+    /// The payloads received for an alert, newest first.
+    pub async fn occurrences(&self, alert_id: &AlertId) -> Result<Vec<AlertOccurrence>, AppError> {
+        Ok(self.alerts.occurrences(alert_id).await?)
+    }
+
+    /// One page of alerts matching `filter`, and how many match in total.
+    pub async fn list(&self, filter: &AlertFilter) -> Result<(Vec<Alert>, u64), AppError> {
+        let alerts = self.alerts.find_by_filter(filter).await?;
+        let total = self.alerts.count(filter).await?;
+        Ok((alerts, total))
+    }
+
+    /// How many alerts no route matched, the default policy's included.
+    pub async fn count_unrouted(&self) -> Result<u64, AppError> {
+        let filter = AlertFilter {
+            unrouted: true,
+            ..Default::default()
+        };
+        Ok(self.alerts.count(&filter).await?)
+    }
+
     pub async fn receive(&self, raw: RawAlert, now: DateTime<Utc>) -> Result<AlertId, AppError> {
         let labels = raw.labels.clone();
         let fingerprint = Fingerprint::from_labels(&labels);
 
-        // Source-initiated resolve
+        // The open alert with this fingerprint, if any
+        let open = self
+            .alerts
+            .find_open_by_fingerprint(fingerprint.as_str())
+            .await?;
+
+        // Source-initiated resolve; repeats land on the alert already resolved
         if raw.status.to_lowercase() == "resolved" {
-            let mut alert = self
-                .alerts
-                .find_by_fingerprint(fingerprint.as_str())
-                .await?
-                .ok_or(AppError::Port(PortError::NotFound))?;
+            let mut alert = match open {
+                Some(alert) => alert,
+                None => self
+                    .alerts
+                    .find_by_fingerprint(fingerprint.as_str())
+                    .await?
+                    .ok_or(AppError::Port(PortError::NotFound))?,
+            };
+            let before = alert.clone();
             let alert_id = alert.id().clone();
             let resolved_by = format!("source:{}", raw.source);
             let events = alert.resolve(resolved_by, now)?;
             if !events.is_empty() {
-                self.escalation_queue
-                    .cancel_for_alert(&alert_id.to_string())
-                    .await?;
+                self.stop_paging(&alert_id).await?;
                 self.alerts.save(&alert).await?;
                 self.events.publish(events).await?;
+                self.record(&Actor::system(), "alert.resolve", &before, &alert, now)
+                    .await?;
             }
             return Ok(alert_id);
         }
 
-        // Dedup check
-        if let Some(existing) = self
-            .alerts
-            .find_by_fingerprint(fingerprint.as_str())
-            .await?
-        {
-            let existing_id = existing.id().clone();
-            self.events
-                .publish(vec![DomainEvent::AlertDeduplicated(AlertDeduplicated {
-                    alert_id: existing_id.clone(),
-                    fingerprint: fingerprint.to_string(),
-                    occurred_at: now,
-                })])
-                .await?;
-            return Ok(existing_id);
+        let severity = parse_severity(&raw.severity);
+
+        // Dedup against the open alert; once resolved, it fires anew
+        if let Some(mut existing) = open {
+            let before = existing.clone();
+            let mut events = existing.record_occurrence(raw.summary.clone(), now);
+            events.extend(existing.change_severity(severity, now)?);
+            self.alerts.save(&existing).await?;
+            self.keep_payload(existing.id(), &raw, now).await?;
+            self.events.publish(events).await?;
+            if existing.severity() != before.severity() && existing.status() == Status::Firing {
+                self.reroute(&before, &existing, now).await?;
+            }
+            return Ok(existing.id().clone());
         }
 
-        // Parse severity
-        let severity = match raw.severity.to_lowercase().as_str() {
-            "critical" => Severity::Critical,
-            "warning" => Severity::Warning,
-            _ => Severity::Info,
-        };
-
         // Create alert
-        let (alert, creation_events) = Alert::new(
-            raw.external_id,
-            Source::new(raw.source),
+        let (mut alert, mut events) = Alert::new(
+            raw.external_id.clone(),
+            Source::new(raw.source.clone()),
             severity,
-            labels.clone(),
-            raw.summary,
+            labels,
+            raw.summary.clone(),
             now,
         );
+        let latest = self
+            .alerts
+            .find_by_fingerprint(fingerprint.as_str())
+            .await?;
+        if let Some(previous) = &latest {
+            alert.follow(previous);
+        }
         let alert_id = alert.id().clone();
 
+        // Route — flag it when only the default policy, or nothing, took it
+        let router = self.router().await?;
+        let matches = router.match_alert(&alert);
+        if matches.iter().all(|m| m.is_default()) {
+            let default_policy_id = matches.first().map(|m| m.policy_id.clone());
+            events.extend(alert.mark_unrouted(default_policy_id, now));
+        }
+
         // Save
         self.alerts.save(&alert).await?;
+        self.keep_payload(&alert_id, &raw, now).await?;
 
         // Publish creation events
-        self.events.publish(creation_events).await?;
+        self.events.publish(events).await?;
 
-        // Route — match labels to policy (best effort, no error if unmatched)
-        if let Some(_policy_id) = self.router.match_alert(&labels) {
-            // Escalation enqueuing will be handled when we have full policy resolution
-            // For now, the routing match is recorded
-        }
+        self.escalate(&alert_id, matches, now).await?;
 
         Ok(alert_id)
     }
@@ -115,13 +201,11 @@ where
         &self,
         alert_id: &AlertId,
         user_id: UserId,
+        actor: &Actor,
         now: DateTime<Utc>,
     ) -> Result<(), AppError> {
-        let mut alert = self
-            .alerts
-            .find_by_id(&alert_id.to_string())
-            .await?
-            .ok_or(AppError::Port(PortError::NotFound))?;
+        let mut alert = self.load(alert_id).await?;
+        let before = alert.clone();
 
         let events = alert.acknowledge(user_id, now)?;
 
@@ -130,11 +214,11 @@ where
         }
 
         // TODO: wrap cancel+save+publish in a transaction once adapter supports it
-        self.escalation_queue
-            .cancel_for_alert(&alert_id.to_string())
-            .await?;
+        self.stop_paging(alert_id).await?;
         self.alerts.save(&alert).await?;
         self.events.publish(events).await?;
+        self.record(actor, "alert.acknowledge", &before, &alert, now)
+            .await?;
 
         Ok(())
     }
@@ -143,13 +227,11 @@ where
         &self,
         alert_id: &AlertId,
         resolved_by: String,
+        actor: &Actor,
         now: DateTime<Utc>,
     ) -> Result<(), AppError> {
-        let mut alert = self
-            .alerts
-            .find_by_id(&alert_id.to_string())
-            .await?
-            .ok_or(AppError::Port(PortError::NotFound))?;
+        let mut alert = self.load(alert_id).await?;
+        let before = alert.clone();
 
         let events = alert.resolve(resolved_by, now)?;
 
@@ -157,33 +239,417 @@ where
             return Ok(());
         }
 
-        self.escalation_queue
-            .cancel_for_alert(&alert_id.to_string())
+        self.stop_paging(alert_id).await?;
+        self.alerts.save(&alert).await?;
+        self.events.publish(events).await?;
+        self.record(actor, "alert.resolve", &before, &alert, now)
             .await?;
+
+        Ok(())
+    }
+
+    /// Pages again from the start of every policy the alert matches.
+    async fn restart_escalation(&self, alert: &Alert, now: DateTime<Utc>) -> Result<(), AppError> {
+        let router = self.router().await?;
+        self.stop_paging(alert.id()).await?;
+        self.escalate(alert.id(), router.match_alert(alert), now)
+            .await
+    }
+
+    /// Starts every matched policy once, honouring suppression windows.
+    async fn escalate(
+        &self,
+        alert_id: &AlertId,
+        matches: Vec<RouteMatch>,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut started = Vec::new();
+        for matched in matches {
+            if started.contains(&matched.policy_id) {
+                continue;
+            }
+            let options = &matched.options;
+            let starts_at = if options.suppressed() {
+                None
+            } else {
+                match options.suppressed_until(now) {
+                    None => Some(now),
+                    Some(until) => match options.after_suppression.unwrap_or_default() {
+                        AfterSuppression::Drop => None,
+                        AfterSuppression::Escalate => Some(until),
+                    },
+                }
+            };
+            if starts_at != Some(now) {
+                self.events
+                    .publish(vec![DomainEvent::AlertSuppressed(AlertSuppressed {
+                        alert_id: alert_id.clone(),
+                        policy_id: matched.policy_id.clone(),
+                        escalates_at: starts_at,
+                        occurred_at: now,
+                    })])
+                    .await?;
+            }
+            if let Some(fires_at) = starts_at {
+                // Acknowledging or resolving cancels it; firing skips it if
+                // the alert is no longer firing by then.
+                self.queue_step(alert_id, matched.policy_id.clone(), (0, 0), None, fires_at)
+                    .await?;
+            }
+            started.push(matched.policy_id);
+        }
+        Ok(())
+    }
+
+    /// After a severity change, a firing alert starts over from the
+    /// policies it now matches if it got more severe or they differ.
+    async fn reroute(
+        &self,
+        before: &Alert,
+        after: &Alert,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let router = self.router().await?;
+        let policies = |matches: &[RouteMatch]| -> Vec<PolicyId> {
+            matches.iter().map(|m| m.policy_id.clone()).collect()
+        };
+        let matches = router.match_alert(after);
+        let moved = policies(&router.match_alert(before)) != policies(&matches);
+        if after.severity().rank() > before.severity().rank() || moved {
+            self.stop_paging(after.id()).await?;
+            self.escalate(after.id(), matches, now).await?;
+        }
+        Ok(())
+    }
+
+    /// Appends `raw` to the alert's bounded payload history.
+    async fn keep_payload(
+        &self,
+        alert_id: &AlertId,
+        raw: &RawAlert,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        if self.history_limit > 0 {
+            let occurrence = AlertOccurrence::new(alert_id.clone(), raw, now);
+            self.alerts
+                .record_occurrence(&occurrence, self.history_limit)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Acknowledges the alert until `until`; `wake_snoozed` pages again
+    /// after that.
+    pub async fn snooze(
+        &self,
+        alert_id: &AlertId,
+        user_id: UserId,
+        until: DateTime<Utc>,
+        actor: &Actor,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut alert = self.load(alert_id).await?;
+        let before = alert.clone();
+        let events = alert.snooze(user_id, until, now)?;
+
+        self.stop_paging(alert_id).await?;
         self.alerts.save(&alert).await?;
         self.events.publish(events).await?;
+        self.record(actor, "alert.snooze", &before, &alert, now)
+            .await
+    }
+
+    /// Takes back an acknowledgement or snooze and pages again.
+    pub async fn unacknowledge(
+        &self,
+        alert_id: &AlertId,
+        user_id: UserId,
+        actor: &Actor,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut alert = self.load(alert_id).await?;
+        let before = alert.clone();
+        let events = alert.unacknowledge(Some(user_id), now)?;
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        self.alerts.save(&alert).await?;
+        self.events.publish(events).await?;
+        self.restart_escalation(&alert, now).await?;
+        self.record(actor, "alert.unacknowledge", &before, &alert, now)
+            .await
+    }
+
+    /// Sets a resolved alert firing and pages again, unless the same
+    /// problem has fired since as a newer alert or is open as another one.
+    pub async fn reopen(
+        &self,
+        alert_id: &AlertId,
+        user_id: UserId,
+        actor: &Actor,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut alert = self.load(alert_id).await?;
+        let fingerprint = alert.fingerprint().as_str();
+        let latest = self.alerts.find_by_fingerprint(fingerprint).await?;
+        let open = self.alerts.find_open_by_fingerprint(fingerprint).await?;
+        if [latest, open].iter().flatten().any(|a| a.id() != alert_id) {
+            return Err(DomainError::AlertSuperseded.into());
+        }
+        let before = alert.clone();
+        let events = alert.reopen(user_id, now);
+        if events.is_empty() {
+            return Ok(());
+        }
 
+        self.alerts.save(&alert).await?;
+        self.events.publish(events).await?;
+        self.restart_escalation(&alert, now).await?;
+        self.record(actor, "alert.reopen", &before, &alert, now)
+            .await
+    }
+
+    /// Sets every alert whose snooze has run out firing again and pages
+    /// again. Returns how many woke up.
+    pub async fn wake_snoozed(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let due = self.alerts.find_snoozed_due(now).await?;
+        for mut alert in due.iter().cloned() {
+            let before = alert.clone();
+            let events = alert.wake(now);
+            self.alerts.save(&alert).await?;
+            self.events.publish(events).await?;
+            self.restart_escalation(&alert, now).await?;
+            self.record(&Actor::system(), "alert.wake", &before, &alert, now)
+                .await?;
+        }
+        Ok(due.len())
+    }
+
+    /// Sets every alert whose acknowledgement has run out firing again and
+    /// resumes each policy after the step that last paged. Returns how
+    /// many timed out.
+    pub async fn expire_acks(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let expired = self.alerts.find_ack_expired(now).await?;
+        for mut alert in expired.iter().cloned() {
+            let before = alert.clone();
+            let events = alert.expire_ack(now);
+            self.alerts.save(&alert).await?;
+            self.events.publish(events).await?;
+            for paged in alert.paged() {
+                let next = self.following(paged).await?;
+                self.queue_step(alert.id(), paged.policy_id.clone(), next, None, now)
+                    .await?;
+            }
+            self.record(&Actor::system(), "alert.ack_timeout", &before, &alert, now)
+                .await?;
+        }
+        Ok(expired.len())
+    }
+
+    /// Hands the alert over to `target` and pages it now, through the
+    /// channels of the step that last paged. Pending steps are cancelled
+    /// and escalation stops there.
+    pub async fn reassign(
+        &self,
+        alert_id: &AlertId,
+        target: EscalationTarget,
+        user_id: UserId,
+        actor: &Actor,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut alert = self.load(alert_id).await?;
+        let (policy_id, position) = match alert.paged().last() {
+            Some(paged) => (
+                paged.policy_id.clone(),
+                (paged.step_order, paged.repetition),
+            ),
+            None => {
+                let policy_id = self.matched_policies(&alert).await?.into_iter().next();
+                let policy_id = policy_id.ok_or_else(|| {
+                    AppError::Routing("no escalation policy takes this alert".into())
+                })?;
+                (policy_id, (0, 0))
+            }
+        };
+        let before = alert.clone();
+        let events = alert.reassign(target.clone(), user_id, now)?;
+
+        self.stop_paging(alert_id).await?;
+        self.alerts.save(&alert).await?;
+        self.events.publish(events).await?;
+        self.queue_step(alert_id, policy_id, position, Some(target), now)
+            .await?;
+        self.record(actor, "alert.reassign", &before, &alert, now)
+            .await
+    }
+
+    /// Pages the next step of every policy now rather than after its
+    /// wait, or the first step if none has paged yet. Pending steps are
+    /// cancelled; a policy with no step left pages its last one again.
+    pub async fn escalate_now(
+        &self,
+        alert_id: &AlertId,
+        user_id: UserId,
+        actor: &Actor,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut alert = self.load(alert_id).await?;
+        let mut next: Vec<(PolicyId, (u32, u32))> = Vec::new();
+        if alert.paged().is_empty() {
+            let policies = self.matched_policies(&alert).await?;
+            next.extend(policies.into_iter().map(|p| (p, (0, 0))));
+        }
+        for paged in alert.paged() {
+            next.push((paged.policy_id.clone(), self.following(paged).await?));
+        }
+        let before = alert.clone();
+        let events = alert.unacknowledge(Some(user_id), now)?;
+        if next.is_empty() {
+            return Err(AppError::Routing(
+                "no escalation policy takes this alert".into(),
+            ));
+        }
+
+        self.stop_paging(alert_id).await?;
+        self.alerts.save(&alert).await?;
+        self.events.publish(events).await?;
+        for (policy_id, position) in next {
+            self.queue_step(alert_id, policy_id, position, None, now)
+                .await?;
+        }
+        self.record(actor, "alert.escalate", &before, &alert, now)
+            .await
+    }
+
+    /// Where the policy goes after `paged`, repeats included. With no
+    /// step left, or no policy, the step that paged pages again.
+    async fn following(&self, paged: &PagedStep) -> Result<(u32, u32), AppError> {
+        let policy = self
+            .policies
+            .find_by_id(&paged.policy_id.to_string())
+            .await?;
+        let next = policy.and_then(|p| p.next_position(paged.step_order, paged.repetition));
+        Ok(next.unwrap_or((paged.step_order, paged.repetition)))
+    }
+
+    /// The stored routes, compiled again only after they change.
+    async fn router(&self) -> Result<Arc<AlertRouter>, AppError> {
+        let revision = self.routes.revision().await?;
+        if let Some((cached, router)) = &*self.router.lock().unwrap() {
+            if *cached == revision {
+                return Ok(router.clone());
+            }
+        }
+        let router = Arc::new(AlertRouter::load(&self.routes).await?);
+        *self.router.lock().unwrap() = Some((revision, router.clone()));
+        Ok(router)
+    }
+
+    /// The policies the alert's routes send it to, first match first.
+    async fn matched_policies(&self, alert: &Alert) -> Result<Vec<PolicyId>, AppError> {
+        let router = self.router().await?;
+        let mut policies = Vec::new();
+        for matched in router.match_alert(alert) {
+            if !policies.contains(&matched.policy_id) {
+                policies.push(matched.policy_id);
+            }
+        }
+        Ok(policies)
+    }
+
+    /// The escalation worker pages the step at `(step_order, repetition)`
+    /// once `fires_at` comes.
+    async fn queue_step(
+        &self,
+        alert_id: &AlertId,
+        policy_id: PolicyId,
+        (step_order, repetition): (u32, u32),
+        target: Option<EscalationTarget>,
+        fires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        self.escalation_queue
+            .enqueue_step(PendingEscalation {
+                id: uuid::Uuid::new_v4().to_string(),
+                alert_id: alert_id.clone(),
+                policy_id,
+                step_order,
+                repetition,
+                fires_at,
+                status: QueueStatus::Pending,
+                target,
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn load(&self, alert_id: &AlertId) -> Result<Alert, AppError> {
+        self.alerts
+            .find_by_id(&alert_id.to_string())
+            .await?
+            .ok_or(AppError::Port(PortError::NotFound))
+    }
+
+    async fn record(
+        &self,
+        actor: &Actor,
+        action: &str,
+        before: &Alert,
+        after: &Alert,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let entry = audit::entry(
+            actor,
+            action,
+            format!("alert:{}", after.id()),
+            Some(before),
+            Some(after),
+            now,
+        )?;
+        self.audit.append(&entry).await?;
+        Ok(())
+    }
+
+    /// Cancels pending escalation steps and undelivered notifications.
+    async fn stop_paging(&self, alert_id: &AlertId) -> Result<(), AppError> {
+        let id = alert_id.to_string();
+        self.escalation_queue.cancel_for_alert(&id).await?;
+        self.notifications.cancel_for_alert(&id).await?;
         Ok(())
     }
 }
 
+fn parse_severity(raw: &str) -> Severity {
+    match raw.to_lowercase().as_str() {
+        "critical" => Severity::Critical,
+        "warning" => Severity::Warning,
+        _ => Severity::Info,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audit::tests::MockAuditLog;
+    use crate::escalation_service::tests::MockPolicyRepo;
     use async_trait::async_trait;
     use rouse_core::alert::{Alert, Status};
+    use rouse_core::channel::Channel;
     use rouse_core::error::DomainError;
+    use rouse_core::escalation::{EscalationPolicy, EscalationStep};
     use rouse_core::events::DomainEvent;
+    use rouse_core::ids::PolicyId;
+    use rouse_core::routing::{Matcher, Route};
     use rouse_ports::error::PortError;
     use rouse_ports::types::*;
     use std::collections::BTreeMap;
-    use std::sync::Mutex;
 
     // --- Mock Adapters ---
 
     #[derive(Default)]
     struct MockAlertRepo {
         alerts: Mutex<Vec<Alert>>,
+        occurrences: Mutex<Vec<AlertOccurrence>>,
     }
 
     #[async_trait]
@@ -205,22 +671,111 @@ mod tests {
             let alerts = self.alerts.lock().unwrap();
             Ok(alerts
                 .iter()
+                .rev()
                 .find(|a| a.fingerprint().as_str() == fp)
                 .cloned())
         }
-        async fn find_by_filter(&self, _filter: &AlertFilter) -> Result<Vec<Alert>, PortError> {
-            Ok(vec![])
+        async fn find_open_by_fingerprint(&self, fp: &str) -> Result<Option<Alert>, PortError> {
+            let alerts = self.alerts.lock().unwrap();
+            Ok(alerts
+                .iter()
+                .rev()
+                .find(|a| a.fingerprint().as_str() == fp && a.status() != Status::Resolved)
+                .cloned())
+        }
+        async fn find_by_filter(&self, filter: &AlertFilter) -> Result<Vec<Alert>, PortError> {
+            let alerts = self.alerts.lock().unwrap();
+            Ok(alerts
+                .iter()
+                .filter(|a| !filter.unrouted || a.is_unrouted())
+                .cloned()
+                .collect())
+        }
+        async fn find_snoozed_due(&self, now: DateTime<Utc>) -> Result<Vec<Alert>, PortError> {
+            let alerts = self.alerts.lock().unwrap();
+            Ok(alerts
+                .iter()
+                .filter(|a| matches!(a.status(), Status::Snoozed { until } if until <= now))
+                .cloned()
+                .collect())
+        }
+        async fn find_ack_expired(&self, now: DateTime<Utc>) -> Result<Vec<Alert>, PortError> {
+            let alerts = self.alerts.lock().unwrap();
+            Ok(alerts
+                .iter()
+                .filter(|a| a.status() == Status::Acknowledged)
+                .filter(|a| a.ack_expires_at().is_some_and(|at| at <= now))
+                .cloned()
+                .collect())
+        }
+        async fn count(&self, filter: &AlertFilter) -> Result<u64, PortError> {
+            Ok(self.find_by_filter(filter).await?.len() as u64)
+        }
+        async fn record_occurrence(
+            &self,
+            occurrence: &AlertOccurrence,
+            keep: usize,
+        ) -> Result<(), PortError> {
+            let mut occurrences = self.occurrences.lock().unwrap();
+            occurrences.push(occurrence.clone());
+            let alert_id = &occurrence.alert_id;
+            while occurrences
+                .iter()
+                .filter(|o| &o.alert_id == alert_id)
+                .count()
+                > keep
+            {
+                let oldest = occurrences
+                    .iter()
+                    .position(|o| &o.alert_id == alert_id)
+                    .unwrap();
+                occurrences.remove(oldest);
+            }
+            Ok(())
+        }
+        async fn occurrences(&self, alert_id: &AlertId) -> Result<Vec<AlertOccurrence>, PortError> {
+            let occurrences = self.occurrences.lock().unwrap();
+            Ok(occurrences
+                .iter()
+                .rev()
+                .filter(|o| &o.alert_id == alert_id)
+                .cloned()
+                .collect())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockRouteRepo {
+        routes: Vec<Route>,
+        default_policy: Option<PolicyId>,
+        revision: Mutex<u64>,
+        loads: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl RouteRepository for MockRouteRepo {
+        async fn list_all(&self) -> Result<Vec<Route>, PortError> {
+            *self.loads.lock().unwrap() += 1;
+            Ok(self.routes.clone())
+        }
+        async fn default_policy(&self) -> Result<Option<PolicyId>, PortError> {
+            Ok(self.default_policy.clone())
+        }
+        async fn revision(&self) -> Result<u64, PortError> {
+            Ok(*self.revision.lock().unwrap())
         }
     }
 
     #[derive(Default)]
     struct MockEscalationQueue {
+        enqueued: Mutex<Vec<PendingEscalation>>,
         cancelled: Mutex<Vec<String>>,
     }
 
     #[async_trait]
     impl EscalationQueue for MockEscalationQueue {
-        async fn enqueue_step(&self, _step: PendingEscalation) -> Result<(), PortError> {
+        async fn enqueue_step(&self, step: PendingEscalation) -> Result<(), PortError> {
+            self.enqueued.lock().unwrap().push(step);
             Ok(())
         }
         async fn poll_due(&self) -> Result<Vec<PendingEscalation>, PortError> {
@@ -235,6 +790,39 @@ mod tests {
         }
     }
 
+    #[derive(Default)]
+    struct MockNotificationQueue {
+        cancelled: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl NotificationQueue for MockNotificationQueue {
+        async fn enqueue(&self, _notification: PendingNotification) -> Result<(), PortError> {
+            Ok(())
+        }
+        async fn poll_pending(&self) -> Result<Vec<PendingNotification>, PortError> {
+            Ok(vec![])
+        }
+        async fn mark_sent(&self, _id: &str) -> Result<(), PortError> {
+            Ok(())
+        }
+        async fn mark_failed(
+            &self,
+            _id: &str,
+            _error: &str,
+            _next_attempt: DateTime<Utc>,
+        ) -> Result<(), PortError> {
+            Ok(())
+        }
+        async fn mark_dead(&self, _id: &str) -> Result<(), PortError> {
+            Ok(())
+        }
+        async fn cancel_for_alert(&self, alert_id: &str) -> Result<(), PortError> {
+            self.cancelled.lock().unwrap().push(alert_id.to_string());
+            Ok(())
+        }
+    }
+
     #[derive(Default)]
     struct MockEventPublisher {
         events: Mutex<Vec<DomainEvent>>,
@@ -254,6 +842,14 @@ mod tests {
             .with_timezone(&Utc)
     }
 
+    fn operator() -> Actor {
+        Actor {
+            user_id: Some(UserId::new()),
+            source: rouse_ports::types::AuditSource::Slack,
+            ip: None,
+        }
+    }
+
     fn make_raw_alert(service: &str) -> RawAlert {
         RawAlert {
             external_id: "ext-1".into(),
@@ -265,15 +861,57 @@ mod tests {
         }
     }
 
-    fn make_service() -> AlertService<MockAlertRepo, MockEscalationQueue, MockEventPublisher> {
+    type TestService = AlertService<
+        MockAlertRepo,
+        MockEscalationQueue,
+        MockNotificationQueue,
+        MockEventPublisher,
+        MockAuditLog,
+        MockRouteRepo,
+        MockPolicyRepo,
+    >;
+
+    fn make_service_with_repo(routes: MockRouteRepo) -> TestService {
         AlertService::new(
             MockAlertRepo::default(),
             MockEscalationQueue::default(),
+            MockNotificationQueue::default(),
             MockEventPublisher::default(),
-            AlertRouter::new(vec![]),
+            MockAuditLog::default(),
+            routes,
+            MockPolicyRepo::default(),
         )
     }
 
+    fn make_service_with_routes(routes: Vec<Route>) -> TestService {
+        make_service_with_repo(MockRouteRepo {
+            routes,
+            ..Default::default()
+        })
+    }
+
+    /// A policy of `steps` steps, stored for the service to find.
+    fn add_policy(svc: &TestService, steps: u32, repeat_count: u32) -> PolicyId {
+        let steps = (0..steps)
+            .map(|order| {
+                EscalationStep::new(
+                    order,
+                    600,
+                    vec![EscalationTarget::User(UserId::new())],
+                    vec![Channel::Slack],
+                )
+            })
+            .collect();
+        let policy = EscalationPolicy::new("primary".into(), steps, repeat_count).unwrap();
+        let id = policy.id().clone();
+        svc.policies.policies.lock().unwrap().push(policy);
+        id
+    }
+
+    fn make_service() -> TestService {
+        make_service_with_routes(vec![])
+    }
+
     #[tokio::test]
     async fn receive_new_alert_saves_and_publishes_event() {
         let svc = make_service();
@@ -287,7 +925,7 @@ mod tests {
         assert_eq!(alerts[0].status(), Status::Firing);
 
         let events = svc.events.events.lock().unwrap();
-        assert_eq!(events.len(), 1);
+        assert_eq!(events.len(), 2); // no routes, so also AlertUnrouted
         assert_eq!(events[0].event_type(), "alert.received");
     }
 
@@ -304,31 +942,551 @@ mod tests {
 
         let alerts = svc.alerts.alerts.lock().unwrap();
         assert_eq!(alerts.len(), 1); // only one saved
+        assert_eq!(alerts[0].occurrence_count(), 2);
 
         let events = svc.events.events.lock().unwrap();
-        assert_eq!(events.len(), 2); // AlertReceived + AlertDeduplicated
-        assert_eq!(events[1].event_type(), "alert.deduplicated");
+        assert_eq!(events.len(), 3); // AlertReceived + AlertUnrouted + AlertDeduplicated
+        assert_eq!(events[2].event_type(), "alert.deduplicated");
     }
 
     #[tokio::test]
-    async fn receive_no_matching_policy_saved_not_routed() {
-        use crate::router::Route;
+    async fn receive_keeps_a_bounded_payload_history() {
+        let svc = make_service().with_history_limit(3);
+        let mut alert_id = None;
+        for minute in 0..5 {
+            let mut raw = make_raw_alert("api");
+            raw.summary = format!("High CPU ({minute})");
+            let at = now() + chrono::Duration::minutes(minute);
+            alert_id = Some(svc.receive(raw, at).await.unwrap());
+        }
+        let alert_id = alert_id.unwrap();
 
-        let svc = AlertService::new(
-            MockAlertRepo::default(),
-            MockEscalationQueue::default(),
-            MockEventPublisher::default(),
-            AlertRouter::new(vec![Route {
-                matchers: BTreeMap::from([("service".into(), "web".into())]),
-                policy_id: rouse_core::ids::PolicyId::new(),
-            }]),
+        let alert = svc
+            .alerts
+            .find_by_id(&alert_id.to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(alert.occurrence_count(), 5);
+        assert_eq!(alert.summary(), "High CPU (4)");
+        assert_eq!(alert.last_seen_at(), now() + chrono::Duration::minutes(4));
+
+        let history = svc.occurrences(&alert_id).await.unwrap();
+        let summaries: Vec<_> = history.iter().map(|o| o.summary.as_str()).collect();
+        assert_eq!(summaries, ["High CPU (4)", "High CPU (3)", "High CPU (2)"]);
+
+        let quiet = make_service().with_history_limit(0);
+        let id = quiet.receive(make_raw_alert("api"), now()).await.unwrap();
+        assert!(quiet.occurrences(&id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn receive_at_higher_severity_restarts_escalation_on_the_new_route() {
+        let (critical, rest) = (PolicyId::new(), PolicyId::new());
+        let svc = make_service_with_routes(vec![
+            Route::new(
+                vec!["severity>=critical".parse().unwrap()],
+                critical.clone(),
+            ),
+            Route::new(vec![], rest.clone()),
+        ]);
+        let mut raw = make_raw_alert("api");
+        raw.severity = "warning".into();
+        let alert_id = svc.receive(raw.clone(), now()).await.unwrap();
+
+        // Down from warning to info: still `rest`, so escalation carries on.
+        raw.severity = "info".into();
+        svc.receive(raw.clone(), now()).await.unwrap();
+        assert!(svc.escalation_queue.cancelled.lock().unwrap().is_empty());
+
+        raw.severity = "critical".into();
+        svc.receive(raw, now()).await.unwrap();
+
+        let alerts = svc.alerts.alerts.lock().unwrap();
+        assert_eq!(alerts[0].severity(), Severity::Critical);
+        let cancelled = svc.escalation_queue.cancelled.lock().unwrap();
+        assert_eq!(*cancelled, [alert_id.to_string()]);
+        let enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+        let policies: Vec<_> = enqueued.iter().map(|e| &e.policy_id).collect();
+        assert_eq!(policies, [&rest, &critical]);
+
+        let events = svc.events.events.lock().unwrap();
+        let changes: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                DomainEvent::AlertSeverityChanged(c) => Some((c.from, c.to)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (Severity::Warning, Severity::Info),
+                (Severity::Info, Severity::Critical)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn snoozed_alert_pages_again_once_it_wakes() {
+        let policy_id = PolicyId::new();
+        let svc = make_service_with_routes(vec![Route::new(vec![], policy_id)]);
+        let alert_id = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        let until = now() + chrono::Duration::minutes(30);
+        let user_id = UserId::new();
+
+        svc.snooze(&alert_id, user_id, until, &operator(), now())
+            .await
+            .unwrap();
+        assert_eq!(svc.escalation_queue.cancelled.lock().unwrap().len(), 1);
+        assert_eq!(svc.wake_snoozed(now()).await.unwrap(), 0);
+        assert_eq!(svc.wake_snoozed(until).await.unwrap(), 1);
+
+        let alert = svc.load(&alert_id).await.unwrap();
+        assert_eq!(alert.status(), Status::Firing);
+        let enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+        assert_eq!(enqueued.len(), 2);
+        assert_eq!(enqueued[1].fires_at, until);
+
+        let entries = svc.audit.entries.lock().unwrap();
+        let actions: Vec<_> = entries.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(actions, ["alert.snooze", "alert.wake"]);
+    }
+
+    #[tokio::test]
+    async fn expired_ack_repeats_or_pages_the_last_step_again() {
+        let svc = make_service();
+        let once = add_policy(&svc, 1, 0);
+        let repeating = add_policy(&svc, 1, 2);
+        let alert_id = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        let mut alert = svc.load(&alert_id).await.unwrap();
+        alert.record_page(once.clone(), 0, 0, Some(600));
+        alert.record_page(repeating.clone(), 0, 1, Some(600));
+        svc.alerts.save(&alert).await.unwrap();
+        svc.acknowledge(&alert_id, UserId::new(), &operator(), now())
+            .await
+            .unwrap();
+
+        let expires = now() + chrono::Duration::minutes(10);
+        assert_eq!(svc.expire_acks(expires).await.unwrap(), 1);
+
+        let enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+        let resumed: Vec<_> = enqueued
+            .iter()
+            .filter(|e| e.fires_at == expires)
+            .map(|e| (&e.policy_id, e.step_order, e.repetition))
+            .collect();
+        assert_eq!(resumed, [(&once, 0, 0), (&repeating, 0, 2)]);
+    }
+
+    #[tokio::test]
+    async fn expired_ack_resumes_after_the_acknowledgers_step() {
+        let svc = make_service();
+        let policy_id = add_policy(&svc, 3, 0);
+        let alert_id = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        // Step 1 paged the acknowledger, as the escalation service records.
+        let mut alert = svc.load(&alert_id).await.unwrap();
+        alert.record_page(policy_id.clone(), 1, 0, Some(600));
+        svc.alerts.save(&alert).await.unwrap();
+        let user_id = UserId::new();
+        svc.acknowledge(&alert_id, user_id.clone(), &operator(), now())
+            .await
+            .unwrap();
+
+        let expires = now() + chrono::Duration::minutes(10);
+        assert_eq!(svc.expire_acks(now()).await.unwrap(), 0);
+        assert_eq!(svc.expire_acks(expires).await.unwrap(), 1);
+
+        let alert = svc.load(&alert_id).await.unwrap();
+        assert_eq!(alert.status(), Status::Firing);
+        let enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+        let resumed = enqueued.last().unwrap();
+        assert_eq!(resumed.policy_id, policy_id);
+        assert_eq!(resumed.step_order, 2);
+        assert_eq!(resumed.fires_at, expires);
+        let events = svc.events.events.lock().unwrap();
+        match events.last().unwrap() {
+            DomainEvent::AlertAckTimedOut(e) => assert_eq!(e.acknowledged_by, Some(user_id)),
+            other => panic!("expected AlertAckTimedOut, got {other:?}"),
+        }
+        let entries = svc.audit.entries.lock().unwrap();
+        assert_eq!(entries.last().unwrap().action, "alert.ack_timeout");
+    }
+
+    #[tokio::test]
+    async fn reassign_hands_the_alert_over_now() {
+        let policy_id = PolicyId::new();
+        let svc = make_service_with_routes(vec![Route::new(vec![], policy_id.clone())]);
+        let alert_id = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        let user_id = UserId::new();
+        svc.acknowledge(&alert_id, user_id.clone(), &operator(), now())
+            .await
+            .unwrap();
+        let team = EscalationTarget::Team(rouse_core::ids::TeamId::new());
+
+        svc.reassign(&alert_id, team.clone(), user_id, &operator(), now())
+            .await
+            .unwrap();
+
+        let alert = svc.load(&alert_id).await.unwrap();
+        assert_eq!(alert.status(), Status::Firing);
+        assert_eq!(alert.reassigned_to(), Some(&team));
+        let events = svc.events.events.lock().unwrap();
+        match events.last().unwrap() {
+            DomainEvent::AlertReassigned(e) => assert_eq!(e.target, team),
+            other => panic!("expected AlertReassigned, got {other:?}"),
+        }
+        assert_eq!(svc.escalation_queue.cancelled.lock().unwrap().len(), 2);
+        let enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+        let handed = enqueued.last().unwrap();
+        assert_eq!(handed.policy_id, policy_id);
+        assert_eq!(handed.step_order, 0);
+        assert_eq!(handed.target, Some(team));
+        assert_eq!(handed.fires_at, now());
+        let entries = svc.audit.entries.lock().unwrap();
+        assert_eq!(entries.last().unwrap().action, "alert.reassign");
+    }
+
+    #[tokio::test]
+    async fn reassign_needs_a_policy_to_page_through() {
+        let svc = make_service();
+        let alert_id = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        let team = EscalationTarget::Team(rouse_core::ids::TeamId::new());
+
+        let result = svc
+            .reassign(&alert_id, team, UserId::new(), &operator(), now())
+            .await;
+
+        assert!(matches!(result, Err(AppError::Routing(_))));
+    }
+
+    #[tokio::test]
+    async fn escalate_now_pages_the_next_step() {
+        let svc = make_service();
+        let policy_id = add_policy(&svc, 2, 0);
+        let alert_id = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        let mut alert = svc.load(&alert_id).await.unwrap();
+        alert.record_page(policy_id.clone(), 0, 0, None);
+        svc.alerts.save(&alert).await.unwrap();
+
+        svc.escalate_now(&alert_id, UserId::new(), &operator(), now())
+            .await
+            .unwrap();
+
+        assert_eq!(svc.escalation_queue.cancelled.lock().unwrap().len(), 1);
+        let enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+        let next = enqueued.last().unwrap();
+        assert_eq!(next.policy_id, policy_id);
+        assert_eq!(next.step_order, 1);
+        assert_eq!(next.target, None);
+        assert_eq!(next.fires_at, now());
+        let entries = svc.audit.entries.lock().unwrap();
+        assert_eq!(entries.last().unwrap().action, "alert.escalate");
+    }
+
+    #[tokio::test]
+    async fn escalate_now_past_the_last_step_repeats_or_pages_it_again() {
+        let svc = make_service();
+        let once = add_policy(&svc, 1, 0);
+        let repeating = add_policy(&svc, 1, 1);
+        let alert_id = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        let mut alert = svc.load(&alert_id).await.unwrap();
+        alert.record_page(once.clone(), 0, 0, None);
+        alert.record_page(repeating.clone(), 0, 0, None);
+        svc.alerts.save(&alert).await.unwrap();
+
+        svc.escalate_now(&alert_id, UserId::new(), &operator(), now())
+            .await
+            .unwrap();
+
+        let enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+        let paged: Vec<_> = enqueued
+            .iter()
+            .map(|e| (&e.policy_id, e.step_order, e.repetition))
+            .collect();
+        assert_eq!(paged, [(&once, 0, 0), (&repeating, 0, 1)]);
+    }
+
+    #[tokio::test]
+    async fn escalate_now_without_a_policy_fails() {
+        let svc = make_service();
+        let alert_id = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        let audited = svc.audit.entries.lock().unwrap().len();
+
+        let result = svc
+            .escalate_now(&alert_id, UserId::new(), &operator(), now())
+            .await;
+
+        assert!(matches!(result, Err(AppError::Routing(_))));
+        assert!(svc.escalation_queue.enqueued.lock().unwrap().is_empty());
+        assert_eq!(svc.audit.entries.lock().unwrap().len(), audited);
+    }
+
+    #[tokio::test]
+    async fn escalate_now_of_resolved_alert_fails() {
+        let svc = make_service();
+        let alert_id = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        svc.resolve(&alert_id, "operator".into(), &operator(), now())
+            .await
+            .unwrap();
+
+        let result = svc
+            .escalate_now(&alert_id, UserId::new(), &operator(), now())
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::AlertAlreadyResolved))
+        ));
+    }
+
+    #[tokio::test]
+    async fn unacknowledge_pages_again() {
+        let svc = make_service_with_routes(vec![Route::new(vec![], PolicyId::new())]);
+        let alert_id = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        let user_id = UserId::new();
+        svc.acknowledge(&alert_id, user_id.clone(), &operator(), now())
+            .await
+            .unwrap();
+
+        svc.unacknowledge(&alert_id, user_id, &operator(), now())
+            .await
+            .unwrap();
+
+        let alert = svc.load(&alert_id).await.unwrap();
+        assert_eq!(alert.status(), Status::Firing);
+        assert_eq!(svc.escalation_queue.enqueued.lock().unwrap().len(), 2);
+        let events = svc.events.events.lock().unwrap();
+        assert_eq!(events.last().unwrap().event_type(), "alert.unacknowledged");
+    }
+
+    #[tokio::test]
+    async fn reopen_refuses_when_a_newer_alert_is_open() {
+        let svc = make_service();
+        let first = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        svc.resolve(&first, "operator".into(), &operator(), now())
+            .await
+            .unwrap();
+
+        svc.reopen(&first, UserId::new(), &operator(), now())
+            .await
+            .unwrap();
+        assert_eq!(svc.load(&first).await.unwrap().status(), Status::Firing);
+
+        svc.resolve(&first, "operator".into(), &operator(), now())
+            .await
+            .unwrap();
+        svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        let result = svc.reopen(&first, UserId::new(), &operator(), now()).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::AlertSuperseded))
+        ));
+    }
+
+    #[tokio::test]
+    async fn reopen_refuses_once_a_newer_alert_exists() {
+        let svc = make_service();
+        let first = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        svc.resolve(&first, "operator".into(), &operator(), now())
+            .await
+            .unwrap();
+        let second = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        svc.resolve(&second, "operator".into(), &operator(), now())
+            .await
+            .unwrap();
+
+        // Both are resolved, but only the newest may be reopened.
+        let result = svc.reopen(&first, UserId::new(), &operator(), now()).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::AlertSuperseded))
+        ));
+        assert_eq!(svc.load(&first).await.unwrap().status(), Status::Resolved);
+        svc.reopen(&second, UserId::new(), &operator(), now())
+            .await
+            .unwrap();
+        assert_eq!(svc.load(&second).await.unwrap().status(), Status::Firing);
+    }
+
+    #[tokio::test]
+    async fn receive_after_resolve_opens_a_linked_alert() {
+        let svc = make_service();
+        let first = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        svc.resolve(&first, "operator".into(), &operator(), now())
+            .await
+            .unwrap();
+
+        let later = now() + chrono::Duration::days(7);
+        let second = svc.receive(make_raw_alert("api"), later).await.unwrap();
+        assert_ne!(first, second);
+        assert_eq!(
+            svc.receive(make_raw_alert("api"), later).await.unwrap(),
+            second
         );
+
+        let alerts = svc.alerts.alerts.lock().unwrap();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[1].status(), Status::Firing);
+        assert_eq!(alerts[1].previous_alert_id(), Some(&first));
+        assert_eq!(alerts[1].occurrence_count(), 2);
+        assert_eq!(alerts[0].occurrence_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn receive_no_matching_policy_saved_not_routed() {
+        let svc = make_service_with_routes(vec![Route::new(
+            vec![Matcher::equal("service", "web")],
+            PolicyId::new(),
+        )]);
         let raw = make_raw_alert("api"); // won't match "web"
 
         svc.receive(raw, now()).await.unwrap();
 
         let alerts = svc.alerts.alerts.lock().unwrap();
         assert_eq!(alerts.len(), 1); // alert still saved
+        assert!(alerts[0].is_unrouted());
+        assert!(svc.escalation_queue.enqueued.lock().unwrap().is_empty());
+
+        let events = svc.events.events.lock().unwrap();
+        assert_eq!(events[1].event_type(), "alert.unrouted");
+    }
+
+    #[tokio::test]
+    async fn receive_unmatched_alert_escalates_to_default_policy() {
+        let fallback = PolicyId::new();
+        let svc = make_service_with_repo(MockRouteRepo {
+            routes: vec![Route::new(
+                vec![Matcher::equal("service", "web")],
+                PolicyId::new(),
+            )],
+            default_policy: Some(fallback.clone()),
+            ..Default::default()
+        });
+
+        svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        svc.receive(make_raw_alert("web"), now()).await.unwrap();
+        assert_eq!(svc.count_unrouted().await.unwrap(), 1);
+
+        let enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+        assert_eq!(enqueued.len(), 2);
+        assert_eq!(enqueued[0].policy_id, fallback);
+
+        let events = svc.events.events.lock().unwrap();
+        match &events[1] {
+            DomainEvent::AlertUnrouted(e) => {
+                assert_eq!(e.labels["service"], "api");
+                assert_eq!(e.default_policy_id, Some(fallback.clone()));
+            }
+            other => panic!("expected AlertUnrouted, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn receive_routed_alert_enqueues_first_step() {
+        let policy_id = PolicyId::new();
+        let svc = make_service_with_routes(vec![Route::new(
+            vec![Matcher::equal("service", "api")],
+            policy_id.clone(),
+        )]);
+
+        let alert_id = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+
+        let enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+        assert_eq!(enqueued.len(), 1);
+        assert_eq!(enqueued[0].alert_id, alert_id);
+        assert_eq!(enqueued[0].policy_id, policy_id);
+        assert_eq!(enqueued[0].step_order, 0);
+        assert_eq!(enqueued[0].fires_at, now());
+    }
+
+    #[tokio::test]
+    async fn router_is_compiled_again_only_after_routes_change() {
+        let svc = make_service_with_routes(vec![Route::new(vec![], PolicyId::new())]);
+
+        svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        svc.receive(make_raw_alert("web"), now()).await.unwrap();
+        assert_eq!(*svc.routes.loads.lock().unwrap(), 1);
+
+        *svc.routes.revision.lock().unwrap() += 1;
+        svc.receive(make_raw_alert("db"), now()).await.unwrap();
+        assert_eq!(*svc.routes.loads.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn receive_fans_out_to_each_matched_policy_once() {
+        use rouse_core::ids::PolicyId;
+
+        let (first, second) = (PolicyId::new(), PolicyId::new());
+        let continuing = |policy_id: &PolicyId| Route {
+            continue_matching: true,
+            ..Route::new(vec![Matcher::equal("service", "api")], policy_id.clone())
+        };
+        let mut muted = Route::new(vec![], PolicyId::new());
+        muted.options.suppress = Some(true);
+        let svc = make_service_with_routes(vec![
+            continuing(&first),
+            continuing(&second),
+            continuing(&first),
+            muted,
+        ]);
+
+        svc.receive(make_raw_alert("api"), now()).await.unwrap();
+
+        let enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+        let policies: Vec<_> = enqueued.iter().map(|e| &e.policy_id).collect();
+        assert_eq!(policies, [&first, &second]);
+    }
+
+    #[tokio::test]
+    async fn receive_during_suppression_window_drops_or_defers_escalation() {
+        use chrono::{NaiveTime, Weekday};
+        use rouse_core::routing::{RouteOptions, SuppressionWindow};
+
+        let hm = |h| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+        // now() is a Wednesday at 10:00 UTC.
+        let office_hours = |after| RouteOptions {
+            suppress_hours: Some(vec![SuppressionWindow::new(
+                chrono_tz::UTC,
+                vec![Weekday::Wed],
+                hm(9),
+                hm(17),
+            )]),
+            after_suppression: Some(after),
+            ..Default::default()
+        };
+        let (dropped, deferred) = (PolicyId::new(), PolicyId::new());
+        let mut routes = vec![
+            Route::new(vec![], dropped.clone()),
+            Route::new(vec![], deferred.clone()),
+        ];
+        routes[0].continue_matching = true;
+        routes[0].options = office_hours(AfterSuppression::Drop);
+        routes[1].options = office_hours(AfterSuppression::Escalate);
+        let svc = make_service_with_routes(routes);
+
+        svc.receive(make_raw_alert("api"), now()).await.unwrap();
+
+        let enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+        assert_eq!(enqueued.len(), 1);
+        assert_eq!(enqueued[0].policy_id, deferred);
+        assert_eq!(enqueued[0].fires_at, now() + chrono::Duration::hours(7));
+
+        let events = svc.events.events.lock().unwrap();
+        let suppressed: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                DomainEvent::AlertSuppressed(s) => Some((&s.policy_id, s.escalates_at)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            suppressed,
+            [
+                (&dropped, None),
+                (&deferred, Some(now() + chrono::Duration::hours(7)))
+            ]
+        );
     }
 
     #[tokio::test]
@@ -348,7 +1506,9 @@ mod tests {
         let alert_id = svc.receive(raw, now()).await.unwrap();
 
         let user_id = UserId::new();
-        svc.acknowledge(&alert_id, user_id, now()).await.unwrap();
+        svc.acknowledge(&alert_id, user_id, &operator(), now())
+            .await
+            .unwrap();
 
         let alerts = svc.alerts.alerts.lock().unwrap();
         let alert = alerts.iter().find(|a| a.id() == &alert_id).unwrap();
@@ -356,6 +1516,8 @@ mod tests {
 
         let cancelled = svc.escalation_queue.cancelled.lock().unwrap();
         assert!(cancelled.contains(&alert_id.to_string()));
+        let cancelled = svc.notifications.cancelled.lock().unwrap();
+        assert!(cancelled.contains(&alert_id.to_string()));
 
         let events = svc.events.events.lock().unwrap();
         assert!(events
@@ -370,14 +1532,16 @@ mod tests {
         let alert_id = svc.receive(raw, now()).await.unwrap();
 
         let user_id = UserId::new();
-        svc.acknowledge(&alert_id, user_id.clone(), now())
+        svc.acknowledge(&alert_id, user_id.clone(), &operator(), now())
             .await
             .unwrap();
 
         let events_before = svc.events.events.lock().unwrap().len();
         let cancelled_before = svc.escalation_queue.cancelled.lock().unwrap().len();
 
-        svc.acknowledge(&alert_id, user_id, now()).await.unwrap();
+        svc.acknowledge(&alert_id, user_id, &operator(), now())
+            .await
+            .unwrap();
 
         let events_after = svc.events.events.lock().unwrap().len();
         let cancelled_after = svc.escalation_queue.cancelled.lock().unwrap().len();
@@ -391,11 +1555,13 @@ mod tests {
         let raw = make_raw_alert("api");
         let alert_id = svc.receive(raw, now()).await.unwrap();
 
-        svc.resolve(&alert_id, "operator".into(), now())
+        svc.resolve(&alert_id, "operator".into(), &operator(), now())
             .await
             .unwrap();
 
-        let result = svc.acknowledge(&alert_id, UserId::new(), now()).await;
+        let result = svc
+            .acknowledge(&alert_id, UserId::new(), &operator(), now())
+            .await;
         assert!(matches!(
             result,
             Err(AppError::Domain(DomainError::AlertAlreadyResolved))
@@ -408,7 +1574,7 @@ mod tests {
         let raw = make_raw_alert("api");
         let alert_id = svc.receive(raw, now()).await.unwrap();
 
-        svc.resolve(&alert_id, "operator".into(), now())
+        svc.resolve(&alert_id, "operator".into(), &operator(), now())
             .await
             .unwrap();
 
@@ -458,17 +1624,45 @@ mod tests {
         let raw = make_raw_alert("api");
         let alert_id = svc.receive(raw, now()).await.unwrap();
 
-        svc.resolve(&alert_id, "operator".into(), now())
+        svc.resolve(&alert_id, "operator".into(), &operator(), now())
             .await
             .unwrap();
 
         let events_before = svc.events.events.lock().unwrap().len();
 
-        svc.resolve(&alert_id, "another".into(), now())
+        svc.resolve(&alert_id, "another".into(), &operator(), now())
             .await
             .unwrap();
 
         let events_after = svc.events.events.lock().unwrap().len();
         assert_eq!(events_before, events_after);
+        assert_eq!(svc.audit.entries.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn acknowledge_and_resolve_are_audited() {
+        let svc = make_service();
+        let alert_id = svc.receive(make_raw_alert("api"), now()).await.unwrap();
+        let actor = operator();
+
+        svc.acknowledge(&alert_id, actor.user_id.clone().unwrap(), &actor, now())
+            .await
+            .unwrap();
+        svc.resolve(&alert_id, "operator".into(), &actor, now())
+            .await
+            .unwrap();
+
+        let entries = svc.audit.entries.lock().unwrap();
+        let actions: Vec<_> = entries.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(actions, ["alert.acknowledge", "alert.resolve"]);
+        assert_eq!(entries[0].actor, actor);
+        assert_eq!(entries[0].target, format!("alert:{alert_id}"));
+        let status = entries[0]
+            .changes
+            .iter()
+            .find(|c| c.field == "status")
+            .unwrap();
+        assert_eq!(status.before, Some("Firing".into()));
+        assert_eq!(status.after, Some("Acknowledged".into()));
     }
 }
@@ -0,0 +1,226 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::AuditLog;
+use rouse_ports::types::{Actor, AuditChange, AuditEntry, AuditFilter};
+
+use crate::error::AppError;
+
+/// Field-level differences between two JSON documents. Objects are compared
+/// key by key; anything else, arrays included, is compared whole.
+pub fn diff(before: &Value, after: &Value) -> Vec<AuditChange> {
+    let mut changes = Vec::new();
+    diff_into("", Some(before), Some(after), &mut changes);
+    changes
+}
+
+fn diff_into(
+    path: &str,
+    before: Option<&Value>,
+    after: Option<&Value>,
+    out: &mut Vec<AuditChange>,
+) {
+    if before == after {
+        return;
+    }
+    if let (Some(Value::Object(b)), Some(Value::Object(a))) = (before, after) {
+        let mut keys: Vec<&String> = b.keys().chain(a.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let field = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            diff_into(&field, b.get(key), a.get(key), out);
+        }
+        return;
+    }
+    out.push(AuditChange {
+        field: path.to_string(),
+        before: before.cloned(),
+        after: after.cloned(),
+    });
+}
+
+/// Builds the entry for `actor` doing `action` to `target`. `before` is
+/// `None` for creations and `after` is `None` for deletions; actions on
+/// state that is never shown, like passwords, pass neither.
+pub fn entry<T: Serialize>(
+    actor: &Actor,
+    action: &str,
+    target: String,
+    before: Option<&T>,
+    after: Option<&T>,
+    now: DateTime<Utc>,
+) -> Result<AuditEntry, AppError> {
+    let to_value = |record: Option<&T>| {
+        record
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| PortError::Persistence(e.to_string()))
+    };
+    let changes = match (to_value(before)?, to_value(after)?) {
+        (Some(before), Some(after)) => diff(&before, &after),
+        (None, None) => Vec::new(),
+        (before, after) => vec![AuditChange {
+            field: String::new(),
+            before,
+            after,
+        }],
+    };
+    Ok(AuditEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        actor: actor.clone(),
+        action: action.to_string(),
+        target,
+        changes,
+        at: now,
+    })
+}
+
+/// Records state-changing actions and answers who did what.
+pub struct AuditService<L>
+where
+    L: AuditLog,
+{
+    log: L,
+}
+
+impl<L> AuditService<L>
+where
+    L: AuditLog,
+{
+    pub fn new(log: L) -> Self {
+        Self { log }
+    }
+
+    /// Updates that changed nothing are not recorded.
+    pub async fn record<T: Serialize>(
+        &self,
+        actor: &Actor,
+        action: &str,
+        target: String,
+        before: Option<&T>,
+        after: Option<&T>,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let is_update = before.is_some() && after.is_some();
+        let entry = entry(actor, action, target, before, after, now)?;
+        if is_update && entry.changes.is_empty() {
+            return Ok(());
+        }
+        self.log.append(&entry).await?;
+        Ok(())
+    }
+
+    pub async fn query(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>, AppError> {
+        Ok(self.log.query(filter).await?)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use rouse_core::ids::UserId;
+    use rouse_ports::types::AuditSource;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    pub(crate) struct MockAuditLog {
+        pub(crate) entries: Mutex<Vec<AuditEntry>>,
+    }
+
+    #[async_trait]
+    impl AuditLog for MockAuditLog {
+        async fn append(&self, entry: &AuditEntry) -> Result<(), PortError> {
+            self.entries.lock().unwrap().push(entry.clone());
+            Ok(())
+        }
+        async fn query(&self, _filter: &AuditFilter) -> Result<Vec<AuditEntry>, PortError> {
+            Ok(self.entries.lock().unwrap().clone())
+        }
+    }
+
+    fn actor() -> Actor {
+        Actor {
+            user_id: Some(UserId::new()),
+            source: AuditSource::Api,
+            ip: None,
+        }
+    }
+
+    #[test]
+    fn diff_reports_nested_fields() {
+        let before = json!({"role": "User", "contacts": {"Slack": "U1"}, "dnd": [1]});
+        let after = json!({"role": "Admin", "contacts": {"Slack": "U1", "Sms": "+1"}, "dnd": [1]});
+        let changes = diff(&before, &after);
+        assert_eq!(
+            changes,
+            vec![
+                AuditChange {
+                    field: "contacts.Sms".into(),
+                    before: None,
+                    after: Some(json!("+1")),
+                },
+                AuditChange {
+                    field: "role".into(),
+                    before: Some(json!("User")),
+                    after: Some(json!("Admin")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn creations_record_the_whole_record() {
+        let record = json!({"name": "platform"});
+        let entry = entry(
+            &actor(),
+            "team.create",
+            "team:t1".into(),
+            None,
+            Some(&record),
+            Utc::now(),
+        )
+        .unwrap();
+        assert_eq!(entry.changes.len(), 1);
+        assert_eq!(entry.changes[0].before, None);
+        assert_eq!(entry.changes[0].after, Some(record));
+    }
+
+    #[tokio::test]
+    async fn unchanged_updates_are_not_recorded() {
+        let svc = AuditService::new(MockAuditLog::default());
+        let record = json!({"role": "User"});
+        svc.record(
+            &actor(),
+            "user.update",
+            "user:u1".into(),
+            Some(&record),
+            Some(&record),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        assert!(svc.log.entries.lock().unwrap().is_empty());
+
+        let changed = json!({"role": "Admin"});
+        svc.record(
+            &actor(),
+            "user.update",
+            "user:u1".into(),
+            Some(&record),
+            Some(&changed),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(svc.query(&AuditFilter::default()).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,423 @@
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Duration, Utc};
+
+use rouse_core::authz::Principal;
+use rouse_core::error::DomainError;
+use rouse_core::ids::UserId;
+use rouse_core::user::{Role, User};
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::{CredentialHasher, CredentialRepository, TenantScoped, UserRepository};
+use rouse_ports::types::{AuthToken, TokenKind};
+
+use crate::error::AppError;
+
+pub const MIN_PASSWORD_LEN: usize = 12;
+pub const SESSION_TTL: Duration = Duration::hours(12);
+
+/// Password login, sessions and API tokens. Tokens are handed out once in
+/// clear and only their digest is stored. Logins and new tokens belong to
+/// the repositories' tenant; `authenticate` accepts tokens of any tenant.
+pub struct AuthService<U, C, H>
+where
+    U: UserRepository + TenantScoped,
+    C: CredentialRepository + TenantScoped,
+    H: CredentialHasher,
+{
+    users: U,
+    credentials: C,
+    hasher: H,
+    /// Checked against when there is no real hash, so a login for an
+    /// unknown user takes as long as one with a wrong password.
+    dummy_hash: OnceLock<String>,
+}
+
+impl<U, C, H> AuthService<U, C, H>
+where
+    U: UserRepository + TenantScoped,
+    C: CredentialRepository + TenantScoped,
+    H: CredentialHasher,
+{
+    pub fn new(users: U, credentials: C, hasher: H) -> Self {
+        Self {
+            users,
+            credentials,
+            hasher,
+            dummy_hash: OnceLock::new(),
+        }
+    }
+
+    /// Creates the first admin when there are no users yet.
+    pub async fn bootstrap_admin(
+        &self,
+        username: String,
+        email: String,
+        password: &str,
+    ) -> Result<Option<User>, AppError> {
+        if !self.users.list_all().await?.is_empty() {
+            return Ok(None);
+        }
+        let user = User::new(username, email, Role::Admin);
+        self.users.save(&user).await?;
+        self.set_password(&user.id().to_string(), password).await?;
+        Ok(Some(user))
+    }
+
+    pub async fn set_password(&self, user_id: &str, password: &str) -> Result<(), AppError> {
+        if password.chars().count() < MIN_PASSWORD_LEN {
+            return Err(DomainError::WeakPassword(MIN_PASSWORD_LEN).into());
+        }
+        self.users
+            .find_by_id(user_id)
+            .await?
+            .ok_or(AppError::Port(PortError::NotFound))?;
+        let hash = self.hasher.hash_password(password)?;
+        self.credentials.set_password_hash(user_id, &hash).await?;
+        Ok(())
+    }
+
+    /// Returns the session token and its record. Unknown users and wrong
+    /// passwords fail the same way.
+    pub async fn login(
+        &self,
+        username: &str,
+        password: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(String, AuthToken), AppError> {
+        let user = self.users.find_by_username(username).await?;
+        let hash = match &user {
+            Some(user) => {
+                self.credentials
+                    .password_hash(&user.id().to_string())
+                    .await?
+            }
+            None => None,
+        };
+        let verified = match &hash {
+            Some(hash) => self.hasher.verify_password(password, hash),
+            None => {
+                self.hasher.verify_password(password, self.dummy_hash()?);
+                false
+            }
+        };
+        match user {
+            Some(user) if verified => self.start_session(user.id(), now).await,
+            _ => Err(AppError::Unauthenticated),
+        }
+    }
+
+    fn dummy_hash(&self) -> Result<&str, AppError> {
+        if let Some(hash) = self.dummy_hash.get() {
+            return Ok(hash);
+        }
+        let hash = self
+            .hasher
+            .hash_password(&uuid::Uuid::new_v4().to_string())?;
+        Ok(self.dummy_hash.get_or_init(|| hash))
+    }
+
+    /// Issues a session for a user who signed in some other way, e.g. SSO.
+    pub async fn start_session(
+        &self,
+        user_id: &UserId,
+        now: DateTime<Utc>,
+    ) -> Result<(String, AuthToken), AppError> {
+        self.issue(user_id, TokenKind::Session, now, Some(now + SESSION_TTL))
+            .await
+    }
+
+    pub async fn logout(&self, token: &str) -> Result<(), AppError> {
+        if let Some(record) = self.find(token).await? {
+            self.credentials
+                .for_tenant(&record.tenant_id)
+                .delete_token(&record.id)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn create_api_token(
+        &self,
+        user_id: &str,
+        name: String,
+        now: DateTime<Utc>,
+    ) -> Result<(String, AuthToken), AppError> {
+        let user = self
+            .users
+            .find_by_id(user_id)
+            .await?
+            .ok_or(AppError::Port(PortError::NotFound))?;
+        self.issue(user.id(), TokenKind::Api { name }, now, None)
+            .await
+    }
+
+    pub async fn list_api_tokens(&self, user_id: &str) -> Result<Vec<AuthToken>, AppError> {
+        let tokens = self.credentials.list_tokens(user_id).await?;
+        Ok(tokens
+            .into_iter()
+            .filter(|t| matches!(t.kind, TokenKind::Api { .. }))
+            .collect())
+    }
+
+    pub async fn revoke_api_token(&self, user_id: &str, token_id: &str) -> Result<(), AppError> {
+        let owned = self
+            .list_api_tokens(user_id)
+            .await?
+            .iter()
+            .any(|t| t.id == token_id);
+        if !owned {
+            return Err(AppError::Port(PortError::NotFound));
+        }
+        self.credentials.delete_token(token_id).await?;
+        Ok(())
+    }
+
+    /// Resolves a bearer or session token to the user behind it, with the
+    /// role they have now rather than when the token was issued. The
+    /// principal carries the token's tenant.
+    pub async fn authenticate(
+        &self,
+        token: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Principal, AppError> {
+        let record = self.find(token).await?.ok_or(AppError::Unauthenticated)?;
+        if record.expires_at.is_some_and(|at| at <= now) {
+            self.credentials
+                .for_tenant(&record.tenant_id)
+                .delete_token(&record.id)
+                .await?;
+            return Err(AppError::Unauthenticated);
+        }
+        let user = self
+            .users
+            .for_tenant(&record.tenant_id)
+            .find_by_id(&record.user_id.to_string())
+            .await?
+            .ok_or(AppError::Unauthenticated)?;
+        Ok(Principal::new(
+            record.tenant_id,
+            user.id().clone(),
+            user.role(),
+        ))
+    }
+
+    async fn find(&self, token: &str) -> Result<Option<AuthToken>, AppError> {
+        let digest = self.hasher.digest_token(token);
+        Ok(self.credentials.find_token(&digest).await?)
+    }
+
+    async fn issue(
+        &self,
+        user_id: &UserId,
+        kind: TokenKind,
+        now: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(String, AuthToken), AppError> {
+        let secret = format!(
+            "rouse_{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let record = AuthToken {
+            id: uuid::Uuid::new_v4().to_string(),
+            tenant_id: self.credentials.tenant().clone(),
+            user_id: user_id.clone(),
+            kind,
+            digest: self.hasher.digest_token(&secret),
+            created_at: now,
+            expires_at,
+        };
+        self.credentials.save_token(&record).await?;
+        Ok((secret, record))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user_service::tests::{MockUserRepo, PRIMARY_TENANT};
+    use async_trait::async_trait;
+    use rouse_core::ids::TenantId;
+    use std::sync::{Arc, Mutex};
+
+    /// Single store shared by every tenant handle, like `MockUserRepo`.
+    #[derive(Default)]
+    struct MockCredentials {
+        passwords: Arc<Mutex<Vec<(String, String)>>>,
+        tokens: Arc<Mutex<Vec<AuthToken>>>,
+    }
+
+    impl TenantScoped for MockCredentials {
+        fn tenant(&self) -> &TenantId {
+            &PRIMARY_TENANT
+        }
+        fn for_tenant(&self, _tenant: &TenantId) -> Self {
+            Self {
+                passwords: self.passwords.clone(),
+                tokens: self.tokens.clone(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl CredentialRepository for MockCredentials {
+        async fn set_password_hash(&self, user_id: &str, hash: &str) -> Result<(), PortError> {
+            let mut passwords = self.passwords.lock().unwrap();
+            passwords.retain(|(id, _)| id != user_id);
+            passwords.push((user_id.to_string(), hash.to_string()));
+            Ok(())
+        }
+        async fn password_hash(&self, user_id: &str) -> Result<Option<String>, PortError> {
+            let passwords = self.passwords.lock().unwrap();
+            Ok(passwords
+                .iter()
+                .find(|(id, _)| id == user_id)
+                .map(|(_, hash)| hash.clone()))
+        }
+        async fn save_token(&self, token: &AuthToken) -> Result<(), PortError> {
+            self.tokens.lock().unwrap().push(token.clone());
+            Ok(())
+        }
+        async fn find_token(&self, digest: &str) -> Result<Option<AuthToken>, PortError> {
+            let tokens = self.tokens.lock().unwrap();
+            Ok(tokens.iter().find(|t| t.digest == digest).cloned())
+        }
+        async fn list_tokens(&self, user_id: &str) -> Result<Vec<AuthToken>, PortError> {
+            let tokens = self.tokens.lock().unwrap();
+            Ok(tokens
+                .iter()
+                .filter(|t| t.user_id.to_string() == user_id)
+                .cloned()
+                .collect())
+        }
+        async fn delete_token(&self, id: &str) -> Result<(), PortError> {
+            self.tokens.lock().unwrap().retain(|t| t.id != id);
+            Ok(())
+        }
+    }
+
+    /// Reversible stand-in; the real hashing lives in the adapters.
+    #[derive(Default)]
+    struct PlainHasher {
+        verified: Mutex<usize>,
+    }
+
+    impl CredentialHasher for PlainHasher {
+        fn hash_password(&self, password: &str) -> Result<String, PortError> {
+            Ok(format!("hashed:{password}"))
+        }
+        fn verify_password(&self, password: &str, hash: &str) -> bool {
+            *self.verified.lock().unwrap() += 1;
+            hash == format!("hashed:{password}")
+        }
+        fn digest_token(&self, token: &str) -> String {
+            format!("digest:{token}")
+        }
+    }
+
+    type TestService = AuthService<MockUserRepo, MockCredentials, PlainHasher>;
+
+    fn now() -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339("2025-01-15T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    async fn setup() -> (TestService, User) {
+        let svc = AuthService::new(
+            MockUserRepo::default(),
+            MockCredentials::default(),
+            PlainHasher::default(),
+        );
+        let user = svc
+            .bootstrap_admin("root".into(), "root@test.com".into(), "a long passphrase")
+            .await
+            .unwrap()
+            .unwrap();
+        (svc, user)
+    }
+
+    #[tokio::test]
+    async fn bootstrap_only_runs_on_empty_store() {
+        let (svc, user) = setup().await;
+        assert_eq!(user.role(), Role::Admin);
+        let again = svc
+            .bootstrap_admin("other".into(), "o@test.com".into(), "a long passphrase")
+            .await
+            .unwrap();
+        assert!(again.is_none());
+    }
+
+    #[tokio::test]
+    async fn login_issues_expiring_session() {
+        let (svc, user) = setup().await;
+        let (token, record) = svc.login("root", "a long passphrase", now()).await.unwrap();
+        assert_eq!(record.expires_at, Some(now() + SESSION_TTL));
+        assert!(!svc.credentials.tokens.lock().unwrap()[0]
+            .digest
+            .contains("a long passphrase"));
+
+        let principal = svc.authenticate(&token, now()).await.unwrap();
+        assert_eq!(
+            principal,
+            Principal::new(TenantId::primary(), user.id().clone(), Role::Admin)
+        );
+
+        let expired = svc.authenticate(&token, now() + SESSION_TTL).await;
+        assert!(matches!(expired, Err(AppError::Unauthenticated)));
+        assert!(svc.credentials.tokens.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn bad_credentials_are_rejected() {
+        let (svc, _) = setup().await;
+        for (user, password) in [("root", "wrong password!"), ("nobody", "a long passphrase")] {
+            let result = svc.login(user, password, now()).await;
+            assert!(matches!(result, Err(AppError::Unauthenticated)));
+        }
+        let result = svc.authenticate("rouse_made_up", now()).await;
+        assert!(matches!(result, Err(AppError::Unauthenticated)));
+        // Unknown users still cost a password check.
+        assert_eq!(*svc.hasher.verified.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn short_password_is_rejected() {
+        let (svc, user) = setup().await;
+        let result = svc.set_password(&user.id().to_string(), "short").await;
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::WeakPassword(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn api_tokens_can_be_listed_and_revoked() {
+        let (svc, user) = setup().await;
+        let id = user.id().to_string();
+        let (token, record) = svc.create_api_token(&id, "ci".into(), now()).await.unwrap();
+        svc.login("root", "a long passphrase", now()).await.unwrap();
+
+        assert_eq!(svc.list_api_tokens(&id).await.unwrap().len(), 1);
+        assert!(svc
+            .authenticate(&token, now() + Duration::days(400))
+            .await
+            .is_ok());
+
+        let other = UserId::new().to_string();
+        assert!(svc.revoke_api_token(&other, &record.id).await.is_err());
+        svc.revoke_api_token(&id, &record.id).await.unwrap();
+        assert!(svc.authenticate(&token, now()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn role_changes_apply_to_existing_tokens() {
+        let (svc, user) = setup().await;
+        let id = user.id().to_string();
+        let (token, _) = svc.create_api_token(&id, "ci".into(), now()).await.unwrap();
+
+        svc.users.users.lock().unwrap()[0].set_role(Role::Viewer);
+        let principal = svc.authenticate(&token, now()).await.unwrap();
+        assert_eq!(principal.role, Role::Viewer);
+    }
+}
@@ -0,0 +1,1227 @@
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use serde_json::{json, Map, Value};
+
+use rouse_core::channel::Channel;
+use rouse_core::escalation::{EscalationPolicy, EscalationStep, EscalationTarget, OnCallModifier};
+use rouse_core::ids::{PolicyId, UserId};
+use rouse_core::routing::{Matcher, Route, RouteOptions};
+use rouse_core::schedule::lint::{self, LintContext, LintOptions};
+use rouse_core::schedule::{HandoffTime, Rotation, Schedule};
+use rouse_core::user::{Role, User};
+use rouse_ports::outbound::{AuditLog, ConfigStore};
+use rouse_ports::types::{Actor, AuditChange, AuditEntry, ConfigChanges, ConfigSnapshot};
+
+use crate::audit::diff;
+use crate::error::AppError;
+use crate::router::AlertRouter;
+
+/// Schedules are linted over this window before they are applied.
+const LINT_WINDOW: Duration = Duration::weeks(4);
+
+/// A user as declared in the config file. Contact details are left to the
+/// user, so they are not part of it.
+#[derive(Debug, Clone)]
+pub struct UserSpec {
+    pub name: String,
+    pub line: usize,
+    pub email: String,
+    pub role: Role,
+}
+
+/// A schedule as declared in the config file. People are referred to by
+/// username; `line` points back into the file for error messages.
+#[derive(Debug, Clone)]
+pub struct ScheduleSpec {
+    pub name: String,
+    pub line: usize,
+    pub timezone: Tz,
+    pub rotation: Rotation,
+    pub participants: Vec<String>,
+    pub handoff: HandoffTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetSpec {
+    OnCall {
+        schedule: String,
+        modifier: OnCallModifier,
+    },
+    User(String),
+    Team(String),
+    /// A bare name: a user if one has that username, else a team.
+    Named(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct StepSpec {
+    pub wait_seconds: u64,
+    pub targets: Vec<TargetSpec>,
+    pub channels: Vec<Channel>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PolicySpec {
+    pub name: String,
+    pub line: usize,
+    pub steps: Vec<StepSpec>,
+    pub repeat: u32,
+    /// How long an acknowledgement holds before paging resumes.
+    pub ack_timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RouteSpec {
+    pub line: usize,
+    pub matchers: Vec<Matcher>,
+    /// Inherited from the parent route when `None`.
+    pub policy: Option<String>,
+    pub continue_matching: bool,
+    pub options: RouteOptions,
+    pub routes: Vec<RouteSpec>,
+}
+
+impl RouteSpec {
+    /// Every policy named by this route or the routes below it.
+    fn policies(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.policy.as_deref().into_iter().collect();
+        names.extend(self.routes.iter().flat_map(RouteSpec::policies));
+        names
+    }
+}
+
+/// The policy that takes alerts no route matched.
+#[derive(Debug, Clone)]
+pub struct DefaultPolicySpec {
+    pub name: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSpec {
+    pub users: Vec<UserSpec>,
+    pub schedules: Vec<ScheduleSpec>,
+    pub policies: Vec<PolicySpec>,
+    pub routes: Vec<RouteSpec>,
+    pub default_policy: Option<DefaultPolicySpec>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Create,
+    Update,
+    Delete,
+}
+
+impl Action {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    User,
+    Schedule,
+    Policy,
+    Route,
+}
+
+impl Kind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Schedule => "schedule",
+            Self::Policy => "policy",
+            Self::Route => "route",
+        }
+    }
+}
+
+/// One line of a plan, keyed by the name used in the config file. Routes
+/// have no name and are keyed by their position, counting from 1; the
+/// default policy is the route keyed `default`.
+#[derive(Debug, Clone)]
+pub struct PlannedChange {
+    pub action: Action,
+    pub kind: Kind,
+    pub name: String,
+    /// Names stand in for ids, so this reads like the config file.
+    pub fields: Vec<AuditChange>,
+}
+
+/// The difference between a config and what is stored.
+#[derive(Debug, Default)]
+pub struct Plan {
+    pub changes: Vec<PlannedChange>,
+    pub unchanged: usize,
+    /// Problems that do not block the apply, e.g. config-managed users
+    /// who have no verified contact yet.
+    pub warnings: Vec<String>,
+    writes: ConfigChanges,
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn count(&self, action: Action) -> usize {
+        self.changes.iter().filter(|c| c.action == action).count()
+    }
+
+    /// Adds the change from `before` to `after`, if any, and says whether
+    /// there was one.
+    fn record(
+        &mut self,
+        kind: Kind,
+        name: &str,
+        before: Option<Value>,
+        after: Option<Value>,
+    ) -> bool {
+        let action = match (&before, &after) {
+            (None, Some(_)) => Action::Create,
+            (Some(_), None) => Action::Delete,
+            (Some(b), Some(a)) if b != a => Action::Update,
+            _ => {
+                self.unchanged += 1;
+                return false;
+            }
+        };
+        let empty = json!({});
+        self.changes.push(PlannedChange {
+            action,
+            kind,
+            name: name.to_string(),
+            fields: diff(
+                before.as_ref().unwrap_or(&empty),
+                after.as_ref().unwrap_or(&empty),
+            ),
+        });
+        true
+    }
+}
+
+/// Reconciles users, schedules, escalation policies and routes with a
+/// declarative config. Everything is matched by name. What the config
+/// declares is marked managed; managed objects the config no longer
+/// declares are deleted, and everything else is left alone.
+/// Applied changes are audited as the config file, one entry per change.
+pub struct ConfigService<C: ConfigStore, L: AuditLog> {
+    store: C,
+    audit: L,
+}
+
+impl<C: ConfigStore, L: AuditLog> ConfigService<C, L> {
+    pub fn new(store: C, audit: L) -> Self {
+        Self { store, audit }
+    }
+
+    /// What `apply` would do. All problems are reported together in
+    /// `AppError::Config`.
+    pub async fn plan(&self, spec: &ConfigSpec, now: DateTime<Utc>) -> Result<Plan, AppError> {
+        let stored = self.store.snapshot().await?;
+        let mut desired = stored.clone();
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+
+        let users: Vec<User> = spec.users.iter().map(|u| build_user(u, &stored)).collect();
+        for user in &users {
+            replace_or_push(&mut desired.users, user, |u| u.id());
+        }
+        let deleted_users = undeclared(&stored.users, |u| {
+            u.is_managed() && !spec.users.iter().any(|s| s.name == u.username())
+        });
+        desired
+            .users
+            .retain(|u| !deleted_users.iter().any(|d| d.id() == u.id()));
+
+        let mut schedules = Vec::new();
+        for s in &spec.schedules {
+            match build_schedule(s, &desired) {
+                Ok(built) => schedules.push(built),
+                Err(e) => errors.push(format!("line {}: schedule `{}`: {e}", s.line, s.name)),
+            }
+        }
+        let deleted_schedules = undeclared(&stored.schedules, |s| {
+            s.is_managed() && !spec.schedules.iter().any(|d| d.name == s.name())
+        });
+        desired
+            .schedules
+            .retain(|s| !deleted_schedules.iter().any(|d| d.id() == s.id()));
+        for schedule in &schedules {
+            replace_or_push(&mut desired.schedules, schedule, |s| s.id());
+        }
+        for schedule in &schedules {
+            let (errs, warns) = lint_findings(schedule, &desired, now);
+            let line = spec
+                .schedules
+                .iter()
+                .find(|s| s.name == schedule.name())
+                .map_or(0, |s| s.line);
+            if !errs.is_empty() {
+                let e = errs.join("; ");
+                errors.push(format!("line {line}: schedule `{}`: {e}", schedule.name()));
+            }
+            for w in warns {
+                warnings.push(format!("line {line}: schedule `{}`: {w}", schedule.name()));
+            }
+        }
+
+        // Things that refer to a declaration that failed are skipped rather
+        // than reported again as unknown.
+        let failed_schedule = |name: &str| {
+            spec.schedules.iter().any(|s| s.name == name)
+                && !schedules.iter().any(|s| s.name() == name)
+        };
+        let mut policies = Vec::new();
+        for p in &spec.policies {
+            let depends_on_failed = p.steps.iter().flat_map(|s| &s.targets).any(
+                |t| matches!(t, TargetSpec::OnCall { schedule, .. } if failed_schedule(schedule)),
+            );
+            if depends_on_failed {
+                continue;
+            }
+            match build_policy(p, &desired) {
+                Ok(built) => policies.push(built),
+                Err(e) => errors.push(format!("line {}: policy `{}`: {e}", p.line, p.name)),
+            }
+        }
+        let deleted_policies = undeclared(&stored.policies, |p| {
+            p.is_managed() && !spec.policies.iter().any(|d| d.name == p.name())
+        });
+        desired
+            .policies
+            .retain(|p| !deleted_policies.iter().any(|d| d.id() == p.id()));
+        for policy in &policies {
+            replace_or_push(&mut desired.policies, policy, |p| p.id());
+        }
+
+        let failed_policy = |name: &str| {
+            spec.policies.iter().any(|p| p.name == name)
+                && !policies.iter().any(|p| p.name() == name)
+        };
+        let mut routes = Vec::new();
+        for r in &spec.routes {
+            if r.policies().into_iter().any(failed_policy) {
+                continue;
+            }
+            let Some(route) = build_route(r, &desired, &mut errors) else {
+                continue;
+            };
+            match AlertRouter::new(vec![route.clone()]) {
+                Ok(_) => routes.push(route),
+                Err(e) => errors.push(format!("line {}: route: {e}", r.line)),
+            }
+        }
+        let default_policy_id = match &spec.default_policy {
+            Some(d) => {
+                let id = policy_id(&d.name, &desired);
+                if id.is_none() && !failed_policy(&d.name) {
+                    errors.push(format!(
+                        "line {}: default_policy: unknown escalation policy `{}`",
+                        d.line, d.name
+                    ));
+                }
+                id
+            }
+            None => None,
+        };
+        errors.extend(dangling_references(&stored, &desired));
+
+        if !errors.is_empty() {
+            return Err(AppError::Config(errors.join("\n")));
+        }
+
+        let mut plan = Plan {
+            warnings,
+            ..Plan::default()
+        };
+        for user in users {
+            let before = stored.users.iter().find(|u| u.id() == user.id());
+            if plan.record(
+                Kind::User,
+                user.username(),
+                before.map(user_view),
+                Some(user_view(&user)),
+            ) {
+                plan.writes.users.push(user);
+            }
+        }
+        for user in deleted_users {
+            plan.record(Kind::User, user.username(), Some(user_view(&user)), None);
+            plan.writes.deleted_users.push(user.id().clone());
+        }
+        for schedule in schedules {
+            let before = stored.schedules.iter().find(|s| s.id() == schedule.id());
+            if plan.record(
+                Kind::Schedule,
+                schedule.name(),
+                before.map(|s| schedule_view(s, &stored)),
+                Some(schedule_view(&schedule, &desired)),
+            ) {
+                plan.writes.schedules.push(schedule);
+            }
+        }
+        for schedule in deleted_schedules {
+            let before = schedule_view(&schedule, &stored);
+            plan.record(Kind::Schedule, schedule.name(), Some(before), None);
+            plan.writes.deleted_schedules.push(schedule.id().clone());
+        }
+        for policy in policies {
+            let before = stored.policies.iter().find(|p| p.id() == policy.id());
+            if plan.record(
+                Kind::Policy,
+                policy.name(),
+                before.map(|p| policy_view(p, &stored)),
+                Some(policy_view(&policy, &desired)),
+            ) {
+                plan.writes.policies.push(policy);
+            }
+        }
+        for policy in deleted_policies {
+            let before = policy_view(&policy, &stored);
+            plan.record(Kind::Policy, policy.name(), Some(before), None);
+            plan.writes.deleted_policies.push(policy.id().clone());
+        }
+        for position in 0..stored.routes.len().max(routes.len()) {
+            plan.record(
+                Kind::Route,
+                &(position + 1).to_string(),
+                stored.routes.get(position).map(|r| route_view(r, &stored)),
+                routes.get(position).map(|r| route_view(r, &desired)),
+            );
+        }
+        plan.writes.routes = routes;
+        if stored.default_policy_id.is_some() || default_policy_id.is_some() {
+            let view = |id: &PolicyId, known| route_view(&Route::new(vec![], id.clone()), known);
+            plan.record(
+                Kind::Route,
+                "default",
+                stored
+                    .default_policy_id
+                    .as_ref()
+                    .map(|id| view(id, &stored)),
+                default_policy_id.as_ref().map(|id| view(id, &desired)),
+            );
+        }
+        plan.writes.default_policy_id = default_policy_id;
+        Ok(plan)
+    }
+
+    /// Plans and, unless there is nothing to do, writes the plan in one
+    /// go. Applying the same config twice changes nothing.
+    pub async fn apply(&self, spec: &ConfigSpec, now: DateTime<Utc>) -> Result<Plan, AppError> {
+        let plan = self.plan(spec, now).await?;
+        if !plan.is_empty() {
+            self.store.commit(&plan.writes).await?;
+        }
+        for change in &plan.changes {
+            let kind = change.kind.as_str();
+            self.audit
+                .append(&AuditEntry {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    actor: Actor::config(),
+                    action: format!("{kind}.{}", change.action.as_str()),
+                    target: format!("{kind}:{}", change.name),
+                    changes: change.fields.clone(),
+                    at: now,
+                })
+                .await?;
+        }
+        Ok(plan)
+    }
+}
+
+fn replace_or_push<T: Clone, K: PartialEq>(items: &mut Vec<T>, item: &T, key: impl Fn(&T) -> &K) {
+    match items.iter_mut().find(|i| key(i) == key(item)) {
+        Some(existing) => *existing = item.clone(),
+        None => items.push(item.clone()),
+    }
+}
+
+fn undeclared<T: Clone>(stored: &[T], is_undeclared: impl Fn(&T) -> bool) -> Vec<T> {
+    stored
+        .iter()
+        .filter(|t| is_undeclared(t))
+        .cloned()
+        .collect()
+}
+
+fn build_user(spec: &UserSpec, known: &ConfigSnapshot) -> User {
+    let mut user = match known.users.iter().find(|u| u.username() == spec.name) {
+        Some(existing) => existing.clone(),
+        None => User::new(spec.name.clone(), spec.email.clone(), spec.role),
+    };
+    user.set_email(spec.email.clone());
+    user.set_role(spec.role);
+    user.set_managed(true);
+    user
+}
+
+fn build_schedule(spec: &ScheduleSpec, known: &ConfigSnapshot) -> Result<Schedule, String> {
+    let participants = spec
+        .participants
+        .iter()
+        .map(|name| user_id(name, known).ok_or_else(|| format!("unknown user `{name}`")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut schedule = match known.schedules.iter().find(|s| s.name() == spec.name) {
+        Some(existing) => {
+            let mut schedule = existing.clone();
+            schedule
+                .reconfigure(
+                    spec.timezone,
+                    spec.rotation.clone(),
+                    participants,
+                    spec.handoff.clone(),
+                )
+                .map_err(|e| e.to_string())?;
+            schedule
+        }
+        None => Schedule::new(
+            spec.name.clone(),
+            spec.timezone,
+            spec.rotation.clone(),
+            participants,
+            spec.handoff.clone(),
+        )
+        .map_err(|e| e.to_string())?,
+    };
+    schedule.set_managed(true);
+    Ok(schedule)
+}
+
+/// Lint errors, which block a config, and the findings it only warns
+/// about. Config-managed users get their contacts through the API after
+/// the config creates them, so not being able to reach one yet is a
+/// warning rather than an error.
+fn lint_findings(
+    schedule: &Schedule,
+    known: &ConfigSnapshot,
+    now: DateTime<Utc>,
+) -> (Vec<String>, Vec<String>) {
+    let others: Vec<Schedule> = known
+        .schedules
+        .iter()
+        .filter(|s| s.id() != schedule.id())
+        .cloned()
+        .collect();
+    let ctx = LintContext {
+        users: &known.users,
+        other_schedules: &others,
+        options: LintOptions::default(),
+    };
+    let report = lint::lint(schedule, &ctx, now, now + LINT_WINDOW);
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    for issue in report.errors() {
+        match issue {
+            lint::LintIssue::UnreachableParticipant { user_id } => {
+                let message = format!(
+                    "`{}` cannot be reached on any verified channel",
+                    username(user_id, known)
+                );
+                let managed = known
+                    .users
+                    .iter()
+                    .any(|u| u.id() == user_id && u.is_managed());
+                if managed {
+                    warnings.push(message);
+                } else {
+                    errors.push(message);
+                }
+            }
+            lint::LintIssue::Gap { start, end } => {
+                errors.push(format!("nobody is on call from {start} to {end}"));
+            }
+            other => errors.push(format!("{other:?}")),
+        }
+    }
+    (errors, warnings)
+}
+
+fn build_policy(spec: &PolicySpec, known: &ConfigSnapshot) -> Result<EscalationPolicy, String> {
+    let mut steps = Vec::with_capacity(spec.steps.len());
+    for (order, step) in spec.steps.iter().enumerate() {
+        let targets = step
+            .targets
+            .iter()
+            .map(|t| target(t, known))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("step {}: {e}", order + 1))?;
+        steps.push(EscalationStep::new(
+            order as u32,
+            step.wait_seconds,
+            targets,
+            step.channels.clone(),
+        ));
+    }
+
+    let mut policy = match known.policies.iter().find(|p| p.name() == spec.name) {
+        Some(p) => p.clone(),
+        None => EscalationPolicy::new(spec.name.clone(), steps.clone(), spec.repeat)
+            .map_err(|e| e.to_string())?,
+    };
+    policy
+        .reconfigure(steps, spec.repeat)
+        .map_err(|e| e.to_string())?;
+    policy.set_ack_timeout(spec.ack_timeout_secs);
+    policy.set_managed(true);
+    Ok(policy)
+}
+
+fn target(spec: &TargetSpec, known: &ConfigSnapshot) -> Result<EscalationTarget, String> {
+    let team = |name: &str| known.teams.iter().find(|t| t.name() == name);
+    match spec {
+        TargetSpec::OnCall { schedule, modifier } => known
+            .schedules
+            .iter()
+            .find(|s| s.name() == schedule)
+            .map(|s| EscalationTarget::OnCall {
+                schedule_id: s.id().clone(),
+                modifier: *modifier,
+            })
+            .ok_or_else(|| format!("unknown schedule `{schedule}`")),
+        TargetSpec::User(name) => user_id(name, known)
+            .map(EscalationTarget::User)
+            .ok_or_else(|| format!("unknown user `{name}`")),
+        TargetSpec::Team(name) => team(name)
+            .map(|t| EscalationTarget::Team(t.id().clone()))
+            .ok_or_else(|| format!("unknown team `{name}`")),
+        TargetSpec::Named(name) => user_id(name, known)
+            .map(EscalationTarget::User)
+            .or_else(|| team(name).map(|t| EscalationTarget::Team(t.id().clone())))
+            .ok_or_else(|| format!("no user or team named `{name}`")),
+    }
+}
+
+/// Objects the config does not manage may still point at users and
+/// schedules it is about to delete.
+fn dangling_references(stored: &ConfigSnapshot, desired: &ConfigSnapshot) -> Vec<String> {
+    let user_deleted = |id: &UserId| {
+        !desired.users.iter().any(|u| u.id() == id) && stored.users.iter().any(|u| u.id() == id)
+    };
+    let mut errors = Vec::new();
+    for schedule in &desired.schedules {
+        for id in schedule.participants().iter().filter(|id| user_deleted(id)) {
+            errors.push(format!(
+                "schedule `{}` still has `{}` as a participant, who is no longer declared",
+                schedule.name(),
+                username(id, stored)
+            ));
+        }
+    }
+    for policy in &desired.policies {
+        for t in policy.steps().iter().flat_map(|s| s.targets()) {
+            let deleted = match t {
+                EscalationTarget::User(id) => user_deleted(id),
+                EscalationTarget::OnCall { schedule_id, .. } => {
+                    !desired.schedules.iter().any(|s| s.id() == schedule_id)
+                        && stored.schedules.iter().any(|s| s.id() == schedule_id)
+                }
+                EscalationTarget::Team(_) => false,
+            };
+            if deleted {
+                errors.push(format!(
+                    "policy `{}` still notifies {}, which is no longer declared",
+                    policy.name(),
+                    describe_target(t, stored)
+                ));
+            }
+        }
+    }
+    errors
+}
+
+fn user_id(username: &str, known: &ConfigSnapshot) -> Option<UserId> {
+    known
+        .users
+        .iter()
+        .find(|u| u.username() == username)
+        .map(|u| u.id().clone())
+}
+
+fn policy_id(name: &str, known: &ConfigSnapshot) -> Option<PolicyId> {
+    known
+        .policies
+        .iter()
+        .find(|p| p.name() == name)
+        .map(|p| p.id().clone())
+}
+
+/// `None` if the route or any below it names an unknown policy; each one
+/// is reported.
+fn build_route(
+    spec: &RouteSpec,
+    known: &ConfigSnapshot,
+    errors: &mut Vec<String>,
+) -> Option<Route> {
+    let policy_id = match &spec.policy {
+        Some(name) => match policy_id(name, known) {
+            Some(id) => Some(id),
+            None => {
+                errors.push(format!(
+                    "line {}: route: unknown escalation policy `{name}`",
+                    spec.line
+                ));
+                None
+            }
+        },
+        None => None,
+    };
+    let routes: Vec<Option<Route>> = spec
+        .routes
+        .iter()
+        .map(|child| build_route(child, known, errors))
+        .collect();
+    if spec.policy.is_some() && policy_id.is_none() {
+        return None;
+    }
+    Some(Route {
+        matchers: spec.matchers.clone(),
+        policy_id,
+        continue_matching: spec.continue_matching,
+        options: spec.options.clone(),
+        routes: routes.into_iter().collect::<Option<_>>()?,
+    })
+}
+
+fn username(id: &UserId, known: &ConfigSnapshot) -> String {
+    known
+        .users
+        .iter()
+        .find(|u| u.id() == id)
+        .map_or_else(|| id.to_string(), |u| u.username().to_string())
+}
+
+/// A target the way the config file spells it.
+fn describe_target(target: &EscalationTarget, known: &ConfigSnapshot) -> String {
+    match target {
+        EscalationTarget::OnCall {
+            schedule_id,
+            modifier,
+        } => {
+            let name = known
+                .schedules
+                .iter()
+                .find(|s| s.id() == schedule_id)
+                .map_or_else(|| schedule_id.to_string(), |s| s.name().to_string());
+            match modifier {
+                OnCallModifier::Current => format!("on-call({name})"),
+                other => format!("on-call({name}, {})", format!("{other:?}").to_lowercase()),
+            }
+        }
+        EscalationTarget::User(id) => username(id, known),
+        EscalationTarget::Team(id) => {
+            let name = known
+                .teams
+                .iter()
+                .find(|t| t.id() == id)
+                .map_or_else(|| id.to_string(), |t| t.name().to_string());
+            format!("team({name})")
+        }
+    }
+}
+
+// Views hold what the config file controls, with names instead of ids, so
+// plans compare and read the way the file does.
+
+fn user_view(user: &User) -> Value {
+    json!({
+        "email": user.email(),
+        "role": user.role(),
+        "managed": user.is_managed(),
+    })
+}
+
+fn schedule_view(schedule: &Schedule, known: &ConfigSnapshot) -> Value {
+    let participants: Vec<String> = schedule
+        .participants()
+        .iter()
+        .map(|id| username(id, known))
+        .collect();
+    json!({
+        "timezone": schedule.timezone().name(),
+        "rotation": schedule.rotation(),
+        "participants": participants,
+        "handoff": schedule.handoff(),
+        "managed": schedule.is_managed(),
+    })
+}
+
+fn policy_view(policy: &EscalationPolicy, known: &ConfigSnapshot) -> Value {
+    // Keyed by step number so a plan points at the step that changes.
+    let steps: Map<String, Value> = policy
+        .steps()
+        .iter()
+        .enumerate()
+        .map(|(index, step)| {
+            let notify: Vec<String> = step
+                .targets()
+                .iter()
+                .map(|t| describe_target(t, known))
+                .collect();
+            let view = json!({
+                "wait_seconds": step.wait_seconds(),
+                "notify": notify,
+                "channels": step.channels(),
+            });
+            ((index + 1).to_string(), view)
+        })
+        .collect();
+    json!({
+        "steps": steps,
+        "repeat": policy.repeat_count(),
+        "ack_timeout_seconds": policy.ack_timeout_secs(),
+        "managed": policy.is_managed(),
+    })
+}
+
+fn route_view(route: &Route, known: &ConfigSnapshot) -> Value {
+    let mut view = Map::new();
+    view.insert("match".into(), json!(route.matchers));
+    if let Some(id) = &route.policy_id {
+        let policy = known
+            .policies
+            .iter()
+            .find(|p| p.id() == id)
+            .map_or_else(|| id.to_string(), |p| p.name().to_string());
+        view.insert("policy".into(), policy.into());
+    }
+    if route.continue_matching {
+        view.insert("continue".into(), true.into());
+    }
+    if !route.options.is_empty() {
+        view.insert("options".into(), json!(route.options));
+    }
+    if !route.routes.is_empty() {
+        let routes = route.routes.iter().map(|r| route_view(r, known)).collect();
+        view.insert("routes".into(), Value::Array(routes));
+    }
+    Value::Object(view)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::Weekday;
+    use rouse_ports::error::PortError;
+
+    use super::*;
+    use crate::audit::tests::MockAuditLog;
+
+    /// Applies commits to an in-memory snapshot.
+    #[derive(Default)]
+    struct MockConfigStore {
+        state: Mutex<ConfigSnapshot>,
+        commits: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl ConfigStore for MockConfigStore {
+        async fn snapshot(&self) -> Result<ConfigSnapshot, PortError> {
+            Ok(self.state.lock().unwrap().clone())
+        }
+
+        async fn commit(&self, changes: &ConfigChanges) -> Result<(), PortError> {
+            let mut state = self.state.lock().unwrap();
+            state
+                .users
+                .retain(|u| !changes.deleted_users.contains(u.id()));
+            state
+                .schedules
+                .retain(|s| !changes.deleted_schedules.contains(s.id()));
+            state
+                .policies
+                .retain(|p| !changes.deleted_policies.contains(p.id()));
+            for user in &changes.users {
+                replace_or_push(&mut state.users, user, |u| u.id());
+            }
+            for schedule in &changes.schedules {
+                replace_or_push(&mut state.schedules, schedule, |s| s.id());
+            }
+            for policy in &changes.policies {
+                replace_or_push(&mut state.policies, policy, |p| p.id());
+            }
+            state.routes = changes.routes.clone();
+            state.default_policy_id = changes.default_policy_id.clone();
+            *self.commits.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339("2025-01-15T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn reachable(name: &str) -> User {
+        let mut user = User::new(name.to_string(), format!("{name}@example.com"), Role::User);
+        user.set_slack_id(format!("U-{name}"));
+        user.mark_verified(Channel::Slack, now()).unwrap();
+        user
+    }
+
+    type TestService = ConfigService<MockConfigStore, MockAuditLog>;
+
+    fn make_service(usernames: &[&str]) -> TestService {
+        let store = MockConfigStore::default();
+        store.state.lock().unwrap().users = usernames.iter().map(|n| reachable(n)).collect();
+        ConfigService::new(store, MockAuditLog::default())
+    }
+
+    fn state(svc: &TestService) -> ConfigSnapshot {
+        svc.store.state.lock().unwrap().clone()
+    }
+
+    fn schedule_spec(participants: &[&str]) -> ScheduleSpec {
+        ScheduleSpec {
+            name: "platform-team".into(),
+            line: 3,
+            timezone: "Europe/Zurich".parse().unwrap(),
+            rotation: Rotation::Weekly,
+            participants: participants.iter().map(|p| p.to_string()).collect(),
+            handoff: HandoffTime {
+                day: Weekday::Mon,
+                hour: 9,
+                minute: 0,
+            },
+        }
+    }
+
+    fn spec(participants: &[&str]) -> ConfigSpec {
+        ConfigSpec {
+            users: vec![],
+            schedules: vec![schedule_spec(participants)],
+            policies: vec![PolicySpec {
+                name: "platform-critical".into(),
+                line: 10,
+                steps: vec![
+                    StepSpec {
+                        wait_seconds: 0,
+                        targets: vec![TargetSpec::OnCall {
+                            schedule: "platform-team".into(),
+                            modifier: OnCallModifier::Current,
+                        }],
+                        channels: vec![Channel::Slack],
+                    },
+                    StepSpec {
+                        wait_seconds: 600,
+                        targets: vec![TargetSpec::Named("alice".into())],
+                        channels: vec![Channel::Slack, Channel::Phone],
+                    },
+                ],
+                repeat: 1,
+                ack_timeout_secs: None,
+            }],
+            routes: vec![RouteSpec {
+                line: 20,
+                matchers: vec![Matcher::equal("severity", "critical")],
+                policy: Some("platform-critical".into()),
+                ..Default::default()
+            }],
+            default_policy: None,
+        }
+    }
+
+    fn names(plan: &Plan, action: Action) -> Vec<String> {
+        plan.changes
+            .iter()
+            .filter(|c| c.action == action)
+            .map(|c| format!("{}.{}", c.kind.as_str(), c.name))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn apply_creates_then_is_idempotent() {
+        let svc = make_service(&["alice", "bob"]);
+
+        let first = svc.apply(&spec(&["alice", "bob"]), now()).await.unwrap();
+        assert_eq!(
+            names(&first, Action::Create),
+            [
+                "schedule.platform-team",
+                "policy.platform-critical",
+                "route.1"
+            ]
+        );
+        let stored = state(&svc);
+        assert!(stored.schedules[0].is_managed());
+        assert_eq!(
+            stored.routes[0].policy_id.as_ref(),
+            Some(stored.policies[0].id())
+        );
+
+        let second = svc.apply(&spec(&["alice", "bob"]), now()).await.unwrap();
+        assert!(second.is_empty());
+        assert_eq!(second.unchanged, 3);
+        assert_eq!(*svc.store.commits.lock().unwrap(), 1);
+
+        let entries = svc.audit.entries.lock().unwrap();
+        let audited: Vec<(&str, &str)> = entries
+            .iter()
+            .map(|e| (e.action.as_str(), e.target.as_str()))
+            .collect();
+        assert_eq!(
+            audited,
+            [
+                ("schedule.create", "schedule:platform-team"),
+                ("policy.create", "policy:platform-critical"),
+                ("route.create", "route:1"),
+            ]
+        );
+        assert_eq!(entries[0].actor, Actor::config());
+        assert!(!entries[0].changes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn changes_update_in_place_and_plan_writes_nothing() {
+        let svc = make_service(&["alice", "bob"]);
+        svc.apply(&spec(&["alice", "bob"]), now()).await.unwrap();
+        let id = state(&svc).schedules[0].id().clone();
+
+        let mut changed = spec(&["bob", "alice"]);
+        changed.policies[0].steps[1].wait_seconds = 300;
+        let plan = svc.plan(&changed, now()).await.unwrap();
+        assert_eq!(
+            names(&plan, Action::Update),
+            ["schedule.platform-team", "policy.platform-critical"]
+        );
+        assert_eq!(plan.changes[0].fields[0].field, "participants");
+        assert_eq!(plan.changes[1].fields[0].field, "steps.2.wait_seconds");
+        assert_eq!(*svc.store.commits.lock().unwrap(), 1);
+
+        svc.apply(&changed, now()).await.unwrap();
+        let stored = state(&svc);
+        assert_eq!(stored.schedules[0].id(), &id);
+        assert_eq!(stored.schedules[0].participants()[0], *stored.users[1].id());
+    }
+
+    #[tokio::test]
+    async fn ack_timeout_is_planned_and_stored() {
+        let svc = make_service(&["alice"]);
+        svc.apply(&spec(&["alice"]), now()).await.unwrap();
+
+        let mut changed = spec(&["alice"]);
+        changed.policies[0].ack_timeout_secs = Some(1800);
+        let plan = svc.plan(&changed, now()).await.unwrap();
+        assert_eq!(names(&plan, Action::Update), ["policy.platform-critical"]);
+        assert_eq!(plan.changes[0].fields[0].field, "ack_timeout_seconds");
+
+        svc.apply(&changed, now()).await.unwrap();
+        assert_eq!(state(&svc).policies[0].ack_timeout_secs(), Some(1800));
+    }
+
+    #[tokio::test]
+    async fn managed_objects_no_longer_declared_are_deleted() {
+        let svc = make_service(&["alice"]);
+        let mut spec = spec(&["alice"]);
+        spec.users.push(UserSpec {
+            name: "carol".into(),
+            line: 1,
+            email: "carol@example.com".into(),
+            role: Role::Viewer,
+        });
+        svc.apply(&spec, now()).await.unwrap();
+
+        let plan = svc.apply(&ConfigSpec::default(), now()).await.unwrap();
+        assert_eq!(
+            names(&plan, Action::Delete),
+            [
+                "user.carol",
+                "schedule.platform-team",
+                "policy.platform-critical",
+                "route.1"
+            ]
+        );
+        let stored = state(&svc);
+        // alice was never declared, so she stays.
+        assert_eq!(stored.users.len(), 1);
+        assert!(stored.schedules.is_empty() && stored.routes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn declaring_an_existing_user_adopts_it() {
+        let svc = make_service(&["alice"]);
+        let spec = ConfigSpec {
+            users: vec![UserSpec {
+                name: "alice".into(),
+                line: 2,
+                email: "alice@example.com".into(),
+                role: Role::Admin,
+            }],
+            ..Default::default()
+        };
+
+        let plan = svc.apply(&spec, now()).await.unwrap();
+        let fields: Vec<&str> = plan.changes[0]
+            .fields
+            .iter()
+            .map(|f| f.field.as_str())
+            .collect();
+        assert_eq!(names(&plan, Action::Update), ["user.alice"]);
+        assert_eq!(fields, ["managed", "role"]);
+        assert!(state(&svc).users[0].is_managed());
+    }
+
+    #[tokio::test]
+    async fn every_problem_is_reported_with_its_line_and_nothing_is_written() {
+        let svc = make_service(&["alice"]);
+        let mut spec = spec(&["alice", "dave"]);
+        spec.routes[0].policy = Some("missing".into());
+
+        let Err(AppError::Config(message)) = svc.apply(&spec, now()).await else {
+            panic!("expected a config error");
+        };
+        assert!(message.contains("line 3: schedule `platform-team`: unknown user `dave`"));
+        // The policy only fails because the schedule did; that is not repeated.
+        assert!(!message.contains("line 10"));
+        assert!(message.contains("line 20: route: unknown escalation policy `missing`"));
+        assert_eq!(*svc.store.commits.lock().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn default_policy_is_set_and_cleared_as_the_default_route() {
+        let svc = make_service(&["alice", "bob"]);
+        let mut spec = spec(&["alice", "bob"]);
+        spec.default_policy = Some(DefaultPolicySpec {
+            name: "missing".into(),
+            line: 30,
+        });
+        let Err(AppError::Config(message)) = svc.apply(&spec, now()).await else {
+            panic!("expected a config error");
+        };
+        assert_eq!(
+            message,
+            "line 30: default_policy: unknown escalation policy `missing`"
+        );
+
+        spec.default_policy.as_mut().unwrap().name = "platform-critical".into();
+        let plan = svc.apply(&spec, now()).await.unwrap();
+        assert!(names(&plan, Action::Create).contains(&"route.default".to_string()));
+        let stored = state(&svc);
+        assert_eq!(
+            stored.default_policy_id.as_ref(),
+            Some(stored.policies[0].id())
+        );
+        assert!(svc.apply(&spec, now()).await.unwrap().is_empty());
+
+        spec.default_policy = None;
+        let plan = svc.apply(&spec, now()).await.unwrap();
+        assert_eq!(names(&plan, Action::Delete), ["route.default"]);
+        assert_eq!(state(&svc).default_policy_id, None);
+    }
+
+    #[tokio::test]
+    async fn child_routes_are_resolved_and_diffed_with_their_parent() {
+        let svc = make_service(&["alice", "bob"]);
+        let mut spec = spec(&["alice", "bob"]);
+        spec.routes[0].routes = vec![RouteSpec {
+            line: 23,
+            matchers: vec!["env=staging".parse().unwrap()],
+            policy: Some("missing".into()),
+            ..Default::default()
+        }];
+        let Err(AppError::Config(message)) = svc.apply(&spec, now()).await else {
+            panic!("expected a config error");
+        };
+        assert_eq!(
+            message,
+            "line 23: route: unknown escalation policy `missing`"
+        );
+
+        spec.routes[0].routes[0].policy = None;
+        svc.apply(&spec, now()).await.unwrap();
+        assert_eq!(state(&svc).routes[0].routes[0].policy_id, None);
+
+        spec.routes[0].routes[0].options.suppress = Some(true);
+        let plan = svc.plan(&spec, now()).await.unwrap();
+        assert_eq!(names(&plan, Action::Update), ["route.1"]);
+        assert_eq!(plan.changes[0].fields[0].field, "routes");
+    }
+
+    #[tokio::test]
+    async fn routes_with_invalid_patterns_are_rejected() {
+        let svc = make_service(&["alice"]);
+        let mut spec = spec(&["alice"]);
+        spec.routes[0].matchers = vec!["service=~\"(api\"".parse().unwrap()];
+
+        let Err(AppError::Config(message)) = svc.apply(&spec, now()).await else {
+            panic!("expected a config error");
+        };
+        assert!(message.starts_with("line 20: route: "), "{message}");
+        assert!(message.contains("invalid pattern"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn undeclaring_a_user_still_on_an_unmanaged_schedule_is_an_error() {
+        let svc = make_service(&["alice"]);
+        let mut spec = spec(&["alice", "bob"]);
+        spec.users.push(UserSpec {
+            name: "bob".into(),
+            line: 1,
+            email: "bob@example.com".into(),
+            role: Role::User,
+        });
+        svc.store.state.lock().unwrap().users.push(reachable("bob"));
+        svc.apply(&spec, now()).await.unwrap();
+        svc.store.state.lock().unwrap().schedules[0].set_managed(false);
+
+        spec.users.clear();
+        spec.schedules.clear();
+        let Err(AppError::Config(message)) = svc.apply(&spec, now()).await else {
+            panic!("expected a config error");
+        };
+        assert!(
+            message.contains("schedule `platform-team` still has `bob`"),
+            "{message}"
+        );
+    }
+
+    #[tokio::test]
+    async fn schedules_with_hard_lint_errors_are_rejected() {
+        let svc = make_service(&["alice"]);
+        let unreachable = User::new("bob".into(), "bob@example.com".into(), Role::User);
+        svc.store.state.lock().unwrap().users.push(unreachable);
+
+        let Err(AppError::Config(message)) = svc.apply(&spec(&["alice", "bob"]), now()).await
+        else {
+            panic!("expected a config error");
+        };
+        assert!(message.contains("`bob` cannot be reached"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn declared_users_without_contacts_only_warn() {
+        let svc = make_service(&[]);
+        let mut spec = spec(&["alice", "bob"]);
+        for name in ["alice", "bob"] {
+            spec.users.push(UserSpec {
+                name: name.into(),
+                line: 1,
+                email: format!("{name}@example.com"),
+                role: Role::User,
+            });
+        }
+
+        let plan = svc.apply(&spec, now()).await.unwrap();
+        assert_eq!(plan.count(Action::Create), 5);
+        assert_eq!(plan.warnings.len(), 2);
+        assert!(
+            plan.warnings[0].contains("`alice` cannot be reached"),
+            "{:?}",
+            plan.warnings
+        );
+        let stored = state(&svc);
+        assert_eq!(stored.users.len(), 2);
+        assert_eq!(stored.schedules.len(), 1);
+    }
+}
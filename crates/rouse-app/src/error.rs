@@ -1,5 +1,5 @@
 use rouse_core::error::DomainError;
-use rouse_ports::error::{ParseError, PortError};
+use rouse_ports::error::{IdentityError, NotifyError, ParseError, PortError};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -10,6 +10,15 @@ pub enum AppError {
     Port(#[from] PortError),
     #[error("parse error: {0}")]
     Parse(#[from] ParseError),
+    #[error("notification error: {0}")]
+    Notify(#[from] NotifyError),
+    #[error("single sign-on failed: {0}")]
+    Identity(#[from] IdentityError),
+    #[error("authentication required")]
+    Unauthenticated,
     #[error("routing error: {0}")]
     Routing(String),
+    /// One problem per line, each prefixed with where it is in the file.
+    #[error("invalid configuration:\n{0}")]
+    Config(String),
 }
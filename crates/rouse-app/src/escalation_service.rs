@@ -0,0 +1,743 @@
+use chrono::{DateTime, TimeDelta, Utc};
+
+use rouse_core::alert::Status;
+use rouse_core::error::DomainError;
+use rouse_core::escalation::TargetResolver;
+use rouse_core::events::{AlertEscalated, DomainEvent};
+use rouse_core::user::QuietDecision;
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::{
+    AlertRepository, EscalationQueue, EscalationRepository, EventPublisher, NotificationQueue,
+    ScheduleRepository, TeamRepository, UserRepository,
+};
+use rouse_ports::types::{PendingEscalation, PendingNotification, QueueStatus};
+
+use crate::error::AppError;
+
+/// Fires due escalation steps: resolves each step's targets to people and
+/// queues one notification per person and channel, timed by their rules.
+pub struct EscalationService<A, P, EQ, NQ, EP, S, T, U>
+where
+    A: AlertRepository,
+    P: EscalationRepository,
+    EQ: EscalationQueue,
+    NQ: NotificationQueue,
+    EP: EventPublisher,
+    S: ScheduleRepository,
+    T: TeamRepository,
+    U: UserRepository,
+{
+    alerts: A,
+    policies: P,
+    escalation_queue: EQ,
+    notifications: NQ,
+    events: EP,
+    schedules: S,
+    teams: T,
+    users: U,
+}
+
+impl<A, P, EQ, NQ, EP, S, T, U> EscalationService<A, P, EQ, NQ, EP, S, T, U>
+where
+    A: AlertRepository,
+    P: EscalationRepository,
+    EQ: EscalationQueue,
+    NQ: NotificationQueue,
+    EP: EventPublisher,
+    S: ScheduleRepository,
+    T: TeamRepository,
+    U: UserRepository,
+{
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        alerts: A,
+        policies: P,
+        escalation_queue: EQ,
+        notifications: NQ,
+        events: EP,
+        schedules: S,
+        teams: T,
+        users: U,
+    ) -> Self {
+        Self {
+            alerts,
+            policies,
+            escalation_queue,
+            notifications,
+            events,
+            schedules,
+            teams,
+            users,
+        }
+    }
+
+    /// Fires every due step and returns how many were processed. A step
+    /// that fails is logged and does not hold up the others.
+    pub async fn fire_due(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let due = self.escalation_queue.poll_due().await?;
+        for step in &due {
+            if let Err(e) = self.fire(step, now).await {
+                tracing::warn!(
+                    step = %step.id,
+                    alert = %step.alert_id,
+                    "escalation step failed: {e}"
+                );
+            }
+        }
+        Ok(due.len())
+    }
+
+    async fn fire(&self, pending: &PendingEscalation, now: DateTime<Utc>) -> Result<(), AppError> {
+        self.escalation_queue.mark_fired(&pending.id).await?;
+
+        let mut alert = self
+            .alerts
+            .find_by_id(&pending.alert_id.to_string())
+            .await?
+            .ok_or(AppError::Port(PortError::NotFound))?;
+        if alert.status() != Status::Firing {
+            return Ok(());
+        }
+
+        let policy = self
+            .policies
+            .find_by_id(&pending.policy_id.to_string())
+            .await?
+            .ok_or(AppError::Port(PortError::NotFound))?;
+        let Some(step) = policy.steps().get(pending.step_order as usize) else {
+            return Ok(());
+        };
+        // Kept so an acknowledgement that runs out resumes after this step.
+        alert.record_page(
+            policy.id().clone(),
+            pending.step_order,
+            pending.repetition,
+            policy.ack_timeout_secs(),
+        );
+        self.alerts.save(&alert).await?;
+
+        let schedules = self.schedules.list_all().await?;
+        let teams = self.teams.list_all().await?;
+        let resolver = TargetResolver {
+            schedules: &schedules,
+            teams: &teams,
+        };
+
+        // A handed-over alert pages only whom it was handed to.
+        let targets = match &pending.target {
+            Some(target) => std::slice::from_ref(target),
+            None => step.targets(),
+        };
+        let paged = resolver.resolve_all(targets, now);
+        for user_id in &paged {
+            let Some(user) = self.users.find_by_id(&user_id.to_string()).await? else {
+                continue;
+            };
+            let mut fallback_sent = false;
+            for planned in user.notification_plan(alert.severity(), step.channels()) {
+                let send_at = after(now, planned.delay_secs)?;
+                let (channel, target, send_at) =
+                    match user.quiet_decision(alert.severity(), send_at) {
+                        QuietDecision::Deliver => (planned.channel, planned.target, send_at),
+                        QuietDecision::Defer(until) => (planned.channel, planned.target, until),
+                        // One fallback message per step is enough.
+                        QuietDecision::Fallback(_) if fallback_sent => continue,
+                        QuietDecision::Fallback(channel) => {
+                            let Some(target) = user.verified_contact(channel) else {
+                                continue;
+                            };
+                            fallback_sent = true;
+                            (channel, target.to_string(), send_at)
+                        }
+                    };
+                self.notifications
+                    .enqueue(PendingNotification {
+                        id: uuid::Uuid::new_v4().to_string(),
+                        alert_id: alert.id().clone(),
+                        channel,
+                        target,
+                        payload: alert.summary().to_string(),
+                        status: QueueStatus::Pending,
+                        next_attempt_at: send_at,
+                        retry_count: 0,
+                        created_at: now,
+                    })
+                    .await?;
+            }
+        }
+
+        self.events
+            .publish(vec![DomainEvent::AlertEscalated(AlertEscalated {
+                alert_id: alert.id().clone(),
+                step: pending.step_order + 1,
+                targets: paged.iter().map(ToString::to_string).collect(),
+                occurred_at: now,
+            })])
+            .await?;
+        if pending.target.is_some() {
+            return Ok(());
+        }
+
+        let next = policy.next_step(pending.step_order, pending.repetition);
+        let position = policy.next_position(pending.step_order, pending.repetition);
+        if let (Some(next), Some((step_order, repetition))) = (next, position) {
+            self.escalation_queue
+                .enqueue_step(PendingEscalation {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    alert_id: pending.alert_id.clone(),
+                    policy_id: pending.policy_id.clone(),
+                    step_order,
+                    repetition,
+                    fires_at: after(now, next.wait_seconds())?,
+                    status: QueueStatus::Pending,
+                    target: None,
+                })
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// `secs` from `now`, or an error rather than a panic when that is out
+/// of range.
+fn after(now: DateTime<Utc>, secs: u64) -> Result<DateTime<Utc>, AppError> {
+    i64::try_from(secs)
+        .ok()
+        .and_then(TimeDelta::try_seconds)
+        .and_then(|delay| now.checked_add_signed(delay))
+        .ok_or_else(|| DomainError::DelayTooLong(secs).into())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::schedule_service::tests::MockScheduleRepo;
+    use crate::user_service::tests::MockUserRepo;
+    use async_trait::async_trait;
+    use chrono::Duration;
+    use rouse_core::alert::{Alert, Severity, Source};
+    use rouse_core::channel::Channel;
+    use rouse_core::escalation::{EscalationPolicy, EscalationStep, EscalationTarget};
+    use rouse_core::ids::{AlertId, PolicyId, UserId};
+    use rouse_core::user::{DndWindow, NotificationRule, Role, Team, User};
+    use rouse_ports::types::{AlertFilter, AlertOccurrence};
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    pub(crate) struct MockAlertRepo {
+        pub(crate) alerts: Mutex<Vec<Alert>>,
+    }
+
+    #[async_trait]
+    impl AlertRepository for MockAlertRepo {
+        async fn save(&self, alert: &Alert) -> Result<(), PortError> {
+            let mut alerts = self.alerts.lock().unwrap();
+            alerts.retain(|a| a.id() != alert.id());
+            alerts.push(alert.clone());
+            Ok(())
+        }
+        async fn find_by_id(&self, id: &str) -> Result<Option<Alert>, PortError> {
+            let alerts = self.alerts.lock().unwrap();
+            Ok(alerts.iter().find(|a| a.id().to_string() == id).cloned())
+        }
+        async fn find_by_fingerprint(&self, _fp: &str) -> Result<Option<Alert>, PortError> {
+            Ok(None)
+        }
+        async fn find_open_by_fingerprint(&self, _fp: &str) -> Result<Option<Alert>, PortError> {
+            Ok(None)
+        }
+        async fn find_by_filter(&self, _filter: &AlertFilter) -> Result<Vec<Alert>, PortError> {
+            Ok(vec![])
+        }
+        async fn find_snoozed_due(&self, _now: DateTime<Utc>) -> Result<Vec<Alert>, PortError> {
+            Ok(vec![])
+        }
+        async fn find_ack_expired(&self, _now: DateTime<Utc>) -> Result<Vec<Alert>, PortError> {
+            Ok(vec![])
+        }
+        async fn count(&self, _filter: &AlertFilter) -> Result<u64, PortError> {
+            Ok(0)
+        }
+        async fn record_occurrence(
+            &self,
+            _occurrence: &AlertOccurrence,
+            _keep: usize,
+        ) -> Result<(), PortError> {
+            Ok(())
+        }
+        async fn occurrences(
+            &self,
+            _alert_id: &AlertId,
+        ) -> Result<Vec<AlertOccurrence>, PortError> {
+            Ok(vec![])
+        }
+    }
+
+    #[derive(Default)]
+    pub(crate) struct MockPolicyRepo {
+        pub(crate) policies: Mutex<Vec<EscalationPolicy>>,
+    }
+
+    #[async_trait]
+    impl EscalationRepository for MockPolicyRepo {
+        async fn save(&self, policy: &EscalationPolicy) -> Result<(), PortError> {
+            let mut policies = self.policies.lock().unwrap();
+            policies.retain(|p| p.id() != policy.id());
+            policies.push(policy.clone());
+            Ok(())
+        }
+        async fn find_by_id(&self, id: &str) -> Result<Option<EscalationPolicy>, PortError> {
+            let policies = self.policies.lock().unwrap();
+            Ok(policies.iter().find(|p| p.id().to_string() == id).cloned())
+        }
+        async fn list_all(&self) -> Result<Vec<EscalationPolicy>, PortError> {
+            Ok(self.policies.lock().unwrap().clone())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEscalationQueue {
+        due: Mutex<Vec<PendingEscalation>>,
+        enqueued: Mutex<Vec<PendingEscalation>>,
+        fired: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl EscalationQueue for MockEscalationQueue {
+        async fn enqueue_step(&self, step: PendingEscalation) -> Result<(), PortError> {
+            self.enqueued.lock().unwrap().push(step);
+            Ok(())
+        }
+        async fn poll_due(&self) -> Result<Vec<PendingEscalation>, PortError> {
+            Ok(std::mem::take(&mut *self.due.lock().unwrap()))
+        }
+        async fn cancel_for_alert(&self, _alert_id: &str) -> Result<(), PortError> {
+            Ok(())
+        }
+        async fn mark_fired(&self, id: &str) -> Result<(), PortError> {
+            self.fired.lock().unwrap().push(id.to_string());
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockNotificationQueue {
+        queued: Mutex<Vec<PendingNotification>>,
+    }
+
+    #[async_trait]
+    impl NotificationQueue for MockNotificationQueue {
+        async fn enqueue(&self, notification: PendingNotification) -> Result<(), PortError> {
+            self.queued.lock().unwrap().push(notification);
+            Ok(())
+        }
+        async fn poll_pending(&self) -> Result<Vec<PendingNotification>, PortError> {
+            Ok(vec![])
+        }
+        async fn mark_sent(&self, _id: &str) -> Result<(), PortError> {
+            Ok(())
+        }
+        async fn mark_failed(
+            &self,
+            _id: &str,
+            _error: &str,
+            _next_attempt: DateTime<Utc>,
+        ) -> Result<(), PortError> {
+            Ok(())
+        }
+        async fn mark_dead(&self, _id: &str) -> Result<(), PortError> {
+            Ok(())
+        }
+        async fn cancel_for_alert(&self, _alert_id: &str) -> Result<(), PortError> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockEventPublisher {
+        events: Mutex<Vec<DomainEvent>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockEventPublisher {
+        async fn publish(&self, events: Vec<DomainEvent>) -> Result<(), PortError> {
+            self.events.lock().unwrap().extend(events);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockTeamRepo {
+        teams: Mutex<Vec<Team>>,
+    }
+
+    #[async_trait]
+    impl TeamRepository for MockTeamRepo {
+        async fn save(&self, team: &Team) -> Result<(), PortError> {
+            self.teams.lock().unwrap().push(team.clone());
+            Ok(())
+        }
+        async fn find_by_id(&self, id: &str) -> Result<Option<Team>, PortError> {
+            let teams = self.teams.lock().unwrap();
+            Ok(teams.iter().find(|t| t.id().to_string() == id).cloned())
+        }
+        async fn list_all(&self) -> Result<Vec<Team>, PortError> {
+            Ok(self.teams.lock().unwrap().clone())
+        }
+        async fn delete(&self, _id: &str) -> Result<(), PortError> {
+            Ok(())
+        }
+    }
+
+    type TestService = EscalationService<
+        MockAlertRepo,
+        MockPolicyRepo,
+        MockEscalationQueue,
+        MockNotificationQueue,
+        MockEventPublisher,
+        MockScheduleRepo,
+        MockTeamRepo,
+        MockUserRepo,
+    >;
+
+    fn make_service() -> TestService {
+        EscalationService::new(
+            MockAlertRepo::default(),
+            MockPolicyRepo::default(),
+            MockEscalationQueue::default(),
+            MockNotificationQueue::default(),
+            MockEventPublisher::default(),
+            MockScheduleRepo::default(),
+            MockTeamRepo::default(),
+            MockUserRepo::default(),
+        )
+    }
+
+    fn now() -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339("2025-01-15T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn add_user(svc: &TestService, rules: Vec<NotificationRule>) -> UserId {
+        let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        user.set_slack_id("U123".into());
+        user.set_contact(Channel::Phone, Some("+41791234567".into()))
+            .unwrap();
+        user.mark_verified(Channel::Slack, now()).unwrap();
+        user.mark_verified(Channel::Phone, now()).unwrap();
+        user.set_notification_rules(rules);
+        let id = user.id().clone();
+        svc.users.users.lock().unwrap().push(user);
+        id
+    }
+
+    fn add_alert(svc: &TestService, severity: Severity) -> Alert {
+        let (alert, _) = Alert::new(
+            "ext-1".into(),
+            Source::new("alertmanager"),
+            severity,
+            BTreeMap::new(),
+            "High CPU".into(),
+            now(),
+        );
+        svc.alerts.alerts.lock().unwrap().push(alert.clone());
+        alert
+    }
+
+    fn add_policy(svc: &TestService, user: &UserId) -> PolicyId {
+        add_repeating_policy(svc, user, 0)
+    }
+
+    fn add_repeating_policy(svc: &TestService, user: &UserId, repeat_count: u32) -> PolicyId {
+        let steps = vec![
+            EscalationStep::new(
+                0,
+                0,
+                vec![EscalationTarget::User(user.clone())],
+                vec![Channel::Slack],
+            ),
+            EscalationStep::new(
+                1,
+                600,
+                vec![EscalationTarget::User(UserId::new())],
+                vec![Channel::Slack],
+            ),
+        ];
+        let policy = EscalationPolicy::new("primary".into(), steps, repeat_count).unwrap();
+        let id = policy.id().clone();
+        svc.policies.policies.lock().unwrap().push(policy);
+        id
+    }
+
+    fn due(svc: &TestService, alert: &Alert, policy_id: &PolicyId, step_order: u32) {
+        svc.escalation_queue
+            .due
+            .lock()
+            .unwrap()
+            .push(PendingEscalation {
+                id: format!("step-{step_order}"),
+                alert_id: alert.id().clone(),
+                policy_id: policy_id.clone(),
+                step_order,
+                repetition: 0,
+                fires_at: now(),
+                status: QueueStatus::Pending,
+                target: None,
+            });
+    }
+
+    fn paging_rules() -> Vec<NotificationRule> {
+        vec![
+            NotificationRule::new(Channel::Slack, 0),
+            NotificationRule::new(Channel::Sms, 120).for_severities(vec![Severity::Critical]),
+            NotificationRule::new(Channel::Phone, 300).for_severities(vec![Severity::Critical]),
+        ]
+    }
+
+    #[tokio::test]
+    async fn critical_alert_follows_user_rules() {
+        let svc = make_service();
+        let user = add_user(&svc, paging_rules());
+        let alert = add_alert(&svc, Severity::Critical);
+        let policy_id = add_policy(&svc, &user);
+        due(&svc, &alert, &policy_id, 0);
+
+        assert_eq!(svc.fire_due(now()).await.unwrap(), 1);
+
+        let queued = svc.notifications.queued.lock().unwrap();
+        let plan: Vec<_> = queued
+            .iter()
+            .map(|n| (n.channel, (n.next_attempt_at - now()).num_seconds()))
+            .collect();
+        assert_eq!(
+            plan,
+            [
+                (Channel::Slack, 0),
+                (Channel::Sms, 120),
+                (Channel::Phone, 300)
+            ]
+        );
+        assert_eq!(queued[1].target, "+41791234567");
+    }
+
+    #[tokio::test]
+    async fn warning_alert_only_uses_matching_rules() {
+        let svc = make_service();
+        let user = add_user(&svc, paging_rules());
+        let alert = add_alert(&svc, Severity::Warning);
+        let policy_id = add_policy(&svc, &user);
+        due(&svc, &alert, &policy_id, 0);
+
+        svc.fire_due(now()).await.unwrap();
+
+        let queued = svc.notifications.queued.lock().unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].channel, Channel::Slack);
+    }
+
+    #[tokio::test]
+    async fn user_without_rules_gets_step_channels() {
+        let svc = make_service();
+        let user = add_user(&svc, vec![]);
+        let alert = add_alert(&svc, Severity::Critical);
+        let policy_id = add_policy(&svc, &user);
+        due(&svc, &alert, &policy_id, 0);
+
+        svc.fire_due(now()).await.unwrap();
+
+        let queued = svc.notifications.queued.lock().unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].channel, Channel::Slack);
+        assert_eq!(queued[0].target, "U123");
+    }
+
+    fn quiet_user(svc: &TestService, fallback: Option<Channel>) -> UserId {
+        let id = add_user(svc, paging_rules());
+        let user = &mut svc.users.users.lock().unwrap()[0];
+        let window = DndWindow::new(now(), now() + Duration::hours(2)).unwrap();
+        user.add_dnd(window, now());
+        user.set_quiet_fallback(fallback);
+        id
+    }
+
+    #[tokio::test]
+    async fn quiet_user_gets_non_critical_alerts_later() {
+        let svc = make_service();
+        let user = quiet_user(&svc, None);
+        let alert = add_alert(&svc, Severity::Warning);
+        let policy_id = add_policy(&svc, &user);
+        due(&svc, &alert, &policy_id, 0);
+
+        svc.fire_due(now()).await.unwrap();
+
+        let queued = svc.notifications.queued.lock().unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].next_attempt_at, now() + Duration::hours(2));
+    }
+
+    #[tokio::test]
+    async fn quiet_user_is_still_paged_for_critical() {
+        let svc = make_service();
+        let user = quiet_user(&svc, None);
+        let alert = add_alert(&svc, Severity::Critical);
+        let policy_id = add_policy(&svc, &user);
+        due(&svc, &alert, &policy_id, 0);
+
+        svc.fire_due(now()).await.unwrap();
+
+        let queued = svc.notifications.queued.lock().unwrap();
+        assert_eq!(queued.len(), 3);
+        assert_eq!(queued[0].next_attempt_at, now());
+    }
+
+    #[tokio::test]
+    async fn quiet_user_fallback_channel_is_used_once() {
+        let svc = make_service();
+        let user = quiet_user(&svc, Some(Channel::Sms));
+        svc.users.users.lock().unwrap()[0].set_notification_rules(vec![
+            NotificationRule::new(Channel::Slack, 0),
+            NotificationRule::new(Channel::Phone, 60),
+        ]);
+        let alert = add_alert(&svc, Severity::Warning);
+        let policy_id = add_policy(&svc, &user);
+        due(&svc, &alert, &policy_id, 0);
+
+        svc.fire_due(now()).await.unwrap();
+
+        let queued = svc.notifications.queued.lock().unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].channel, Channel::Sms);
+        assert_eq!(queued[0].next_attempt_at, now());
+    }
+
+    #[tokio::test]
+    async fn firing_schedules_next_step() {
+        let svc = make_service();
+        let user = add_user(&svc, vec![]);
+        let alert = add_alert(&svc, Severity::Critical);
+        let policy_id = add_policy(&svc, &user);
+        due(&svc, &alert, &policy_id, 0);
+
+        svc.fire_due(now()).await.unwrap();
+
+        assert_eq!(*svc.escalation_queue.fired.lock().unwrap(), ["step-0"]);
+        let enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+        assert_eq!(enqueued.len(), 1);
+        assert_eq!(enqueued[0].step_order, 1);
+        assert_eq!(enqueued[0].fires_at, now() + Duration::seconds(600));
+        let alert = svc.alerts.alerts.lock().unwrap()[0].clone();
+        assert_eq!(alert.paged()[0].policy_id, policy_id);
+        assert_eq!(alert.paged()[0].step_order, 0);
+
+        let events = svc.events.events.lock().unwrap();
+        match &events[0] {
+            DomainEvent::AlertEscalated(e) => {
+                assert_eq!(e.step, 1);
+                assert_eq!(e.targets, [user.to_string()]);
+            }
+            other => panic!("expected AlertEscalated, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_step_does_not_hold_up_the_batch() {
+        let svc = make_service();
+        let user = add_user(&svc, vec![]);
+        let alert = add_alert(&svc, Severity::Critical);
+        // Its policy was deleted since the step was queued.
+        due(&svc, &alert, &PolicyId::new(), 0);
+        let policy_id = add_policy(&svc, &user);
+        due(&svc, &alert, &policy_id, 0);
+
+        assert_eq!(svc.fire_due(now()).await.unwrap(), 2);
+
+        assert_eq!(svc.notifications.queued.lock().unwrap().len(), 1);
+        assert_eq!(svc.escalation_queue.enqueued.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn delay_out_of_range_is_an_error() {
+        let svc = make_service();
+        let user = add_user(&svc, vec![NotificationRule::new(Channel::Slack, u64::MAX)]);
+        let alert = add_alert(&svc, Severity::Critical);
+        let policy_id = add_policy(&svc, &user);
+        due(&svc, &alert, &policy_id, 0);
+        let pending = svc.escalation_queue.due.lock().unwrap()[0].clone();
+
+        let result = svc.fire(&pending, now()).await;
+
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::DelayTooLong(u64::MAX)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn last_step_starts_over_while_repeats_remain() {
+        let svc = make_service();
+        let user = add_user(&svc, vec![]);
+        let alert = add_alert(&svc, Severity::Critical);
+        let policy_id = add_repeating_policy(&svc, &user, 1);
+        due(&svc, &alert, &policy_id, 1);
+
+        svc.fire_due(now()).await.unwrap();
+        {
+            let mut enqueued = svc.escalation_queue.enqueued.lock().unwrap();
+            assert_eq!(enqueued.len(), 1);
+            assert_eq!((enqueued[0].step_order, enqueued[0].repetition), (0, 1));
+            let mut again = enqueued.remove(0);
+            again.id = "step-1-again".into();
+            again.step_order = 1;
+            svc.escalation_queue.due.lock().unwrap().push(again);
+        }
+
+        svc.fire_due(now()).await.unwrap();
+        assert!(svc.escalation_queue.enqueued.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn handed_over_step_pages_only_its_target() {
+        let svc = make_service();
+        let user = add_user(&svc, vec![]);
+        let alert = add_alert(&svc, Severity::Critical);
+        let policy_id = add_policy(&svc, &UserId::new());
+        due(&svc, &alert, &policy_id, 1);
+        svc.escalation_queue.due.lock().unwrap()[0].target =
+            Some(EscalationTarget::User(user.clone()));
+
+        svc.fire_due(now()).await.unwrap();
+
+        let queued = svc.notifications.queued.lock().unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].target, "U123");
+        assert!(svc.escalation_queue.enqueued.lock().unwrap().is_empty());
+        let events = svc.events.events.lock().unwrap();
+        match &events[0] {
+            DomainEvent::AlertEscalated(e) => {
+                assert_eq!(e.step, 2);
+                assert_eq!(e.targets, [user.to_string()]);
+            }
+            other => panic!("expected AlertEscalated, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn acknowledged_alert_is_not_paged() {
+        let svc = make_service();
+        let user = add_user(&svc, paging_rules());
+        let mut alert = add_alert(&svc, Severity::Critical);
+        alert.acknowledge(user.clone(), now()).unwrap();
+        svc.alerts.save(&alert).await.unwrap();
+        let policy_id = add_policy(&svc, &user);
+        due(&svc, &alert, &policy_id, 0);
+
+        svc.fire_due(now()).await.unwrap();
+
+        assert!(svc.notifications.queued.lock().unwrap().is_empty());
+        assert!(svc.escalation_queue.enqueued.lock().unwrap().is_empty());
+        assert_eq!(svc.escalation_queue.fired.lock().unwrap().len(), 1);
+    }
+}
@@ -1,6 +1,16 @@
 pub mod alert_service;
+pub mod audit;
+pub mod auth_service;
+pub mod config_service;
 pub mod error;
+pub mod escalation_service;
 pub mod grouping_service;
 pub mod noise_service;
+pub mod notification_service;
 pub mod router;
+pub mod routing_service;
 pub mod schedule_service;
+pub mod sso_service;
+pub mod team_service;
+pub mod user_service;
+pub mod verification_service;
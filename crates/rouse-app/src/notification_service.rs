@@ -0,0 +1,312 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+
+use rouse_ports::error::NotifyError;
+use rouse_ports::outbound::{AlertRepository, NotificationQueue, Notifier};
+use rouse_ports::types::{Notification, PendingNotification};
+
+use crate::error::AppError;
+
+/// Failed attempts retried before a notification is given up as dead.
+pub const MAX_RETRIES: u32 = 5;
+
+/// Delivers queued notifications through the notifier for their channel,
+/// retrying failures with backoff.
+pub struct NotificationService<A, NQ>
+where
+    A: AlertRepository,
+    NQ: NotificationQueue,
+{
+    alerts: A,
+    queue: NQ,
+    notifiers: Vec<Arc<dyn Notifier>>,
+    base_url: String,
+}
+
+impl<A, NQ> NotificationService<A, NQ>
+where
+    A: AlertRepository,
+    NQ: NotificationQueue,
+{
+    /// `base_url` is where messages link back to.
+    pub fn new(alerts: A, queue: NQ, notifiers: Vec<Arc<dyn Notifier>>, base_url: String) -> Self {
+        Self {
+            alerts,
+            queue,
+            notifiers,
+            base_url,
+        }
+    }
+
+    /// Attempts every notification that is due and returns how many. One
+    /// that fails is logged and does not hold up the others.
+    pub async fn deliver_due(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let due = self.queue.poll_pending().await?;
+        for pending in &due {
+            if let Err(e) = self.deliver(pending, now).await {
+                tracing::warn!(
+                    notification = %pending.id,
+                    alert = %pending.alert_id,
+                    "notification delivery failed: {e}"
+                );
+            }
+        }
+        Ok(due.len())
+    }
+
+    async fn deliver(
+        &self,
+        pending: &PendingNotification,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let Some(alert) = self
+            .alerts
+            .find_by_id(&pending.alert_id.to_string())
+            .await?
+        else {
+            self.queue.mark_dead(&pending.id).await?;
+            return Ok(());
+        };
+        // Kept queued so it goes out once the channel is configured.
+        let Some(notifier) = self
+            .notifiers
+            .iter()
+            .find(|n| n.channel() == pending.channel)
+        else {
+            return self
+                .retry(pending, &NotifyError::ChannelUnavailable, now)
+                .await;
+        };
+
+        let notification = Notification {
+            alert_id: alert.id().clone(),
+            severity: alert.severity(),
+            summary: pending.payload.clone(),
+            labels: alert.labels().clone(),
+            target: pending.target.clone(),
+            base_url: self.base_url.clone(),
+        };
+        match notifier.notify(&notification).await {
+            Ok(_) => self.queue.mark_sent(&pending.id).await?,
+            // Retrying cannot fix an address the channel rejects.
+            Err(NotifyError::InvalidTarget) => self.queue.mark_dead(&pending.id).await?,
+            Err(e) => return self.retry(pending, &e, now).await,
+        }
+        Ok(())
+    }
+
+    async fn retry(
+        &self,
+        pending: &PendingNotification,
+        error: &NotifyError,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        if pending.retry_count >= MAX_RETRIES {
+            self.queue.mark_dead(&pending.id).await?;
+        } else {
+            let next_attempt = now + backoff(pending.retry_count);
+            self.queue
+                .mark_failed(&pending.id, &error.to_string(), next_attempt)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// 30 seconds after the first failure, doubling after each one after.
+fn backoff(retry_count: u32) -> Duration {
+    Duration::seconds(30 << retry_count.min(MAX_RETRIES))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::escalation_service::tests::MockAlertRepo;
+    use async_trait::async_trait;
+    use rouse_core::alert::{Alert, Severity, Source};
+    use rouse_core::channel::Channel;
+    use rouse_ports::error::PortError;
+    use rouse_ports::types::{NotifyResult, QueueStatus};
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    /// The status a notification was left in, and its retry time if failed.
+    type Outcome = (QueueStatus, Option<DateTime<Utc>>);
+
+    /// Records what became of each notification.
+    #[derive(Default)]
+    struct MockQueue {
+        due: Mutex<Vec<PendingNotification>>,
+        outcomes: Mutex<Vec<Outcome>>,
+    }
+
+    #[async_trait]
+    impl NotificationQueue for MockQueue {
+        async fn enqueue(&self, notification: PendingNotification) -> Result<(), PortError> {
+            self.due.lock().unwrap().push(notification);
+            Ok(())
+        }
+        async fn poll_pending(&self) -> Result<Vec<PendingNotification>, PortError> {
+            Ok(std::mem::take(&mut *self.due.lock().unwrap()))
+        }
+        async fn mark_sent(&self, _id: &str) -> Result<(), PortError> {
+            self.outcomes
+                .lock()
+                .unwrap()
+                .push((QueueStatus::Sent, None));
+            Ok(())
+        }
+        async fn mark_failed(
+            &self,
+            _id: &str,
+            _error: &str,
+            next_attempt: DateTime<Utc>,
+        ) -> Result<(), PortError> {
+            let outcome = (QueueStatus::Failed, Some(next_attempt));
+            self.outcomes.lock().unwrap().push(outcome);
+            Ok(())
+        }
+        async fn mark_dead(&self, _id: &str) -> Result<(), PortError> {
+            self.outcomes
+                .lock()
+                .unwrap()
+                .push((QueueStatus::Dead, None));
+            Ok(())
+        }
+        async fn cancel_for_alert(&self, _alert_id: &str) -> Result<(), PortError> {
+            Ok(())
+        }
+    }
+
+    /// Fails every notification with `fail`, if set.
+    struct MockNotifier {
+        fail: Option<fn() -> NotifyError>,
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl Notifier for MockNotifier {
+        async fn notify(&self, notification: &Notification) -> Result<NotifyResult, NotifyError> {
+            self.sent.lock().unwrap().push(notification.clone());
+            match self.fail {
+                Some(fail) => Err(fail()),
+                None => Ok(NotifyResult::default()),
+            }
+        }
+        async fn send_text(&self, _target: &str, _text: &str) -> Result<NotifyResult, NotifyError> {
+            Ok(NotifyResult::default())
+        }
+        fn channel(&self) -> Channel {
+            Channel::Slack
+        }
+    }
+
+    type TestService = NotificationService<MockAlertRepo, MockQueue>;
+
+    fn now() -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339("2025-01-15T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn setup(fail: Option<fn() -> NotifyError>) -> (TestService, Arc<MockNotifier>) {
+        let notifier = Arc::new(MockNotifier {
+            fail,
+            sent: Mutex::new(vec![]),
+        });
+        let svc = NotificationService::new(
+            MockAlertRepo::default(),
+            MockQueue::default(),
+            vec![notifier.clone()],
+            "https://rouse.example.com".into(),
+        );
+        (svc, notifier)
+    }
+
+    fn queue(svc: &TestService, channel: Channel, retry_count: u32) {
+        let (alert, _) = Alert::new(
+            "ext-1".into(),
+            Source::new("alertmanager"),
+            Severity::Critical,
+            BTreeMap::new(),
+            "High CPU".into(),
+            now(),
+        );
+        svc.queue.due.lock().unwrap().push(PendingNotification {
+            id: "n-1".into(),
+            alert_id: alert.id().clone(),
+            channel,
+            target: "U123".into(),
+            payload: alert.summary().into(),
+            status: QueueStatus::Pending,
+            next_attempt_at: now(),
+            retry_count,
+            created_at: now(),
+        });
+        svc.alerts.alerts.lock().unwrap().push(alert);
+    }
+
+    fn outcome(svc: &TestService) -> Outcome {
+        let outcomes = svc.queue.outcomes.lock().unwrap();
+        assert_eq!(outcomes.len(), 1);
+        outcomes[0]
+    }
+
+    #[tokio::test]
+    async fn delivered_notification_is_marked_sent() {
+        let (svc, notifier) = setup(None);
+        queue(&svc, Channel::Slack, 0);
+
+        assert_eq!(svc.deliver_due(now()).await.unwrap(), 1);
+
+        assert_eq!(outcome(&svc), (QueueStatus::Sent, None));
+        let sent = notifier.sent.lock().unwrap();
+        assert_eq!(sent[0].target, "U123");
+        assert_eq!(sent[0].summary, "High CPU");
+        assert_eq!(sent[0].base_url, "https://rouse.example.com");
+    }
+
+    #[tokio::test]
+    async fn failed_delivery_backs_off() {
+        let (svc, _) = setup(Some(|| NotifyError::RateLimited));
+        queue(&svc, Channel::Slack, 2);
+
+        svc.deliver_due(now()).await.unwrap();
+
+        let retry_at = now() + Duration::seconds(120);
+        assert_eq!(outcome(&svc), (QueueStatus::Failed, Some(retry_at)));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let (svc, _) = setup(Some(|| NotifyError::DeliveryFailed("boom".into())));
+        queue(&svc, Channel::Slack, MAX_RETRIES);
+
+        svc.deliver_due(now()).await.unwrap();
+
+        assert_eq!(outcome(&svc), (QueueStatus::Dead, None));
+    }
+
+    #[tokio::test]
+    async fn rejected_target_is_not_retried() {
+        let (svc, _) = setup(Some(|| NotifyError::InvalidTarget));
+        queue(&svc, Channel::Slack, 0);
+
+        svc.deliver_due(now()).await.unwrap();
+
+        assert_eq!(outcome(&svc), (QueueStatus::Dead, None));
+    }
+
+    #[tokio::test]
+    async fn unconfigured_channel_waits_for_a_notifier() {
+        let (svc, notifier) = setup(None);
+        queue(&svc, Channel::Sms, 0);
+
+        svc.deliver_due(now()).await.unwrap();
+
+        let retry_at = now() + Duration::seconds(30);
+        assert_eq!(outcome(&svc), (QueueStatus::Failed, Some(retry_at)));
+        assert!(notifier.sent.lock().unwrap().is_empty());
+    }
+}
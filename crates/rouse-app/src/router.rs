@@ -1,94 +1,438 @@
 use std::collections::BTreeMap;
 
+use regex::Regex;
+use serde::Serialize;
+
+use rouse_core::alert::{Alert, Severity};
 use rouse_core::ids::PolicyId;
+use rouse_core::routing::{parse_severity, MatchField, MatchOp, Matcher, Route, RouteOptions};
+use rouse_ports::outbound::RouteRepository;
+
+use crate::error::AppError;
+
+enum Test {
+    Equal(String),
+    NotEqual(String),
+    Regex(Regex),
+    NotRegex(Regex),
+    Exists,
+    Absent,
+    AtLeast(Severity),
+}
+
+struct CompiledMatcher {
+    field: MatchField,
+    test: Test,
+}
 
-pub struct Route {
-    pub matchers: BTreeMap<String, String>,
+impl CompiledMatcher {
+    fn new(matcher: &Matcher) -> Result<Self, AppError> {
+        // Severities compare in any case, like everywhere else they are parsed.
+        let value = match matcher.field {
+            MatchField::Severity => matcher.value.to_ascii_lowercase(),
+            _ => matcher.value.clone(),
+        };
+        let pattern = || {
+            Regex::new(&format!("^(?:{value})$"))
+                .map_err(|e| AppError::Routing(format!("invalid pattern in `{matcher}`: {e}")))
+        };
+        let test = match matcher.op {
+            MatchOp::Equal => Test::Equal(value.clone()),
+            MatchOp::NotEqual => Test::NotEqual(value.clone()),
+            MatchOp::Regex => Test::Regex(pattern()?),
+            MatchOp::NotRegex => Test::NotRegex(pattern()?),
+            MatchOp::Exists => Test::Exists,
+            MatchOp::Absent => Test::Absent,
+            MatchOp::AtLeast => Test::AtLeast(parse_severity(&value).ok_or_else(|| {
+                AppError::Routing(format!("`{matcher}` needs critical, warning or info"))
+            })?),
+        };
+        Ok(Self {
+            field: matcher.field.clone(),
+            test,
+        })
+    }
+
+    /// A missing label reads as empty, as in Alertmanager.
+    fn matches(&self, input: &RoutingInput<'_>) -> bool {
+        let value = match &self.field {
+            MatchField::Severity => severity_name(input.severity),
+            MatchField::Source => input.source,
+            MatchField::Label(label) => input.labels.get(label).map_or("", String::as_str),
+        };
+        match &self.test {
+            Test::Equal(expected) => value == expected,
+            Test::NotEqual(expected) => value != expected,
+            Test::Regex(re) => re.is_match(value),
+            Test::NotRegex(re) => !re.is_match(value),
+            Test::Exists => !value.is_empty(),
+            Test::Absent => value.is_empty(),
+            Test::AtLeast(min) => input.severity.rank() >= min.rank(),
+        }
+    }
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "critical",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+    }
+}
+
+/// What routes look at: an alert, or a label set being tried out.
+#[derive(Debug, Clone, Copy)]
+pub struct RoutingInput<'a> {
+    pub severity: Severity,
+    pub source: &'a str,
+    pub labels: &'a BTreeMap<String, String>,
+}
+
+impl<'a> From<&'a Alert> for RoutingInput<'a> {
+    fn from(alert: &'a Alert) -> Self {
+        Self {
+            severity: alert.severity(),
+            source: alert.source().as_str(),
+            labels: alert.labels(),
+        }
+    }
+}
+
+/// A route an alert ended up on, with what it inherited on the way there.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteMatch {
+    /// Positions from the top, counting from 1: `2.1` is the first child of
+    /// the second route. `default` for the default policy.
+    pub path: String,
     pub policy_id: PolicyId,
+    pub options: RouteOptions,
+}
+
+const DEFAULT_PATH: &str = "default";
+
+impl RouteMatch {
+    /// No route matched; the default policy took the alert.
+    pub fn is_default(&self) -> bool {
+        self.path == DEFAULT_PATH
+    }
+}
+
+struct CompiledRoute {
+    matchers: Vec<CompiledMatcher>,
+    policy_id: Option<PolicyId>,
+    continue_matching: bool,
+    options: RouteOptions,
+    routes: Vec<CompiledRoute>,
+}
+
+impl CompiledRoute {
+    fn new(route: Route, path: &str, inherited: Option<&PolicyId>) -> Result<Self, AppError> {
+        let policy_id = route.policy_id.as_ref().or(inherited);
+        if policy_id.is_none() {
+            return Err(AppError::Routing(format!(
+                "route {path} has no escalation policy"
+            )));
+        }
+        let routes = route
+            .routes
+            .into_iter()
+            .enumerate()
+            .map(|(i, child)| Self::new(child, &format!("{path}.{}", i + 1), policy_id))
+            .collect::<Result<_, AppError>>()?;
+        Ok(Self {
+            matchers: route
+                .matchers
+                .iter()
+                .map(CompiledMatcher::new)
+                .collect::<Result<_, AppError>>()?,
+            policy_id: route.policy_id,
+            continue_matching: route.continue_matching,
+            options: route.options,
+            routes,
+        })
+    }
 }
 
 pub struct AlertRouter {
-    routes: Vec<Route>,
+    routes: Vec<CompiledRoute>,
+    default_policy_id: Option<PolicyId>,
 }
 
 impl AlertRouter {
-    pub fn new(routes: Vec<Route>) -> Self {
-        Self { routes }
+    /// Compiles every pattern once, up front. A pattern that does not
+    /// compile, or a route left without a policy, fails the whole router.
+    pub fn new(routes: Vec<Route>) -> Result<Self, AppError> {
+        let routes = routes
+            .into_iter()
+            .enumerate()
+            .map(|(i, route)| CompiledRoute::new(route, &(i + 1).to_string(), None))
+            .collect::<Result<_, AppError>>()?;
+        Ok(Self {
+            routes,
+            default_policy_id: None,
+        })
     }
 
-    pub fn match_alert(&self, labels: &BTreeMap<String, String>) -> Option<&PolicyId> {
-        self.routes.iter().find_map(|route| {
-            let all_match = route.matchers.iter().all(|(k, v)| labels.get(k) == Some(v));
-            if all_match {
-                Some(&route.policy_id)
-            } else {
-                None
+    /// The stored routes and default policy.
+    pub async fn load<R: RouteRepository>(repo: &R) -> Result<Self, AppError> {
+        Ok(Self::new(repo.list_all().await?)?.with_default_policy(repo.default_policy().await?))
+    }
+
+    /// Sends alerts that no route matched to `policy_id`.
+    pub fn with_default_policy(mut self, policy_id: Option<PolicyId>) -> Self {
+        self.default_policy_id = policy_id;
+        self
+    }
+
+    pub fn match_alert(&self, alert: &Alert) -> Vec<RouteMatch> {
+        self.route(alert.into())
+    }
+
+    /// Walks the tree like Alertmanager: at each level the first matching
+    /// route wins unless it says `continue`, and a route hands the alert to
+    /// its deepest matching children if it has any.
+    pub fn route(&self, input: RoutingInput<'_>) -> Vec<RouteMatch> {
+        let mut matches = Vec::new();
+        walk(
+            &self.routes,
+            &input,
+            "",
+            None,
+            &RouteOptions::default(),
+            &mut matches,
+        );
+        if let (true, Some(policy_id)) = (matches.is_empty(), &self.default_policy_id) {
+            matches.push(RouteMatch {
+                path: DEFAULT_PATH.into(),
+                policy_id: policy_id.clone(),
+                options: RouteOptions::default(),
+            });
+        }
+        matches
+    }
+}
+
+/// Returns whether any of `routes` matched.
+fn walk(
+    routes: &[CompiledRoute],
+    input: &RoutingInput<'_>,
+    parent_path: &str,
+    parent_policy: Option<&PolicyId>,
+    parent_options: &RouteOptions,
+    matches: &mut Vec<RouteMatch>,
+) -> bool {
+    let mut matched = false;
+    for (i, route) in routes.iter().enumerate() {
+        if !route.matchers.iter().all(|m| m.matches(input)) {
+            continue;
+        }
+        matched = true;
+        let path = match parent_path {
+            "" => (i + 1).to_string(),
+            parent => format!("{parent}.{}", i + 1),
+        };
+        let policy_id = route.policy_id.as_ref().or(parent_policy);
+        let options = route.options.inherit(parent_options);
+        if !walk(&route.routes, input, &path, policy_id, &options, matches) {
+            if let Some(policy_id) = policy_id {
+                matches.push(RouteMatch {
+                    path,
+                    policy_id: policy_id.clone(),
+                    options,
+                });
             }
-        })
+        }
+        if !route.continue_matching {
+            break;
+        }
     }
+    matched
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::Utc;
+    use rouse_core::alert::Source;
+
     use super::*;
 
+    fn alert(severity: Severity, labels: &[(&str, &str)]) -> Alert {
+        let labels: BTreeMap<String, String> = labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Alert::new(
+            "ext-1".into(),
+            Source::new("prometheus"),
+            severity,
+            labels,
+            "test".into(),
+            Utc::now(),
+        )
+        .0
+    }
+
+    fn route(matchers: &[&str]) -> Route {
+        Route::new(
+            matchers.iter().map(|m| m.parse().unwrap()).collect(),
+            PolicyId::new(),
+        )
+    }
+
+    fn matches(matchers: &[&str], alert: &Alert) -> bool {
+        !AlertRouter::new(vec![route(matchers)])
+            .unwrap()
+            .match_alert(alert)
+            .is_empty()
+    }
+
+    fn paths(router: &AlertRouter, alert: &Alert) -> Vec<String> {
+        router
+            .match_alert(alert)
+            .into_iter()
+            .map(|m| m.path)
+            .collect()
+    }
+
     #[test]
     fn router_matches_first_route() {
-        let policy_a = PolicyId::new();
-        let policy_b = PolicyId::new();
-        let router = AlertRouter::new(vec![
-            Route {
-                matchers: BTreeMap::from([("service".into(), "api".into())]),
-                policy_id: policy_a.clone(),
-            },
-            Route {
-                matchers: BTreeMap::from([("service".into(), "web".into())]),
-                policy_id: policy_b,
-            },
-        ]);
+        let api = route(&["service=api"]);
+        let web = route(&["service=web"]);
+        let expected = api.policy_id.clone().unwrap();
+        let router = AlertRouter::new(vec![api, web]).unwrap();
 
-        let labels = BTreeMap::from([
-            ("service".into(), "api".into()),
-            ("env".into(), "prod".into()),
-        ]);
-        assert_eq!(router.match_alert(&labels), Some(&policy_a));
+        let alert = alert(Severity::Critical, &[("service", "api"), ("env", "prod")]);
+        let matches = router.match_alert(&alert);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].policy_id, expected);
     }
 
     #[test]
     fn router_no_match_returns_none() {
-        let router = AlertRouter::new(vec![Route {
-            matchers: BTreeMap::from([("service".into(), "api".into())]),
-            policy_id: PolicyId::new(),
-        }]);
-
-        let labels = BTreeMap::from([("service".into(), "unknown".into())]);
-        assert_eq!(router.match_alert(&labels), None);
+        let router = AlertRouter::new(vec![route(&["service=api"])]).unwrap();
+        let alert = alert(Severity::Critical, &[("service", "unknown")]);
+        assert!(router.match_alert(&alert).is_empty());
     }
 
     #[test]
     fn router_requires_all_matchers() {
-        let router = AlertRouter::new(vec![Route {
-            matchers: BTreeMap::from([
-                ("service".into(), "api".into()),
-                ("env".into(), "prod".into()),
-            ]),
-            policy_id: PolicyId::new(),
-        }]);
-
-        // Only one matcher matches — should not match
-        let labels = BTreeMap::from([("service".into(), "api".into())]);
-        assert_eq!(router.match_alert(&labels), None);
+        let alert = alert(Severity::Critical, &[("service", "api")]);
+        assert!(!matches(&["service=api", "env=prod"], &alert));
     }
 
     #[test]
     fn router_empty_matchers_matches_everything() {
-        let policy = PolicyId::new();
-        let router = AlertRouter::new(vec![Route {
-            matchers: BTreeMap::new(),
-            policy_id: policy.clone(),
-        }]);
-
-        let labels = BTreeMap::from([("anything".into(), "here".into())]);
-        assert_eq!(router.match_alert(&labels), Some(&policy));
+        let alert = alert(Severity::Info, &[("anything", "here")]);
+        assert!(matches(&[], &alert));
+    }
+
+    #[test]
+    fn operators_follow_alertmanager() {
+        let alert = alert(Severity::Warning, &[("service", "api-gw"), ("env", "prod")]);
+        assert!(matches(&["env!=staging"], &alert));
+        assert!(matches(&["service=~\"api.*\""], &alert));
+        // Patterns are anchored.
+        assert!(!matches(&["service=~api"], &alert));
+        assert!(matches(&["service!~\"web|db\""], &alert));
+        // Missing labels read as empty.
+        assert!(matches(&["team!=db"], &alert));
+        assert!(matches(&["team=\"\""], &alert));
+        assert!(matches(&["env", "!team"], &alert));
+        assert!(!matches(&["team"], &alert));
+    }
+
+    #[test]
+    fn severity_and_source_are_matched_on_the_alert() {
+        let alert = alert(Severity::Warning, &[]);
+        assert!(matches(&["severity=Warning", "source=prometheus"], &alert));
+        assert!(matches(&["severity>=warning"], &alert));
+        assert!(!matches(&["severity>=critical"], &alert));
+        assert!(matches(&["severity=~\"warning|critical\""], &alert));
+        assert!(!matches(&["source!=prometheus"], &alert));
+    }
+
+    #[test]
+    fn children_refine_their_parent_and_inherit_from_it() {
+        let mut api = route(&["service=api"]);
+        api.options.suppress = Some(false);
+        let critical = route(&["severity=critical"]);
+        let staging = Route {
+            policy_id: None,
+            options: RouteOptions {
+                suppress: Some(true),
+                ..Default::default()
+            },
+            ..route(&["env=staging"])
+        };
+        api.routes = vec![critical.clone(), staging];
+        let router = AlertRouter::new(vec![api.clone(), route(&[])]).unwrap();
+
+        let matches = router.match_alert(&alert(Severity::Critical, &[("service", "api")]));
+        assert_eq!(matches[0].path, "1.1");
+        assert_eq!(Some(&matches[0].policy_id), critical.policy_id.as_ref());
+        assert_eq!(matches[0].options.suppress, Some(false));
+
+        let matches = router.match_alert(&alert(
+            Severity::Warning,
+            &[("service", "api"), ("env", "staging")],
+        ));
+        assert_eq!(matches[0].path, "1.2");
+        assert_eq!(Some(&matches[0].policy_id), api.policy_id.as_ref());
+        assert!(matches[0].options.suppressed());
+
+        // No child matches, so the parent keeps it; siblings are not tried.
+        let prod = alert(Severity::Warning, &[("service", "api")]);
+        assert_eq!(paths(&router, &prod), ["1"]);
+        let web = alert(Severity::Warning, &[("service", "web")]);
+        assert_eq!(paths(&router, &web), ["2"]);
+    }
+
+    #[test]
+    fn continue_fans_out_to_later_routes() {
+        let mut api = route(&["service=api"]);
+        api.continue_matching = true;
+        let router =
+            AlertRouter::new(vec![api, route(&["severity>=warning"]), route(&[])]).unwrap();
+
+        let both = alert(Severity::Critical, &[("service", "api")]);
+        assert_eq!(paths(&router, &both), ["1", "2"]);
+        let info = alert(Severity::Info, &[("service", "api")]);
+        assert_eq!(paths(&router, &info), ["1", "3"]);
+    }
+
+    #[test]
+    fn default_policy_takes_what_no_route_matched() {
+        let fallback = PolicyId::new();
+        let router = AlertRouter::new(vec![route(&["service=api"])])
+            .unwrap()
+            .with_default_policy(Some(fallback.clone()));
+
+        let api = router.match_alert(&alert(Severity::Info, &[("service", "api")]));
+        assert!(!api[0].is_default());
+        let lost = router.match_alert(&alert(Severity::Info, &[("servcie", "api")]));
+        assert_eq!(lost.len(), 1);
+        assert!(lost[0].is_default());
+        assert_eq!(lost[0].policy_id, fallback);
+    }
+
+    #[test]
+    fn every_route_needs_a_policy() {
+        let mut orphan = route(&["service=api"]);
+        orphan.policy_id = None;
+        let Err(AppError::Routing(message)) = AlertRouter::new(vec![route(&[]), orphan]) else {
+            panic!("expected a routing error");
+        };
+        assert_eq!(message, "route 2 has no escalation policy");
+    }
+
+    #[test]
+    fn invalid_patterns_fail_construction() {
+        let Err(AppError::Routing(message)) = AlertRouter::new(vec![route(&["service=~\"(api\""])])
+        else {
+            panic!("expected a routing error");
+        };
+        assert!(message.contains("service=~\"(api\""), "{message}");
     }
 }
@@ -0,0 +1,25 @@
+use rouse_ports::outbound::RouteRepository;
+
+use crate::error::AppError;
+use crate::router::{AlertRouter, RouteMatch, RoutingInput};
+
+pub struct RoutingService<R>
+where
+    R: RouteRepository,
+{
+    routes: R,
+}
+
+impl<R> RoutingService<R>
+where
+    R: RouteRepository,
+{
+    pub fn new(routes: R) -> Self {
+        Self { routes }
+    }
+
+    /// Where an alert like `input` would go, without creating one.
+    pub async fn test(&self, input: RoutingInput<'_>) -> Result<Vec<RouteMatch>, AppError> {
+        Ok(AlertRouter::load(&self.routes).await?.route(input))
+    }
+}
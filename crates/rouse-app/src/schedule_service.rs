@@ -1,28 +1,45 @@
 use chrono::{DateTime, Utc};
 
-use rouse_core::ids::{OverrideId, ScheduleId, UserId};
-use rouse_core::schedule::{Schedule, ScheduleOverride};
+use rouse_core::error::DomainError;
+use rouse_core::ids::{OverrideId, ScheduleId, SwapId, UserId};
+use rouse_core::schedule::lint::{self, LintContext, LintOptions, LintReport};
+use rouse_core::schedule::{
+    OverlapPolicy, Schedule, ScheduleOverride, SwapDecision, SwapProposal, SwapRequest, SwapStatus,
+};
 use rouse_ports::error::PortError;
-use rouse_ports::outbound::{EventPublisher, ScheduleRepository};
+use rouse_ports::outbound::{
+    EventPublisher, ScheduleRepository, SwapRequestRepository, UserRepository,
+};
 
 use crate::error::AppError;
 
-pub struct ScheduleService<S, EP>
+pub struct ScheduleService<S, SW, U, EP>
 where
     S: ScheduleRepository,
+    SW: SwapRequestRepository,
+    U: UserRepository,
     EP: EventPublisher,
 {
     schedules: S,
+    swaps: SW,
+    users: U,
     events: EP,
 }
 
-impl<S, EP> ScheduleService<S, EP>
+impl<S, SW, U, EP> ScheduleService<S, SW, U, EP>
 where
     S: ScheduleRepository,
+    SW: SwapRequestRepository,
+    U: UserRepository,
     EP: EventPublisher,
 {
-    pub fn new(schedules: S, events: EP) -> Self {
-        Self { schedules, events }
+    pub fn new(schedules: S, swaps: SW, users: U, events: EP) -> Self {
+        Self {
+            schedules,
+            swaps,
+            users,
+            events,
+        }
     }
 
     pub async fn create_schedule(&self, schedule: Schedule) -> Result<ScheduleId, AppError> {
@@ -31,16 +48,16 @@ where
         Ok(id)
     }
 
+    pub async fn get_schedule(&self, schedule_id: &str) -> Result<Schedule, AppError> {
+        self.load(schedule_id).await
+    }
+
     pub async fn who_is_on_call(
         &self,
         schedule_id: &str,
         at: DateTime<Utc>,
     ) -> Result<UserId, AppError> {
-        let schedule = self
-            .schedules
-            .find_by_id(schedule_id)
-            .await?
-            .ok_or(AppError::Port(PortError::NotFound))?;
+        let schedule = self.load(schedule_id).await?;
         Ok(schedule.who_is_on_call(at))
     }
 
@@ -50,12 +67,10 @@ where
         ovr: ScheduleOverride,
         now: DateTime<Utc>,
     ) -> Result<(), AppError> {
-        let mut schedule = self
-            .schedules
-            .find_by_id(schedule_id)
-            .await?
-            .ok_or(AppError::Port(PortError::NotFound))?;
+        let mut schedule = self.load(schedule_id).await?;
+        self.ensure_can_be_on_call(ovr.user_id()).await?;
 
+        schedule.prune_expired(now);
         let events = schedule.add_override(ovr, now)?;
         self.schedules.save(&schedule).await?;
         self.events.publish(events).await?;
@@ -63,42 +78,228 @@ where
         Ok(())
     }
 
+    /// Overrides on the schedule that share any time with `[start, end)`.
+    pub async fn list_overrides(
+        &self,
+        schedule_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<ScheduleOverride>, AppError> {
+        let schedule = self.load(schedule_id).await?;
+        Ok(schedule
+            .overrides_between(start, end)
+            .into_iter()
+            .cloned()
+            .collect())
+    }
+
+    pub async fn get_override(
+        &self,
+        schedule_id: &str,
+        override_id: &str,
+    ) -> Result<ScheduleOverride, AppError> {
+        let schedule = self.load(schedule_id).await?;
+        let ovr_id = OverrideId::parse(override_id)?;
+        schedule
+            .overrides()
+            .iter()
+            .find(|o| *o.id() == ovr_id)
+            .cloned()
+            .ok_or(AppError::Domain(DomainError::OverrideNotFound))
+    }
+
+    pub async fn update_override(
+        &self,
+        schedule_id: &str,
+        override_id: &str,
+        user_id: UserId,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut schedule = self.load(schedule_id).await?;
+        let ovr_id = OverrideId::parse(override_id)?;
+        self.ensure_can_be_on_call(&user_id).await?;
+
+        schedule.prune_expired(now);
+        let events = schedule.update_override(&ovr_id, user_id, start, end, now)?;
+        self.schedules.save(&schedule).await?;
+        self.events.publish(events).await?;
+
+        Ok(())
+    }
+
+    pub async fn set_overlap_policy(
+        &self,
+        schedule_id: &str,
+        policy: OverlapPolicy,
+    ) -> Result<(), AppError> {
+        let mut schedule = self.load(schedule_id).await?;
+        schedule.set_overlap_policy(policy);
+        self.schedules.save(&schedule).await?;
+        Ok(())
+    }
+
     pub async fn remove_override(
         &self,
         schedule_id: &str,
         override_id: &str,
         now: DateTime<Utc>,
     ) -> Result<(), AppError> {
-        let mut schedule = self
-            .schedules
-            .find_by_id(schedule_id)
-            .await?
-            .ok_or(AppError::Port(PortError::NotFound))?;
+        let mut schedule = self.load(schedule_id).await?;
 
         let ovr_id = OverrideId::parse(override_id)?;
         let events = schedule.remove_override(&ovr_id, now)?;
 
         if !events.is_empty() {
+            schedule.prune_expired(now);
             self.schedules.save(&schedule).await?;
             self.events.publish(events).await?;
         }
 
         Ok(())
     }
+
+    pub async fn request_swap(
+        &self,
+        schedule_id: &str,
+        proposal: SwapProposal,
+        now: DateTime<Utc>,
+    ) -> Result<SwapId, AppError> {
+        let schedule = self.load(schedule_id).await?;
+
+        let (swap, events) = SwapRequest::propose(schedule.id().clone(), proposal, now)?;
+        let swap_id = swap.id().clone();
+        self.swaps.save(&swap).await?;
+        self.events.publish(events).await?;
+
+        Ok(swap_id)
+    }
+
+    /// Accept or decline a pending swap. Overrides are only written to the
+    /// schedule when the counterpart accepts.
+    pub async fn respond_to_swap(
+        &self,
+        swap_id: &str,
+        responder: &UserId,
+        decision: SwapDecision,
+        now: DateTime<Utc>,
+    ) -> Result<SwapStatus, AppError> {
+        let mut swap = self
+            .swaps
+            .find_by_id(swap_id)
+            .await?
+            .ok_or(AppError::Port(PortError::NotFound))?;
+
+        let mut events = swap.respond(responder, decision, now)?;
+
+        if swap.status() == SwapStatus::Accepted {
+            let mut schedule = self.load(&swap.schedule_id().to_string()).await?;
+            schedule.prune_expired(now);
+            for ovr in swap.overrides() {
+                self.ensure_can_be_on_call(ovr.user_id()).await?;
+                events.extend(schedule.add_override(ovr, now)?);
+            }
+            self.schedules.save(&schedule).await?;
+        }
+
+        self.swaps.save(&swap).await?;
+        self.events.publish(events).await?;
+
+        Ok(swap.status())
+    }
+
+    pub async fn cancel_swap(
+        &self,
+        swap_id: &str,
+        by: &UserId,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let mut swap = self
+            .swaps
+            .find_by_id(swap_id)
+            .await?
+            .ok_or(AppError::Port(PortError::NotFound))?;
+
+        let events = swap.cancel(by, now)?;
+        self.swaps.save(&swap).await?;
+        self.events.publish(events).await?;
+
+        Ok(())
+    }
+
+    pub async fn get_swap(&self, swap_id: &str) -> Result<SwapRequest, AppError> {
+        self.swaps
+            .find_by_id(swap_id)
+            .await?
+            .ok_or(AppError::Port(PortError::NotFound))
+    }
+
+    pub async fn list_swaps(&self, schedule_id: &str) -> Result<Vec<SwapRequest>, AppError> {
+        Ok(self.swaps.list_by_schedule(schedule_id).await?)
+    }
+
+    /// Checks `[start, end)` for gaps, unreachable people, overly long
+    /// shifts and back-to-back duty across schedules.
+    pub async fn lint_schedule(
+        &self,
+        schedule_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        options: LintOptions,
+    ) -> Result<LintReport, AppError> {
+        let schedule = self.load(schedule_id).await?;
+        let others = self.schedules.list_all().await?;
+
+        let mut ids: Vec<&UserId> = schedule.participants().iter().collect();
+        ids.extend(schedule.overrides().iter().map(|o| o.user_id()));
+        ids.sort_by_key(|id| id.to_string());
+        ids.dedup();
+
+        let mut users = Vec::with_capacity(ids.len());
+        for id in ids {
+            users.extend(self.users.find_by_id(&id.to_string()).await?);
+        }
+
+        let ctx = LintContext {
+            users: &users,
+            other_schedules: &others,
+            options,
+        };
+        Ok(lint::lint(&schedule, &ctx, start, end))
+    }
+
+    async fn load(&self, schedule_id: &str) -> Result<Schedule, AppError> {
+        self.schedules
+            .find_by_id(schedule_id)
+            .await?
+            .ok_or(AppError::Port(PortError::NotFound))
+    }
+
+    /// Unknown users and users without any contact method cannot take a shift.
+    async fn ensure_can_be_on_call(&self, user_id: &UserId) -> Result<(), AppError> {
+        match self.users.find_by_id(&user_id.to_string()).await? {
+            Some(user) if user.can_be_on_call() => Ok(()),
+            _ => Err(AppError::Domain(DomainError::UserCannotBeOnCall)),
+        }
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use crate::user_service::tests::MockUserRepo;
     use async_trait::async_trait;
+    use rouse_core::channel::Channel;
     use rouse_core::events::DomainEvent;
     use rouse_core::schedule::{HandoffTime, Rotation};
+    use rouse_core::user::{Role, User};
     use rouse_ports::error::PortError;
     use std::sync::Mutex;
 
     #[derive(Default)]
-    struct MockScheduleRepo {
-        schedules: Mutex<Vec<Schedule>>,
+    pub(crate) struct MockScheduleRepo {
+        pub(crate) schedules: Mutex<Vec<Schedule>>,
     }
 
     #[async_trait]
@@ -121,6 +322,36 @@ mod tests {
         }
     }
 
+    #[derive(Default)]
+    struct MockSwapRepo {
+        swaps: Mutex<Vec<SwapRequest>>,
+    }
+
+    #[async_trait]
+    impl SwapRequestRepository for MockSwapRepo {
+        async fn save(&self, swap: &SwapRequest) -> Result<(), PortError> {
+            let mut swaps = self.swaps.lock().unwrap();
+            if let Some(pos) = swaps.iter().position(|s| s.id() == swap.id()) {
+                swaps[pos] = swap.clone();
+            } else {
+                swaps.push(swap.clone());
+            }
+            Ok(())
+        }
+        async fn find_by_id(&self, id: &str) -> Result<Option<SwapRequest>, PortError> {
+            let swaps = self.swaps.lock().unwrap();
+            Ok(swaps.iter().find(|s| s.id().to_string() == id).cloned())
+        }
+        async fn list_by_schedule(&self, schedule_id: &str) -> Result<Vec<SwapRequest>, PortError> {
+            let swaps = self.swaps.lock().unwrap();
+            Ok(swaps
+                .iter()
+                .filter(|s| s.schedule_id().to_string() == schedule_id)
+                .cloned()
+                .collect())
+        }
+    }
+
     #[derive(Default)]
     struct MockEventPublisher {
         events: Mutex<Vec<DomainEvent>>,
@@ -152,12 +383,32 @@ mod tests {
             .with_timezone(&Utc)
     }
 
-    fn make_users(n: usize) -> Vec<UserId> {
-        (0..n).map(|_| UserId::new()).collect()
+    /// Registers `n` reachable users with the service and returns their ids.
+    fn make_users(svc: &TestService, n: usize) -> Vec<UserId> {
+        (0..n).map(|_| add_user(svc, true)).collect()
     }
 
-    fn make_service() -> ScheduleService<MockScheduleRepo, MockEventPublisher> {
-        ScheduleService::new(MockScheduleRepo::default(), MockEventPublisher::default())
+    fn add_user(svc: &TestService, reachable: bool) -> UserId {
+        let mut user = User::new("oncall".into(), "oncall@example.com".into(), Role::User);
+        if reachable {
+            user.set_slack_id("U123".into());
+            user.mark_verified(Channel::Slack, Utc::now()).unwrap();
+        }
+        let id = user.id().clone();
+        svc.users.users.lock().unwrap().push(user);
+        id
+    }
+
+    type TestService =
+        ScheduleService<MockScheduleRepo, MockSwapRepo, MockUserRepo, MockEventPublisher>;
+
+    fn make_service() -> TestService {
+        ScheduleService::new(
+            MockScheduleRepo::default(),
+            MockSwapRepo::default(),
+            MockUserRepo::default(),
+            MockEventPublisher::default(),
+        )
     }
 
     fn make_schedule(users: Vec<UserId>) -> Schedule {
@@ -174,7 +425,7 @@ mod tests {
     #[tokio::test]
     async fn create_schedule_saves() {
         let svc = make_service();
-        let users = make_users(3);
+        let users = make_users(&svc, 3);
         let schedule = make_schedule(users);
         let schedule_id = schedule.id().clone();
 
@@ -189,7 +440,7 @@ mod tests {
     #[tokio::test]
     async fn who_is_on_call_delegates_to_domain() {
         let svc = make_service();
-        let users = make_users(3);
+        let users = make_users(&svc, 3);
         let schedule = make_schedule(users.clone());
         let schedule_id = schedule.id().clone();
 
@@ -206,13 +457,13 @@ mod tests {
     #[tokio::test]
     async fn add_override_persists_and_publishes() {
         let svc = make_service();
-        let users = make_users(2);
+        let users = make_users(&svc, 2);
         let schedule = make_schedule(users);
         let schedule_id = schedule.id().clone();
 
         svc.create_schedule(schedule).await.unwrap();
 
-        let override_user = UserId::new();
+        let override_user = add_user(&svc, true);
         let ovr = ScheduleOverride::new(
             override_user.clone(),
             ts("2025-01-14T00:00:00Z"),
@@ -241,14 +492,14 @@ mod tests {
         use rouse_core::error::DomainError;
 
         let svc = make_service();
-        let users = make_users(1);
+        let users = make_users(&svc, 1);
         let schedule = make_schedule(users);
         let schedule_id = schedule.id().clone();
 
         svc.create_schedule(schedule).await.unwrap();
 
         let ovr = ScheduleOverride::new(
-            UserId::new(),
+            add_user(&svc, true),
             ts("2025-01-15T10:00:00Z"),
             ts("2025-01-15T09:00:00Z"), // end before start
         );
@@ -265,14 +516,14 @@ mod tests {
     #[tokio::test]
     async fn remove_override_persists_and_publishes() {
         let svc = make_service();
-        let users = make_users(1);
+        let users = make_users(&svc, 1);
         let schedule = make_schedule(users);
         let schedule_id = schedule.id().clone();
 
         svc.create_schedule(schedule).await.unwrap();
 
         let ovr = ScheduleOverride::new(
-            UserId::new(),
+            add_user(&svc, true),
             ts("2025-01-14T00:00:00Z"),
             ts("2025-01-16T00:00:00Z"),
         );
@@ -306,4 +557,354 @@ mod tests {
             .await;
         assert!(matches!(result, Err(AppError::Port(PortError::NotFound))));
     }
+
+    fn cover_proposal(requester: &UserId, counterpart: &UserId) -> SwapProposal {
+        SwapProposal {
+            requester: requester.clone(),
+            counterpart: counterpart.clone(),
+            kind: rouse_core::schedule::SwapKind::Cover,
+            start: ts("2025-01-14T00:00:00Z"),
+            end: ts("2025-01-15T00:00:00Z"),
+            reason: Some("family event".into()),
+        }
+    }
+
+    async fn setup_swap(svc: &TestService) -> (ScheduleId, SwapId, Vec<UserId>) {
+        let users = make_users(svc, 2);
+        let schedule = make_schedule(users.clone());
+        let schedule_id = schedule.id().clone();
+        svc.create_schedule(schedule).await.unwrap();
+
+        let swap_id = svc
+            .request_swap(
+                &schedule_id.to_string(),
+                cover_proposal(&users[0], &users[1]),
+                ts("2025-01-13T09:00:00Z"),
+            )
+            .await
+            .unwrap();
+        (schedule_id, swap_id, users)
+    }
+
+    #[tokio::test]
+    async fn request_swap_does_not_touch_schedule() {
+        let svc = make_service();
+        let (schedule_id, _, _) = setup_swap(&svc).await;
+
+        let schedules = svc.schedules.schedules.lock().unwrap();
+        let schedule = schedules.iter().find(|s| s.id() == &schedule_id).unwrap();
+        let on_call = schedule.who_is_on_call(ts("2025-01-14T10:00:00Z"));
+        assert!(schedule.participants().contains(&on_call));
+
+        let events = svc.events.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type(), "swap.requested");
+    }
+
+    #[tokio::test]
+    async fn request_swap_unknown_schedule_fails() {
+        let svc = make_service();
+        let users = make_users(&svc, 2);
+        let result = svc
+            .request_swap(
+                &ScheduleId::new().to_string(),
+                cover_proposal(&users[0], &users[1]),
+                ts("2025-01-13T09:00:00Z"),
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Port(PortError::NotFound))));
+    }
+
+    #[tokio::test]
+    async fn accepting_swap_applies_audited_override() {
+        let svc = make_service();
+        let (schedule_id, swap_id, users) = setup_swap(&svc).await;
+
+        let status = svc
+            .respond_to_swap(
+                &swap_id.to_string(),
+                &users[1],
+                SwapDecision::Accept,
+                ts("2025-01-13T10:00:00Z"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(status, SwapStatus::Accepted);
+
+        let on_call = svc
+            .who_is_on_call(&schedule_id.to_string(), ts("2025-01-14T10:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(on_call, users[1]);
+
+        let events = svc.events.events.lock().unwrap();
+        let types: Vec<_> = events.iter().map(|e| e.event_type()).collect();
+        assert_eq!(types, ["swap.requested", "swap.accepted", "oncall.changed"]);
+    }
+
+    #[tokio::test]
+    async fn declining_swap_leaves_schedule_unchanged() {
+        let svc = make_service();
+        let (schedule_id, swap_id, users) = setup_swap(&svc).await;
+        let before = svc
+            .who_is_on_call(&schedule_id.to_string(), ts("2025-01-14T10:00:00Z"))
+            .await
+            .unwrap();
+
+        let status = svc
+            .respond_to_swap(
+                &swap_id.to_string(),
+                &users[1],
+                SwapDecision::Decline,
+                ts("2025-01-13T10:00:00Z"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(status, SwapStatus::Declined);
+
+        let after = svc
+            .who_is_on_call(&schedule_id.to_string(), ts("2025-01-14T10:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(before, after);
+
+        let events = svc.events.events.lock().unwrap();
+        assert_eq!(events.last().unwrap().event_type(), "swap.declined");
+    }
+
+    #[tokio::test]
+    async fn requester_cannot_accept_own_swap() {
+        use rouse_core::error::DomainError;
+
+        let svc = make_service();
+        let (_, swap_id, users) = setup_swap(&svc).await;
+
+        let result = svc
+            .respond_to_swap(
+                &swap_id.to_string(),
+                &users[0],
+                SwapDecision::Accept,
+                ts("2025-01-13T10:00:00Z"),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::NotSwapCounterpart))
+        ));
+    }
+
+    #[tokio::test]
+    async fn cancel_swap_persists_and_publishes() {
+        let svc = make_service();
+        let (schedule_id, swap_id, users) = setup_swap(&svc).await;
+
+        svc.cancel_swap(&swap_id.to_string(), &users[0], ts("2025-01-13T10:00:00Z"))
+            .await
+            .unwrap();
+
+        let swaps = svc.list_swaps(&schedule_id.to_string()).await.unwrap();
+        assert_eq!(swaps[0].status(), SwapStatus::Cancelled);
+
+        let events = svc.events.events.lock().unwrap();
+        assert_eq!(events.last().unwrap().event_type(), "swap.cancelled");
+    }
+
+    async fn setup_schedule(svc: &TestService) -> (String, Vec<UserId>) {
+        let users = make_users(svc, 2);
+        let schedule = make_schedule(users.clone());
+        let schedule_id = svc.create_schedule(schedule).await.unwrap();
+        (schedule_id.to_string(), users)
+    }
+
+    #[tokio::test]
+    async fn add_override_rejects_unreachable_user() {
+        let svc = make_service();
+        let (schedule_id, _) = setup_schedule(&svc).await;
+
+        let ovr = ScheduleOverride::new(
+            add_user(&svc, false),
+            ts("2025-01-14T00:00:00Z"),
+            ts("2025-01-15T00:00:00Z"),
+        );
+        let result = svc
+            .add_override(&schedule_id, ovr, ts("2025-01-13T00:00:00Z"))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::UserCannotBeOnCall))
+        ));
+
+        let ovr = ScheduleOverride::new(
+            UserId::new(),
+            ts("2025-01-14T00:00:00Z"),
+            ts("2025-01-15T00:00:00Z"),
+        );
+        let result = svc
+            .add_override(&schedule_id, ovr, ts("2025-01-13T00:00:00Z"))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::UserCannotBeOnCall))
+        ));
+    }
+
+    #[tokio::test]
+    async fn add_override_rejects_overlap_by_default() {
+        let svc = make_service();
+        let (schedule_id, users) = setup_schedule(&svc).await;
+
+        let first = ScheduleOverride::new(
+            users[0].clone(),
+            ts("2025-01-14T00:00:00Z"),
+            ts("2025-01-16T00:00:00Z"),
+        );
+        svc.add_override(&schedule_id, first, ts("2025-01-13T00:00:00Z"))
+            .await
+            .unwrap();
+
+        let second = ScheduleOverride::new(
+            users[1].clone(),
+            ts("2025-01-15T00:00:00Z"),
+            ts("2025-01-17T00:00:00Z"),
+        );
+        let result = svc
+            .add_override(&schedule_id, second, ts("2025-01-13T00:00:00Z"))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::OverlappingOverride))
+        ));
+    }
+
+    #[tokio::test]
+    async fn split_policy_lets_new_override_win() {
+        let svc = make_service();
+        let (schedule_id, users) = setup_schedule(&svc).await;
+        svc.set_overlap_policy(&schedule_id, OverlapPolicy::Split)
+            .await
+            .unwrap();
+
+        for (user, start, end) in [
+            (&users[0], "2025-01-14T00:00:00Z", "2025-01-17T00:00:00Z"),
+            (&users[1], "2025-01-15T00:00:00Z", "2025-01-16T00:00:00Z"),
+        ] {
+            let ovr = ScheduleOverride::new(user.clone(), ts(start), ts(end));
+            svc.add_override(&schedule_id, ovr, ts("2025-01-13T00:00:00Z"))
+                .await
+                .unwrap();
+        }
+
+        for (at, expected) in [
+            ("2025-01-14T12:00:00Z", &users[0]),
+            ("2025-01-15T12:00:00Z", &users[1]),
+            ("2025-01-16T12:00:00Z", &users[0]),
+        ] {
+            let on_call = svc.who_is_on_call(&schedule_id, ts(at)).await.unwrap();
+            assert_eq!(&on_call, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn lint_flags_unreachable_participant() {
+        let svc = make_service();
+        let users = vec![add_user(&svc, true), add_user(&svc, false)];
+        let schedule_id = svc
+            .create_schedule(make_schedule(users.clone()))
+            .await
+            .unwrap()
+            .to_string();
+
+        let report = svc
+            .lint_schedule(
+                &schedule_id,
+                ts("2025-01-13T00:00:00Z"),
+                ts("2025-02-10T00:00:00Z"),
+                LintOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert!(report.has_errors());
+        assert_eq!(report.errors().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn list_overrides_filters_by_window() {
+        let svc = make_service();
+        let (schedule_id, users) = setup_schedule(&svc).await;
+
+        for (start, end) in [
+            ("2025-01-14T00:00:00Z", "2025-01-15T00:00:00Z"),
+            ("2025-01-20T00:00:00Z", "2025-01-21T00:00:00Z"),
+        ] {
+            let ovr = ScheduleOverride::new(users[0].clone(), ts(start), ts(end));
+            svc.add_override(&schedule_id, ovr, ts("2025-01-13T00:00:00Z"))
+                .await
+                .unwrap();
+        }
+
+        let listed = svc
+            .list_overrides(
+                &schedule_id,
+                ts("2025-01-13T00:00:00Z"),
+                ts("2025-01-16T00:00:00Z"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].start(), ts("2025-01-14T00:00:00Z"));
+    }
+
+    #[tokio::test]
+    async fn update_override_changes_user_and_period() {
+        let svc = make_service();
+        let (schedule_id, users) = setup_schedule(&svc).await;
+
+        let ovr = ScheduleOverride::new(
+            users[0].clone(),
+            ts("2025-01-14T00:00:00Z"),
+            ts("2025-01-15T00:00:00Z"),
+        );
+        let ovr_id = ovr.id().to_string();
+        svc.add_override(&schedule_id, ovr, ts("2025-01-13T00:00:00Z"))
+            .await
+            .unwrap();
+
+        svc.update_override(
+            &schedule_id,
+            &ovr_id,
+            users[1].clone(),
+            ts("2025-01-14T00:00:00Z"),
+            ts("2025-01-16T00:00:00Z"),
+            ts("2025-01-13T00:00:00Z"),
+        )
+        .await
+        .unwrap();
+
+        let on_call = svc
+            .who_is_on_call(&schedule_id, ts("2025-01-15T12:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(on_call, users[1]);
+    }
+
+    #[tokio::test]
+    async fn update_unknown_override_fails() {
+        let svc = make_service();
+        let (schedule_id, users) = setup_schedule(&svc).await;
+
+        let result = svc
+            .update_override(
+                &schedule_id,
+                &OverrideId::new().to_string(),
+                users[0].clone(),
+                ts("2025-01-14T00:00:00Z"),
+                ts("2025-01-15T00:00:00Z"),
+                ts("2025-01-13T00:00:00Z"),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::OverrideNotFound))
+        ));
+    }
 }
@@ -0,0 +1,424 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use rouse_core::error::DomainError;
+use rouse_core::user::group_mapping::{role_for_groups, teams_for_groups};
+use rouse_core::user::{GroupMapping, Role, Team, User};
+use rouse_ports::error::IdentityError;
+use rouse_ports::outbound::{AuditLog, IdentityProvider, TeamRepository, UserRepository};
+use rouse_ports::types::{Actor, AuthorizationRequest, IdentityClaims};
+
+use crate::audit;
+use crate::error::AppError;
+
+/// Signs users in through an external identity provider and keeps their
+/// role and team memberships in line with the provider's groups. What the
+/// groups change is audited as rouse's own doing.
+pub struct SsoService<U, T, P, L>
+where
+    U: UserRepository,
+    T: TeamRepository,
+    P: IdentityProvider,
+    L: AuditLog,
+{
+    users: U,
+    teams: T,
+    provider: P,
+    audit: L,
+    mappings: Vec<GroupMapping>,
+    default_role: Role,
+}
+
+impl<U, T, P, L> SsoService<U, T, P, L>
+where
+    U: UserRepository,
+    T: TeamRepository,
+    P: IdentityProvider,
+    L: AuditLog,
+{
+    /// `default_role` is given to users no role mapping applies to.
+    pub fn new(
+        users: U,
+        teams: T,
+        provider: P,
+        audit: L,
+        mappings: Vec<GroupMapping>,
+        default_role: Role,
+    ) -> Self {
+        Self {
+            users,
+            teams,
+            provider,
+            audit,
+            mappings,
+            default_role,
+        }
+    }
+
+    /// Returns the provider URL to redirect to and the secrets the callback
+    /// must present again.
+    pub async fn begin(&self) -> Result<(String, AuthorizationRequest), AppError> {
+        let request = AuthorizationRequest {
+            state: uuid::Uuid::new_v4().simple().to_string(),
+            nonce: uuid::Uuid::new_v4().simple().to_string(),
+            pkce_verifier: format!(
+                "{}{}",
+                uuid::Uuid::new_v4().simple(),
+                uuid::Uuid::new_v4().simple()
+            ),
+        };
+        let url = self.provider.authorization_url(&request).await?;
+        Ok((url, request))
+    }
+
+    /// Completes sign-in, creating the user on first login.
+    pub async fn complete(
+        &self,
+        code: &str,
+        request: &AuthorizationRequest,
+        now: DateTime<Utc>,
+    ) -> Result<User, AppError> {
+        let claims = self.provider.exchange_code(code, request).await?;
+        let mut user = self.find_or_create(&claims).await?;
+        let before = self.users.find_by_id(&user.id().to_string()).await?;
+
+        let roles_managed = self.mappings.iter().any(|m| m.role.is_some());
+        if roles_managed {
+            user.set_role(
+                role_for_groups(&self.mappings, &claims.groups).unwrap_or(self.default_role),
+            );
+        }
+        if let (Some(email), true) = (&claims.email, claims.email_verified) {
+            user.set_email(email.clone());
+        }
+        self.users.save(&user).await?;
+        let action = if before.is_some() {
+            "user.update"
+        } else {
+            "user.create"
+        };
+        let target = format!("user:{}", user.id());
+        self.record(action, target, before.as_ref(), Some(&user), now)
+            .await?;
+        self.sync_teams(&user, &claims.groups, now).await?;
+        Ok(user)
+    }
+
+    /// Updates that changed nothing are not recorded.
+    async fn record<R: Serialize>(
+        &self,
+        action: &str,
+        target: String,
+        before: Option<&R>,
+        after: Option<&R>,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let entry = audit::entry(&Actor::system(), action, target, before, after, now)?;
+        if !entry.changes.is_empty() {
+            self.audit.append(&entry).await?;
+        }
+        Ok(())
+    }
+
+    /// Matches by linked identity first, then links an existing account with
+    /// the same verified email.
+    async fn find_or_create(&self, claims: &IdentityClaims) -> Result<User, AppError> {
+        let external_id = format!("{}|{}", claims.issuer, claims.subject);
+        let users = self.users.list_all().await?;
+        if let Some(user) = users
+            .iter()
+            .find(|u| u.external_id() == Some(external_id.as_str()))
+        {
+            return Ok(user.clone());
+        }
+
+        let email = claims
+            .email
+            .clone()
+            .ok_or_else(|| IdentityError::Rejected("ID token has no email".into()))?;
+        if claims.email_verified {
+            if let Some(user) = users
+                .iter()
+                .find(|u| u.external_id().is_none() && u.email() == email)
+            {
+                let mut user = user.clone();
+                user.link_identity(external_id);
+                return Ok(user);
+            }
+        }
+
+        let preferred = claims.username.clone().unwrap_or_else(|| email.clone());
+        let username = if users.iter().any(|u| u.username() == preferred) {
+            email.clone()
+        } else {
+            preferred
+        };
+        let mut user = User::new(username, email, self.default_role);
+        user.link_identity(external_id);
+        Ok(user)
+    }
+
+    async fn sync_teams(
+        &self,
+        user: &User,
+        groups: &[String],
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let (granted, withheld) = teams_for_groups(&self.mappings, groups);
+        let teams = self.teams.list_all().await?;
+
+        for name in &granted {
+            match teams.iter().find(|t| t.name() == name) {
+                Some(team) if team.members().contains(user.id()) => {}
+                Some(before) => {
+                    let mut team = before.clone();
+                    team.add_member(user.id().clone());
+                    self.teams.save(&team).await?;
+                    let target = format!("team:{}", team.id());
+                    self.record("team.member.add", target, Some(before), Some(&team), now)
+                        .await?;
+                }
+                None => {
+                    let team = Team::new(name.clone(), vec![user.id().clone()])?;
+                    self.teams.save(&team).await?;
+                    let target = format!("team:{}", team.id());
+                    self.record("team.create", target, None, Some(&team), now)
+                        .await?;
+                }
+            }
+        }
+
+        for before in teams
+            .iter()
+            .filter(|t| withheld.contains(&t.name().to_string()))
+        {
+            let mut team = before.clone();
+            match team.remove_member(user.id()) {
+                Ok(()) => {
+                    self.teams.save(&team).await?;
+                    let target = format!("team:{}", team.id());
+                    self.record("team.member.remove", target, Some(before), Some(&team), now)
+                        .await?;
+                }
+                // A team cannot be left empty; the last member stays until
+                // someone else joins.
+                Err(DomainError::NotTeamMember | DomainError::TeamRequiresMember) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::tests::MockAuditLog;
+    use crate::team_service::tests::MockTeamRepo;
+    use crate::user_service::tests::MockUserRepo;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// Hands out whatever claims the test set, for the code "ok" only.
+    struct MockProvider {
+        claims: Mutex<IdentityClaims>,
+    }
+
+    #[async_trait]
+    impl IdentityProvider for MockProvider {
+        async fn authorization_url(
+            &self,
+            request: &AuthorizationRequest,
+        ) -> Result<String, IdentityError> {
+            Ok(format!(
+                "https://idp.test/authorize?state={}",
+                request.state
+            ))
+        }
+        async fn exchange_code(
+            &self,
+            code: &str,
+            _request: &AuthorizationRequest,
+        ) -> Result<IdentityClaims, IdentityError> {
+            if code != "ok" {
+                return Err(IdentityError::Rejected("invalid_grant".into()));
+            }
+            Ok(self.claims.lock().unwrap().clone())
+        }
+    }
+
+    type TestService = SsoService<MockUserRepo, MockTeamRepo, MockProvider, MockAuditLog>;
+
+    fn claims(groups: &[&str]) -> IdentityClaims {
+        IdentityClaims {
+            issuer: "https://idp.test".into(),
+            subject: "u-42".into(),
+            email: Some("alice@corp.example".into()),
+            email_verified: true,
+            username: Some("alice".into()),
+            groups: groups.iter().map(|g| g.to_string()).collect(),
+        }
+    }
+
+    fn mapping(group: &str, role: Option<Role>, team: Option<&str>) -> GroupMapping {
+        GroupMapping {
+            group: group.into(),
+            role,
+            team: team.map(Into::into),
+        }
+    }
+
+    fn make_service(groups: &[&str]) -> TestService {
+        SsoService::new(
+            MockUserRepo::default(),
+            MockTeamRepo::default(),
+            MockProvider {
+                claims: Mutex::new(claims(groups)),
+            },
+            MockAuditLog::default(),
+            vec![
+                mapping("sre", Some(Role::User), Some("platform")),
+                mapping("sre-leads", Some(Role::Admin), None),
+                mapping("dba", None, Some("database")),
+            ],
+            Role::Viewer,
+        )
+    }
+
+    async fn sign_in(svc: &TestService) -> Result<User, AppError> {
+        let (_, request) = svc.begin().await?;
+        svc.complete("ok", &request, now()).await
+    }
+
+    fn now() -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339("2025-01-15T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn audited(svc: &TestService) -> Vec<String> {
+        let entries = svc.audit.entries.lock().unwrap();
+        entries.iter().map(|e| e.action.clone()).collect()
+    }
+
+    fn team<'a>(teams: &'a [Team], name: &str) -> &'a Team {
+        teams.iter().find(|t| t.name() == name).unwrap()
+    }
+
+    #[tokio::test]
+    async fn begin_generates_fresh_secrets() {
+        let svc = make_service(&[]);
+        let (url, first) = svc.begin().await.unwrap();
+        let (_, second) = svc.begin().await.unwrap();
+        assert!(url.ends_with(&first.state));
+        assert_ne!(first.state, second.state);
+        assert_ne!(first.nonce, first.state);
+        assert!(first.pkce_verifier.len() >= 43, "RFC 7636 minimum");
+    }
+
+    #[tokio::test]
+    async fn first_login_provisions_user_with_mapped_role_and_teams() {
+        let svc = make_service(&["sre", "staff"]);
+        let user = sign_in(&svc).await.unwrap();
+
+        assert_eq!(user.username(), "alice");
+        assert_eq!(user.role(), Role::User);
+        assert_eq!(user.external_id(), Some("https://idp.test|u-42"));
+        let teams = svc.teams.teams.lock().unwrap();
+        assert_eq!(teams.len(), 1);
+        assert_eq!(team(&teams, "platform").members(), [user.id().clone()]);
+        assert_eq!(audited(&svc), ["user.create", "team.create"]);
+        let entries = svc.audit.entries.lock().unwrap();
+        assert_eq!(entries[0].actor, Actor::system());
+        assert_eq!(entries[0].target, format!("user:{}", user.id()));
+    }
+
+    #[tokio::test]
+    async fn later_logins_follow_group_changes() {
+        let svc = make_service(&["sre", "dba"]);
+        let alice = sign_in(&svc).await.unwrap();
+        let bob = User::new("bob".into(), "bob@corp.example".into(), Role::User);
+        svc.users.save(&bob).await.unwrap();
+        {
+            let mut teams = svc.teams.teams.lock().unwrap();
+            for team in teams.iter_mut() {
+                team.add_member(bob.id().clone());
+            }
+        }
+
+        *svc.provider.claims.lock().unwrap() = claims(&["sre-leads"]);
+        svc.audit.entries.lock().unwrap().clear();
+        let again = sign_in(&svc).await.unwrap();
+
+        assert_eq!(again.id(), alice.id(), "same identity, same user");
+        assert_eq!(again.role(), Role::Admin);
+        {
+            let teams = svc.teams.teams.lock().unwrap();
+            assert_eq!(team(&teams, "platform").members(), [bob.id().clone()]);
+            assert_eq!(team(&teams, "database").members(), [bob.id().clone()]);
+        }
+        assert_eq!(svc.users.users.lock().unwrap().len(), 2);
+        assert_eq!(
+            audited(&svc),
+            ["user.update", "team.member.remove", "team.member.remove"]
+        );
+        let role_changed = svc.audit.entries.lock().unwrap()[0]
+            .changes
+            .iter()
+            .any(|c| c.field == "role");
+        assert!(role_changed);
+
+        svc.audit.entries.lock().unwrap().clear();
+        sign_in(&svc).await.unwrap();
+        assert!(audited(&svc).is_empty(), "nothing changed");
+    }
+
+    #[tokio::test]
+    async fn unmapped_groups_fall_back_to_default_role() {
+        let svc = make_service(&["sre"]);
+        sign_in(&svc).await.unwrap();
+        *svc.provider.claims.lock().unwrap() = claims(&["marketing"]);
+        let user = sign_in(&svc).await.unwrap();
+        assert_eq!(user.role(), Role::Viewer);
+    }
+
+    #[tokio::test]
+    async fn existing_account_is_linked_by_verified_email() {
+        let svc = make_service(&["sre"]);
+        let existing = User::new("alice.m".into(), "alice@corp.example".into(), Role::User);
+        svc.users.save(&existing).await.unwrap();
+
+        let user = sign_in(&svc).await.unwrap();
+        assert_eq!(user.id(), existing.id());
+        assert_eq!(user.username(), "alice.m");
+        assert!(user.external_id().is_some());
+    }
+
+    #[tokio::test]
+    async fn unverified_email_does_not_take_over_accounts() {
+        let svc = make_service(&["sre"]);
+        let existing = User::new("alice".into(), "alice@corp.example".into(), Role::Admin);
+        svc.users.save(&existing).await.unwrap();
+        svc.provider.claims.lock().unwrap().email_verified = false;
+
+        let result = sign_in(&svc).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Port(rouse_ports::error::PortError::Conflict(_)))
+        ));
+        let users = svc.users.users.lock().unwrap();
+        assert_eq!(users.len(), 1);
+        assert!(users[0].external_id().is_none());
+    }
+
+    #[tokio::test]
+    async fn rejected_code_is_reported() {
+        let svc = make_service(&["sre"]);
+        let (_, request) = svc.begin().await.unwrap();
+        let result = svc.complete("forged", &request, now()).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Identity(IdentityError::Rejected(_)))
+        ));
+    }
+}
@@ -0,0 +1,191 @@
+use rouse_core::ids::UserId;
+use rouse_core::user::Team;
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::{TeamRepository, UserRepository};
+
+use crate::error::AppError;
+
+pub struct TeamService<T, U>
+where
+    T: TeamRepository,
+    U: UserRepository,
+{
+    teams: T,
+    users: U,
+}
+
+impl<T, U> TeamService<T, U>
+where
+    T: TeamRepository,
+    U: UserRepository,
+{
+    pub fn new(teams: T, users: U) -> Self {
+        Self { teams, users }
+    }
+
+    pub async fn create_team(&self, name: String, members: Vec<UserId>) -> Result<Team, AppError> {
+        for member in &members {
+            self.ensure_user_exists(member).await?;
+        }
+        let team = Team::new(name, members)?;
+        self.teams.save(&team).await?;
+        Ok(team)
+    }
+
+    pub async fn get_team(&self, id: &str) -> Result<Team, AppError> {
+        self.teams
+            .find_by_id(id)
+            .await?
+            .ok_or(AppError::Port(PortError::NotFound))
+    }
+
+    pub async fn list_teams(&self) -> Result<Vec<Team>, AppError> {
+        Ok(self.teams.list_all().await?)
+    }
+
+    pub async fn rename_team(&self, id: &str, name: String) -> Result<Team, AppError> {
+        let mut team = self.get_team(id).await?;
+        team.rename(name);
+        self.teams.save(&team).await?;
+        Ok(team)
+    }
+
+    pub async fn add_member(&self, id: &str, user_id: UserId) -> Result<Team, AppError> {
+        let mut team = self.get_team(id).await?;
+        self.ensure_user_exists(&user_id).await?;
+        team.add_member(user_id);
+        self.teams.save(&team).await?;
+        Ok(team)
+    }
+
+    pub async fn remove_member(&self, id: &str, user_id: &UserId) -> Result<Team, AppError> {
+        let mut team = self.get_team(id).await?;
+        team.remove_member(user_id)?;
+        self.teams.save(&team).await?;
+        Ok(team)
+    }
+
+    pub async fn delete_team(&self, id: &str) -> Result<(), AppError> {
+        self.get_team(id).await?;
+        self.teams.delete(id).await?;
+        Ok(())
+    }
+
+    async fn ensure_user_exists(&self, user_id: &UserId) -> Result<(), AppError> {
+        self.users
+            .find_by_id(&user_id.to_string())
+            .await?
+            .map(|_| ())
+            .ok_or(AppError::Port(PortError::NotFound))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::user_service::tests::MockUserRepo;
+    use async_trait::async_trait;
+    use rouse_core::error::DomainError;
+    use rouse_core::user::{Role, User};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    pub(crate) struct MockTeamRepo {
+        pub(crate) teams: Mutex<Vec<Team>>,
+    }
+
+    #[async_trait]
+    impl TeamRepository for MockTeamRepo {
+        async fn save(&self, team: &Team) -> Result<(), PortError> {
+            let mut teams = self.teams.lock().unwrap();
+            if teams
+                .iter()
+                .any(|t| t.id() != team.id() && t.name() == team.name())
+            {
+                return Err(PortError::Conflict("team name already in use".into()));
+            }
+            teams.retain(|t| t.id() != team.id());
+            teams.push(team.clone());
+            Ok(())
+        }
+        async fn find_by_id(&self, id: &str) -> Result<Option<Team>, PortError> {
+            let teams = self.teams.lock().unwrap();
+            Ok(teams.iter().find(|t| t.id().to_string() == id).cloned())
+        }
+        async fn list_all(&self) -> Result<Vec<Team>, PortError> {
+            Ok(self.teams.lock().unwrap().clone())
+        }
+        async fn delete(&self, id: &str) -> Result<(), PortError> {
+            self.teams
+                .lock()
+                .unwrap()
+                .retain(|t| t.id().to_string() != id);
+            Ok(())
+        }
+    }
+
+    type TestService = TeamService<MockTeamRepo, MockUserRepo>;
+
+    fn make_service() -> TestService {
+        TeamService::new(MockTeamRepo::default(), MockUserRepo::default())
+    }
+
+    fn add_user(svc: &TestService, name: &str) -> UserId {
+        let user = User::new(name.into(), format!("{name}@test.com"), Role::User);
+        let id = user.id().clone();
+        svc.users.users.lock().unwrap().push(user);
+        id
+    }
+
+    #[tokio::test]
+    async fn create_team_requires_known_members() {
+        let svc = make_service();
+        let result = svc.create_team("backend".into(), vec![UserId::new()]).await;
+        assert!(matches!(result, Err(AppError::Port(PortError::NotFound))));
+
+        let alice = add_user(&svc, "alice");
+        let team = svc
+            .create_team("backend".into(), vec![alice])
+            .await
+            .unwrap();
+        assert_eq!(svc.list_teams().await.unwrap()[0].id(), team.id());
+    }
+
+    #[tokio::test]
+    async fn add_and_remove_members() {
+        let svc = make_service();
+        let (alice, bob) = (add_user(&svc, "alice"), add_user(&svc, "bob"));
+        let team = svc
+            .create_team("backend".into(), vec![alice.clone()])
+            .await
+            .unwrap();
+        let id = team.id().to_string();
+
+        let team = svc.add_member(&id, bob.clone()).await.unwrap();
+        assert_eq!(team.members(), [alice.clone(), bob.clone()]);
+
+        let team = svc.remove_member(&id, &alice).await.unwrap();
+        assert_eq!(team.members(), std::slice::from_ref(&bob));
+
+        let result = svc.remove_member(&id, &bob).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::TeamRequiresMember))
+        ));
+    }
+
+    #[tokio::test]
+    async fn duplicate_team_name_conflicts() {
+        let svc = make_service();
+        let alice = add_user(&svc, "alice");
+        svc.create_team("backend".into(), vec![alice.clone()])
+            .await
+            .unwrap();
+
+        let result = svc.create_team("backend".into(), vec![alice]).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Port(PortError::Conflict(_)))
+        ));
+    }
+}
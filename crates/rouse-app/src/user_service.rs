@@ -0,0 +1,390 @@
+use chrono::{DateTime, Utc};
+
+use rouse_core::channel::Channel;
+use rouse_core::error::DomainError;
+use rouse_core::escalation::EscalationTarget;
+use rouse_core::user::{DndWindow, NotificationRule, QuietHours, Role, User};
+use rouse_ports::error::PortError;
+use rouse_ports::outbound::{
+    EscalationRepository, ScheduleRepository, TeamRepository, UserRepository,
+};
+
+use crate::error::AppError;
+
+/// Teams, schedules and policies are only read, to refuse deleting a user
+/// they still page.
+pub struct UserService<U, T, S, P>
+where
+    U: UserRepository,
+    T: TeamRepository,
+    S: ScheduleRepository,
+    P: EscalationRepository,
+{
+    users: U,
+    teams: T,
+    schedules: S,
+    policies: P,
+}
+
+impl<U, T, S, P> UserService<U, T, S, P>
+where
+    U: UserRepository,
+    T: TeamRepository,
+    S: ScheduleRepository,
+    P: EscalationRepository,
+{
+    pub fn new(users: U, teams: T, schedules: S, policies: P) -> Self {
+        Self {
+            users,
+            teams,
+            schedules,
+            policies,
+        }
+    }
+
+    pub async fn create_user(
+        &self,
+        username: String,
+        email: String,
+        role: Role,
+    ) -> Result<User, AppError> {
+        let user = User::new(username, email, role);
+        self.users.save(&user).await?;
+        Ok(user)
+    }
+
+    pub async fn get_user(&self, id: &str) -> Result<User, AppError> {
+        self.users
+            .find_by_id(id)
+            .await?
+            .ok_or(AppError::Port(PortError::NotFound))
+    }
+
+    pub async fn list_users(&self) -> Result<Vec<User>, AppError> {
+        Ok(self.users.list_all().await?)
+    }
+
+    pub async fn update_user(
+        &self,
+        id: &str,
+        email: Option<String>,
+        role: Option<Role>,
+    ) -> Result<User, AppError> {
+        let mut user = self.get_user(id).await?;
+        if user.is_managed() {
+            return Err(DomainError::ManagedByConfig.into());
+        }
+        if let Some(email) = email {
+            user.set_email(email);
+        }
+        if let Some(role) = role {
+            user.set_role(role);
+        }
+        self.users.save(&user).await?;
+        Ok(user)
+    }
+
+    /// Sets or clears (`None`) the user's address on `channel`.
+    pub async fn set_contact(
+        &self,
+        id: &str,
+        channel: Channel,
+        value: Option<String>,
+    ) -> Result<User, AppError> {
+        let mut user = self.get_user(id).await?;
+        user.set_contact(channel, value)?;
+        self.users.save(&user).await?;
+        Ok(user)
+    }
+
+    /// Replaces the user's quiet hours and fallback channel; `None` clears.
+    pub async fn set_quiet_hours(
+        &self,
+        id: &str,
+        quiet_hours: Option<QuietHours>,
+        fallback: Option<Channel>,
+    ) -> Result<User, AppError> {
+        let mut user = self.get_user(id).await?;
+        user.set_quiet_hours(quiet_hours);
+        user.set_quiet_fallback(fallback);
+        self.users.save(&user).await?;
+        Ok(user)
+    }
+
+    /// Replaces how the user wants to be paged; empty means every step
+    /// channel right away.
+    pub async fn set_notification_rules(
+        &self,
+        id: &str,
+        rules: Vec<NotificationRule>,
+    ) -> Result<User, AppError> {
+        for rule in &rules {
+            rule.validate()?;
+        }
+        let mut user = self.get_user(id).await?;
+        user.set_notification_rules(rules);
+        self.users.save(&user).await?;
+        Ok(user)
+    }
+
+    pub async fn add_dnd(
+        &self,
+        id: &str,
+        window: DndWindow,
+        now: DateTime<Utc>,
+    ) -> Result<User, AppError> {
+        let mut user = self.get_user(id).await?;
+        user.add_dnd(window, now);
+        self.users.save(&user).await?;
+        Ok(user)
+    }
+
+    pub async fn clear_dnd(&self, id: &str) -> Result<User, AppError> {
+        let mut user = self.get_user(id).await?;
+        user.clear_dnd();
+        self.users.save(&user).await?;
+        Ok(user)
+    }
+
+    /// Refuses while a team, schedule or policy still names the user, so
+    /// escalation never resolves to someone who is gone.
+    pub async fn delete_user(&self, id: &str) -> Result<(), AppError> {
+        let user = self.get_user(id).await?;
+        if user.is_managed() {
+            return Err(DomainError::ManagedByConfig.into());
+        }
+        if let Some(reference) = self.first_reference(&user).await? {
+            return Err(DomainError::UserInUse(reference).into());
+        }
+        self.users.delete(id).await?;
+        Ok(())
+    }
+
+    async fn first_reference(&self, user: &User) -> Result<Option<String>, AppError> {
+        let id = user.id();
+        if let Some(team) = self
+            .teams
+            .list_all()
+            .await?
+            .into_iter()
+            .find(|t| t.members().contains(id))
+        {
+            return Ok(Some(format!("team `{}`", team.name())));
+        }
+        if let Some(schedule) = self.schedules.list_all().await?.into_iter().find(|s| {
+            s.participants().contains(id) || s.overrides().iter().any(|o| o.user_id() == id)
+        }) {
+            return Ok(Some(format!("schedule `{}`", schedule.name())));
+        }
+        let target = EscalationTarget::User(id.clone());
+        if let Some(policy) = self
+            .policies
+            .list_all()
+            .await?
+            .into_iter()
+            .find(|p| p.steps().iter().any(|s| s.targets().contains(&target)))
+        {
+            return Ok(Some(format!("policy `{}`", policy.name())));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::escalation_service::tests::MockPolicyRepo;
+    use crate::schedule_service::tests::MockScheduleRepo;
+    use crate::team_service::tests::MockTeamRepo;
+    use async_trait::async_trait;
+    use rouse_core::error::DomainError;
+    use rouse_core::ids::TenantId;
+    use rouse_ports::outbound::TenantScoped;
+    use std::sync::{Arc, LazyLock, Mutex};
+
+    pub(crate) static PRIMARY_TENANT: LazyLock<TenantId> = LazyLock::new(TenantId::primary);
+
+    /// In-memory users with the same uniqueness rules as the SQLite adapter.
+    /// Holds a single tenant: every tenant handle shares the same users.
+    #[derive(Default)]
+    pub(crate) struct MockUserRepo {
+        pub(crate) users: Arc<Mutex<Vec<User>>>,
+    }
+
+    impl TenantScoped for MockUserRepo {
+        fn tenant(&self) -> &TenantId {
+            &PRIMARY_TENANT
+        }
+        fn for_tenant(&self, _tenant: &TenantId) -> Self {
+            Self {
+                users: self.users.clone(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepo {
+        async fn save(&self, user: &User) -> Result<(), PortError> {
+            let mut users = self.users.lock().unwrap();
+            let taken = users.iter().any(|u| {
+                u.id() != user.id()
+                    && (u.username() == user.username() || u.email() == user.email())
+            });
+            if taken {
+                return Err(PortError::Conflict(
+                    "username or email already in use".into(),
+                ));
+            }
+            users.retain(|u| u.id() != user.id());
+            users.push(user.clone());
+            Ok(())
+        }
+        async fn find_by_id(&self, id: &str) -> Result<Option<User>, PortError> {
+            let users = self.users.lock().unwrap();
+            Ok(users.iter().find(|u| u.id().to_string() == id).cloned())
+        }
+        async fn find_by_username(&self, username: &str) -> Result<Option<User>, PortError> {
+            let users = self.users.lock().unwrap();
+            Ok(users.iter().find(|u| u.username() == username).cloned())
+        }
+        async fn list_all(&self) -> Result<Vec<User>, PortError> {
+            Ok(self.users.lock().unwrap().clone())
+        }
+        async fn delete(&self, id: &str) -> Result<(), PortError> {
+            self.users
+                .lock()
+                .unwrap()
+                .retain(|u| u.id().to_string() != id);
+            Ok(())
+        }
+    }
+
+    type TestService = UserService<MockUserRepo, MockTeamRepo, MockScheduleRepo, MockPolicyRepo>;
+
+    fn make_service() -> TestService {
+        UserService::new(
+            MockUserRepo::default(),
+            MockTeamRepo::default(),
+            MockScheduleRepo::default(),
+            MockPolicyRepo::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn create_and_get_user() {
+        let svc = make_service();
+        let user = svc
+            .create_user("alice".into(), "alice@test.com".into(), Role::User)
+            .await
+            .unwrap();
+
+        let found = svc.get_user(&user.id().to_string()).await.unwrap();
+        assert_eq!(found.username(), "alice");
+    }
+
+    #[tokio::test]
+    async fn duplicate_username_conflicts() {
+        let svc = make_service();
+        svc.create_user("alice".into(), "alice@test.com".into(), Role::User)
+            .await
+            .unwrap();
+
+        let result = svc
+            .create_user("alice".into(), "alice2@test.com".into(), Role::User)
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::Port(PortError::Conflict(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn update_user_and_contacts() {
+        let svc = make_service();
+        let user = svc
+            .create_user("alice".into(), "alice@test.com".into(), Role::User)
+            .await
+            .unwrap();
+        let id = user.id().to_string();
+
+        let user = svc
+            .update_user(&id, Some("alice@corp.com".into()), Some(Role::Admin))
+            .await
+            .unwrap();
+        assert_eq!(user.email(), "alice@corp.com");
+        assert_eq!(user.role(), Role::Admin);
+
+        let user = svc
+            .set_contact(&id, Channel::Slack, Some("U123".into()))
+            .await
+            .unwrap();
+        assert_eq!(user.contact_for(Channel::Slack), Some("U123"));
+        assert!(!user.can_be_on_call(), "contact is not verified yet");
+
+        let result = svc.set_contact(&id, Channel::Webhook, None).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::UnsupportedContactChannel))
+        ));
+    }
+
+    #[tokio::test]
+    async fn delete_user_removes_and_then_not_found() {
+        let svc = make_service();
+        let user = svc
+            .create_user("alice".into(), "alice@test.com".into(), Role::User)
+            .await
+            .unwrap();
+        let id = user.id().to_string();
+
+        svc.delete_user(&id).await.unwrap();
+        assert!(matches!(
+            svc.delete_user(&id).await,
+            Err(AppError::Port(PortError::NotFound))
+        ));
+    }
+
+    #[tokio::test]
+    async fn delete_user_refuses_while_a_team_pages_them() {
+        let svc = make_service();
+        let user = svc
+            .create_user("alice".into(), "alice@test.com".into(), Role::User)
+            .await
+            .unwrap();
+        let team = rouse_core::user::Team::new("sre".into(), vec![user.id().clone()]).unwrap();
+        svc.teams.save(&team).await.unwrap();
+        let id = user.id().to_string();
+
+        let result = svc.delete_user(&id).await;
+        assert!(
+            matches!(result, Err(AppError::Domain(DomainError::UserInUse(ref r))) if r == "team `sre`")
+        );
+
+        svc.teams.delete(&team.id().to_string()).await.unwrap();
+        svc.delete_user(&id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn managed_users_are_read_only_except_for_contacts() {
+        let svc = make_service();
+        let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        user.set_managed(true);
+        svc.users.save(&user).await.unwrap();
+        let id = user.id().to_string();
+
+        for result in [
+            svc.update_user(&id, None, Some(Role::Admin))
+                .await
+                .map(|_| ()),
+            svc.delete_user(&id).await,
+        ] {
+            assert!(matches!(
+                result,
+                Err(AppError::Domain(DomainError::ManagedByConfig))
+            ));
+        }
+        svc.set_contact(&id, Channel::Slack, Some("U1".into()))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use rouse_core::channel::Channel;
+use rouse_core::error::DomainError;
+use rouse_core::user::{User, VerificationChallenge};
+use rouse_ports::error::{NotifyError, PortError};
+use rouse_ports::outbound::{Notifier, UserRepository, VerificationRepository};
+
+use crate::error::AppError;
+
+/// Proves contact addresses work by sending a one-time code through them.
+pub struct VerificationService<U, V>
+where
+    U: UserRepository,
+    V: VerificationRepository,
+{
+    users: U,
+    challenges: V,
+    notifiers: Vec<Arc<dyn Notifier>>,
+}
+
+impl<U, V> VerificationService<U, V>
+where
+    U: UserRepository,
+    V: VerificationRepository,
+{
+    pub fn new(users: U, challenges: V, notifiers: Vec<Arc<dyn Notifier>>) -> Self {
+        Self {
+            users,
+            challenges,
+            notifiers,
+        }
+    }
+
+    /// Sends a fresh code to the user's address on `channel`, replacing any
+    /// code sent earlier. Returns when the code expires.
+    pub async fn start(
+        &self,
+        user_id: &str,
+        channel: Channel,
+        now: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>, AppError> {
+        let user = self.get_user(user_id).await?;
+        if channel == Channel::Webhook {
+            return Err(DomainError::UnsupportedContactChannel.into());
+        }
+        let address = user
+            .contact_for(channel)
+            .ok_or(DomainError::MissingContact)?;
+        let notifier = self
+            .notifiers
+            .iter()
+            .find(|n| n.channel() == channel)
+            .ok_or(NotifyError::ChannelUnavailable)?;
+
+        let code = format!("{:06}", uuid::Uuid::new_v4().as_u128() % 1_000_000);
+        let challenge =
+            VerificationChallenge::new(user.id().clone(), channel, address.to_string(), code, now);
+        notifier
+            .send_text(
+                address,
+                &format!("Your rouse verification code is {}", challenge.code()),
+            )
+            .await?;
+        self.challenges.save(&challenge).await?;
+        Ok(challenge.expires_at())
+    }
+
+    /// Checks `code` and marks the address verified. Expired codes, codes
+    /// out of attempts and codes sent to an address that has since changed
+    /// are discarded.
+    pub async fn confirm(
+        &self,
+        user_id: &str,
+        channel: Channel,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<User, AppError> {
+        let mut user = self.get_user(user_id).await?;
+        let mut challenge = self
+            .challenges
+            .find(user_id, channel)
+            .await?
+            .ok_or(DomainError::InvalidVerificationCode)?;
+
+        if user.contact_for(channel) != Some(challenge.address()) {
+            self.challenges.delete(user_id, channel).await?;
+            return Err(DomainError::InvalidVerificationCode.into());
+        }
+
+        if let Err(e) = challenge.attempt(code, now) {
+            if challenge.is_exhausted() || matches!(e, DomainError::VerificationExpired) {
+                self.challenges.delete(user_id, channel).await?;
+            } else {
+                self.challenges.save(&challenge).await?;
+            }
+            return Err(e.into());
+        }
+
+        user.mark_verified(channel, now)?;
+        self.users.save(&user).await?;
+        self.challenges.delete(user_id, channel).await?;
+        Ok(user)
+    }
+
+    async fn get_user(&self, id: &str) -> Result<User, AppError> {
+        self.users
+            .find_by_id(id)
+            .await?
+            .ok_or(AppError::Port(PortError::NotFound))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::user_service::tests::MockUserRepo;
+    use async_trait::async_trait;
+    use rouse_core::user::Role;
+    use rouse_ports::types::{Notification, NotifyResult};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MockChallengeRepo {
+        challenges: Mutex<Vec<VerificationChallenge>>,
+    }
+
+    #[async_trait]
+    impl VerificationRepository for MockChallengeRepo {
+        async fn save(&self, challenge: &VerificationChallenge) -> Result<(), PortError> {
+            let mut challenges = self.challenges.lock().unwrap();
+            challenges.retain(|c| {
+                !(c.user_id() == challenge.user_id() && c.channel() == challenge.channel())
+            });
+            challenges.push(challenge.clone());
+            Ok(())
+        }
+        async fn find(
+            &self,
+            user_id: &str,
+            channel: Channel,
+        ) -> Result<Option<VerificationChallenge>, PortError> {
+            let challenges = self.challenges.lock().unwrap();
+            Ok(challenges
+                .iter()
+                .find(|c| c.user_id().to_string() == user_id && c.channel() == channel)
+                .cloned())
+        }
+        async fn delete(&self, user_id: &str, channel: Channel) -> Result<(), PortError> {
+            self.challenges
+                .lock()
+                .unwrap()
+                .retain(|c| !(c.user_id().to_string() == user_id && c.channel() == channel));
+            Ok(())
+        }
+    }
+
+    /// Records every text it is asked to send.
+    #[derive(Default)]
+    struct RecordingNotifier {
+        sent: Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn notify(&self, _notification: &Notification) -> Result<NotifyResult, NotifyError> {
+            Ok(NotifyResult::default())
+        }
+        async fn send_text(&self, target: &str, text: &str) -> Result<NotifyResult, NotifyError> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((target.to_string(), text.to_string()));
+            Ok(NotifyResult::default())
+        }
+        fn channel(&self) -> Channel {
+            Channel::Slack
+        }
+    }
+
+    type TestService = VerificationService<MockUserRepo, MockChallengeRepo>;
+
+    fn now() -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339("2025-01-15T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn setup() -> (TestService, Arc<RecordingNotifier>, String) {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let svc = VerificationService::new(
+            MockUserRepo::default(),
+            MockChallengeRepo::default(),
+            vec![notifier.clone()],
+        );
+        let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        user.set_slack_id("U123".into());
+        let id = user.id().to_string();
+        svc.users.users.lock().unwrap().push(user);
+        (svc, notifier, id)
+    }
+
+    fn sent_code(notifier: &RecordingNotifier) -> String {
+        let sent = notifier.sent.lock().unwrap();
+        let (target, text) = sent.last().unwrap();
+        assert_eq!(target, "U123");
+        text.rsplit(' ').next().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn confirming_sent_code_verifies_contact() {
+        let (svc, notifier, id) = setup();
+        svc.start(&id, Channel::Slack, now()).await.unwrap();
+        let code = sent_code(&notifier);
+
+        let user = svc
+            .confirm(&id, Channel::Slack, &code, now())
+            .await
+            .unwrap();
+        assert_eq!(user.verified_at(Channel::Slack), Some(now()));
+        assert!(user.can_be_on_call());
+        assert!(svc.challenges.challenges.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn wrong_code_is_rejected() {
+        let (svc, _notifier, id) = setup();
+        svc.start(&id, Channel::Slack, now()).await.unwrap();
+
+        let result = svc.confirm(&id, Channel::Slack, "bad", now()).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::InvalidVerificationCode))
+        ));
+        let user = svc.users.find_by_id(&id).await.unwrap().unwrap();
+        assert!(!user.can_be_on_call());
+    }
+
+    #[tokio::test]
+    async fn code_for_changed_address_is_discarded() {
+        let (svc, notifier, id) = setup();
+        svc.start(&id, Channel::Slack, now()).await.unwrap();
+        let code = sent_code(&notifier);
+        {
+            let mut users = svc.users.users.lock().unwrap();
+            users[0]
+                .set_contact(Channel::Slack, Some("U999".into()))
+                .unwrap();
+        }
+
+        assert!(svc
+            .confirm(&id, Channel::Slack, &code, now())
+            .await
+            .is_err());
+        assert!(svc.challenges.challenges.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn channel_without_notifier_is_unavailable() {
+        let (svc, _notifier, id) = setup();
+        {
+            let mut users = svc.users.users.lock().unwrap();
+            users[0]
+                .set_contact(Channel::Telegram, Some("42".into()))
+                .unwrap();
+        }
+
+        let result = svc.start(&id, Channel::Telegram, now()).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Notify(NotifyError::ChannelUnavailable))
+        ));
+        let result = svc.start(&id, Channel::Discord, now()).await;
+        assert!(matches!(
+            result,
+            Err(AppError::Domain(DomainError::MissingContact))
+        ));
+    }
+}
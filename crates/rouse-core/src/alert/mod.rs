@@ -8,12 +8,17 @@ pub mod status;
 
 use std::collections::BTreeMap;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::error::DomainError;
-use crate::events::{AlertAcknowledged, AlertReceived, AlertResolved, DomainEvent};
-use crate::ids::{AlertId, UserId};
+use crate::escalation::EscalationTarget;
+use crate::events::{
+    AlertAckTimedOut, AlertAcknowledged, AlertDeduplicated, AlertReassigned, AlertReceived,
+    AlertReopened, AlertResolved, AlertSeverityChanged, AlertSnoozed, AlertUnacknowledged,
+    AlertUnrouted, DomainEvent,
+};
+use crate::ids::{AlertId, PolicyId, UserId};
 
 pub use fingerprint::Fingerprint;
 pub use severity::Severity;
@@ -34,6 +39,42 @@ pub struct Alert {
     acknowledged_at: Option<DateTime<Utc>>,
     acknowledged_by: Option<UserId>,
     resolved_at: Option<DateTime<Utc>>,
+    /// No route matched it, whether or not the default policy took it.
+    #[serde(default)]
+    unrouted: bool,
+    /// The resolved alert with the same fingerprint that this one re-fires.
+    #[serde(default)]
+    previous_alert_id: Option<AlertId>,
+    #[serde(default = "first_occurrence")]
+    occurrence_count: u32,
+    /// `None` until it fires again while still open.
+    #[serde(default)]
+    last_seen_at: Option<DateTime<Utc>>,
+    /// One entry per policy that has paged for the alert.
+    #[serde(default)]
+    paged: Vec<PagedStep>,
+    /// When an acknowledgement runs out, if a policy sets a timeout.
+    #[serde(default)]
+    ack_expires_at: Option<DateTime<Utc>>,
+    /// Whom the alert was last handed over to.
+    #[serde(default)]
+    reassigned_to: Option<EscalationTarget>,
+}
+
+/// The last step of a policy that paged for an alert.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PagedStep {
+    pub policy_id: PolicyId,
+    pub step_order: u32,
+    /// The policy's round when the step fired.
+    #[serde(default)]
+    pub repetition: u32,
+    /// The policy's ack timeout when the step fired.
+    pub ack_timeout_secs: Option<u64>,
+}
+
+fn first_occurrence() -> u32 {
+    1
 }
 
 impl Alert {
@@ -60,6 +101,13 @@ impl Alert {
             acknowledged_at: None,
             acknowledged_by: None,
             resolved_at: None,
+            unrouted: false,
+            previous_alert_id: None,
+            occurrence_count: 1,
+            last_seen_at: None,
+            paged: Vec::new(),
+            ack_expires_at: None,
+            reassigned_to: None,
         };
         let events = vec![DomainEvent::AlertReceived(AlertReceived {
             alert_id: id,
@@ -78,10 +126,18 @@ impl Alert {
         match self.status {
             Status::Resolved => Err(DomainError::AlertAlreadyResolved),
             Status::Acknowledged => Ok(vec![]),
-            Status::Firing => {
+            // Acknowledging a snoozed alert keeps it acknowledged for good.
+            Status::Firing | Status::Snoozed { .. } => {
                 self.status = Status::Acknowledged;
                 self.acknowledged_at = Some(now);
                 self.acknowledged_by = Some(user_id.clone());
+                self.ack_expires_at = self
+                    .paged
+                    .iter()
+                    .filter_map(|p| p.ack_timeout_secs)
+                    .min()
+                    .and_then(|secs| TimeDelta::try_seconds(i64::try_from(secs).ok()?))
+                    .and_then(|timeout| now.checked_add_signed(timeout));
                 Ok(vec![DomainEvent::AlertAcknowledged(AlertAcknowledged {
                     alert_id: self.id.clone(),
                     user_id,
@@ -98,9 +154,10 @@ impl Alert {
     ) -> Result<Vec<DomainEvent>, DomainError> {
         match self.status {
             Status::Resolved => Ok(vec![]),
-            Status::Firing | Status::Acknowledged => {
+            Status::Firing | Status::Acknowledged | Status::Snoozed { .. } => {
                 self.status = Status::Resolved;
                 self.resolved_at = Some(now);
+                self.ack_expires_at = None;
                 Ok(vec![DomainEvent::AlertResolved(AlertResolved {
                     alert_id: self.id.clone(),
                     resolved_by,
@@ -110,6 +167,228 @@ impl Alert {
         }
     }
 
+    /// Acknowledges the alert until `until`; `wake` then sets it firing
+    /// again.
+    pub fn snooze(
+        &mut self,
+        user_id: UserId,
+        until: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<DomainEvent>, DomainError> {
+        if self.status == Status::Resolved {
+            return Err(DomainError::AlertAlreadyResolved);
+        }
+        if until <= now {
+            return Err(DomainError::SnoozeInPast);
+        }
+        self.status = Status::Snoozed { until };
+        self.acknowledged_at = Some(now);
+        self.acknowledged_by = Some(user_id.clone());
+        self.ack_expires_at = None;
+        Ok(vec![DomainEvent::AlertSnoozed(AlertSnoozed {
+            alert_id: self.id.clone(),
+            user_id,
+            until,
+            occurred_at: now,
+        })])
+    }
+
+    /// Takes back an acknowledgement or snooze. `user_id` is `None` when
+    /// a snooze runs out.
+    pub fn unacknowledge(
+        &mut self,
+        user_id: Option<UserId>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<DomainEvent>, DomainError> {
+        match self.status {
+            Status::Resolved => Err(DomainError::AlertAlreadyResolved),
+            Status::Firing => Ok(vec![]),
+            Status::Acknowledged | Status::Snoozed { .. } => {
+                self.status = Status::Firing;
+                self.acknowledged_at = None;
+                self.acknowledged_by = None;
+                self.ack_expires_at = None;
+                // Escalation starts over.
+                self.paged.clear();
+                Ok(vec![DomainEvent::AlertUnacknowledged(
+                    AlertUnacknowledged {
+                        alert_id: self.id.clone(),
+                        user_id,
+                        occurred_at: now,
+                    },
+                )])
+            }
+        }
+    }
+
+    /// Sets a snoozed alert firing again once its snooze has run out.
+    pub fn wake(&mut self, now: DateTime<Utc>) -> Vec<DomainEvent> {
+        match self.status {
+            Status::Snoozed { until } if until <= now => {
+                self.unacknowledge(None, now).unwrap_or_default()
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Sets an acknowledged alert firing again once its acknowledgement
+    /// has run out. Paging resumes after the steps in `paged`.
+    pub fn expire_ack(&mut self, now: DateTime<Utc>) -> Vec<DomainEvent> {
+        match (self.status, self.ack_expires_at) {
+            (Status::Acknowledged, Some(at)) if at <= now => {
+                self.status = Status::Firing;
+                self.acknowledged_at = None;
+                self.ack_expires_at = None;
+                vec![DomainEvent::AlertAckTimedOut(AlertAckTimedOut {
+                    alert_id: self.id.clone(),
+                    acknowledged_by: self.acknowledged_by.take(),
+                    occurred_at: now,
+                })]
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Hands the alert over to `target`. An acknowledgement or snooze is
+    /// taken back, since the new owner has yet to see it.
+    pub fn reassign(
+        &mut self,
+        target: EscalationTarget,
+        user_id: UserId,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<DomainEvent>, DomainError> {
+        let mut events = self.unacknowledge(Some(user_id.clone()), now)?;
+        self.reassigned_to = Some(target.clone());
+        events.push(DomainEvent::AlertReassigned(AlertReassigned {
+            alert_id: self.id.clone(),
+            user_id,
+            target,
+            occurred_at: now,
+        }));
+        Ok(events)
+    }
+
+    pub fn reassigned_to(&self) -> Option<&EscalationTarget> {
+        self.reassigned_to.as_ref()
+    }
+
+    /// Records that `step_order` of a policy paged for the alert.
+    pub fn record_page(
+        &mut self,
+        policy_id: PolicyId,
+        step_order: u32,
+        repetition: u32,
+        ack_timeout_secs: Option<u64>,
+    ) {
+        self.paged.retain(|p| p.policy_id != policy_id);
+        self.paged.push(PagedStep {
+            policy_id,
+            step_order,
+            repetition,
+            ack_timeout_secs,
+        });
+    }
+
+    pub fn paged(&self) -> &[PagedStep] {
+        &self.paged
+    }
+
+    pub fn ack_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.ack_expires_at
+    }
+
+    /// Sets a resolved alert firing again; a no-op for open alerts.
+    pub fn reopen(&mut self, user_id: UserId, now: DateTime<Utc>) -> Vec<DomainEvent> {
+        if self.status != Status::Resolved {
+            return vec![];
+        }
+        self.status = Status::Firing;
+        self.acknowledged_at = None;
+        self.acknowledged_by = None;
+        self.resolved_at = None;
+        self.paged.clear();
+        vec![DomainEvent::AlertReopened(AlertReopened {
+            alert_id: self.id.clone(),
+            user_id,
+            occurred_at: now,
+        })]
+    }
+
+    /// Moves the alert to `severity`; a no-op if it already has it.
+    pub fn change_severity(
+        &mut self,
+        severity: Severity,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<DomainEvent>, DomainError> {
+        if self.status == Status::Resolved {
+            return Err(DomainError::AlertAlreadyResolved);
+        }
+        if severity == self.severity {
+            return Ok(vec![]);
+        }
+        let from = std::mem::replace(&mut self.severity, severity);
+        Ok(vec![DomainEvent::AlertSeverityChanged(
+            AlertSeverityChanged {
+                alert_id: self.id.clone(),
+                from,
+                to: severity,
+                occurred_at: now,
+            },
+        )])
+    }
+
+    /// Records that no route matched. `default_policy_id` is the policy
+    /// that takes it instead, if one is configured.
+    pub fn mark_unrouted(
+        &mut self,
+        default_policy_id: Option<PolicyId>,
+        now: DateTime<Utc>,
+    ) -> Vec<DomainEvent> {
+        self.unrouted = true;
+        vec![DomainEvent::AlertUnrouted(AlertUnrouted {
+            alert_id: self.id.clone(),
+            labels: self.labels.clone(),
+            default_policy_id,
+            occurred_at: now,
+        })]
+    }
+
+    pub fn is_unrouted(&self) -> bool {
+        self.unrouted
+    }
+
+    /// The same alert fired again while still open. The summary is
+    /// replaced by the latest one; the labels are what the fingerprint is
+    /// made of, so they cannot have changed.
+    pub fn record_occurrence(&mut self, summary: String, now: DateTime<Utc>) -> Vec<DomainEvent> {
+        self.summary = summary;
+        self.occurrence_count += 1;
+        self.last_seen_at = Some(now);
+        vec![DomainEvent::AlertDeduplicated(AlertDeduplicated {
+            alert_id: self.id.clone(),
+            fingerprint: self.fingerprint.to_string(),
+            occurrence_count: self.occurrence_count,
+            occurred_at: now,
+        })]
+    }
+
+    /// Links this alert to the resolved one it fires again.
+    pub fn follow(&mut self, previous: &Alert) {
+        self.previous_alert_id = Some(previous.id.clone());
+    }
+
+    pub fn previous_alert_id(&self) -> Option<&AlertId> {
+        self.previous_alert_id.as_ref()
+    }
+
+    pub fn occurrence_count(&self) -> u32 {
+        self.occurrence_count
+    }
+
+    pub fn last_seen_at(&self) -> DateTime<Utc> {
+        self.last_seen_at.unwrap_or(self.created_at)
+    }
+
     pub fn id(&self) -> &AlertId {
         &self.id
     }
@@ -255,6 +534,205 @@ mod tests {
         assert!(events.is_empty());
     }
 
+    #[test]
+    fn mark_unrouted_flags_alert_and_returns_event() {
+        let mut alert = make_alert();
+        assert!(!alert.is_unrouted());
+        let events = alert.mark_unrouted(None, now());
+        assert!(alert.is_unrouted());
+        assert_eq!(events[0].event_type(), "alert.unrouted");
+    }
+
+    #[test]
+    fn snooze_wakes_up_firing_once_it_runs_out() {
+        let mut alert = make_alert();
+        let until = now() + chrono::Duration::hours(1);
+        let events = alert.snooze(UserId::new(), until, now()).unwrap();
+        assert_eq!(events[0].event_type(), "alert.snoozed");
+        assert_eq!(alert.status(), Status::Snoozed { until });
+        assert!(alert.acknowledged_by().is_some());
+
+        assert!(alert.wake(now()).is_empty());
+        let events = alert.wake(until);
+        assert_eq!(alert.status(), Status::Firing);
+        assert!(alert.acknowledged_by().is_none());
+        match &events[0] {
+            DomainEvent::AlertUnacknowledged(e) => assert_eq!(e.user_id, None),
+            other => panic!("expected AlertUnacknowledged, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn snooze_must_end_in_the_future() {
+        let mut alert = make_alert();
+        assert_eq!(
+            alert.snooze(UserId::new(), now(), now()),
+            Err(DomainError::SnoozeInPast)
+        );
+        alert.resolve("operator".into(), now()).unwrap();
+        assert_eq!(
+            alert.snooze(UserId::new(), now() + chrono::Duration::hours(1), now()),
+            Err(DomainError::AlertAlreadyResolved)
+        );
+    }
+
+    #[test]
+    fn unacknowledge_returns_to_firing() {
+        let mut alert = make_alert();
+        assert!(alert.unacknowledge(None, now()).unwrap().is_empty());
+        alert.acknowledge(UserId::new(), now()).unwrap();
+        let events = alert.unacknowledge(Some(UserId::new()), now()).unwrap();
+        assert_eq!(events[0].event_type(), "alert.unacknowledged");
+        assert_eq!(alert.status(), Status::Firing);
+    }
+
+    #[test]
+    fn ack_expires_after_the_shortest_policy_timeout() {
+        let mut alert = make_alert();
+        alert.record_page(PolicyId::new(), 0, 0, Some(1800));
+        alert.record_page(PolicyId::new(), 1, 0, Some(900));
+        alert.record_page(PolicyId::new(), 0, 0, None);
+        let user = UserId::new();
+        alert.acknowledge(user.clone(), now()).unwrap();
+        let expires = now() + chrono::Duration::minutes(15);
+        assert_eq!(alert.ack_expires_at(), Some(expires));
+
+        assert!(alert.expire_ack(now()).is_empty());
+        let events = alert.expire_ack(expires);
+        assert_eq!(alert.status(), Status::Firing);
+        assert_eq!(alert.ack_expires_at(), None);
+        assert_eq!(alert.paged().len(), 3);
+        match &events[0] {
+            DomainEvent::AlertAckTimedOut(e) => assert_eq!(e.acknowledged_by, Some(user)),
+            other => panic!("expected AlertAckTimedOut, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn ack_timeout_too_long_to_add_holds() {
+        let mut alert = make_alert();
+        alert.record_page(PolicyId::new(), 0, 0, Some(u64::MAX));
+        alert.acknowledge(UserId::new(), now()).unwrap();
+        assert_eq!(alert.ack_expires_at(), None);
+    }
+
+    #[test]
+    fn ack_without_timeout_holds() {
+        let mut alert = make_alert();
+        let policy = PolicyId::new();
+        alert.record_page(policy.clone(), 0, 0, None);
+        alert.record_page(policy, 1, 0, None);
+        assert_eq!(alert.paged().len(), 1);
+        alert.acknowledge(UserId::new(), now()).unwrap();
+        assert_eq!(alert.ack_expires_at(), None);
+        assert!(alert
+            .expire_ack(now() + chrono::Duration::days(1))
+            .is_empty());
+    }
+
+    #[test]
+    fn reassign_takes_back_the_acknowledgement() {
+        let mut alert = make_alert();
+        let team = EscalationTarget::Team(crate::ids::TeamId::new());
+        let user = UserId::new();
+        let events = alert.reassign(team.clone(), user.clone(), now()).unwrap();
+        assert_eq!(
+            events,
+            [DomainEvent::AlertReassigned(AlertReassigned {
+                alert_id: alert.id().clone(),
+                user_id: user,
+                target: team.clone(),
+                occurred_at: now(),
+            })]
+        );
+        assert_eq!(alert.reassigned_to(), Some(&team));
+
+        alert.acknowledge(UserId::new(), now()).unwrap();
+        let events = alert.reassign(team.clone(), UserId::new(), now()).unwrap();
+        assert_eq!(events[0].event_type(), "alert.unacknowledged");
+        assert_eq!(events[1].event_type(), "alert.reassigned");
+        assert_eq!(alert.status(), Status::Firing);
+
+        alert.resolve("operator".into(), now()).unwrap();
+        assert_eq!(
+            alert.reassign(team, UserId::new(), now()),
+            Err(DomainError::AlertAlreadyResolved)
+        );
+    }
+
+    #[test]
+    fn reopen_only_applies_to_resolved_alerts() {
+        let mut alert = make_alert();
+        assert!(alert.reopen(UserId::new(), now()).is_empty());
+        alert.resolve("operator".into(), now()).unwrap();
+        let events = alert.reopen(UserId::new(), now());
+        assert_eq!(events[0].event_type(), "alert.reopened");
+        assert_eq!(alert.status(), Status::Firing);
+    }
+
+    #[test]
+    fn change_severity_returns_event_once() {
+        let mut alert = make_alert();
+        let events = alert.change_severity(Severity::Warning, now()).unwrap();
+        assert_eq!(alert.severity(), Severity::Warning);
+        match &events[0] {
+            DomainEvent::AlertSeverityChanged(e) => {
+                assert_eq!((e.from, e.to), (Severity::Critical, Severity::Warning));
+            }
+            other => panic!("expected AlertSeverityChanged, got {other:?}"),
+        }
+        assert!(alert
+            .change_severity(Severity::Warning, now())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn change_severity_of_resolved_alert_fails() {
+        let mut alert = make_alert();
+        alert.resolve("operator".into(), now()).unwrap();
+        assert_eq!(
+            alert.change_severity(Severity::Info, now()),
+            Err(DomainError::AlertAlreadyResolved)
+        );
+    }
+
+    #[test]
+    fn record_occurrence_counts_repeat_fires() {
+        let mut alert = make_alert();
+        assert_eq!(alert.occurrence_count(), 1);
+        assert_eq!(alert.last_seen_at(), now());
+
+        let later = now() + chrono::Duration::minutes(5);
+        let events = alert.record_occurrence("CPU is still high".into(), later);
+        assert_eq!(alert.summary(), "CPU is still high");
+        assert_eq!(alert.occurrence_count(), 2);
+        assert_eq!(alert.last_seen_at(), later);
+        match &events[0] {
+            DomainEvent::AlertDeduplicated(e) => assert_eq!(e.occurrence_count, 2),
+            other => panic!("expected AlertDeduplicated, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn alerts_stored_before_occurrences_were_tracked_still_load() {
+        let mut json = serde_json::to_value(make_alert()).unwrap();
+        let fields = json.as_object_mut().unwrap();
+        for field in [
+            "previous_alert_id",
+            "occurrence_count",
+            "last_seen_at",
+            "paged",
+            "ack_expires_at",
+            "reassigned_to",
+        ] {
+            fields.remove(field);
+        }
+        let alert: Alert = serde_json::from_value(json).unwrap();
+        assert_eq!(alert.occurrence_count(), 1);
+        assert_eq!(alert.previous_alert_id(), None);
+    }
+
     #[test]
     fn fingerprint_ignores_label_order() {
         // BTreeMap is inherently sorted, so insertion order doesn't matter.
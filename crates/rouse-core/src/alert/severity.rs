@@ -6,3 +6,14 @@ pub enum Severity {
     Warning,
     Info,
 }
+
+impl Severity {
+    /// Higher is more severe.
+    pub fn rank(self) -> u8 {
+        match self {
+            Self::Critical => 3,
+            Self::Warning => 2,
+            Self::Info => 1,
+        }
+    }
+}
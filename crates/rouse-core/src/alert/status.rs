@@ -1,8 +1,25 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Status {
     Firing,
     Acknowledged,
+    /// Acknowledged until `until`, then firing again.
+    Snoozed {
+        until: DateTime<Utc>,
+    },
     Resolved,
 }
+
+impl Status {
+    /// The variant alone, as stored and filtered on.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Firing => "Firing",
+            Self::Acknowledged => "Acknowledged",
+            Self::Snoozed { .. } => "Snoozed",
+            Self::Resolved => "Resolved",
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Which role may perform which operation. Roles are ordered: admins can do
+//! everything users can, users everything viewers can.
+
+use crate::error::DomainError;
+use crate::ids::{TenantId, UserId};
+use crate::user::Role;
+
+/// The authenticated caller of an operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    pub tenant_id: TenantId,
+    pub user_id: UserId,
+    pub role: Role,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation<'a> {
+    ViewAlerts,
+    ViewSchedules,
+    ViewUsers,
+    ViewTeams,
+    AcknowledgeAlert,
+    ResolveAlert,
+    ConfigureSchedule,
+    /// Add, move or remove an override placing `user_id` on call.
+    ManageOverride {
+        user_id: &'a UserId,
+    },
+    /// Request, accept, decline or cancel a swap as `user_id`.
+    SwapAs {
+        user_id: &'a UserId,
+    },
+    /// Contacts, quiet hours, password and tokens of `user_id`.
+    ManageProfile {
+        user_id: &'a UserId,
+    },
+    ManageUsers,
+    ManageTeams,
+    ViewAuditLog,
+}
+
+impl Principal {
+    pub fn new(tenant_id: TenantId, user_id: UserId, role: Role) -> Self {
+        Self {
+            tenant_id,
+            user_id,
+            role,
+        }
+    }
+
+    /// Lowest role allowed to perform `op` as this principal.
+    fn required_role(&self, op: Operation<'_>) -> Role {
+        match op {
+            Operation::ViewAlerts
+            | Operation::ViewSchedules
+            | Operation::ViewUsers
+            | Operation::ViewTeams => Role::Viewer,
+            Operation::ManageProfile { user_id } if *user_id == self.user_id => Role::Viewer,
+            Operation::AcknowledgeAlert | Operation::ResolveAlert => Role::User,
+            Operation::ManageOverride { user_id } | Operation::SwapAs { user_id }
+                if *user_id == self.user_id =>
+            {
+                Role::User
+            }
+            Operation::ManageOverride { .. }
+            | Operation::SwapAs { .. }
+            | Operation::ManageProfile { .. }
+            | Operation::ConfigureSchedule
+            | Operation::ManageUsers
+            | Operation::ManageTeams
+            | Operation::ViewAuditLog => Role::Admin,
+        }
+    }
+
+    pub fn authorize(&self, op: Operation<'_>) -> Result<(), DomainError> {
+        if self.role.rank() >= self.required_role(op).rank() {
+            Ok(())
+        } else {
+            Err(DomainError::Forbidden)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowed(role: Role, op: Operation<'_>, me: &UserId) -> bool {
+        Principal::new(TenantId::primary(), me.clone(), role)
+            .authorize(op)
+            .is_ok()
+    }
+
+    /// `[viewer, user, admin]` expectations for an operation.
+    fn check(op: Operation<'_>, me: &UserId, expected: [bool; 3]) {
+        let actual = [Role::Viewer, Role::User, Role::Admin].map(|r| allowed(r, op, me));
+        assert_eq!(actual, expected, "{op:?}");
+    }
+
+    #[test]
+    fn everyone_can_read() {
+        let me = UserId::new();
+        for op in [
+            Operation::ViewAlerts,
+            Operation::ViewSchedules,
+            Operation::ViewUsers,
+            Operation::ViewTeams,
+        ] {
+            check(op, &me, [true, true, true]);
+        }
+    }
+
+    #[test]
+    fn users_can_work_alerts() {
+        let me = UserId::new();
+        check(Operation::AcknowledgeAlert, &me, [false, true, true]);
+        check(Operation::ResolveAlert, &me, [false, true, true]);
+    }
+
+    #[test]
+    fn users_manage_only_their_own_overrides_and_swaps() {
+        let (me, other) = (UserId::new(), UserId::new());
+        check(
+            Operation::ManageOverride { user_id: &me },
+            &me,
+            [false, true, true],
+        );
+        check(
+            Operation::ManageOverride { user_id: &other },
+            &me,
+            [false, false, true],
+        );
+        check(Operation::SwapAs { user_id: &me }, &me, [false, true, true]);
+        check(
+            Operation::SwapAs { user_id: &other },
+            &me,
+            [false, false, true],
+        );
+    }
+
+    #[test]
+    fn everyone_manages_their_own_profile() {
+        let (me, other) = (UserId::new(), UserId::new());
+        check(
+            Operation::ManageProfile { user_id: &me },
+            &me,
+            [true, true, true],
+        );
+        check(
+            Operation::ManageProfile { user_id: &other },
+            &me,
+            [false, false, true],
+        );
+    }
+
+    #[test]
+    fn configuration_is_admin_only() {
+        let me = UserId::new();
+        for op in [
+            Operation::ConfigureSchedule,
+            Operation::ManageUsers,
+            Operation::ManageTeams,
+            Operation::ViewAuditLog,
+        ] {
+            check(op, &me, [false, false, true]);
+        }
+    }
+}
@@ -8,8 +8,16 @@ pub enum DomainError {
     ScheduleRequiresParticipant,
     #[error("invalid phone format")]
     InvalidPhoneFormat,
+    #[error("invalid rotation: {0}")]
+    InvalidRotation(String),
     #[error("invalid override period")]
     InvalidOverridePeriod,
+    #[error("override overlaps an existing override")]
+    OverlappingOverride,
+    #[error("override not found")]
+    OverrideNotFound,
+    #[error("user cannot be on call")]
+    UserCannotBeOnCall,
     #[error("invalid id: {0}")]
     InvalidId(String),
     #[error("policy requires at least one step")]
@@ -20,4 +28,42 @@ pub enum DomainError {
     StepRequiresChannel,
     #[error("team requires at least one member")]
     TeamRequiresMember,
+    #[error("user is not a member of the team")]
+    NotTeamMember,
+    #[error("channel has no per-user contact")]
+    UnsupportedContactChannel,
+    #[error("no contact address set for this channel")]
+    MissingContact,
+    #[error("invalid verification code")]
+    InvalidVerificationCode,
+    #[error("verification code expired")]
+    VerificationExpired,
+    #[error("invalid notification rule: {0}")]
+    InvalidNotificationRule(String),
+    #[error("quiet period must not be empty")]
+    InvalidQuietPeriod,
+    #[error("operation not permitted for this role")]
+    Forbidden,
+    #[error("password must be at least {0} characters")]
+    WeakPassword(usize),
+    #[error("cannot swap a shift with yourself")]
+    SwapWithSelf,
+    #[error("swap request is no longer pending")]
+    SwapNotPending,
+    #[error("only the counterpart can respond to a swap request")]
+    NotSwapCounterpart,
+    #[error("only the requester can cancel a swap request")]
+    NotSwapRequester,
+    #[error("managed by the config file; change it there")]
+    ManagedByConfig,
+    #[error("invalid matcher {0}")]
+    InvalidMatcher(String),
+    #[error("snooze must end in the future")]
+    SnoozeInPast,
+    #[error("a newer alert with the same fingerprint is open")]
+    AlertSuperseded,
+    #[error("user is still referenced by {0}")]
+    UserInUse(String),
+    #[error("a delay of {0} seconds is too long to schedule")]
+    DelayTooLong(u64),
 }
@@ -8,7 +8,13 @@ use crate::events::DomainEvent;
 use crate::ids::PolicyId;
 
 pub use step::EscalationStep;
-pub use target::{EscalationTarget, OnCallModifier};
+pub use target::{EscalationTarget, OnCallModifier, TargetResolver};
+
+/// The longest an acknowledgement may hold paging back.
+pub const MAX_ACK_TIMEOUT_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// The longest a step may wait after the one before it.
+pub const MAX_STEP_WAIT_SECS: u64 = 7 * 24 * 60 * 60;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EscalationPolicy {
@@ -16,6 +22,11 @@ pub struct EscalationPolicy {
     name: String,
     steps: Vec<EscalationStep>,
     repeat_count: u32,
+    #[serde(default)]
+    managed: bool,
+    /// How long an acknowledgement holds before paging resumes.
+    #[serde(default)]
+    ack_timeout_secs: Option<u64>,
 }
 
 impl EscalationPolicy {
@@ -32,6 +43,8 @@ impl EscalationPolicy {
             name,
             steps,
             repeat_count,
+            managed: false,
+            ack_timeout_secs: None,
         })
     }
 
@@ -46,17 +59,39 @@ impl EscalationPolicy {
         }
     }
 
-    pub fn add_step(&mut self, step: EscalationStep) -> Result<Vec<DomainEvent>, DomainError> {
-        if step.targets().is_empty() {
-            return Err(DomainError::StepRequiresTarget);
-        }
-        if step.channels().is_empty() {
-            return Err(DomainError::StepRequiresChannel);
+    /// The position and round of the step after `current` in round
+    /// `repetition`; past the last step a new round starts at the first.
+    pub fn next_position(&self, current: u32, repetition: u32) -> Option<(u32, u32)> {
+        self.next_step(current, repetition)?;
+        if (current as usize + 1) < self.steps.len() {
+            Some((current + 1, repetition))
+        } else {
+            Some((0, repetition + 1))
         }
+    }
+
+    pub fn add_step(&mut self, step: EscalationStep) -> Result<Vec<DomainEvent>, DomainError> {
+        validate_step(&step)?;
         self.steps.push(step);
         Ok(vec![])
     }
 
+    /// Replaces all steps, keeping the id. Every step needs a target and a
+    /// channel.
+    pub fn reconfigure(
+        &mut self,
+        steps: Vec<EscalationStep>,
+        repeat_count: u32,
+    ) -> Result<(), DomainError> {
+        if steps.is_empty() {
+            return Err(DomainError::PolicyRequiresStep);
+        }
+        steps.iter().try_for_each(validate_step)?;
+        self.steps = steps;
+        self.repeat_count = repeat_count;
+        Ok(())
+    }
+
     pub fn first_step(&self) -> &EscalationStep {
         &self.steps[0]
     }
@@ -76,6 +111,33 @@ impl EscalationPolicy {
     pub fn repeat_count(&self) -> u32 {
         self.repeat_count
     }
+
+    /// Declared in the config file: edits go there, not through the API.
+    pub fn is_managed(&self) -> bool {
+        self.managed
+    }
+
+    pub fn set_managed(&mut self, managed: bool) {
+        self.managed = managed;
+    }
+
+    pub fn ack_timeout_secs(&self) -> Option<u64> {
+        self.ack_timeout_secs
+    }
+
+    pub fn set_ack_timeout(&mut self, secs: Option<u64>) {
+        self.ack_timeout_secs = secs;
+    }
+}
+
+fn validate_step(step: &EscalationStep) -> Result<(), DomainError> {
+    if step.targets().is_empty() {
+        return Err(DomainError::StepRequiresTarget);
+    }
+    if step.channels().is_empty() {
+        return Err(DomainError::StepRequiresChannel);
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -138,6 +200,15 @@ mod tests {
         assert!(policy.next_step(0, 1).is_none());
     }
 
+    #[test]
+    fn next_position_starts_a_new_round_until_repeats_run_out() {
+        let steps = vec![make_step(0, 0), make_step(1, 600)];
+        let policy = EscalationPolicy::new("p".into(), steps, 1).unwrap();
+        assert_eq!(policy.next_position(0, 0), Some((1, 0)));
+        assert_eq!(policy.next_position(1, 0), Some((0, 1)));
+        assert_eq!(policy.next_position(1, 1), None);
+    }
+
     #[test]
     fn step_requires_target() {
         let mut policy = EscalationPolicy::new("p".into(), vec![make_step(0, 0)], 0).unwrap();
@@ -154,6 +225,23 @@ mod tests {
         assert!(matches!(result, Err(DomainError::StepRequiresChannel)));
     }
 
+    #[test]
+    fn reconfigure_validates_every_step() {
+        let mut policy = EscalationPolicy::new("p".into(), vec![make_step(0, 0)], 0).unwrap();
+        let id = policy.id().clone();
+        policy
+            .reconfigure(vec![make_step(0, 0), make_step(1, 600)], 2)
+            .unwrap();
+        assert_eq!(policy.id(), &id);
+        assert_eq!(policy.steps().len(), 2);
+        assert_eq!(policy.repeat_count(), 2);
+
+        let silent = EscalationStep::new(0, 0, vec![EscalationTarget::User(UserId::new())], vec![]);
+        let result = policy.reconfigure(vec![silent], 0);
+        assert!(matches!(result, Err(DomainError::StepRequiresChannel)));
+        assert_eq!(policy.steps().len(), 2);
+    }
+
     #[test]
     fn channel_enum_is_exhaustive() {
         // Verify all channel variants exist
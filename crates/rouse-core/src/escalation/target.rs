@@ -1,6 +1,9 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::ids::{ScheduleId, TeamId, UserId};
+use crate::schedule::Schedule;
+use crate::user::Team;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EscalationTarget {
@@ -14,6 +17,155 @@ pub enum EscalationTarget {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OnCallModifier {
+    /// Whoever is on call right now, overrides included.
     Current,
+    /// The next person in rotation order.
     Next,
+    /// The person before the current one in rotation order.
+    Previous,
+    /// Whoever is on call on the schedule's backup layer.
+    Secondary,
+}
+
+/// Turns escalation targets into the people to notify at a given time.
+pub struct TargetResolver<'a> {
+    pub schedules: &'a [Schedule],
+    pub teams: &'a [Team],
+}
+
+impl TargetResolver<'_> {
+    /// Unknown schedules and teams, and schedules without a backup layer
+    /// asked for `Secondary`, resolve to nobody.
+    pub fn resolve(&self, target: &EscalationTarget, at: DateTime<Utc>) -> Vec<UserId> {
+        match target {
+            EscalationTarget::User(id) => vec![id.clone()],
+            EscalationTarget::Team(id) => self
+                .teams
+                .iter()
+                .find(|t| t.id() == id)
+                .map(|t| t.members().to_vec())
+                .unwrap_or_default(),
+            EscalationTarget::OnCall {
+                schedule_id,
+                modifier,
+            } => self
+                .schedules
+                .iter()
+                .find(|s| s.id() == schedule_id)
+                .and_then(|s| s.on_call(*modifier, at))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Resolves every target, keeping the first occurrence of each person.
+    pub fn resolve_all(&self, targets: &[EscalationTarget], at: DateTime<Utc>) -> Vec<UserId> {
+        let mut users: Vec<UserId> = Vec::new();
+        for user in targets.iter().flat_map(|t| self.resolve(t, at)) {
+            if !users.contains(&user) {
+                users.push(user);
+            }
+        }
+        users
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::{BackupLayer, HandoffTime, Rotation};
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn schedule(users: Vec<UserId>) -> Schedule {
+        Schedule::new(
+            "primary".into(),
+            "UTC".parse().unwrap(),
+            Rotation::Daily,
+            users,
+            HandoffTime {
+                day: chrono::Weekday::Mon,
+                hour: 0,
+                minute: 0,
+            },
+        )
+        .unwrap()
+    }
+
+    fn on_call(schedule_id: &ScheduleId, modifier: OnCallModifier) -> EscalationTarget {
+        EscalationTarget::OnCall {
+            schedule_id: schedule_id.clone(),
+            modifier,
+        }
+    }
+
+    #[test]
+    fn resolves_each_modifier() {
+        let users = vec![UserId::new(), UserId::new()];
+        let backup = UserId::new();
+        let mut sched = schedule(users.clone());
+        sched.set_backup(Some(
+            BackupLayer::new(Rotation::Weekly, vec![backup.clone()]).unwrap(),
+        ));
+        let schedules = [sched.clone()];
+        let resolver = TargetResolver {
+            schedules: &schedules,
+            teams: &[],
+        };
+        let at = ts("2025-01-15T12:00:00Z");
+        let id = sched.id();
+
+        let current = resolver.resolve(&on_call(id, OnCallModifier::Current), at);
+        let next = resolver.resolve(&on_call(id, OnCallModifier::Next), at);
+        let previous = resolver.resolve(&on_call(id, OnCallModifier::Previous), at);
+        assert_eq!(current.len(), 1);
+        assert_ne!(current, next);
+        assert_eq!(next, previous);
+        assert_eq!(
+            resolver.resolve(&on_call(id, OnCallModifier::Secondary), at),
+            [backup]
+        );
+    }
+
+    #[test]
+    fn missing_secondary_and_unknown_schedule_resolve_to_nobody() {
+        let sched = schedule(vec![UserId::new()]);
+        let schedules = [sched.clone()];
+        let resolver = TargetResolver {
+            schedules: &schedules,
+            teams: &[],
+        };
+        let at = ts("2025-01-15T12:00:00Z");
+
+        assert!(resolver
+            .resolve(&on_call(sched.id(), OnCallModifier::Secondary), at)
+            .is_empty());
+        assert!(resolver
+            .resolve(&on_call(&ScheduleId::new(), OnCallModifier::Current), at)
+            .is_empty());
+    }
+
+    #[test]
+    fn resolve_all_expands_teams_and_dedups() {
+        let (a, b) = (UserId::new(), UserId::new());
+        let team = Team::new("backend".into(), vec![a.clone(), b.clone()]).unwrap();
+        let teams = [team.clone()];
+        let resolver = TargetResolver {
+            schedules: &[],
+            teams: &teams,
+        };
+
+        let users = resolver.resolve_all(
+            &[
+                EscalationTarget::User(b.clone()),
+                EscalationTarget::Team(team.id().clone()),
+            ],
+            ts("2025-01-15T12:00:00Z"),
+        );
+        assert_eq!(users, [b, a]);
+    }
 }
@@ -1,21 +1,36 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 
 use crate::alert::severity::Severity;
 use crate::channel::Channel;
-use crate::ids::{AlertId, PolicyId, ScheduleId, UserId};
+use crate::escalation::EscalationTarget;
+use crate::ids::{AlertId, PolicyId, ScheduleId, SwapId, UserId};
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum DomainEvent {
     AlertReceived(AlertReceived),
     AlertDeduplicated(AlertDeduplicated),
+    AlertSuppressed(AlertSuppressed),
+    AlertUnrouted(AlertUnrouted),
+    AlertSeverityChanged(AlertSeverityChanged),
     AlertAcknowledged(AlertAcknowledged),
+    AlertSnoozed(AlertSnoozed),
+    AlertUnacknowledged(AlertUnacknowledged),
+    AlertReopened(AlertReopened),
+    AlertAckTimedOut(AlertAckTimedOut),
+    AlertReassigned(AlertReassigned),
     AlertEscalated(AlertEscalated),
     AlertResolved(AlertResolved),
     NotificationSent(NotificationSent),
     NotificationFailed(NotificationFailed),
     OnCallChanged(OnCallChanged),
     EscalationExhausted(EscalationExhausted),
+    SwapRequested(SwapRequested),
+    SwapAccepted(SwapAccepted),
+    SwapDeclined(SwapDeclined),
+    SwapCancelled(SwapCancelled),
 }
 
 impl DomainEvent {
@@ -23,13 +38,25 @@ impl DomainEvent {
         match self {
             Self::AlertReceived(e) => e.occurred_at,
             Self::AlertDeduplicated(e) => e.occurred_at,
+            Self::AlertSuppressed(e) => e.occurred_at,
+            Self::AlertUnrouted(e) => e.occurred_at,
+            Self::AlertSeverityChanged(e) => e.occurred_at,
             Self::AlertAcknowledged(e) => e.occurred_at,
+            Self::AlertSnoozed(e) => e.occurred_at,
+            Self::AlertUnacknowledged(e) => e.occurred_at,
+            Self::AlertReopened(e) => e.occurred_at,
+            Self::AlertAckTimedOut(e) => e.occurred_at,
+            Self::AlertReassigned(e) => e.occurred_at,
             Self::AlertEscalated(e) => e.occurred_at,
             Self::AlertResolved(e) => e.occurred_at,
             Self::NotificationSent(e) => e.occurred_at,
             Self::NotificationFailed(e) => e.occurred_at,
             Self::OnCallChanged(e) => e.occurred_at,
             Self::EscalationExhausted(e) => e.occurred_at,
+            Self::SwapRequested(e) => e.occurred_at,
+            Self::SwapAccepted(e) => e.occurred_at,
+            Self::SwapDeclined(e) => e.occurred_at,
+            Self::SwapCancelled(e) => e.occurred_at,
         }
     }
 
@@ -37,13 +64,25 @@ impl DomainEvent {
         match self {
             Self::AlertReceived(_) => "alert.received",
             Self::AlertDeduplicated(_) => "alert.deduplicated",
+            Self::AlertSuppressed(_) => "alert.suppressed",
+            Self::AlertUnrouted(_) => "alert.unrouted",
+            Self::AlertSeverityChanged(_) => "alert.severity_changed",
             Self::AlertAcknowledged(_) => "alert.acknowledged",
+            Self::AlertSnoozed(_) => "alert.snoozed",
+            Self::AlertUnacknowledged(_) => "alert.unacknowledged",
+            Self::AlertReopened(_) => "alert.reopened",
+            Self::AlertAckTimedOut(_) => "alert.ack_timed_out",
+            Self::AlertReassigned(_) => "alert.reassigned",
             Self::AlertEscalated(_) => "alert.escalated",
             Self::AlertResolved(_) => "alert.resolved",
             Self::NotificationSent(_) => "notification.sent",
             Self::NotificationFailed(_) => "notification.failed",
             Self::OnCallChanged(_) => "oncall.changed",
             Self::EscalationExhausted(_) => "escalation.exhausted",
+            Self::SwapRequested(_) => "swap.requested",
+            Self::SwapAccepted(_) => "swap.accepted",
+            Self::SwapDeclined(_) => "swap.declined",
+            Self::SwapCancelled(_) => "swap.cancelled",
         }
     }
 }
@@ -60,6 +99,36 @@ pub struct AlertReceived {
 pub struct AlertDeduplicated {
     pub alert_id: AlertId,
     pub fingerprint: String,
+    /// How many times the alert has fired, this time included.
+    pub occurrence_count: u32,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// A route held back escalation to `policy_id`. `escalates_at` is when it
+/// will start anyway, if the alert is still firing then.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertSuppressed {
+    pub alert_id: AlertId,
+    pub policy_id: PolicyId,
+    pub escalates_at: Option<DateTime<Utc>>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// No route matched. The labels are kept so a mistyped one can be spotted.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertUnrouted {
+    pub alert_id: AlertId,
+    pub labels: BTreeMap<String, String>,
+    pub default_policy_id: Option<PolicyId>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// A repeat fire came in at another severity.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertSeverityChanged {
+    pub alert_id: AlertId,
+    pub from: Severity,
+    pub to: Severity,
     pub occurred_at: DateTime<Utc>,
 }
 
@@ -70,6 +139,49 @@ pub struct AlertAcknowledged {
     pub occurred_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertSnoozed {
+    pub alert_id: AlertId,
+    pub user_id: UserId,
+    pub until: DateTime<Utc>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Back to firing. `user_id` is `None` when a snooze ran out.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertUnacknowledged {
+    pub alert_id: AlertId,
+    pub user_id: Option<UserId>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertReopened {
+    pub alert_id: AlertId,
+    pub user_id: UserId,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Nobody resolved the alert in time after `acknowledged_by` took it, so
+/// paging resumed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertAckTimedOut {
+    pub alert_id: AlertId,
+    pub acknowledged_by: Option<UserId>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// `user_id` handed the alert over to `target`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertReassigned {
+    pub alert_id: AlertId,
+    pub user_id: UserId,
+    pub target: EscalationTarget,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// A step paged `targets`, the people it resolved to. `step` counts from
+/// 1, as in the config file.
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct AlertEscalated {
     pub alert_id: AlertId,
@@ -118,6 +230,39 @@ pub struct EscalationExhausted {
     pub occurred_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SwapRequested {
+    pub swap_id: SwapId,
+    pub schedule_id: ScheduleId,
+    pub requester: UserId,
+    pub counterpart: UserId,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SwapAccepted {
+    pub swap_id: SwapId,
+    pub schedule_id: ScheduleId,
+    pub approved_by: UserId,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SwapDeclined {
+    pub swap_id: SwapId,
+    pub schedule_id: ScheduleId,
+    pub declined_by: UserId,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SwapCancelled {
+    pub swap_id: SwapId,
+    pub schedule_id: ScheduleId,
+    pub cancelled_by: UserId,
+    pub occurred_at: DateTime<Utc>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,13 +278,25 @@ mod tests {
         let types = [
             "alert.received",
             "alert.deduplicated",
+            "alert.suppressed",
+            "alert.unrouted",
+            "alert.severity_changed",
             "alert.acknowledged",
+            "alert.snoozed",
+            "alert.unacknowledged",
+            "alert.reopened",
+            "alert.ack_timed_out",
+            "alert.reassigned",
             "alert.escalated",
             "alert.resolved",
             "notification.sent",
             "notification.failed",
             "oncall.changed",
             "escalation.exhausted",
+            "swap.requested",
+            "swap.accepted",
+            "swap.declined",
+            "swap.cancelled",
         ];
         let mut unique = std::collections::HashSet::new();
         for t in &types {
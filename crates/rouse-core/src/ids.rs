@@ -41,6 +41,15 @@ define_id!(PolicyId);
 define_id!(TeamId);
 define_id!(GroupId);
 define_id!(OverrideId);
+define_id!(SwapId);
+define_id!(TenantId);
+
+impl TenantId {
+    /// The only tenant of a self-hosted install.
+    pub fn primary() -> Self {
+        Self(Uuid::nil())
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -69,5 +78,13 @@ mod tests {
         let _team = TeamId::new();
         let _group = GroupId::new();
         let _override_id = OverrideId::new();
+        let _swap = SwapId::new();
+        let _tenant = TenantId::new();
+    }
+
+    #[test]
+    fn primary_tenant_is_stable() {
+        assert_eq!(TenantId::primary(), TenantId::primary());
+        assert_ne!(TenantId::primary(), TenantId::new());
     }
 }
@@ -1,8 +1,10 @@
 pub mod alert;
+pub mod authz;
 pub mod channel;
 pub mod error;
 pub mod escalation;
 pub mod events;
 pub mod ids;
+pub mod routing;
 pub mod schedule;
 pub mod user;
@@ -0,0 +1,502 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::alert::Severity;
+use crate::error::DomainError;
+use crate::ids::PolicyId;
+use crate::schedule::tz_serde;
+use crate::user::quiet_hours::local_to_utc;
+
+/// A node of the routing tree: sends alerts that satisfy all of `matchers`
+/// to `policy_id`, unless one of `routes` takes them. Children are only
+/// tried when their parent matched, so they add to its matchers, and they
+/// inherit its policy and options unless they set their own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Route {
+    pub matchers: Vec<Matcher>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_id: Option<PolicyId>,
+    /// Keep trying the following siblings after this route matched, so one
+    /// alert can reach several policies.
+    #[serde(default, rename = "continue", skip_serializing_if = "is_false")]
+    pub continue_matching: bool,
+    #[serde(default, skip_serializing_if = "RouteOptions::is_empty")]
+    pub options: RouteOptions,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<Route>,
+}
+
+impl Route {
+    /// A route without children or options.
+    pub fn new(matchers: Vec<Matcher>, policy_id: PolicyId) -> Self {
+        Self {
+            matchers,
+            policy_id: Some(policy_id),
+            continue_matching: false,
+            options: RouteOptions::default(),
+            routes: Vec::new(),
+        }
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Per-route settings. Unset fields are inherited from the parent route.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteOptions {
+    /// Matched alerts are recorded but not escalated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppress: Option<bool>,
+    /// Matched alerts are recorded but not escalated during these windows.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppress_hours: Option<Vec<SuppressionWindow>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_suppression: Option<AfterSuppression>,
+}
+
+impl RouteOptions {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// These options, with anything unset taken from `parent`.
+    pub fn inherit(&self, parent: &RouteOptions) -> RouteOptions {
+        RouteOptions {
+            suppress: self.suppress.or(parent.suppress),
+            suppress_hours: self
+                .suppress_hours
+                .clone()
+                .or_else(|| parent.suppress_hours.clone()),
+            after_suppression: self.after_suppression.or(parent.after_suppression),
+        }
+    }
+
+    pub fn suppressed(&self) -> bool {
+        self.suppress == Some(true)
+    }
+
+    /// When a suppression window containing `at` ends, following windows
+    /// that run into each other, or `None` outside them.
+    pub fn suppressed_until(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let windows = self.suppress_hours.as_deref()?;
+        let ends_after = |at| windows.iter().filter_map(|w| w.ends_after(at)).max();
+        let mut until = ends_after(at)?;
+        // Windows covering the whole week would never end; a week is enough.
+        for _ in 0..7 {
+            match ends_after(until) {
+                Some(next) => until = next,
+                None => break,
+            }
+        }
+        Some(until)
+    }
+}
+
+/// What happens to an alert that matched during a suppression window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AfterSuppression {
+    /// It is only recorded.
+    #[default]
+    Drop,
+    /// Escalation starts when the window ends, if it is still firing.
+    Escalate,
+}
+
+/// A time-of-day window in its own timezone, on `days` or every day if
+/// that is empty. An `end` before `start` spans midnight and belongs to the
+/// day it starts on; `end` equal to `start` lasts the whole day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuppressionWindow {
+    #[serde(with = "tz_serde")]
+    timezone: Tz,
+    days: Vec<Weekday>,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl SuppressionWindow {
+    pub fn new(timezone: Tz, days: Vec<Weekday>, start: NaiveTime, end: NaiveTime) -> Self {
+        Self {
+            timezone,
+            days,
+            start,
+            end,
+        }
+    }
+
+    /// When the window containing `at` ends, or `None` outside one.
+    pub fn ends_after(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local = at.with_timezone(&self.timezone).naive_local();
+        let (date, time) = (local.date(), local.time());
+        let wraps = self.start >= self.end;
+        let start_date = if !wraps {
+            (time >= self.start && time < self.end).then_some(date)?
+        } else if time >= self.start {
+            date
+        } else if time < self.end {
+            date.pred_opt()?
+        } else {
+            return None;
+        };
+        if !self.days.is_empty() && !self.days.contains(&start_date.weekday()) {
+            return None;
+        }
+        let end_date = if wraps {
+            start_date.succ_opt()?
+        } else {
+            start_date
+        };
+        Some(local_to_utc(self.timezone, end_date.and_time(self.end)))
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    pub fn days(&self) -> &[Weekday] {
+        &self.days
+    }
+
+    pub fn start(&self) -> NaiveTime {
+        self.start
+    }
+
+    pub fn end(&self) -> NaiveTime {
+        self.end
+    }
+}
+
+/// What a matcher looks at. `severity` and `source` name the alert's own
+/// fields; any other name is a label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchField {
+    Severity,
+    Source,
+    Label(String),
+}
+
+impl MatchField {
+    fn new(name: &str) -> Self {
+        match name {
+            "severity" => Self::Severity,
+            "source" => Self::Source,
+            label => Self::Label(label.to_string()),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Severity => "severity",
+            Self::Source => "source",
+            Self::Label(label) => label,
+        }
+    }
+}
+
+/// Alertmanager's operators, where a missing label reads as empty, plus
+/// existence checks and a severity threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchOp {
+    Equal,
+    NotEqual,
+    /// The whole value matches the pattern.
+    Regex,
+    NotRegex,
+    Exists,
+    Absent,
+    /// Severity only: at least as severe as the value.
+    AtLeast,
+}
+
+impl MatchOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Equal => "=",
+            Self::NotEqual => "!=",
+            Self::Regex => "=~",
+            Self::NotRegex => "!~",
+            Self::AtLeast => ">=",
+            Self::Exists | Self::Absent => "",
+        }
+    }
+}
+
+/// Written like Alertmanager matchers: `service=api`, `env!=staging`,
+/// `service=~"api|web"`, `team!~"db.*"`, plus `runbook` (label is set),
+/// `!runbook` (label is not set) and `severity>=warning`. Stored in that
+/// form too.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Matcher {
+    pub field: MatchField,
+    pub op: MatchOp,
+    /// Empty for `Exists` and `Absent`.
+    pub value: String,
+}
+
+impl Matcher {
+    pub fn equal(name: &str, value: impl Into<String>) -> Self {
+        Self {
+            field: MatchField::new(name),
+            op: MatchOp::Equal,
+            value: value.into(),
+        }
+    }
+}
+
+/// Parses a severity the way matchers spell it, in any case.
+pub fn parse_severity(value: &str) -> Option<Severity> {
+    match value.to_ascii_lowercase().as_str() {
+        "critical" => Some(Severity::Critical),
+        "warning" => Some(Severity::Warning),
+        "info" => Some(Severity::Info),
+        _ => None,
+    }
+}
+
+impl FromStr for Matcher {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| DomainError::InvalidMatcher(format!("`{s}`: {reason}"));
+        let s = s.trim();
+        let name_end = s.find(['=', '!', '~', '>']).unwrap_or(s.len());
+
+        if name_end == 0 {
+            // Only `!name` may start with an operator character.
+            let name = s.strip_prefix('!').ok_or_else(|| invalid("missing name"))?;
+            check_name(name).map_err(invalid)?;
+            return Ok(Self {
+                field: MatchField::new(name),
+                op: MatchOp::Absent,
+                value: String::new(),
+            });
+        }
+
+        let name = s[..name_end].trim();
+        check_name(name).map_err(invalid)?;
+        let rest = &s[name_end..];
+        if rest.is_empty() {
+            return Ok(Self {
+                field: MatchField::new(name),
+                op: MatchOp::Exists,
+                value: String::new(),
+            });
+        }
+
+        let op = [
+            MatchOp::Regex,
+            MatchOp::NotRegex,
+            MatchOp::NotEqual,
+            MatchOp::AtLeast,
+            MatchOp::Equal,
+        ]
+        .into_iter()
+        .find(|op| rest.starts_with(op.symbol()))
+        .ok_or_else(|| invalid("unknown operator"))?;
+        let raw = rest[op.symbol().len()..].trim();
+        let value = match raw.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+            Some(quoted) => quoted.replace("\\\"", "\""),
+            None => raw.to_string(),
+        };
+
+        let field = MatchField::new(name);
+        if op == MatchOp::AtLeast {
+            if field != MatchField::Severity {
+                return Err(invalid("`>=` only applies to severity"));
+            }
+            if parse_severity(&value).is_none() {
+                return Err(invalid("expected critical, warning or info"));
+            }
+        }
+        Ok(Self { field, op, value })
+    }
+}
+
+fn check_name(name: &str) -> Result<(), &'static str> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '/'));
+    if valid {
+        Ok(())
+    } else {
+        Err("invalid name")
+    }
+}
+
+impl fmt::Display for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.field.name();
+        match self.op {
+            MatchOp::Exists => write!(f, "{name}"),
+            MatchOp::Absent => write!(f, "!{name}"),
+            op => {
+                let plain = !self.value.is_empty()
+                    && self
+                        .value
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '/'));
+                if plain {
+                    write!(f, "{name}{}{}", op.symbol(), self.value)
+                } else {
+                    let escaped = self.value.replace('"', "\\\"");
+                    write!(f, "{name}{}\"{escaped}\"", op.symbol())
+                }
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for Matcher {
+    type Error = DomainError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Matcher> for String {
+    fn from(matcher: Matcher) -> Self {
+        matcher.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matchers_parse_and_print_round_trip() {
+        for text in [
+            "service=api",
+            "env!=staging",
+            "service=~\"api|web\"",
+            "team!~\"db.*\"",
+            "runbook",
+            "!runbook",
+            "severity>=warning",
+            "source=prometheus",
+            "summary=\"disk \\\"full\\\"\"",
+        ] {
+            let matcher: Matcher = text.parse().unwrap();
+            assert_eq!(matcher.to_string(), text);
+        }
+
+        let matcher: Matcher = "service =~ \"api|web\"".parse().unwrap();
+        assert_eq!(matcher.field, MatchField::Label("service".into()));
+        assert_eq!(matcher.op, MatchOp::Regex);
+        assert_eq!(matcher.value, "api|web");
+        assert_eq!(
+            "severity=critical".parse::<Matcher>().unwrap().field,
+            MatchField::Severity
+        );
+    }
+
+    #[test]
+    fn malformed_matchers_are_rejected() {
+        for text in [
+            "",
+            "=api",
+            "!",
+            "service<api",
+            "env>=prod",
+            "severity>=loud",
+            "a b=c",
+        ] {
+            assert!(
+                matches!(text.parse::<Matcher>(), Err(DomainError::InvalidMatcher(_))),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn routes_store_matchers_as_text() {
+        let mut route = Route::new(vec![Matcher::equal("service", "api")], PolicyId::new());
+        let json = serde_json::to_value(&route).unwrap();
+        assert_eq!(json["matchers"][0], "service=api");
+        assert!(json.get("continue").is_none() && json.get("routes").is_none());
+        assert_eq!(serde_json::from_value::<Route>(json).unwrap(), route);
+
+        route.continue_matching = true;
+        route.routes.push(Route {
+            policy_id: None,
+            ..Route::new(vec!["env!=staging".parse().unwrap()], PolicyId::new())
+        });
+        let json = serde_json::to_value(&route).unwrap();
+        assert_eq!(json["continue"], true);
+        assert_eq!(serde_json::from_value::<Route>(json).unwrap(), route);
+    }
+
+    #[test]
+    fn options_inherit_what_they_leave_unset() {
+        let parent = RouteOptions {
+            suppress: Some(true),
+            after_suppression: Some(AfterSuppression::Escalate),
+            ..Default::default()
+        };
+        let child = RouteOptions {
+            suppress: Some(false),
+            ..Default::default()
+        };
+        let options = child.inherit(&parent);
+        assert!(!options.suppressed());
+        assert_eq!(options.after_suppression, parent.after_suppression);
+    }
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn hm(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn overnight_windows_belong_to_the_day_they_start() {
+        let zurich: Tz = "Europe/Zurich".parse().unwrap();
+        let fridays = SuppressionWindow::new(zurich, vec![Weekday::Fri], hm(22, 0), hm(8, 0));
+        // 2025-01-17 is a Friday; 23:00 UTC Friday is midnight in Zurich.
+        assert_eq!(
+            fridays.ends_after(ts("2025-01-17T23:00:00Z")),
+            Some(ts("2025-01-18T07:00:00Z"))
+        );
+        // Early Saturday is still Friday night.
+        assert_eq!(
+            fridays.ends_after(ts("2025-01-18T05:00:00Z")),
+            Some(ts("2025-01-18T07:00:00Z"))
+        );
+        // Thursday night is not.
+        assert_eq!(fridays.ends_after(ts("2025-01-16T23:00:00Z")), None);
+        assert_eq!(fridays.ends_after(ts("2025-01-17T12:00:00Z")), None);
+    }
+
+    #[test]
+    fn adjoining_windows_are_followed_to_their_end() {
+        let utc = chrono_tz::UTC;
+        let options = RouteOptions {
+            suppress_hours: Some(vec![
+                SuppressionWindow::new(utc, vec![Weekday::Sat, Weekday::Sun], hm(0, 0), hm(0, 0)),
+                SuppressionWindow::new(utc, vec![], hm(22, 0), hm(8, 0)),
+            ]),
+            ..Default::default()
+        };
+        // Friday night runs into the weekend, which runs into Sunday night.
+        assert_eq!(
+            options.suppressed_until(ts("2025-01-17T23:00:00Z")),
+            Some(ts("2025-01-20T08:00:00Z"))
+        );
+        assert_eq!(options.suppressed_until(ts("2025-01-15T12:00:00Z")), None);
+        assert_eq!(
+            RouteOptions::default().suppressed_until(ts("2025-01-15T23:00:00Z")),
+            None
+        );
+    }
+}
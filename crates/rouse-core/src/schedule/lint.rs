@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Schedule, Shift};
+use crate::ids::{ScheduleId, UserId};
+use crate::user::User;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Severity {
+    /// The schedule cannot page anyone at some point.
+    Error,
+    /// The schedule works but is likely to hurt someone.
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LintIssue {
+    /// Nobody is on call between `start` and `end`.
+    Gap {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    /// A participant is unknown or has no way to be contacted.
+    UnreachableParticipant { user_id: UserId },
+    /// One person is on call longer than the configured limit.
+    LongShift {
+        user_id: UserId,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    /// A shift ends right before the same person starts one on another schedule.
+    BackToBack {
+        user_id: UserId,
+        other_schedule: ScheduleId,
+        at: DateTime<Utc>,
+    },
+}
+
+impl LintIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::Gap { .. } | Self::UnreachableParticipant { .. } => Severity::Error,
+            Self::LongShift { .. } | Self::BackToBack { .. } => Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintFinding {
+    pub severity: Severity,
+    pub issue: LintIssue,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintReport {
+    pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+    fn push(&mut self, issue: LintIssue) {
+        self.findings.push(LintFinding {
+            severity: issue.severity(),
+            issue,
+        });
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Error)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &LintIssue> {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == Severity::Error)
+            .map(|f| &f.issue)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LintOptions {
+    /// Longest acceptable uninterrupted on-call stretch.
+    pub max_consecutive: Duration,
+    /// Minimum rest between shifts on different schedules.
+    pub min_rest: Duration,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            max_consecutive: Duration::weeks(1),
+            min_rest: Duration::hours(8),
+        }
+    }
+}
+
+/// Everything outside the schedule itself the linter looks at.
+pub struct LintContext<'a> {
+    pub users: &'a [User],
+    pub other_schedules: &'a [Schedule],
+    pub options: LintOptions,
+}
+
+pub fn lint(
+    schedule: &Schedule,
+    ctx: &LintContext<'_>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> LintReport {
+    let mut report = LintReport::default();
+    let shifts = schedule.shifts_between(start, end);
+
+    check_gaps(&shifts, start, end, &mut report);
+    check_reachability(&shifts, ctx.users, &mut report);
+
+    for shift in &shifts {
+        if shift.end - shift.start > ctx.options.max_consecutive {
+            report.push(LintIssue::LongShift {
+                user_id: shift.user_id.clone(),
+                start: shift.start,
+                end: shift.end,
+            });
+        }
+    }
+
+    check_back_to_back(schedule, &shifts, ctx, start, end, &mut report);
+    report
+}
+
+fn check_gaps(shifts: &[Shift], start: DateTime<Utc>, end: DateTime<Utc>, report: &mut LintReport) {
+    let mut covered_until = start;
+    for shift in shifts {
+        if shift.start > covered_until {
+            report.push(LintIssue::Gap {
+                start: covered_until,
+                end: shift.start,
+            });
+        }
+        covered_until = covered_until.max(shift.end);
+    }
+    if covered_until < end {
+        report.push(LintIssue::Gap {
+            start: covered_until,
+            end,
+        });
+    }
+}
+
+fn check_reachability(shifts: &[Shift], users: &[User], report: &mut LintReport) {
+    let by_id: HashMap<_, _> = users.iter().map(|u| (u.id(), u)).collect();
+    let mut seen = Vec::new();
+    for shift in shifts {
+        if seen.contains(&&shift.user_id) {
+            continue;
+        }
+        seen.push(&shift.user_id);
+        if !by_id
+            .get(&shift.user_id)
+            .is_some_and(|u| u.can_be_on_call())
+        {
+            report.push(LintIssue::UnreachableParticipant {
+                user_id: shift.user_id.clone(),
+            });
+        }
+    }
+}
+
+fn check_back_to_back(
+    schedule: &Schedule,
+    shifts: &[Shift],
+    ctx: &LintContext<'_>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    report: &mut LintReport,
+) {
+    let min_rest = ctx.options.min_rest;
+    for other in ctx.other_schedules {
+        if other.id() == schedule.id() {
+            continue;
+        }
+        let other_shifts = other.shifts_between(start - min_rest, end + min_rest);
+        for shift in shifts {
+            let follows = other_shifts.iter().any(|o| {
+                o.user_id == shift.user_id
+                    && o.start >= shift.end
+                    && o.start - shift.end <= min_rest
+            });
+            if follows {
+                report.push(LintIssue::BackToBack {
+                    user_id: shift.user_id.clone(),
+                    other_schedule: other.id().clone(),
+                    at: shift.end,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::Channel;
+    use crate::schedule::{HandoffTime, Rotation, ScheduleOverride};
+    use crate::user::Role;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn reachable_user() -> User {
+        let mut user = User::new("oncall".into(), "oncall@example.com".into(), Role::User);
+        user.set_slack_id("U123".into());
+        user.mark_verified(Channel::Slack, ts("2025-01-01T00:00:00Z"))
+            .unwrap();
+        user
+    }
+
+    fn daily(participants: Vec<UserId>) -> Schedule {
+        Schedule::new(
+            "daily".into(),
+            "UTC".parse().unwrap(),
+            Rotation::Daily,
+            participants,
+            HandoffTime {
+                day: chrono::Weekday::Mon,
+                hour: 0,
+                minute: 0,
+            },
+        )
+        .unwrap()
+    }
+
+    fn ctx<'a>(users: &'a [User], others: &'a [Schedule]) -> LintContext<'a> {
+        LintContext {
+            users,
+            other_schedules: others,
+            options: LintOptions::default(),
+        }
+    }
+
+    #[test]
+    fn healthy_schedule_has_no_findings() {
+        let users = [reachable_user(), reachable_user()];
+        let schedule = daily(users.iter().map(|u| u.id().clone()).collect());
+
+        let report = lint(
+            &schedule,
+            &ctx(&users, &[]),
+            ts("2025-01-13T00:00:00Z"),
+            ts("2025-01-20T00:00:00Z"),
+        );
+        assert!(report.findings.is_empty(), "{report:?}");
+    }
+
+    #[test]
+    fn unreachable_participant_is_an_error() {
+        let reachable = reachable_user();
+        let silent = User::new("silent".into(), "silent@example.com".into(), Role::User);
+        let schedule = daily(vec![reachable.id().clone(), silent.id().clone()]);
+        let users = [reachable, silent.clone()];
+
+        let report = lint(
+            &schedule,
+            &ctx(&users, &[]),
+            ts("2025-01-13T00:00:00Z"),
+            ts("2025-01-20T00:00:00Z"),
+        );
+        assert!(report.has_errors());
+        assert_eq!(
+            report.errors().collect::<Vec<_>>(),
+            [&LintIssue::UnreachableParticipant {
+                user_id: silent.id().clone()
+            }]
+        );
+    }
+
+    #[test]
+    fn unknown_override_user_is_unreachable() {
+        let users = [reachable_user()];
+        let mut schedule = daily(vec![users[0].id().clone()]);
+        let stranger = UserId::new();
+        schedule
+            .add_override(
+                ScheduleOverride::new(
+                    stranger.clone(),
+                    ts("2025-01-14T00:00:00Z"),
+                    ts("2025-01-15T00:00:00Z"),
+                ),
+                ts("2025-01-13T00:00:00Z"),
+            )
+            .unwrap();
+
+        let report = lint(
+            &schedule,
+            &ctx(&users, &[]),
+            ts("2025-01-13T00:00:00Z"),
+            ts("2025-01-20T00:00:00Z"),
+        );
+        assert!(report.errors().any(|i| *i
+            == LintIssue::UnreachableParticipant {
+                user_id: stranger.clone()
+            }));
+    }
+
+    #[test]
+    fn long_shift_is_a_warning() {
+        let users = [reachable_user()];
+        let schedule = daily(vec![users[0].id().clone()]);
+        let mut context = ctx(&users, &[]);
+        context.options.max_consecutive = Duration::hours(48);
+
+        let report = lint(
+            &schedule,
+            &context,
+            ts("2025-01-13T00:00:00Z"),
+            ts("2025-01-20T00:00:00Z"),
+        );
+        assert!(!report.has_errors());
+        assert!(matches!(
+            report.findings[0].issue,
+            LintIssue::LongShift { .. }
+        ));
+    }
+
+    #[test]
+    fn back_to_back_across_schedules() {
+        let users = [reachable_user(), reachable_user()];
+        let (a, b) = (users[0].id().clone(), users[1].id().clone());
+        let primary = daily(vec![a.clone(), b.clone()]);
+        let mut other = daily(vec![b.clone()]);
+
+        // `a` takes over the other schedule right after a primary shift ends.
+        let primary_shift = primary
+            .shifts_between(ts("2025-01-13T00:00:00Z"), ts("2025-01-20T00:00:00Z"))
+            .into_iter()
+            .find(|s| s.user_id == a)
+            .unwrap();
+        other
+            .add_override(
+                ScheduleOverride::new(
+                    a.clone(),
+                    primary_shift.end + Duration::hours(2),
+                    primary_shift.end + Duration::hours(10),
+                ),
+                ts("2025-01-01T00:00:00Z"),
+            )
+            .unwrap();
+
+        let others = [other.clone()];
+        let report = lint(
+            &primary,
+            &ctx(&users, &others),
+            ts("2025-01-13T00:00:00Z"),
+            ts("2025-01-20T00:00:00Z"),
+        );
+        assert!(report.findings.iter().any(|f| f.issue
+            == LintIssue::BackToBack {
+                user_id: a.clone(),
+                other_schedule: other.id().clone(),
+                at: primary_shift.end,
+            }));
+    }
+
+    #[test]
+    fn gaps_are_reported_between_shifts() {
+        let shifts = [Shift {
+            user_id: UserId::new(),
+            start: ts("2025-01-13T06:00:00Z"),
+            end: ts("2025-01-13T18:00:00Z"),
+        }];
+        let mut report = LintReport::default();
+        check_gaps(
+            &shifts,
+            ts("2025-01-13T00:00:00Z"),
+            ts("2025-01-14T00:00:00Z"),
+            &mut report,
+        );
+        assert_eq!(report.findings.len(), 2);
+        assert!(report.has_errors());
+    }
+}
@@ -1,18 +1,22 @@
+pub mod lint;
 pub mod rotation;
 pub mod shift_override;
+pub mod swap;
 
 use chrono::{DateTime, Utc};
 use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
 use crate::error::DomainError;
+use crate::escalation::OnCallModifier;
 use crate::events::{DomainEvent, OnCallChanged};
 use crate::ids::{OverrideId, ScheduleId, UserId};
 
 pub use rotation::Rotation;
-pub use shift_override::ScheduleOverride;
+pub use shift_override::{OverrideAudit, ScheduleOverride};
+pub use swap::{SwapDecision, SwapKind, SwapProposal, SwapRequest, SwapStatus};
 
-mod tz_serde {
+pub(crate) mod tz_serde {
     use chrono_tz::Tz;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
@@ -39,6 +43,53 @@ pub struct HandoffTime {
     pub minute: u32,
 }
 
+/// A secondary rotation that runs alongside the primary one. It shares the
+/// schedule's timezone and handoff, and is not affected by overrides.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupLayer {
+    rotation: Rotation,
+    participants: Vec<UserId>,
+}
+
+impl BackupLayer {
+    pub fn new(rotation: Rotation, participants: Vec<UserId>) -> Result<Self, DomainError> {
+        if participants.is_empty() {
+            return Err(DomainError::ScheduleRequiresParticipant);
+        }
+        rotation.validate(participants.len())?;
+        Ok(Self {
+            rotation,
+            participants,
+        })
+    }
+
+    pub fn rotation(&self) -> &Rotation {
+        &self.rotation
+    }
+
+    pub fn participants(&self) -> &[UserId] {
+        &self.participants
+    }
+}
+
+/// A contiguous stretch of time with one person on call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shift {
+    pub user_id: UserId,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// How a new override is treated when it overlaps existing ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverlapPolicy {
+    /// Refuse the new override.
+    #[default]
+    Reject,
+    /// Trim existing overrides so the new one owns the overlapping time.
+    Split,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Schedule {
     id: ScheduleId,
@@ -49,6 +100,12 @@ pub struct Schedule {
     participants: Vec<UserId>,
     handoff: HandoffTime,
     overrides: Vec<ScheduleOverride>,
+    #[serde(default)]
+    overlap_policy: OverlapPolicy,
+    #[serde(default)]
+    backup: Option<BackupLayer>,
+    #[serde(default)]
+    managed: bool,
 }
 
 impl Schedule {
@@ -62,6 +119,7 @@ impl Schedule {
         if participants.is_empty() {
             return Err(DomainError::ScheduleRequiresParticipant);
         }
+        rotation.validate(participants.len())?;
         Ok(Self {
             id: ScheduleId::new(),
             name,
@@ -70,6 +128,9 @@ impl Schedule {
             participants,
             handoff,
             overrides: vec![],
+            overlap_policy: OverlapPolicy::default(),
+            backup: None,
+            managed: false,
         })
     }
 
@@ -86,22 +147,95 @@ impl Schedule {
     }
 
     fn rotation_on_call(&self, at: DateTime<Utc>) -> UserId {
+        self.layer_member(&self.rotation, &self.participants, at)
+    }
+
+    fn layer_member(
+        &self,
+        rotation: &Rotation,
+        participants: &[UserId],
+        at: DateTime<Utc>,
+    ) -> UserId {
         let local = at.with_timezone(&self.timezone);
-        let rotation_secs = self.rotation.duration().num_seconds();
+        let index = rotation.participant_at(local, &self.handoff, participants.len());
+        participants[index].clone()
+    }
 
-        // Calculate the epoch for this schedule: first handoff
-        // We use a fixed epoch and count rotation periods from there
-        let epoch = chrono::NaiveDate::from_ymd_opt(2020, 1, 6) // Monday
-            .unwrap()
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(self.timezone)
-            .unwrap();
+    /// Resolves an on-call modifier at `at`.
+    ///
+    /// `Next` and `Previous` follow rotation order and ignore overrides, so
+    /// covering someone for an afternoon does not change who is up next.
+    /// `Secondary` is `None` when the schedule has no backup layer.
+    pub fn on_call(&self, modifier: OnCallModifier, at: DateTime<Utc>) -> Option<UserId> {
+        match modifier {
+            OnCallModifier::Current => Some(self.who_is_on_call(at)),
+            OnCallModifier::Next => Some(self.rotation_neighbour(at, true)),
+            OnCallModifier::Previous => Some(self.rotation_neighbour(at, false)),
+            OnCallModifier::Secondary => self
+                .backup
+                .as_ref()
+                .map(|b| self.layer_member(&b.rotation, &b.participants, at)),
+        }
+    }
 
-        let elapsed = local.signed_duration_since(epoch).num_seconds();
-        let index = (elapsed / rotation_secs).rem_euclid(self.participants.len() as i64) as usize;
+    /// The closest rotation member before or after the current one who is
+    /// a different person, or the current one for single-person rotations.
+    fn rotation_neighbour(&self, at: DateTime<Utc>, forward: bool) -> UserId {
+        let horizon = chrono::Duration::days(366);
+        let current = self.rotation_on_call(at);
 
-        self.participants[index].clone()
+        if forward {
+            self.rotation
+                .handoffs_between(self.timezone, &self.handoff, at, at + horizon)
+                .into_iter()
+                .map(|h| self.rotation_on_call(h))
+                .find(|u| *u != current)
+        } else {
+            let until = at + chrono::Duration::seconds(1);
+            self.rotation
+                .handoffs_between(self.timezone, &self.handoff, at - horizon, until)
+                .into_iter()
+                .rev()
+                .map(|h| self.rotation_on_call(h - chrono::Duration::seconds(1)))
+                .find(|u| *u != current)
+        }
+        .unwrap_or(current)
+    }
+
+    /// Who is on call over `[start, end)`, with consecutive shifts of the
+    /// same person merged. Overrides are taken into account.
+    pub fn shifts_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<Shift> {
+        if end <= start {
+            return vec![];
+        }
+        let mut points = self
+            .rotation
+            .handoffs_between(self.timezone, &self.handoff, start, end);
+        for ovr in &self.overrides {
+            points.extend(
+                [ovr.start(), ovr.end()]
+                    .into_iter()
+                    .filter(|t| *t > start && *t < end),
+            );
+        }
+        points.push(start);
+        points.sort();
+        points.dedup();
+
+        let mut shifts: Vec<Shift> = Vec::new();
+        for (i, &from) in points.iter().enumerate() {
+            let to = points.get(i + 1).copied().unwrap_or(end);
+            let user_id = self.who_is_on_call(from);
+            match shifts.last_mut() {
+                Some(last) if last.user_id == user_id => last.end = to,
+                _ => shifts.push(Shift {
+                    user_id,
+                    start: from,
+                    end: to,
+                }),
+            }
+        }
+        shifts
     }
 
     pub fn add_override(
@@ -112,6 +246,7 @@ impl Schedule {
         if ovr.end() <= ovr.start() {
             return Err(DomainError::InvalidOverridePeriod);
         }
+        self.make_room_for(&ovr)?;
         let new_user = ovr.user_id().clone();
         self.overrides.push(ovr);
         Ok(vec![DomainEvent::OnCallChanged(OnCallChanged {
@@ -142,6 +277,135 @@ impl Schedule {
         }
     }
 
+    pub fn update_override(
+        &mut self,
+        override_id: &OverrideId,
+        user_id: UserId,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<DomainEvent>, DomainError> {
+        if end <= start {
+            return Err(DomainError::InvalidOverridePeriod);
+        }
+        let pos = self
+            .overrides
+            .iter()
+            .position(|o| o.id() == override_id)
+            .ok_or(DomainError::OverrideNotFound)?;
+
+        let original = self.overrides.remove(pos);
+        let mut updated = original.clone();
+        updated.amend(user_id.clone(), start, end);
+        if let Err(e) = self.make_room_for(&updated) {
+            self.overrides.insert(pos, original);
+            return Err(e);
+        }
+        self.overrides.push(updated);
+
+        Ok(vec![DomainEvent::OnCallChanged(OnCallChanged {
+            schedule_id: self.id.clone(),
+            new_user: user_id,
+            previous_user: Some(original.user_id().clone()),
+            occurred_at: now,
+        })])
+    }
+
+    /// Applies the overlap policy for an override about to be inserted.
+    fn make_room_for(&mut self, ovr: &ScheduleOverride) -> Result<(), DomainError> {
+        let (start, end) = (ovr.start(), ovr.end());
+        match self.overlap_policy {
+            OverlapPolicy::Reject => {
+                if self.overrides.iter().any(|o| o.overlaps(start, end)) {
+                    return Err(DomainError::OverlappingOverride);
+                }
+            }
+            OverlapPolicy::Split => {
+                let mut kept = Vec::with_capacity(self.overrides.len() + 1);
+                for o in self.overrides.drain(..) {
+                    if o.overlaps(start, end) {
+                        kept.extend(o.split_around(start, end));
+                    } else {
+                        kept.push(o);
+                    }
+                }
+                self.overrides = kept;
+            }
+        }
+        Ok(())
+    }
+
+    /// Overrides sharing any time with `[start, end)`, earliest first.
+    pub fn overrides_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<&ScheduleOverride> {
+        let mut found: Vec<_> = self
+            .overrides
+            .iter()
+            .filter(|o| o.overlaps(start, end))
+            .collect();
+        found.sort_by_key(|o| o.start());
+        found
+    }
+
+    /// Drops overrides that ended at or before `now`. Returns how many were removed.
+    pub fn prune_expired(&mut self, now: DateTime<Utc>) -> usize {
+        let before = self.overrides.len();
+        self.overrides.retain(|o| o.end() > now);
+        before - self.overrides.len()
+    }
+
+    /// Replaces the rotation setup, keeping the id, overrides and backup
+    /// layer. Validated like `new`.
+    pub fn reconfigure(
+        &mut self,
+        timezone: Tz,
+        rotation: Rotation,
+        participants: Vec<UserId>,
+        handoff: HandoffTime,
+    ) -> Result<(), DomainError> {
+        if participants.is_empty() {
+            return Err(DomainError::ScheduleRequiresParticipant);
+        }
+        rotation.validate(participants.len())?;
+        self.timezone = timezone;
+        self.rotation = rotation;
+        self.participants = participants;
+        self.handoff = handoff;
+        Ok(())
+    }
+
+    pub fn set_backup(&mut self, backup: Option<BackupLayer>) {
+        self.backup = backup;
+    }
+
+    pub fn backup(&self) -> Option<&BackupLayer> {
+        self.backup.as_ref()
+    }
+
+    /// Declared in the config file: edits go there, not through the API.
+    pub fn is_managed(&self) -> bool {
+        self.managed
+    }
+
+    pub fn set_managed(&mut self, managed: bool) {
+        self.managed = managed;
+    }
+
+    pub fn set_overlap_policy(&mut self, policy: OverlapPolicy) {
+        self.overlap_policy = policy;
+    }
+
+    pub fn overlap_policy(&self) -> OverlapPolicy {
+        self.overlap_policy
+    }
+
+    pub fn overrides(&self) -> &[ScheduleOverride] {
+        &self.overrides
+    }
+
     pub fn id(&self) -> &ScheduleId {
         &self.id
     }
@@ -150,6 +414,10 @@ impl Schedule {
         &self.name
     }
 
+    pub fn rotation(&self) -> &Rotation {
+        &self.rotation
+    }
+
     pub fn participants(&self) -> &[UserId] {
         &self.participants
     }
@@ -204,6 +472,229 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn schedule_rejects_invalid_rotation() {
+        let result = Schedule::new(
+            "split".into(),
+            zurich(),
+            Rotation::WeekdaySplit {
+                weekday: 0,
+                weekend: 1,
+            },
+            make_users(1),
+            handoff_monday_9(),
+        );
+        assert!(matches!(result, Err(DomainError::InvalidRotation(_))));
+    }
+
+    #[test]
+    fn weekday_split_uses_schedule_timezone() {
+        let users = make_users(2);
+        let sched = Schedule::new(
+            "split".into(),
+            zurich(),
+            Rotation::WeekdaySplit {
+                weekday: 0,
+                weekend: 1,
+            },
+            users.clone(),
+            handoff_monday_9(),
+        )
+        .unwrap();
+
+        // 08:30 UTC on Saturday is 09:30 in Zurich, after the handoff.
+        assert_eq!(sched.who_is_on_call(ts("2025-01-18T07:30:00Z")), users[0]);
+        assert_eq!(sched.who_is_on_call(ts("2025-01-18T08:30:00Z")), users[1]);
+    }
+
+    #[test]
+    fn shifts_between_merges_and_includes_overrides() {
+        let users = make_users(2);
+        let mut sched = Schedule::new(
+            "daily".into(),
+            zurich(),
+            Rotation::Daily,
+            users.clone(),
+            handoff_monday_9(),
+        )
+        .unwrap();
+        sched
+            .add_override(
+                ScheduleOverride::new(
+                    users[0].clone(),
+                    ts("2025-01-15T23:00:00Z"),
+                    ts("2025-01-16T12:00:00Z"),
+                ),
+                ts("2025-01-13T00:00:00Z"),
+            )
+            .unwrap();
+
+        let shifts = sched.shifts_between(ts("2025-01-14T23:00:00Z"), ts("2025-01-16T23:00:00Z"));
+        let owners: Vec<_> = shifts.iter().map(|s| s.user_id.clone()).collect();
+        let first = owners[0].clone();
+        let second = if first == users[0] {
+            &users[1]
+        } else {
+            &users[0]
+        };
+        assert_eq!(shifts.first().unwrap().start, ts("2025-01-14T23:00:00Z"));
+        assert_eq!(shifts.last().unwrap().end, ts("2025-01-16T23:00:00Z"));
+        assert!(shifts.windows(2).all(|w| w[0].end == w[1].start));
+        assert!(shifts.windows(2).all(|w| w[0].user_id != w[1].user_id));
+        assert!(owners.contains(second));
+    }
+
+    fn daily_utc(users: Vec<UserId>) -> Schedule {
+        Schedule::new(
+            "daily".into(),
+            "UTC".parse().unwrap(),
+            Rotation::Daily,
+            users,
+            HandoffTime {
+                day: chrono::Weekday::Mon,
+                hour: 0,
+                minute: 0,
+            },
+        )
+        .unwrap()
+    }
+
+    fn rotation_position(sched: &Schedule, users: &[UserId], at: DateTime<Utc>) -> usize {
+        let current = sched.on_call(OnCallModifier::Current, at).unwrap();
+        users.iter().position(|u| *u == current).unwrap()
+    }
+
+    #[test]
+    fn next_and_previous_follow_rotation_order() {
+        let users = make_users(3);
+        let sched = daily_utc(users.clone());
+        let at = ts("2025-01-15T12:00:00Z");
+        let pos = rotation_position(&sched, &users, at);
+
+        assert_eq!(
+            sched.on_call(OnCallModifier::Next, at),
+            Some(users[(pos + 1) % 3].clone())
+        );
+        assert_eq!(
+            sched.on_call(OnCallModifier::Previous, at),
+            Some(users[(pos + 2) % 3].clone())
+        );
+    }
+
+    #[test]
+    fn next_ignores_active_override() {
+        let users = make_users(3);
+        let mut sched = daily_utc(users.clone());
+        let at = ts("2025-01-15T12:00:00Z");
+        let pos = rotation_position(&sched, &users, at);
+
+        // The person who is up next covers the current shift.
+        let next = users[(pos + 1) % 3].clone();
+        sched
+            .add_override(
+                ScheduleOverride::new(
+                    next.clone(),
+                    ts("2025-01-15T00:00:00Z"),
+                    ts("2025-01-16T00:00:00Z"),
+                ),
+                ts("2025-01-14T00:00:00Z"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            sched.on_call(OnCallModifier::Current, at),
+            Some(next.clone())
+        );
+        assert_eq!(sched.on_call(OnCallModifier::Next, at), Some(next));
+        assert_eq!(
+            sched.on_call(OnCallModifier::Previous, at),
+            Some(users[(pos + 2) % 3].clone())
+        );
+    }
+
+    #[test]
+    fn next_at_handoff_boundary_is_following_shift() {
+        let users = make_users(2);
+        let sched = daily_utc(users.clone());
+        let at = ts("2025-01-15T00:00:00Z");
+        let current = sched.on_call(OnCallModifier::Current, at).unwrap();
+        let other = users.iter().find(|u| **u != current).unwrap();
+
+        assert_eq!(
+            sched.on_call(OnCallModifier::Next, at).as_ref(),
+            Some(other)
+        );
+        assert_eq!(
+            sched.on_call(OnCallModifier::Previous, at).as_ref(),
+            Some(other)
+        );
+    }
+
+    #[test]
+    fn single_participant_is_own_neighbour() {
+        let users = make_users(1);
+        let sched = daily_utc(users.clone());
+        let at = ts("2025-01-15T12:00:00Z");
+        assert_eq!(
+            sched.on_call(OnCallModifier::Next, at),
+            Some(users[0].clone())
+        );
+        assert_eq!(
+            sched.on_call(OnCallModifier::Previous, at),
+            Some(users[0].clone())
+        );
+    }
+
+    #[test]
+    fn next_skips_repeated_pattern_entries() {
+        let users = make_users(2);
+        let sched = Schedule::new(
+            "pattern".into(),
+            "UTC".parse().unwrap(),
+            Rotation::Pattern {
+                sequence: vec![0, 0, 1],
+                shift_secs: 86_400,
+            },
+            users.clone(),
+            handoff_monday_9(),
+        )
+        .unwrap();
+
+        // 2020-01-06 is the first slot of the pattern: A, A, B.
+        let at = ts("2020-01-06T12:00:00Z");
+        assert_eq!(
+            sched.on_call(OnCallModifier::Current, at),
+            Some(users[0].clone())
+        );
+        assert_eq!(
+            sched.on_call(OnCallModifier::Next, at),
+            Some(users[1].clone())
+        );
+    }
+
+    #[test]
+    fn secondary_uses_backup_layer() {
+        let users = make_users(2);
+        let backups = make_users(2);
+        let mut sched = daily_utc(users.clone());
+        let at = ts("2025-01-15T12:00:00Z");
+        assert_eq!(sched.on_call(OnCallModifier::Secondary, at), None);
+
+        sched.set_backup(Some(
+            BackupLayer::new(Rotation::Weekly, backups.clone()).unwrap(),
+        ));
+        let secondary = sched.on_call(OnCallModifier::Secondary, at).unwrap();
+        assert!(backups.contains(&secondary));
+    }
+
+    #[test]
+    fn backup_layer_requires_participant() {
+        assert!(matches!(
+            BackupLayer::new(Rotation::Daily, vec![]),
+            Err(DomainError::ScheduleRequiresParticipant)
+        ));
+    }
+
     #[test]
     fn single_participant_always_on_call() {
         let users = make_users(1);
@@ -407,4 +898,219 @@ mod tests {
             .unwrap();
         assert!(events.is_empty());
     }
+
+    fn make_schedule(users: Vec<UserId>) -> Schedule {
+        Schedule::new(
+            "team".into(),
+            zurich(),
+            Rotation::Weekly,
+            users,
+            handoff_monday_9(),
+        )
+        .unwrap()
+    }
+
+    fn ovr(user: &UserId, start: &str, end: &str) -> ScheduleOverride {
+        ScheduleOverride::new(user.clone(), ts(start), ts(end))
+    }
+
+    #[test]
+    fn reconfigure_keeps_identity_and_overrides() {
+        let (a, b) = (UserId::new(), UserId::new());
+        let mut schedule = make_schedule(vec![a.clone()]);
+        let id = schedule.id().clone();
+        schedule
+            .add_override(
+                ovr(&a, "2025-01-15T10:00:00Z", "2025-01-15T12:00:00Z"),
+                ts("2025-01-14T00:00:00Z"),
+            )
+            .unwrap();
+
+        schedule
+            .reconfigure(zurich(), Rotation::Daily, vec![a, b], handoff_monday_9())
+            .unwrap();
+        assert_eq!(schedule.id(), &id);
+        assert_eq!(schedule.rotation(), &Rotation::Daily);
+        assert_eq!(schedule.overrides().len(), 1);
+
+        let result = schedule.reconfigure(zurich(), Rotation::Daily, vec![], handoff_monday_9());
+        assert!(matches!(
+            result,
+            Err(DomainError::ScheduleRequiresParticipant)
+        ));
+        assert_eq!(schedule.participants().len(), 2);
+    }
+
+    #[test]
+    fn overlapping_override_rejected_by_default() {
+        let mut sched = make_schedule(make_users(2));
+        let (alice, bob) = (UserId::new(), UserId::new());
+        sched
+            .add_override(
+                ovr(&alice, "2025-01-14T00:00:00Z", "2025-01-15T00:00:00Z"),
+                ts("2025-01-13T00:00:00Z"),
+            )
+            .unwrap();
+
+        let result = sched.add_override(
+            ovr(&bob, "2025-01-14T12:00:00Z", "2025-01-16T00:00:00Z"),
+            ts("2025-01-13T00:00:00Z"),
+        );
+        assert_eq!(result, Err(DomainError::OverlappingOverride));
+        assert_eq!(sched.overrides().len(), 1);
+    }
+
+    #[test]
+    fn adjacent_overrides_do_not_overlap() {
+        let mut sched = make_schedule(make_users(2));
+        let (alice, bob) = (UserId::new(), UserId::new());
+        sched
+            .add_override(
+                ovr(&alice, "2025-01-14T00:00:00Z", "2025-01-15T00:00:00Z"),
+                ts("2025-01-13T00:00:00Z"),
+            )
+            .unwrap();
+        sched
+            .add_override(
+                ovr(&bob, "2025-01-15T00:00:00Z", "2025-01-16T00:00:00Z"),
+                ts("2025-01-13T00:00:00Z"),
+            )
+            .unwrap();
+        assert_eq!(sched.overrides().len(), 2);
+    }
+
+    #[test]
+    fn split_policy_carves_out_existing_override() {
+        let mut sched = make_schedule(make_users(2));
+        sched.set_overlap_policy(OverlapPolicy::Split);
+        let (alice, bob) = (UserId::new(), UserId::new());
+        sched
+            .add_override(
+                ovr(&alice, "2025-01-14T00:00:00Z", "2025-01-17T00:00:00Z"),
+                ts("2025-01-13T00:00:00Z"),
+            )
+            .unwrap();
+        sched
+            .add_override(
+                ovr(&bob, "2025-01-15T00:00:00Z", "2025-01-16T00:00:00Z"),
+                ts("2025-01-13T00:00:00Z"),
+            )
+            .unwrap();
+
+        assert_eq!(sched.overrides().len(), 3);
+        assert_eq!(sched.who_is_on_call(ts("2025-01-14T12:00:00Z")), alice);
+        assert_eq!(sched.who_is_on_call(ts("2025-01-15T12:00:00Z")), bob);
+        assert_eq!(sched.who_is_on_call(ts("2025-01-16T12:00:00Z")), alice);
+    }
+
+    #[test]
+    fn update_override_moves_period_and_user() {
+        let mut sched = make_schedule(make_users(2));
+        let (alice, bob) = (UserId::new(), UserId::new());
+        let o = ovr(&alice, "2025-01-14T00:00:00Z", "2025-01-15T00:00:00Z");
+        let id = o.id().clone();
+        sched.add_override(o, ts("2025-01-13T00:00:00Z")).unwrap();
+
+        let events = sched
+            .update_override(
+                &id,
+                bob.clone(),
+                ts("2025-01-20T00:00:00Z"),
+                ts("2025-01-21T00:00:00Z"),
+                ts("2025-01-13T00:00:00Z"),
+            )
+            .unwrap();
+        assert_eq!(events[0].event_type(), "oncall.changed");
+        assert_eq!(sched.who_is_on_call(ts("2025-01-20T12:00:00Z")), bob);
+        assert_ne!(sched.who_is_on_call(ts("2025-01-14T12:00:00Z")), alice);
+    }
+
+    #[test]
+    fn update_override_into_overlap_is_rejected_and_unchanged() {
+        let mut sched = make_schedule(make_users(2));
+        let (alice, bob) = (UserId::new(), UserId::new());
+        sched
+            .add_override(
+                ovr(&alice, "2025-01-14T00:00:00Z", "2025-01-15T00:00:00Z"),
+                ts("2025-01-13T00:00:00Z"),
+            )
+            .unwrap();
+        let o = ovr(&bob, "2025-01-16T00:00:00Z", "2025-01-17T00:00:00Z");
+        let id = o.id().clone();
+        sched.add_override(o, ts("2025-01-13T00:00:00Z")).unwrap();
+
+        let result = sched.update_override(
+            &id,
+            bob.clone(),
+            ts("2025-01-14T12:00:00Z"),
+            ts("2025-01-17T00:00:00Z"),
+            ts("2025-01-13T00:00:00Z"),
+        );
+        assert_eq!(result, Err(DomainError::OverlappingOverride));
+        assert_eq!(sched.who_is_on_call(ts("2025-01-16T12:00:00Z")), bob);
+    }
+
+    #[test]
+    fn update_unknown_override_fails() {
+        let mut sched = make_schedule(make_users(1));
+        let result = sched.update_override(
+            &OverrideId::new(),
+            UserId::new(),
+            ts("2025-01-14T00:00:00Z"),
+            ts("2025-01-15T00:00:00Z"),
+            ts("2025-01-13T00:00:00Z"),
+        );
+        assert_eq!(result, Err(DomainError::OverrideNotFound));
+    }
+
+    #[test]
+    fn overrides_between_returns_overlapping_sorted() {
+        let mut sched = make_schedule(make_users(1));
+        let user = UserId::new();
+        for (start, end) in [
+            ("2025-01-20T00:00:00Z", "2025-01-21T00:00:00Z"),
+            ("2025-01-14T00:00:00Z", "2025-01-15T00:00:00Z"),
+            ("2025-02-01T00:00:00Z", "2025-02-02T00:00:00Z"),
+        ] {
+            sched
+                .add_override(ovr(&user, start, end), ts("2025-01-13T00:00:00Z"))
+                .unwrap();
+        }
+
+        let found = sched.overrides_between(ts("2025-01-14T12:00:00Z"), ts("2025-01-31T00:00:00Z"));
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].start(), ts("2025-01-14T00:00:00Z"));
+        assert_eq!(found[1].start(), ts("2025-01-20T00:00:00Z"));
+    }
+
+    #[test]
+    fn prune_expired_drops_only_finished_overrides() {
+        let mut sched = make_schedule(make_users(1));
+        let user = UserId::new();
+        sched
+            .add_override(
+                ovr(&user, "2025-01-14T00:00:00Z", "2025-01-15T00:00:00Z"),
+                ts("2025-01-13T00:00:00Z"),
+            )
+            .unwrap();
+        sched
+            .add_override(
+                ovr(&user, "2025-01-15T00:00:00Z", "2025-01-17T00:00:00Z"),
+                ts("2025-01-13T00:00:00Z"),
+            )
+            .unwrap();
+
+        assert_eq!(sched.prune_expired(ts("2025-01-15T00:00:00Z")), 1);
+        assert_eq!(sched.overrides().len(), 1);
+    }
+
+    #[test]
+    fn schedule_stored_without_overlap_policy_defaults_to_reject() {
+        let sched = make_schedule(make_users(1));
+        let mut json = serde_json::to_value(&sched).unwrap();
+        json.as_object_mut().unwrap().remove("overlap_policy");
+
+        let loaded: Schedule = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded.overlap_policy(), OverlapPolicy::Reject);
+    }
 }
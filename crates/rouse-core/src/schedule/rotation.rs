@@ -1,19 +1,394 @@
-use chrono::Duration;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
+use super::HandoffTime;
+use crate::error::DomainError;
+
+/// The longest fixed-length shift; anything beyond a year is a typo.
+pub const MAX_SHIFT_SECS: i64 = 366 * 24 * 60 * 60;
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Rotation {
     Daily,
     Weekly,
     Custom(i64), // duration in seconds
+    /// Monday to Friday belong to `participants[weekday]`, Saturday and
+    /// Sunday to `participants[weekend]`. Days turn over at the handoff time.
+    WeekdaySplit {
+        weekday: usize,
+        weekend: usize,
+    },
+    /// Calendar months, handing off on `day` (clamped to the month length).
+    Monthly {
+        day: u32,
+    },
+    /// Calendar months, handing off on the `nth` `weekday` (e.g. first Monday).
+    MonthlyNthWeekday {
+        nth: u8,
+        weekday: Weekday,
+    },
+    /// Explicit sequence of participant indices, one per shift of `shift_secs`.
+    Pattern {
+        sequence: Vec<usize>,
+        shift_secs: i64,
+    },
 }
 
 impl Rotation {
-    pub fn duration(&self) -> Duration {
+    /// Shift length for rotations that hand off at a fixed interval.
+    /// Calendar-based rotations have no fixed length.
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            Self::Daily => Some(Duration::days(1)),
+            Self::Weekly => Some(Duration::weeks(1)),
+            Self::Custom(secs) => Some(Duration::seconds(*secs)),
+            Self::Pattern { shift_secs, .. } => Some(Duration::seconds(*shift_secs)),
+            Self::WeekdaySplit { .. } | Self::Monthly { .. } | Self::MonthlyNthWeekday { .. } => {
+                None
+            }
+        }
+    }
+
+    pub fn validate(&self, participants: usize) -> Result<(), DomainError> {
+        let in_range = |idx: &usize| *idx < participants;
         match self {
-            Self::Daily => Duration::days(1),
-            Self::Weekly => Duration::weeks(1),
-            Self::Custom(secs) => Duration::seconds(*secs),
+            Self::Daily | Self::Weekly => Ok(()),
+            Self::Custom(secs) if *secs <= 0 => Err(invalid("shift length must be positive")),
+            Self::Custom(secs) if *secs > MAX_SHIFT_SECS => {
+                Err(invalid("shift length must be at most a year"))
+            }
+            Self::Custom(_) => Ok(()),
+            Self::WeekdaySplit { weekday, weekend } => {
+                if in_range(weekday) && in_range(weekend) {
+                    Ok(())
+                } else {
+                    Err(invalid("participant index out of range"))
+                }
+            }
+            Self::Monthly { day } if !(1..=31).contains(day) => {
+                Err(invalid("day of month must be between 1 and 31"))
+            }
+            Self::Monthly { .. } => Ok(()),
+            Self::MonthlyNthWeekday { nth, .. } if !(1..=4).contains(nth) => {
+                Err(invalid("nth weekday must be between 1 and 4"))
+            }
+            Self::MonthlyNthWeekday { .. } => Ok(()),
+            Self::Pattern { sequence, .. } if sequence.is_empty() => {
+                Err(invalid("pattern requires at least one entry"))
+            }
+            Self::Pattern { shift_secs, .. } if *shift_secs <= 0 => {
+                Err(invalid("shift length must be positive"))
+            }
+            Self::Pattern { shift_secs, .. } if *shift_secs > MAX_SHIFT_SECS => {
+                Err(invalid("shift length must be at most a year"))
+            }
+            Self::Pattern { sequence, .. } => {
+                if sequence.iter().all(in_range) {
+                    Ok(())
+                } else {
+                    Err(invalid("participant index out of range"))
+                }
+            }
+        }
+    }
+
+    /// Index into the participant list for the shift covering `local`.
+    pub(crate) fn participant_at(
+        &self,
+        local: DateTime<Tz>,
+        handoff: &HandoffTime,
+        participants: usize,
+    ) -> usize {
+        let handoff_time =
+            NaiveTime::from_hms_opt(handoff.hour, handoff.minute, 0).unwrap_or(NaiveTime::MIN);
+        let naive = local.naive_local();
+
+        let index = match self {
+            Self::Daily | Self::Weekly | Self::Custom(_) => {
+                let secs = self.duration().map_or(1, |d| d.num_seconds().max(1));
+                shifts_since_epoch(local, secs)
+            }
+            Self::Pattern {
+                sequence,
+                shift_secs,
+            } => {
+                let shift = shifts_since_epoch(local, (*shift_secs).max(1));
+                let pos = shift.rem_euclid(sequence.len().max(1) as i64) as usize;
+                return sequence.get(pos).copied().unwrap_or(0) % participants;
+            }
+            Self::WeekdaySplit { weekday, weekend } => {
+                let day = (naive - handoff_offset(handoff_time)).weekday();
+                let idx = match day {
+                    Weekday::Sat | Weekday::Sun => *weekend,
+                    _ => *weekday,
+                };
+                return idx % participants;
+            }
+            Self::Monthly { day } => months_since_epoch(
+                naive,
+                |y, m| {
+                    let last = last_day_of_month(y, m);
+                    NaiveDate::from_ymd_opt(y, m, (*day).min(last)).unwrap()
+                },
+                handoff_time,
+            ),
+            Self::MonthlyNthWeekday { nth, weekday } => months_since_epoch(
+                naive,
+                |y, m| {
+                    NaiveDate::from_weekday_of_month_opt(y, m, *weekday, *nth)
+                        .unwrap_or_else(|| NaiveDate::from_ymd_opt(y, m, 1).unwrap())
+                },
+                handoff_time,
+            ),
+        };
+
+        index.rem_euclid(participants as i64) as usize
+    }
+
+    /// Instants in `(start, end)` at which the on-call participant may change.
+    pub(crate) fn handoffs_between(
+        &self,
+        tz: Tz,
+        handoff: &HandoffTime,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<DateTime<Utc>> {
+        match self {
+            Self::Daily | Self::Weekly | Self::Custom(_) | Self::Pattern { .. } => {
+                let secs = self.duration().map_or(1, |d| d.num_seconds().max(1));
+                let epoch = epoch(tz).with_timezone(&Utc);
+                let first = (start - epoch).num_seconds().div_euclid(secs) + 1;
+                (first..)
+                    .map(|k| epoch + Duration::seconds(k * secs))
+                    .take_while(|t| *t < end)
+                    .collect()
+            }
+            Self::WeekdaySplit { .. } | Self::Monthly { .. } | Self::MonthlyNthWeekday { .. } => {
+                let handoff_time = NaiveTime::from_hms_opt(handoff.hour, handoff.minute, 0)
+                    .unwrap_or(NaiveTime::MIN);
+                let last = end.with_timezone(&tz).date_naive();
+                start
+                    .with_timezone(&tz)
+                    .date_naive()
+                    .iter_days()
+                    .take_while(|d| *d <= last)
+                    .filter_map(|d| d.and_time(handoff_time).and_local_timezone(tz).earliest())
+                    .map(|t| t.with_timezone(&Utc))
+                    .filter(|t| *t > start && *t < end)
+                    .collect()
+            }
+        }
+    }
+}
+
+fn invalid(reason: &str) -> DomainError {
+    DomainError::InvalidRotation(reason.into())
+}
+
+/// Fixed-length shifts are counted from a Monday in 2020, local time.
+fn epoch(tz: Tz) -> DateTime<Tz> {
+    NaiveDate::from_ymd_opt(2020, 1, 6)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_local_timezone(tz)
+        .unwrap()
+}
+
+fn shifts_since_epoch(local: DateTime<Tz>, shift_secs: i64) -> i64 {
+    let elapsed = local
+        .signed_duration_since(epoch(local.timezone()))
+        .num_seconds();
+    elapsed.div_euclid(shift_secs)
+}
+
+/// Calendar months elapsed since January 2020, where each month starts at
+/// the anchor date returned by `anchor` and the handoff time.
+fn months_since_epoch(
+    naive: NaiveDateTime,
+    anchor: impl Fn(i32, u32) -> NaiveDate,
+    handoff: NaiveTime,
+) -> i64 {
+    let (year, month) = (naive.year(), naive.month());
+    let months = i64::from(year - 2020) * 12 + i64::from(month) - 1;
+    if naive < anchor(year, month).and_time(handoff) {
+        months - 1
+    } else {
+        months
+    }
+}
+
+fn handoff_offset(handoff: NaiveTime) -> Duration {
+    handoff.signed_duration_since(NaiveTime::MIN)
+}
+
+fn last_day_of_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .unwrap()
+        .pred_opt()
+        .unwrap()
+        .day()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zurich() -> Tz {
+        "Europe/Zurich".parse().unwrap()
+    }
+
+    fn handoff_9() -> HandoffTime {
+        HandoffTime {
+            day: Weekday::Mon,
+            hour: 9,
+            minute: 0,
+        }
+    }
+
+    fn local(s: &str) -> DateTime<Tz> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_local_timezone(zurich())
+            .unwrap()
+    }
+
+    #[test]
+    fn legacy_variants_deserialize() {
+        let daily: Rotation = serde_json::from_str("\"Daily\"").unwrap();
+        let custom: Rotation = serde_json::from_str("{\"Custom\":3600}").unwrap();
+        assert_eq!(daily, Rotation::Daily);
+        assert_eq!(custom, Rotation::Custom(3600));
+    }
+
+    #[test]
+    fn new_variants_roundtrip() {
+        let rotations = [
+            Rotation::WeekdaySplit {
+                weekday: 0,
+                weekend: 1,
+            },
+            Rotation::Monthly { day: 15 },
+            Rotation::MonthlyNthWeekday {
+                nth: 1,
+                weekday: Weekday::Mon,
+            },
+            Rotation::Pattern {
+                sequence: vec![0, 0, 1, 2],
+                shift_secs: 86_400,
+            },
+        ];
+        for rotation in rotations {
+            let json = serde_json::to_string(&rotation).unwrap();
+            let back: Rotation = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, rotation);
+        }
+    }
+
+    #[test]
+    fn weekday_split_turns_over_at_handoff() {
+        let rotation = Rotation::WeekdaySplit {
+            weekday: 0,
+            weekend: 1,
+        };
+        let at = |s| rotation.participant_at(local(s), &handoff_9(), 2);
+
+        assert_eq!(at("2025-01-17 12:00"), 0); // Friday
+        assert_eq!(at("2025-01-18 08:59"), 0); // Saturday before handoff
+        assert_eq!(at("2025-01-18 09:00"), 1);
+        assert_eq!(at("2025-01-20 08:00"), 1); // Monday before handoff
+        assert_eq!(at("2025-01-20 09:00"), 0);
+    }
+
+    #[test]
+    fn monthly_follows_calendar_months() {
+        let rotation = Rotation::Monthly { day: 1 };
+        let at = |s| rotation.participant_at(local(s), &handoff_9(), 3);
+
+        let jan = at("2025-01-20 12:00");
+        assert_eq!(at("2025-01-01 09:00"), jan);
+        assert_eq!(at("2025-02-01 08:59"), jan);
+        assert_eq!(at("2025-02-01 09:00"), (jan + 1) % 3);
+        assert_eq!(at("2025-04-15 12:00"), jan);
+    }
+
+    #[test]
+    fn monthly_clamps_to_short_months() {
+        let rotation = Rotation::Monthly { day: 31 };
+        let at = |s| rotation.participant_at(local(s), &handoff_9(), 2);
+
+        let before = at("2025-02-28 08:00");
+        assert_eq!(at("2025-02-28 09:00"), (before + 1) % 2);
+    }
+
+    #[test]
+    fn first_monday_of_month() {
+        let rotation = Rotation::MonthlyNthWeekday {
+            nth: 1,
+            weekday: Weekday::Mon,
+        };
+        let at = |s| rotation.participant_at(local(s), &handoff_9(), 2);
+
+        // First Monday of March 2025 is the 3rd.
+        let feb = at("2025-03-03 08:59");
+        assert_eq!(at("2025-02-10 12:00"), feb);
+        assert_eq!(at("2025-03-03 09:00"), (feb + 1) % 2);
+        assert_eq!(at("2025-03-31 12:00"), (feb + 1) % 2);
+    }
+
+    #[test]
+    fn pattern_repeats_sequence() {
+        let rotation = Rotation::Pattern {
+            sequence: vec![0, 0, 1, 2],
+            shift_secs: 86_400,
+        };
+        let days: Vec<_> = (6..14)
+            .map(|d| {
+                rotation.participant_at(local(&format!("2020-01-{d:02} 12:00")), &handoff_9(), 3)
+            })
+            .collect();
+        assert_eq!(days, [0, 0, 1, 2, 0, 0, 1, 2]);
+    }
+
+    #[test]
+    fn validate_rejects_bad_rotations() {
+        let bad = [
+            Rotation::Custom(0),
+            Rotation::Custom(i64::MAX),
+            Rotation::WeekdaySplit {
+                weekday: 0,
+                weekend: 2,
+            },
+            Rotation::Monthly { day: 0 },
+            Rotation::MonthlyNthWeekday {
+                nth: 5,
+                weekday: Weekday::Fri,
+            },
+            Rotation::Pattern {
+                sequence: vec![],
+                shift_secs: 3600,
+            },
+            Rotation::Pattern {
+                sequence: vec![0, 3],
+                shift_secs: 3600,
+            },
+            Rotation::Pattern {
+                sequence: vec![0],
+                shift_secs: MAX_SHIFT_SECS + 1,
+            },
+        ];
+        for rotation in bad {
+            assert!(
+                matches!(rotation.validate(2), Err(DomainError::InvalidRotation(_))),
+                "{rotation:?} should be invalid"
+            );
         }
+        assert!(Rotation::Weekly.validate(1).is_ok());
     }
 }
@@ -1,7 +1,16 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::ids::{OverrideId, UserId};
+use crate::ids::{OverrideId, SwapId, UserId};
+
+/// Who asked for an override, who agreed to it and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverrideAudit {
+    pub requested_by: UserId,
+    pub approved_by: Option<UserId>,
+    pub reason: Option<String>,
+    pub swap_id: Option<SwapId>,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ScheduleOverride {
@@ -9,6 +18,8 @@ pub struct ScheduleOverride {
     user_id: UserId,
     start: DateTime<Utc>,
     end: DateTime<Utc>,
+    #[serde(default)]
+    audit: Option<OverrideAudit>,
 }
 
 impl ScheduleOverride {
@@ -18,6 +29,19 @@ impl ScheduleOverride {
             user_id,
             start,
             end,
+            audit: None,
+        }
+    }
+
+    pub fn with_audit(
+        user_id: UserId,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        audit: OverrideAudit,
+    ) -> Self {
+        Self {
+            audit: Some(audit),
+            ..Self::new(user_id, start, end)
         }
     }
 
@@ -33,6 +57,40 @@ impl ScheduleOverride {
         at >= self.start && at < self.end
     }
 
+    /// True when this override shares any time with `[start, end)`.
+    pub fn overlaps(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        self.start < end && start < self.end
+    }
+
+    /// The parts of this override left after carving out `[start, end)`.
+    /// The leading part keeps this override's id.
+    pub(crate) fn split_around(self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<Self> {
+        let mut parts = Vec::with_capacity(2);
+        if end < self.end {
+            parts.push(Self {
+                id: OverrideId::new(),
+                start: end.max(self.start),
+                ..self.clone()
+            });
+        }
+        if self.start < start {
+            parts.insert(
+                0,
+                Self {
+                    end: start.min(self.end),
+                    ..self
+                },
+            );
+        }
+        parts
+    }
+
+    pub(crate) fn amend(&mut self, user_id: UserId, start: DateTime<Utc>, end: DateTime<Utc>) {
+        self.user_id = user_id;
+        self.start = start;
+        self.end = end;
+    }
+
     pub fn start(&self) -> DateTime<Utc> {
         self.start
     }
@@ -40,6 +98,10 @@ impl ScheduleOverride {
     pub fn end(&self) -> DateTime<Utc> {
         self.end
     }
+
+    pub fn audit(&self) -> Option<&OverrideAudit> {
+        self.audit.as_ref()
+    }
 }
 
 #[cfg(test)]
@@ -89,4 +151,47 @@ mod tests {
         let ovr = make_override();
         assert!(!ovr.is_active_at(ts("2025-01-15T00:00:01Z")));
     }
+
+    #[test]
+    fn overlaps_is_exclusive_at_boundaries() {
+        let ovr = make_override();
+        assert!(ovr.overlaps(ts("2025-01-14T23:00:00Z"), ts("2025-01-15T01:00:00Z")));
+        assert!(!ovr.overlaps(ts("2025-01-15T00:00:00Z"), ts("2025-01-16T00:00:00Z")));
+        assert!(!ovr.overlaps(ts("2025-01-13T00:00:00Z"), ts("2025-01-14T00:00:00Z")));
+    }
+
+    #[test]
+    fn split_around_middle_keeps_both_ends() {
+        let ovr = make_override();
+        let id = ovr.id().clone();
+        let parts = ovr.split_around(ts("2025-01-14T08:00:00Z"), ts("2025-01-14T12:00:00Z"));
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].id(), &id);
+        assert_eq!(parts[0].end(), ts("2025-01-14T08:00:00Z"));
+        assert_ne!(parts[1].id(), &id);
+        assert_eq!(parts[1].start(), ts("2025-01-14T12:00:00Z"));
+        assert_eq!(parts[1].end(), ts("2025-01-15T00:00:00Z"));
+    }
+
+    #[test]
+    fn split_around_covering_range_removes_override() {
+        let ovr = make_override();
+        let parts = ovr.split_around(ts("2025-01-13T00:00:00Z"), ts("2025-01-16T00:00:00Z"));
+        assert!(parts.is_empty());
+    }
+
+    #[test]
+    fn plain_override_has_no_audit() {
+        assert!(make_override().audit().is_none());
+    }
+
+    #[test]
+    fn deserializes_overrides_stored_without_audit() {
+        let ovr = make_override();
+        let mut json = serde_json::to_value(&ovr).unwrap();
+        json.as_object_mut().unwrap().remove("audit");
+
+        let loaded: ScheduleOverride = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded, ovr);
+    }
 }
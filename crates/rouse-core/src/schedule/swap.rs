@@ -0,0 +1,386 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::DomainError;
+use crate::events::{DomainEvent, SwapAccepted, SwapCancelled, SwapDeclined, SwapRequested};
+use crate::ids::{ScheduleId, SwapId, UserId};
+
+use super::shift_override::{OverrideAudit, ScheduleOverride};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwapKind {
+    /// The counterpart takes the requester's shift, nothing in return.
+    Cover,
+    /// The counterpart takes the requester's shift and the requester
+    /// takes the counterpart's shift in the return period.
+    Swap {
+        return_start: DateTime<Utc>,
+        return_end: DateTime<Utc>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwapStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwapDecision {
+    Accept,
+    Decline,
+}
+
+/// What the requester is asking for, before it becomes a `SwapRequest`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapProposal {
+    pub requester: UserId,
+    pub counterpart: UserId,
+    pub kind: SwapKind,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapRequest {
+    id: SwapId,
+    schedule_id: ScheduleId,
+    requester: UserId,
+    counterpart: UserId,
+    kind: SwapKind,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    reason: Option<String>,
+    status: SwapStatus,
+    created_at: DateTime<Utc>,
+    responded_at: Option<DateTime<Utc>>,
+}
+
+impl SwapRequest {
+    pub fn propose(
+        schedule_id: ScheduleId,
+        proposal: SwapProposal,
+        now: DateTime<Utc>,
+    ) -> Result<(Self, Vec<DomainEvent>), DomainError> {
+        if proposal.requester == proposal.counterpart {
+            return Err(DomainError::SwapWithSelf);
+        }
+        if proposal.end <= proposal.start {
+            return Err(DomainError::InvalidOverridePeriod);
+        }
+        if let SwapKind::Swap {
+            return_start,
+            return_end,
+        } = proposal.kind
+        {
+            if return_end <= return_start {
+                return Err(DomainError::InvalidOverridePeriod);
+            }
+        }
+
+        let swap = Self {
+            id: SwapId::new(),
+            schedule_id,
+            requester: proposal.requester,
+            counterpart: proposal.counterpart,
+            kind: proposal.kind,
+            start: proposal.start,
+            end: proposal.end,
+            reason: proposal.reason,
+            status: SwapStatus::Pending,
+            created_at: now,
+            responded_at: None,
+        };
+        let events = vec![DomainEvent::SwapRequested(SwapRequested {
+            swap_id: swap.id.clone(),
+            schedule_id: swap.schedule_id.clone(),
+            requester: swap.requester.clone(),
+            counterpart: swap.counterpart.clone(),
+            occurred_at: now,
+        })];
+        Ok((swap, events))
+    }
+
+    pub fn respond(
+        &mut self,
+        responder: &UserId,
+        decision: SwapDecision,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<DomainEvent>, DomainError> {
+        if self.status != SwapStatus::Pending {
+            return Err(DomainError::SwapNotPending);
+        }
+        if responder != &self.counterpart {
+            return Err(DomainError::NotSwapCounterpart);
+        }
+        self.responded_at = Some(now);
+        let event = match decision {
+            SwapDecision::Accept => {
+                self.status = SwapStatus::Accepted;
+                DomainEvent::SwapAccepted(SwapAccepted {
+                    swap_id: self.id.clone(),
+                    schedule_id: self.schedule_id.clone(),
+                    approved_by: responder.clone(),
+                    occurred_at: now,
+                })
+            }
+            SwapDecision::Decline => {
+                self.status = SwapStatus::Declined;
+                DomainEvent::SwapDeclined(SwapDeclined {
+                    swap_id: self.id.clone(),
+                    schedule_id: self.schedule_id.clone(),
+                    declined_by: responder.clone(),
+                    occurred_at: now,
+                })
+            }
+        };
+        Ok(vec![event])
+    }
+
+    pub fn cancel(
+        &mut self,
+        by: &UserId,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<DomainEvent>, DomainError> {
+        if self.status != SwapStatus::Pending {
+            return Err(DomainError::SwapNotPending);
+        }
+        if by != &self.requester {
+            return Err(DomainError::NotSwapRequester);
+        }
+        self.status = SwapStatus::Cancelled;
+        self.responded_at = Some(now);
+        Ok(vec![DomainEvent::SwapCancelled(SwapCancelled {
+            swap_id: self.id.clone(),
+            schedule_id: self.schedule_id.clone(),
+            cancelled_by: by.clone(),
+            occurred_at: now,
+        })])
+    }
+
+    /// Overrides to apply once the swap is accepted. Empty while pending,
+    /// declined or cancelled.
+    pub fn overrides(&self) -> Vec<ScheduleOverride> {
+        if self.status != SwapStatus::Accepted {
+            return vec![];
+        }
+        let audit = OverrideAudit {
+            requested_by: self.requester.clone(),
+            approved_by: Some(self.counterpart.clone()),
+            reason: self.reason.clone(),
+            swap_id: Some(self.id.clone()),
+        };
+        let mut overrides = vec![ScheduleOverride::with_audit(
+            self.counterpart.clone(),
+            self.start,
+            self.end,
+            audit.clone(),
+        )];
+        if let SwapKind::Swap {
+            return_start,
+            return_end,
+        } = self.kind
+        {
+            overrides.push(ScheduleOverride::with_audit(
+                self.requester.clone(),
+                return_start,
+                return_end,
+                audit,
+            ));
+        }
+        overrides
+    }
+
+    pub fn id(&self) -> &SwapId {
+        &self.id
+    }
+
+    pub fn schedule_id(&self) -> &ScheduleId {
+        &self.schedule_id
+    }
+
+    pub fn requester(&self) -> &UserId {
+        &self.requester
+    }
+
+    pub fn counterpart(&self) -> &UserId {
+        &self.counterpart
+    }
+
+    pub fn kind(&self) -> SwapKind {
+        self.kind
+    }
+
+    pub fn start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    pub fn end(&self) -> DateTime<Utc> {
+        self.end
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    pub fn status(&self) -> SwapStatus {
+        self.status
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn responded_at(&self) -> Option<DateTime<Utc>> {
+        self.responded_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn cover(requester: &UserId, counterpart: &UserId) -> SwapProposal {
+        SwapProposal {
+            requester: requester.clone(),
+            counterpart: counterpart.clone(),
+            kind: SwapKind::Cover,
+            start: ts("2025-01-14T00:00:00Z"),
+            end: ts("2025-01-15T00:00:00Z"),
+            reason: Some("dentist".into()),
+        }
+    }
+
+    fn propose(proposal: SwapProposal) -> SwapRequest {
+        let (swap, _) =
+            SwapRequest::propose(ScheduleId::new(), proposal, ts("2025-01-13T09:00:00Z")).unwrap();
+        swap
+    }
+
+    #[test]
+    fn propose_emits_requested_event() {
+        let (alice, bob) = (UserId::new(), UserId::new());
+        let (swap, events) = SwapRequest::propose(
+            ScheduleId::new(),
+            cover(&alice, &bob),
+            ts("2025-01-13T09:00:00Z"),
+        )
+        .unwrap();
+        assert_eq!(swap.status(), SwapStatus::Pending);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type(), "swap.requested");
+    }
+
+    #[test]
+    fn propose_with_self_fails() {
+        let alice = UserId::new();
+        let result = SwapRequest::propose(
+            ScheduleId::new(),
+            cover(&alice, &alice),
+            ts("2025-01-13T09:00:00Z"),
+        );
+        assert!(matches!(result, Err(DomainError::SwapWithSelf)));
+    }
+
+    #[test]
+    fn propose_with_invalid_return_period_fails() {
+        let (alice, bob) = (UserId::new(), UserId::new());
+        let mut proposal = cover(&alice, &bob);
+        proposal.kind = SwapKind::Swap {
+            return_start: ts("2025-01-21T00:00:00Z"),
+            return_end: ts("2025-01-20T00:00:00Z"),
+        };
+        let result = SwapRequest::propose(ScheduleId::new(), proposal, ts("2025-01-13T09:00:00Z"));
+        assert!(matches!(result, Err(DomainError::InvalidOverridePeriod)));
+    }
+
+    #[test]
+    fn pending_swap_has_no_overrides() {
+        let swap = propose(cover(&UserId::new(), &UserId::new()));
+        assert!(swap.overrides().is_empty());
+    }
+
+    #[test]
+    fn accepted_cover_gives_shift_to_counterpart() {
+        let (alice, bob) = (UserId::new(), UserId::new());
+        let mut swap = propose(cover(&alice, &bob));
+
+        let events = swap
+            .respond(&bob, SwapDecision::Accept, ts("2025-01-13T10:00:00Z"))
+            .unwrap();
+        assert_eq!(events[0].event_type(), "swap.accepted");
+
+        let overrides = swap.overrides();
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].user_id(), &bob);
+        let audit = overrides[0].audit().unwrap();
+        assert_eq!(audit.requested_by, alice);
+        assert_eq!(audit.approved_by, Some(bob));
+        assert_eq!(audit.reason.as_deref(), Some("dentist"));
+        assert_eq!(audit.swap_id.as_ref(), Some(swap.id()));
+    }
+
+    #[test]
+    fn accepted_swap_gives_return_shift_to_requester() {
+        let (alice, bob) = (UserId::new(), UserId::new());
+        let mut proposal = cover(&alice, &bob);
+        proposal.kind = SwapKind::Swap {
+            return_start: ts("2025-01-20T00:00:00Z"),
+            return_end: ts("2025-01-21T00:00:00Z"),
+        };
+        let mut swap = propose(proposal);
+        swap.respond(&bob, SwapDecision::Accept, ts("2025-01-13T10:00:00Z"))
+            .unwrap();
+
+        let overrides = swap.overrides();
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides[1].user_id(), &alice);
+        assert_eq!(overrides[1].start(), ts("2025-01-20T00:00:00Z"));
+    }
+
+    #[test]
+    fn only_counterpart_can_respond() {
+        let (alice, bob) = (UserId::new(), UserId::new());
+        let mut swap = propose(cover(&alice, &bob));
+        let result = swap.respond(&alice, SwapDecision::Accept, ts("2025-01-13T10:00:00Z"));
+        assert!(matches!(result, Err(DomainError::NotSwapCounterpart)));
+        assert_eq!(swap.status(), SwapStatus::Pending);
+    }
+
+    #[test]
+    fn declined_swap_cannot_be_accepted() {
+        let (alice, bob) = (UserId::new(), UserId::new());
+        let mut swap = propose(cover(&alice, &bob));
+        let events = swap
+            .respond(&bob, SwapDecision::Decline, ts("2025-01-13T10:00:00Z"))
+            .unwrap();
+        assert_eq!(events[0].event_type(), "swap.declined");
+
+        let result = swap.respond(&bob, SwapDecision::Accept, ts("2025-01-13T11:00:00Z"));
+        assert!(matches!(result, Err(DomainError::SwapNotPending)));
+        assert!(swap.overrides().is_empty());
+    }
+
+    #[test]
+    fn only_requester_can_cancel() {
+        let (alice, bob) = (UserId::new(), UserId::new());
+        let mut swap = propose(cover(&alice, &bob));
+        assert!(matches!(
+            swap.cancel(&bob, ts("2025-01-13T10:00:00Z")),
+            Err(DomainError::NotSwapRequester)
+        ));
+
+        let events = swap.cancel(&alice, ts("2025-01-13T10:00:00Z")).unwrap();
+        assert_eq!(events[0].event_type(), "swap.cancelled");
+        assert_eq!(swap.status(), SwapStatus::Cancelled);
+    }
+}
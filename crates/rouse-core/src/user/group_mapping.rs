@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use super::Role;
+
+/// Grants a role and/or team membership to members of an identity-provider
+/// group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupMapping {
+    pub group: String,
+    #[serde(default)]
+    pub role: Option<Role>,
+    /// Team name.
+    #[serde(default)]
+    pub team: Option<String>,
+}
+
+/// Highest role any of `groups` grants, or `None` if no mapping applies.
+pub fn role_for_groups(mappings: &[GroupMapping], groups: &[String]) -> Option<Role> {
+    mappings
+        .iter()
+        .filter(|m| groups.contains(&m.group))
+        .filter_map(|m| m.role)
+        .max_by_key(|role| role.rank())
+}
+
+/// Splits the teams named in `mappings` into those `groups` grant and those
+/// they do not. Teams granted by any group are never in the second list.
+pub fn teams_for_groups(
+    mappings: &[GroupMapping],
+    groups: &[String],
+) -> (Vec<String>, Vec<String>) {
+    let mut granted: Vec<String> = Vec::new();
+    let mut withheld: Vec<String> = Vec::new();
+    for mapping in mappings {
+        let Some(team) = &mapping.team else { continue };
+        if groups.contains(&mapping.group) {
+            if !granted.contains(team) {
+                granted.push(team.clone());
+            }
+        } else if !withheld.contains(team) {
+            withheld.push(team.clone());
+        }
+    }
+    withheld.retain(|t| !granted.contains(t));
+    (granted, withheld)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(group: &str, role: Option<Role>, team: Option<&str>) -> GroupMapping {
+        GroupMapping {
+            group: group.into(),
+            role,
+            team: team.map(Into::into),
+        }
+    }
+
+    fn groups(names: &[&str]) -> Vec<String> {
+        names.iter().map(|g| g.to_string()).collect()
+    }
+
+    #[test]
+    fn highest_matching_role_wins() {
+        let mappings = [
+            mapping("staff", Some(Role::Viewer), None),
+            mapping("sre", Some(Role::User), Some("platform")),
+            mapping("sre-leads", Some(Role::Admin), None),
+        ];
+        assert_eq!(
+            role_for_groups(&mappings, &groups(&["staff", "sre"])),
+            Some(Role::User)
+        );
+        assert_eq!(
+            role_for_groups(&mappings, &groups(&["sre-leads", "staff"])),
+            Some(Role::Admin)
+        );
+        assert_eq!(role_for_groups(&mappings, &groups(&["sales"])), None);
+    }
+
+    #[test]
+    fn teams_are_granted_or_withheld() {
+        let mappings = [
+            mapping("sre", None, Some("platform")),
+            mapping("sre-eu", None, Some("platform")),
+            mapping("dba", None, Some("database")),
+        ];
+        let (granted, withheld) = teams_for_groups(&mappings, &groups(&["sre-eu"]));
+        assert_eq!(granted, ["platform"]);
+        assert_eq!(withheld, ["database"]);
+    }
+}
@@ -1,11 +1,22 @@
+pub mod group_mapping;
+pub mod notification_rule;
 pub mod phone;
+pub mod quiet_hours;
+pub mod verification;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::alert::Severity;
+use crate::channel::Channel;
 use crate::error::DomainError;
 use crate::ids::{TeamId, UserId};
 
+pub use group_mapping::GroupMapping;
+pub use notification_rule::{NotificationRule, PlannedContact};
 pub use phone::Phone;
+pub use quiet_hours::{DndWindow, QuietDecision, QuietHours};
+pub use verification::{ContactVerification, VerificationChallenge};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Role {
@@ -14,6 +25,17 @@ pub enum Role {
     Viewer,
 }
 
+impl Role {
+    /// Higher ranks include every permission of lower ones.
+    pub fn rank(self) -> u8 {
+        match self {
+            Self::Viewer => 0,
+            Self::User => 1,
+            Self::Admin => 2,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     id: UserId,
@@ -25,6 +47,21 @@ pub struct User {
     whatsapp_id: Option<String>,
     phone: Option<Phone>,
     role: Role,
+    #[serde(default)]
+    notification_rules: Vec<NotificationRule>,
+    #[serde(default)]
+    verifications: Vec<ContactVerification>,
+    #[serde(default)]
+    quiet_hours: Option<QuietHours>,
+    #[serde(default)]
+    dnd: Vec<DndWindow>,
+    #[serde(default)]
+    quiet_fallback: Option<Channel>,
+    /// `issuer|subject` of the SSO identity this user signs in with.
+    #[serde(default)]
+    external_id: Option<String>,
+    #[serde(default)]
+    managed: bool,
 }
 
 impl User {
@@ -39,15 +76,27 @@ impl User {
             whatsapp_id: None,
             phone: None,
             role,
+            notification_rules: vec![],
+            verifications: vec![],
+            quiet_hours: None,
+            dnd: vec![],
+            quiet_fallback: None,
+            external_id: None,
+            managed: false,
         }
     }
 
+    /// Only verified contacts count: an address nobody confirmed may not work.
     pub fn can_be_on_call(&self) -> bool {
-        self.phone.is_some()
-            || self.slack_id.is_some()
-            || self.discord_id.is_some()
-            || self.telegram_id.is_some()
-            || self.whatsapp_id.is_some()
+        [
+            Channel::Phone,
+            Channel::Slack,
+            Channel::Discord,
+            Channel::Telegram,
+            Channel::WhatsApp,
+        ]
+        .into_iter()
+        .any(|channel| self.verified_contact(channel).is_some())
     }
 
     pub fn set_phone(&mut self, phone: Phone) {
@@ -70,6 +119,218 @@ impl User {
         self.whatsapp_id = Some(id);
     }
 
+    /// Sets or clears the address used for `channel`. Email is managed with
+    /// `set_email` and webhooks are not per-user.
+    pub fn set_contact(
+        &mut self,
+        channel: Channel,
+        value: Option<String>,
+    ) -> Result<(), DomainError> {
+        match channel {
+            Channel::Slack => self.slack_id = value,
+            Channel::Discord => self.discord_id = value,
+            Channel::Telegram => self.telegram_id = value,
+            Channel::WhatsApp => self.whatsapp_id = value,
+            Channel::Sms | Channel::Phone => {
+                self.phone = value.as_deref().map(Phone::new).transpose()?;
+            }
+            Channel::Email | Channel::Webhook => {
+                return Err(DomainError::UnsupportedContactChannel)
+            }
+        }
+        Ok(())
+    }
+
+    /// Where to reach this user on `channel`, if they can be reached there.
+    pub fn contact_for(&self, channel: Channel) -> Option<&str> {
+        match channel {
+            Channel::Slack => self.slack_id.as_deref(),
+            Channel::Discord => self.discord_id.as_deref(),
+            Channel::Telegram => self.telegram_id.as_deref(),
+            Channel::WhatsApp => self.whatsapp_id.as_deref(),
+            Channel::Sms | Channel::Phone => self.phone.as_ref().map(Phone::as_str),
+            Channel::Email => Some(&self.email),
+            Channel::Webhook => None,
+        }
+    }
+
+    /// Records that the current address on `channel` received a code.
+    /// SMS and phone calls share the number, so verifying one covers both.
+    pub fn mark_verified(
+        &mut self,
+        channel: Channel,
+        at: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        if channel == Channel::Webhook {
+            return Err(DomainError::UnsupportedContactChannel);
+        }
+        let address = self
+            .contact_for(channel)
+            .ok_or(DomainError::MissingContact)?
+            .to_string();
+        self.verifications
+            .retain(|v| !same_address_kind(v.channel, channel));
+        self.verifications.push(ContactVerification {
+            channel,
+            address,
+            verified_at: at,
+        });
+        Ok(())
+    }
+
+    /// When the current address on `channel` was verified, if it was.
+    pub fn verified_at(&self, channel: Channel) -> Option<DateTime<Utc>> {
+        let address = self.contact_for(channel)?;
+        self.verifications
+            .iter()
+            .find(|v| same_address_kind(v.channel, channel) && v.address == address)
+            .map(|v| v.verified_at)
+    }
+
+    /// Like `contact_for`, but only once the address has been verified.
+    pub fn verified_contact(&self, channel: Channel) -> Option<&str> {
+        self.verified_at(channel)?;
+        self.contact_for(channel)
+    }
+
+    pub fn verifications(&self) -> &[ContactVerification] {
+        &self.verifications
+    }
+
+    pub fn set_notification_rules(&mut self, rules: Vec<NotificationRule>) {
+        self.notification_rules = rules;
+    }
+
+    pub fn notification_rules(&self) -> &[NotificationRule] {
+        &self.notification_rules
+    }
+
+    /// Deliveries for an alert of `severity`, earliest first. Users without
+    /// rules are reached immediately on every channel in `fallback`.
+    /// Channels without a verified address are skipped.
+    pub fn notification_plan(
+        &self,
+        severity: Severity,
+        fallback: &[Channel],
+    ) -> Vec<PlannedContact> {
+        let mut plan: Vec<PlannedContact> = if self.notification_rules.is_empty() {
+            fallback
+                .iter()
+                .filter_map(|&channel| self.planned(channel, 0))
+                .collect()
+        } else {
+            self.notification_rules
+                .iter()
+                .filter(|r| r.applies_to(severity))
+                .filter_map(|r| self.planned(r.channel, r.delay_secs))
+                .collect()
+        };
+        plan.sort_by_key(|p| p.delay_secs);
+        plan
+    }
+
+    fn planned(&self, channel: Channel, delay_secs: u64) -> Option<PlannedContact> {
+        self.verified_contact(channel).map(|target| PlannedContact {
+            channel,
+            target: target.to_string(),
+            delay_secs,
+        })
+    }
+
+    pub fn set_quiet_hours(&mut self, quiet_hours: Option<QuietHours>) {
+        self.quiet_hours = quiet_hours;
+    }
+
+    /// Channel used instead of deferring during quiet time, e.g. email.
+    pub fn set_quiet_fallback(&mut self, channel: Option<Channel>) {
+        self.quiet_fallback = channel;
+    }
+
+    /// Adds a do-not-disturb window, dropping ones that are already over.
+    pub fn add_dnd(&mut self, window: DndWindow, now: DateTime<Utc>) {
+        self.dnd.retain(|w| w.end() > now);
+        self.dnd.push(window);
+    }
+
+    pub fn clear_dnd(&mut self) {
+        self.dnd.clear();
+    }
+
+    pub fn quiet_hours(&self) -> Option<&QuietHours> {
+        self.quiet_hours.as_ref()
+    }
+
+    pub fn dnd(&self) -> &[DndWindow] {
+        &self.dnd
+    }
+
+    pub fn quiet_fallback(&self) -> Option<Channel> {
+        self.quiet_fallback
+    }
+
+    /// When the user stops being quiet, if they are quiet at `at`. Adjacent
+    /// or overlapping quiet hours and DND windows are chained.
+    pub fn quiet_until(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut until = at;
+        // Each window can extend the period at most once.
+        for _ in 0..=self.dnd.len() + 1 {
+            let next = self
+                .dnd
+                .iter()
+                .filter(|w| w.contains(until))
+                .map(DndWindow::end)
+                .chain(self.quiet_hours.as_ref().and_then(|q| q.ends_after(until)))
+                .max();
+            match next {
+                Some(end) => until = end,
+                None => break,
+            }
+        }
+        (until > at).then_some(until)
+    }
+
+    /// Critical alerts always page. Anything else sent while the user is
+    /// quiet goes to their verified fallback channel, or waits.
+    pub fn quiet_decision(&self, severity: Severity, at: DateTime<Utc>) -> QuietDecision {
+        if severity == Severity::Critical {
+            return QuietDecision::Deliver;
+        }
+        let Some(until) = self.quiet_until(at) else {
+            return QuietDecision::Deliver;
+        };
+        match self.quiet_fallback {
+            Some(channel) if self.verified_contact(channel).is_some() => {
+                QuietDecision::Fallback(channel)
+            }
+            _ => QuietDecision::Defer(until),
+        }
+    }
+
+    pub fn link_identity(&mut self, external_id: String) {
+        self.external_id = Some(external_id);
+    }
+
+    pub fn external_id(&self) -> Option<&str> {
+        self.external_id.as_deref()
+    }
+
+    /// Declared in the config file: edits go there, not through the API.
+    pub fn is_managed(&self) -> bool {
+        self.managed
+    }
+
+    pub fn set_managed(&mut self, managed: bool) {
+        self.managed = managed;
+    }
+
+    pub fn set_email(&mut self, email: String) {
+        self.email = email;
+    }
+
+    pub fn set_role(&mut self, role: Role) {
+        self.role = role;
+    }
+
     pub fn id(&self) -> &UserId {
         &self.id
     }
@@ -91,6 +352,11 @@ impl User {
     }
 }
 
+fn same_address_kind(a: Channel, b: Channel) -> bool {
+    let phone = |c| matches!(c, Channel::Sms | Channel::Phone);
+    a == b || (phone(a) && phone(b))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Team {
     id: TeamId,
@@ -121,16 +387,47 @@ impl Team {
     pub fn members(&self) -> &[UserId] {
         &self.members
     }
+
+    pub fn rename(&mut self, name: String) {
+        self.name = name;
+    }
+
+    /// Adding an existing member is a no-op.
+    pub fn add_member(&mut self, user_id: UserId) {
+        if !self.members.contains(&user_id) {
+            self.members.push(user_id);
+        }
+    }
+
+    pub fn remove_member(&mut self, user_id: &UserId) -> Result<(), DomainError> {
+        let pos = self
+            .members
+            .iter()
+            .position(|m| m == user_id)
+            .ok_or(DomainError::NotTeamMember)?;
+        if self.members.len() == 1 {
+            return Err(DomainError::TeamRequiresMember);
+        }
+        self.members.remove(pos);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn now() -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339("2025-01-15T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
     #[test]
     fn user_can_be_on_call_with_phone() {
         let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
         user.set_phone(Phone::new("+41791234567").unwrap());
+        user.mark_verified(Channel::Phone, now()).unwrap();
         assert!(user.can_be_on_call());
     }
 
@@ -138,6 +435,7 @@ mod tests {
     fn user_can_be_on_call_with_slack() {
         let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
         user.set_slack_id("U12345".into());
+        user.mark_verified(Channel::Slack, now()).unwrap();
         assert!(user.can_be_on_call());
     }
 
@@ -145,6 +443,7 @@ mod tests {
     fn user_can_be_on_call_with_whatsapp() {
         let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
         user.set_whatsapp_id("+41791234567".into());
+        user.mark_verified(Channel::WhatsApp, now()).unwrap();
         assert!(user.can_be_on_call());
     }
 
@@ -154,6 +453,30 @@ mod tests {
         assert!(!user.can_be_on_call());
     }
 
+    #[test]
+    fn unverified_contact_is_not_enough() {
+        let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        user.set_slack_id("U12345".into());
+        assert!(!user.can_be_on_call());
+        assert_eq!(user.verified_contact(Channel::Slack), None);
+        assert!(matches!(
+            user.mark_verified(Channel::Discord, now()),
+            Err(DomainError::MissingContact)
+        ));
+    }
+
+    #[test]
+    fn changing_address_drops_verification() {
+        let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        user.set_phone(Phone::new("+41791234567").unwrap());
+        user.mark_verified(Channel::Sms, now()).unwrap();
+        assert_eq!(user.verified_at(Channel::Phone), Some(now()));
+
+        user.set_phone(Phone::new("+41790000000").unwrap());
+        assert_eq!(user.verified_at(Channel::Sms), None);
+        assert!(!user.can_be_on_call());
+    }
+
     #[test]
     fn team_requires_member() {
         let result = Team::new("empty".into(), vec![]);
@@ -165,4 +488,185 @@ mod tests {
         let team = Team::new("backend".into(), vec![UserId::new()]);
         assert!(team.is_ok());
     }
+
+    #[test]
+    fn set_contact_updates_and_clears() {
+        let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        user.set_contact(Channel::Telegram, Some("12345".into()))
+            .unwrap();
+        assert_eq!(user.contact_for(Channel::Telegram), Some("12345"));
+        user.mark_verified(Channel::Telegram, now()).unwrap();
+        assert!(user.can_be_on_call());
+
+        user.set_contact(Channel::Telegram, None).unwrap();
+        assert_eq!(user.contact_for(Channel::Telegram), None);
+        assert!(!user.can_be_on_call());
+    }
+
+    #[test]
+    fn set_contact_validates_phone() {
+        let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        assert!(matches!(
+            user.set_contact(Channel::Sms, Some("not a phone".into())),
+            Err(DomainError::InvalidPhoneFormat)
+        ));
+        user.set_contact(Channel::Phone, Some("+41791234567".into()))
+            .unwrap();
+        assert_eq!(user.contact_for(Channel::Sms), Some("+41791234567"));
+    }
+
+    #[test]
+    fn email_is_always_a_contact() {
+        let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        assert_eq!(user.contact_for(Channel::Email), Some("alice@test.com"));
+        assert!(matches!(
+            user.set_contact(Channel::Email, None),
+            Err(DomainError::UnsupportedContactChannel)
+        ));
+    }
+
+    fn paged_user() -> User {
+        let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        user.set_slack_id("U123".into());
+        user.set_phone(Phone::new("+41791234567").unwrap());
+        user.mark_verified(Channel::Slack, now()).unwrap();
+        user.mark_verified(Channel::Sms, now()).unwrap();
+        user
+    }
+
+    #[test]
+    fn notification_plan_follows_rules_by_severity() {
+        let mut user = paged_user();
+        user.set_notification_rules(vec![
+            NotificationRule::new(Channel::Phone, 300).for_severities(vec![Severity::Critical]),
+            NotificationRule::new(Channel::Slack, 0),
+            NotificationRule::new(Channel::Sms, 120).for_severities(vec![Severity::Critical]),
+        ]);
+
+        let critical: Vec<_> = user
+            .notification_plan(Severity::Critical, &[])
+            .into_iter()
+            .map(|p| (p.channel, p.delay_secs))
+            .collect();
+        assert_eq!(
+            critical,
+            [
+                (Channel::Slack, 0),
+                (Channel::Sms, 120),
+                (Channel::Phone, 300)
+            ]
+        );
+
+        let warning = user.notification_plan(Severity::Warning, &[]);
+        assert_eq!(warning.len(), 1);
+        assert_eq!(warning[0].target, "U123");
+    }
+
+    #[test]
+    fn notification_plan_falls_back_to_step_channels() {
+        let user = paged_user();
+        let plan = user.notification_plan(Severity::Info, &[Channel::Slack, Channel::Discord]);
+        assert_eq!(
+            plan,
+            [PlannedContact {
+                channel: Channel::Slack,
+                target: "U123".into(),
+                delay_secs: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn user_without_rules_field_deserializes() {
+        let user = paged_user();
+        let mut json: serde_json::Value = serde_json::to_value(&user).unwrap();
+        json.as_object_mut().unwrap().remove("notification_rules");
+        let back: User = serde_json::from_value(json).unwrap();
+        assert!(back.notification_rules().is_empty());
+    }
+
+    fn hm(h: u32, m: u32) -> chrono::NaiveTime {
+        chrono::NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn critical_pages_through_quiet_hours() {
+        let mut user = paged_user();
+        let quiet = QuietHours::new("Europe/Zurich".parse().unwrap(), hm(22, 0), hm(7, 0));
+        user.set_quiet_hours(Some(quiet.unwrap()));
+
+        let night = at("2025-01-15T23:00:00Z");
+        assert_eq!(
+            user.quiet_decision(Severity::Critical, night),
+            QuietDecision::Deliver
+        );
+        assert_eq!(
+            user.quiet_decision(Severity::Warning, night),
+            QuietDecision::Defer(at("2025-01-16T06:00:00Z"))
+        );
+        assert_eq!(
+            user.quiet_decision(Severity::Warning, at("2025-01-16T09:00:00Z")),
+            QuietDecision::Deliver
+        );
+    }
+
+    #[test]
+    fn dnd_chains_with_quiet_hours() {
+        let mut user = paged_user();
+        let quiet = QuietHours::new(chrono_tz::UTC, hm(22, 0), hm(7, 0)).unwrap();
+        user.set_quiet_hours(Some(quiet));
+        let window = DndWindow::new(at("2025-01-16T06:00:00Z"), at("2025-01-16T12:00:00Z"));
+        user.add_dnd(window.unwrap(), now());
+
+        assert_eq!(
+            user.quiet_until(at("2025-01-15T23:00:00Z")),
+            Some(at("2025-01-16T12:00:00Z"))
+        );
+        assert_eq!(user.quiet_until(at("2025-01-16T13:00:00Z")), None);
+    }
+
+    #[test]
+    fn quiet_time_uses_verified_fallback() {
+        let mut user = paged_user();
+        let window = DndWindow::new(at("2025-01-15T00:00:00Z"), at("2025-01-16T00:00:00Z"));
+        user.add_dnd(window.unwrap(), now());
+
+        user.set_quiet_fallback(Some(Channel::Discord));
+        assert!(matches!(
+            user.quiet_decision(Severity::Info, now()),
+            QuietDecision::Defer(_)
+        ));
+
+        user.set_quiet_fallback(Some(Channel::Slack));
+        assert_eq!(
+            user.quiet_decision(Severity::Info, now()),
+            QuietDecision::Fallback(Channel::Slack)
+        );
+    }
+
+    #[test]
+    fn team_membership_changes() {
+        let (a, b) = (UserId::new(), UserId::new());
+        let mut team = Team::new("backend".into(), vec![a.clone()]).unwrap();
+        team.add_member(b.clone());
+        team.add_member(b.clone());
+        assert_eq!(team.members(), [a.clone(), b.clone()]);
+
+        team.remove_member(&a).unwrap();
+        assert_eq!(team.members(), std::slice::from_ref(&b));
+        assert!(matches!(
+            team.remove_member(&b),
+            Err(DomainError::TeamRequiresMember)
+        ));
+        assert!(matches!(
+            team.remove_member(&a),
+            Err(DomainError::NotTeamMember)
+        ));
+    }
 }
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use crate::alert::Severity;
+use crate::channel::Channel;
+use crate::error::DomainError;
+
+/// The longest a rule may hold a notification back.
+pub const MAX_RULE_DELAY_SECS: u64 = 24 * 60 * 60;
+
+/// "Reach me on `channel`, `delay_secs` after I'm paged, for these severities."
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationRule {
+    pub channel: Channel,
+    pub delay_secs: u64,
+    /// Empty means every severity.
+    #[serde(default)]
+    pub severities: Vec<Severity>,
+}
+
+impl NotificationRule {
+    pub fn new(channel: Channel, delay_secs: u64) -> Self {
+        Self {
+            channel,
+            delay_secs,
+            severities: vec![],
+        }
+    }
+
+    pub fn for_severities(mut self, severities: Vec<Severity>) -> Self {
+        self.severities = severities;
+        self
+    }
+
+    /// Webhooks have no per-user address to deliver to.
+    pub fn validate(&self) -> Result<(), DomainError> {
+        if self.channel == Channel::Webhook {
+            return Err(DomainError::UnsupportedContactChannel);
+        }
+        if self.delay_secs > MAX_RULE_DELAY_SECS {
+            return Err(DomainError::InvalidNotificationRule(format!(
+                "delay must be at most {MAX_RULE_DELAY_SECS} seconds"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn applies_to(&self, severity: Severity) -> bool {
+        self.severities.is_empty() || self.severities.contains(&severity)
+    }
+}
+
+/// One delivery the escalation pipeline should schedule for a user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedContact {
+    pub channel: Channel,
+    pub target: String,
+    pub delay_secs: u64,
+}
@@ -0,0 +1,172 @@
+use chrono::{DateTime, Duration, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::channel::Channel;
+use crate::error::DomainError;
+use crate::schedule::tz_serde;
+
+/// A daily window in the user's own timezone during which non-critical
+/// notifications wait. An `end` before `start` spans midnight.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    #[serde(with = "tz_serde")]
+    timezone: Tz,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl QuietHours {
+    pub fn new(timezone: Tz, start: NaiveTime, end: NaiveTime) -> Result<Self, DomainError> {
+        if start == end {
+            return Err(DomainError::InvalidQuietPeriod);
+        }
+        Ok(Self {
+            timezone,
+            start,
+            end,
+        })
+    }
+
+    /// When the quiet window containing `at` ends, or `None` outside one.
+    pub fn ends_after(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local = at.with_timezone(&self.timezone).naive_local();
+        let (date, time) = (local.date(), local.time());
+        let end_date = if self.start < self.end {
+            (time >= self.start && time < self.end).then_some(date)?
+        } else if time >= self.start {
+            date.succ_opt()?
+        } else if time < self.end {
+            date
+        } else {
+            return None;
+        };
+        Some(local_to_utc(self.timezone, end_date.and_time(self.end)))
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    pub fn start(&self) -> NaiveTime {
+        self.start
+    }
+
+    pub fn end(&self) -> NaiveTime {
+        self.end
+    }
+}
+
+/// Local times skipped by a DST jump resolve to the first valid instant.
+pub(crate) fn local_to_utc(timezone: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    (0..=2)
+        .find_map(|h| {
+            (local + Duration::hours(h))
+                .and_local_timezone(timezone)
+                .earliest()
+        })
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| local.and_utc())
+}
+
+/// An ad-hoc do-not-disturb period, e.g. a flight or a day off.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DndWindow {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+impl DndWindow {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Self, DomainError> {
+        if end <= start {
+            return Err(DomainError::InvalidQuietPeriod);
+        }
+        Ok(Self { start, end })
+    }
+
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.start <= at && at < self.end
+    }
+
+    pub fn start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    pub fn end(&self) -> DateTime<Utc> {
+        self.end
+    }
+}
+
+/// What to do with a non-critical notification that would go out at a
+/// given time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuietDecision {
+    Deliver,
+    /// Hold it until the quiet period ends.
+    Defer(DateTime<Utc>),
+    /// Send it now on the user's fallback channel instead.
+    Fallback(Channel),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339(s)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn hm(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn nights(tz: &str) -> QuietHours {
+        QuietHours::new(tz.parse().unwrap(), hm(22, 0), hm(7, 0)).unwrap()
+    }
+
+    #[test]
+    fn overnight_window_uses_local_time() {
+        let quiet = nights("Europe/Zurich");
+        // 21:30 UTC is 22:30 in Zurich in winter.
+        assert_eq!(
+            quiet.ends_after(ts("2025-01-15T21:30:00Z")),
+            Some(ts("2025-01-16T06:00:00Z"))
+        );
+        // 05:59 UTC is 06:59 local: still quiet, ends a minute later.
+        assert_eq!(
+            quiet.ends_after(ts("2025-01-16T05:59:00Z")),
+            Some(ts("2025-01-16T06:00:00Z"))
+        );
+        assert_eq!(quiet.ends_after(ts("2025-01-16T06:00:00Z")), None);
+        assert_eq!(quiet.ends_after(ts("2025-01-15T20:59:00Z")), None);
+    }
+
+    #[test]
+    fn daytime_window_does_not_wrap() {
+        let quiet = QuietHours::new(chrono_tz::UTC, hm(12, 0), hm(13, 0)).unwrap();
+        assert_eq!(
+            quiet.ends_after(ts("2025-01-15T12:30:00Z")),
+            Some(ts("2025-01-15T13:00:00Z"))
+        );
+        assert_eq!(quiet.ends_after(ts("2025-01-15T23:00:00Z")), None);
+    }
+
+    #[test]
+    fn window_end_follows_dst_change() {
+        let quiet = nights("America/New_York");
+        // Clocks spring forward on 2025-03-09; 07:00 local is then 11:00 UTC.
+        assert_eq!(
+            quiet.ends_after(ts("2025-03-09T04:00:00Z")),
+            Some(ts("2025-03-09T11:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn empty_periods_are_rejected() {
+        assert!(QuietHours::new(chrono_tz::UTC, hm(7, 0), hm(7, 0)).is_err());
+        let at = ts("2025-01-15T10:00:00Z");
+        assert!(DndWindow::new(at, at).is_err());
+    }
+}
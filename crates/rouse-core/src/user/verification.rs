@@ -0,0 +1,137 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::channel::Channel;
+use crate::error::DomainError;
+use crate::ids::UserId;
+
+/// How long a verification code stays valid.
+pub const CODE_TTL: Duration = Duration::minutes(10);
+/// Wrong guesses allowed before the code is burned.
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// Proof that `address` received a code on `channel` at `verified_at`.
+/// Changing the address invalidates it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContactVerification {
+    pub channel: Channel,
+    pub address: String,
+    pub verified_at: DateTime<Utc>,
+}
+
+/// A one-time code sent to a contact address, waiting to be confirmed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationChallenge {
+    user_id: UserId,
+    channel: Channel,
+    address: String,
+    code: String,
+    expires_at: DateTime<Utc>,
+    attempts: u32,
+}
+
+impl VerificationChallenge {
+    pub fn new(
+        user_id: UserId,
+        channel: Channel,
+        address: String,
+        code: String,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            user_id,
+            channel,
+            address,
+            code,
+            expires_at: now + CODE_TTL,
+            attempts: 0,
+        }
+    }
+
+    /// Counts the attempt, then checks `code`. Expired or exhausted
+    /// challenges never match.
+    pub fn attempt(&mut self, code: &str, now: DateTime<Utc>) -> Result<(), DomainError> {
+        self.attempts += 1;
+        if now >= self.expires_at {
+            return Err(DomainError::VerificationExpired);
+        }
+        if self.attempts > MAX_ATTEMPTS || self.code != code {
+            return Err(DomainError::InvalidVerificationCode);
+        }
+        Ok(())
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.attempts >= MAX_ATTEMPTS
+    }
+
+    pub fn user_id(&self) -> &UserId {
+        &self.user_id
+    }
+
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        chrono::DateTime::parse_from_rfc3339("2025-01-15T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn challenge() -> VerificationChallenge {
+        VerificationChallenge::new(
+            UserId::new(),
+            Channel::Sms,
+            "+41791234567".into(),
+            "123456".into(),
+            now(),
+        )
+    }
+
+    #[test]
+    fn correct_code_within_ttl_passes() {
+        let mut c = challenge();
+        assert!(matches!(
+            c.attempt("000000", now()),
+            Err(DomainError::InvalidVerificationCode)
+        ));
+        assert!(c.attempt("123456", now() + Duration::minutes(5)).is_ok());
+    }
+
+    #[test]
+    fn expired_code_fails() {
+        let mut c = challenge();
+        assert!(matches!(
+            c.attempt("123456", now() + CODE_TTL),
+            Err(DomainError::VerificationExpired)
+        ));
+    }
+
+    #[test]
+    fn too_many_attempts_burn_the_code() {
+        let mut c = challenge();
+        for _ in 0..MAX_ATTEMPTS {
+            let _ = c.attempt("000000", now());
+        }
+        assert!(c.is_exhausted());
+        assert!(c.attempt("123456", now()).is_err());
+    }
+}
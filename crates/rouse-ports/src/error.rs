@@ -4,6 +4,8 @@ use thiserror::Error;
 pub enum PortError {
     #[error("not found")]
     NotFound,
+    #[error("conflict: {0}")]
+    Conflict(String),
     #[error("persistence error: {0}")]
     Persistence(String),
     #[error("connection error: {0}")]
@@ -31,3 +33,11 @@ pub enum ParseError {
     #[error("invalid payload: {0}")]
     InvalidPayload(String),
 }
+
+#[derive(Debug, Error)]
+pub enum IdentityError {
+    #[error("identity provider unavailable: {0}")]
+    Unavailable(String),
+    #[error("identity rejected: {0}")]
+    Rejected(String),
+}
@@ -9,16 +9,31 @@ use rouse_core::alert::Alert;
 use rouse_core::channel::Channel;
 use rouse_core::escalation::EscalationPolicy;
 use rouse_core::events::DomainEvent;
-use rouse_core::schedule::Schedule;
+use rouse_core::ids::{AlertId, PolicyId, TenantId};
+use rouse_core::routing::Route;
+use rouse_core::schedule::{Schedule, SwapRequest};
+use rouse_core::user::{Team, User, VerificationChallenge};
 
-use crate::error::{NotifyError, ParseError, PortError};
+use crate::error::{IdentityError, NotifyError, ParseError, PortError};
 use crate::types::{
-    AlertFilter, Notification, NotifyResult, PendingEscalation, PendingNotification, RawAlert,
+    AlertFilter, AlertOccurrence, AuditEntry, AuditFilter, AuthToken, AuthorizationRequest,
+    ConfigChanges, ConfigSnapshot, IdentityClaims, Notification, NotifyResult, PendingEscalation,
+    PendingNotification, RawAlert,
 };
 
+/// A store whose reads and writes are confined to one tenant. Every
+/// repository below only ever sees the rows of its handle's tenant.
+pub trait TenantScoped: Sized {
+    fn tenant(&self) -> &TenantId;
+    /// Another handle on the same store, confined to `tenant`.
+    fn for_tenant(&self, tenant: &TenantId) -> Self;
+}
+
 #[async_trait]
 pub trait Notifier: Send + Sync {
     async fn notify(&self, notification: &Notification) -> Result<NotifyResult, NotifyError>;
+    /// Sends a plain message that is not tied to an alert, e.g. a verification code.
+    async fn send_text(&self, target: &str, text: &str) -> Result<NotifyResult, NotifyError>;
     fn channel(&self) -> Channel;
 }
 
@@ -26,8 +41,26 @@ pub trait Notifier: Send + Sync {
 pub trait AlertRepository: Send + Sync {
     async fn save(&self, alert: &Alert) -> Result<(), PortError>;
     async fn find_by_id(&self, id: &str) -> Result<Option<Alert>, PortError>;
+    /// The newest alert with this fingerprint, whatever its status.
     async fn find_by_fingerprint(&self, fp: &str) -> Result<Option<Alert>, PortError>;
+    /// The newest unresolved alert with this fingerprint.
+    async fn find_open_by_fingerprint(&self, fp: &str) -> Result<Option<Alert>, PortError>;
     async fn find_by_filter(&self, filter: &AlertFilter) -> Result<Vec<Alert>, PortError>;
+    /// Snoozed alerts whose snooze has run out by `now`.
+    async fn find_snoozed_due(&self, now: DateTime<Utc>) -> Result<Vec<Alert>, PortError>;
+    /// Acknowledged alerts whose acknowledgement has run out by `now`.
+    async fn find_ack_expired(&self, now: DateTime<Utc>) -> Result<Vec<Alert>, PortError>;
+    /// How many alerts match `filter`, ignoring its paging.
+    async fn count(&self, filter: &AlertFilter) -> Result<u64, PortError>;
+    /// Appends to the alert's payload history, keeping only the newest
+    /// `keep` occurrences.
+    async fn record_occurrence(
+        &self,
+        occurrence: &AlertOccurrence,
+        keep: usize,
+    ) -> Result<(), PortError>;
+    /// The alert's payload history, newest first.
+    async fn occurrences(&self, alert_id: &AlertId) -> Result<Vec<AlertOccurrence>, PortError>;
 }
 
 #[async_trait]
@@ -37,10 +70,99 @@ pub trait ScheduleRepository: Send + Sync {
     async fn list_all(&self) -> Result<Vec<Schedule>, PortError>;
 }
 
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    /// Fails with `PortError::Conflict` when the username or email is taken.
+    async fn save(&self, user: &User) -> Result<(), PortError>;
+    async fn find_by_id(&self, id: &str) -> Result<Option<User>, PortError>;
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, PortError>;
+    async fn list_all(&self) -> Result<Vec<User>, PortError>;
+    async fn delete(&self, id: &str) -> Result<(), PortError>;
+}
+
+#[async_trait]
+pub trait TeamRepository: Send + Sync {
+    /// Fails with `PortError::Conflict` when the team name is taken.
+    async fn save(&self, team: &Team) -> Result<(), PortError>;
+    async fn find_by_id(&self, id: &str) -> Result<Option<Team>, PortError>;
+    async fn list_all(&self) -> Result<Vec<Team>, PortError>;
+    async fn delete(&self, id: &str) -> Result<(), PortError>;
+}
+
+/// Password hashes and bearer tokens, kept apart from `User` so they never
+/// leave the server.
+#[async_trait]
+pub trait CredentialRepository: Send + Sync {
+    async fn set_password_hash(&self, user_id: &str, hash: &str) -> Result<(), PortError>;
+    async fn password_hash(&self, user_id: &str) -> Result<Option<String>, PortError>;
+    async fn save_token(&self, token: &AuthToken) -> Result<(), PortError>;
+    /// Looks across all tenants: the token is what tells us the tenant.
+    async fn find_token(&self, digest: &str) -> Result<Option<AuthToken>, PortError>;
+    async fn list_tokens(&self, user_id: &str) -> Result<Vec<AuthToken>, PortError>;
+    async fn delete_token(&self, id: &str) -> Result<(), PortError>;
+}
+
+pub trait CredentialHasher: Send + Sync {
+    /// Slow, salted hash for user-chosen passwords.
+    fn hash_password(&self, password: &str) -> Result<String, PortError>;
+    fn verify_password(&self, password: &str, hash: &str) -> bool;
+    /// Fast, deterministic digest for random tokens, so they can be looked up.
+    fn digest_token(&self, token: &str) -> String;
+}
+
+/// External single sign-on via the OIDC authorization-code flow.
+#[async_trait]
+pub trait IdentityProvider: Send + Sync {
+    /// Where to send the browser to sign in.
+    async fn authorization_url(
+        &self,
+        request: &AuthorizationRequest,
+    ) -> Result<String, IdentityError>;
+    /// Redeems the code from the callback and validates the returned ID token.
+    async fn exchange_code(
+        &self,
+        code: &str,
+        request: &AuthorizationRequest,
+    ) -> Result<IdentityClaims, IdentityError>;
+}
+
+/// Outstanding verification codes, at most one per user and channel.
+#[async_trait]
+pub trait VerificationRepository: Send + Sync {
+    /// Replaces any earlier challenge for the same user and channel.
+    async fn save(&self, challenge: &VerificationChallenge) -> Result<(), PortError>;
+    async fn find(
+        &self,
+        user_id: &str,
+        channel: Channel,
+    ) -> Result<Option<VerificationChallenge>, PortError>;
+    async fn delete(&self, user_id: &str, channel: Channel) -> Result<(), PortError>;
+}
+
+#[async_trait]
+pub trait SwapRequestRepository: Send + Sync {
+    async fn save(&self, swap: &SwapRequest) -> Result<(), PortError>;
+    async fn find_by_id(&self, id: &str) -> Result<Option<SwapRequest>, PortError>;
+    async fn list_by_schedule(&self, schedule_id: &str) -> Result<Vec<SwapRequest>, PortError>;
+}
+
 #[async_trait]
 pub trait EscalationRepository: Send + Sync {
     async fn save(&self, policy: &EscalationPolicy) -> Result<(), PortError>;
     async fn find_by_id(&self, id: &str) -> Result<Option<EscalationPolicy>, PortError>;
+    async fn list_all(&self) -> Result<Vec<EscalationPolicy>, PortError>;
+}
+
+/// The routing tree, top-level routes in order. Only written through
+/// `ConfigStore`.
+#[async_trait]
+pub trait RouteRepository: Send + Sync {
+    async fn list_all(&self) -> Result<Vec<Route>, PortError>;
+    /// Takes alerts that no route matched.
+    async fn default_policy(&self) -> Result<Option<PolicyId>, PortError>;
+    /// Changes whenever the routes or the default policy are rewritten,
+    /// so a compiled router can be kept until then.
+    async fn revision(&self) -> Result<u64, PortError>;
 }
 
 #[async_trait]
@@ -55,6 +177,8 @@ pub trait NotificationQueue: Send + Sync {
         next_attempt: DateTime<Utc>,
     ) -> Result<(), PortError>;
     async fn mark_dead(&self, id: &str) -> Result<(), PortError>;
+    /// Drops deliveries for an alert that have not gone out yet.
+    async fn cancel_for_alert(&self, alert_id: &str) -> Result<(), PortError>;
 }
 
 #[async_trait]
@@ -65,6 +189,22 @@ pub trait EscalationQueue: Send + Sync {
     async fn mark_fired(&self, id: &str) -> Result<(), PortError>;
 }
 
+/// Append-only record of who changed what. There is deliberately no way to
+/// edit or remove entries.
+#[async_trait]
+pub trait AuditLog: Send + Sync {
+    async fn append(&self, entry: &AuditEntry) -> Result<(), PortError>;
+    async fn query(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>, PortError>;
+}
+
+/// Backs declarative config: read everything at once, write all of a
+/// reconcile or none of it.
+#[async_trait]
+pub trait ConfigStore: Send + Sync {
+    async fn snapshot(&self) -> Result<ConfigSnapshot, PortError>;
+    async fn commit(&self, changes: &ConfigChanges) -> Result<(), PortError>;
+}
+
 #[async_trait]
 pub trait EventPublisher: Send + Sync {
     async fn publish(&self, events: Vec<DomainEvent>) -> Result<(), PortError>;
@@ -6,7 +6,11 @@ use serde::{Deserialize, Serialize};
 use rouse_core::alert::Severity;
 use rouse_core::alert::Status;
 use rouse_core::channel::Channel;
-use rouse_core::ids::{AlertId, PolicyId};
+use rouse_core::escalation::{EscalationPolicy, EscalationTarget};
+use rouse_core::ids::{AlertId, PolicyId, ScheduleId, TenantId, UserId};
+use rouse_core::routing::Route;
+use rouse_core::schedule::Schedule;
+use rouse_core::user::{Team, User};
 
 /// Raw alert data from an external source, before domain validation.
 #[derive(Debug, Clone)]
@@ -19,6 +23,32 @@ pub struct RawAlert {
     pub status: String,
 }
 
+/// One payload received for an alert, first fire or repeat.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertOccurrence {
+    pub alert_id: AlertId,
+    pub received_at: DateTime<Utc>,
+    pub external_id: String,
+    pub source: String,
+    pub severity: String,
+    pub labels: BTreeMap<String, String>,
+    pub summary: String,
+}
+
+impl AlertOccurrence {
+    pub fn new(alert_id: AlertId, raw: &RawAlert, received_at: DateTime<Utc>) -> Self {
+        Self {
+            alert_id,
+            received_at,
+            external_id: raw.external_id.clone(),
+            source: raw.source.clone(),
+            severity: raw.severity.clone(),
+            labels: raw.labels.clone(),
+            summary: raw.summary.clone(),
+        }
+    }
+}
+
 /// Notification ready to be sent via a channel adapter.
 #[derive(Debug, Clone)]
 pub struct Notification {
@@ -40,10 +70,13 @@ pub struct NotifyResult {
 /// Filter criteria for querying alerts.
 #[derive(Debug, Clone, Default)]
 pub struct AlertFilter {
+    /// Matched by name: any snooze matches a `Snoozed` status.
     pub status: Option<Status>,
     pub severity: Option<Severity>,
     pub source: Option<String>,
     pub search: Option<String>,
+    /// Only alerts that no route matched.
+    pub unrouted: bool,
     pub page: u32,
     pub per_page: u32,
 }
@@ -69,8 +102,13 @@ pub struct PendingEscalation {
     pub alert_id: AlertId,
     pub policy_id: PolicyId,
     pub step_order: u32,
+    /// How many times the policy has gone back to its first step.
+    pub repetition: u32,
     pub fires_at: DateTime<Utc>,
     pub status: QueueStatus,
+    /// Pages this instead of the step's targets, and escalation stops
+    /// there: the alert was handed over to it.
+    pub target: Option<EscalationTarget>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -79,4 +117,150 @@ pub enum QueueStatus {
     Sent,
     Failed,
     Dead,
+    Cancelled,
+}
+
+/// A bearer credential. Only a digest of the secret is stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthToken {
+    pub id: String,
+    #[serde(default = "TenantId::primary")]
+    pub tenant_id: TenantId,
+    pub user_id: UserId,
+    pub kind: TokenKind,
+    pub digest: String,
+    pub created_at: DateTime<Utc>,
+    /// `None` for API tokens, which live until revoked.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TokenKind {
+    /// Long-lived token for scripts and integrations.
+    Api { name: String },
+    /// Issued by password login.
+    Session,
+}
+
+/// Per-login secrets for the OIDC authorization-code flow. The caller keeps
+/// them between the redirect and the callback.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthorizationRequest {
+    pub state: String,
+    pub nonce: String,
+    pub pkce_verifier: String,
+}
+
+/// Identity asserted by a validated OIDC ID token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentityClaims {
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub username: Option<String>,
+    pub groups: Vec<String>,
+}
+
+/// Where an action came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditSource {
+    /// Bearer-token API calls.
+    Api,
+    /// The web UI, i.e. session-cookie calls.
+    Ui,
+    Slack,
+    Sms,
+    /// Rouse itself, e.g. a timeout firing.
+    System,
+    /// The config file, applied at startup or by `rouse config apply`.
+    Config,
+}
+
+/// Who performed an action and through what.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Actor {
+    /// `None` for system actions.
+    pub user_id: Option<UserId>,
+    pub source: AuditSource,
+    pub ip: Option<String>,
+}
+
+impl Actor {
+    pub fn system() -> Self {
+        Self {
+            user_id: None,
+            source: AuditSource::System,
+            ip: None,
+        }
+    }
+
+    pub fn config() -> Self {
+        Self {
+            user_id: None,
+            source: AuditSource::Config,
+            ip: None,
+        }
+    }
+}
+
+/// One field that an audited action changed. `None` means absent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditChange {
+    /// Dotted path into the changed record, e.g. `contacts.Slack`.
+    pub field: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+/// An immutable record of a state-changing action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: String,
+    pub actor: Actor,
+    /// Dotted verb, e.g. `alert.acknowledge` or `user.update`.
+    pub action: String,
+    /// `kind:id` of the record acted on, e.g. `user:<uuid>`.
+    pub target: String,
+    pub changes: Vec<AuditChange>,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub actor: Option<UserId>,
+    /// Matches the action itself and everything under it, so `user`
+    /// covers `user.update`.
+    pub action: Option<String>,
+    pub target: Option<String>,
+    pub source: Option<AuditSource>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Newest first; 0 means the default page size.
+    pub limit: u32,
+}
+
+/// Everything a config file can refer to or change, as currently stored.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSnapshot {
+    pub users: Vec<User>,
+    pub teams: Vec<Team>,
+    pub schedules: Vec<Schedule>,
+    pub policies: Vec<EscalationPolicy>,
+    pub routes: Vec<Route>,
+    pub default_policy_id: Option<PolicyId>,
+}
+
+/// One reconcile's worth of writes. `routes` and `default_policy_id` replace
+/// what is stored.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigChanges {
+    pub users: Vec<User>,
+    pub schedules: Vec<Schedule>,
+    pub policies: Vec<EscalationPolicy>,
+    pub deleted_users: Vec<UserId>,
+    pub deleted_schedules: Vec<ScheduleId>,
+    pub deleted_policies: Vec<PolicyId>,
+    pub routes: Vec<Route>,
+    pub default_policy_id: Option<PolicyId>,
 }
@@ -0,0 +1,453 @@
+use axum::extract::{Path, Query};
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use rouse_core::alert::{Alert, Severity, Status};
+use rouse_core::authz::Operation;
+use rouse_core::escalation::EscalationTarget;
+use rouse_core::ids::AlertId;
+use rouse_ports::types::{AlertFilter, AlertOccurrence};
+
+use super::auth::{Caller, Tenant};
+use super::{ApiError, AppState};
+
+const MAX_PER_PAGE: u32 = 500;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/alerts", get(list_alerts))
+        .route("/api/alerts/{id}", get(get_alert))
+        .route("/api/alerts/{id}/occurrences", get(list_occurrences))
+        .route("/api/alerts/{id}/acknowledge", post(acknowledge))
+        .route("/api/alerts/{id}/unacknowledge", post(unacknowledge))
+        .route("/api/alerts/{id}/snooze", post(snooze))
+        .route("/api/alerts/{id}/reassign", post(reassign))
+        .route("/api/alerts/{id}/escalate", post(escalate))
+        .route("/api/alerts/{id}/resolve", post(resolve))
+        .route("/api/alerts/{id}/reopen", post(reopen))
+        .route("/metrics", get(metrics))
+}
+
+#[derive(Debug, Deserialize)]
+struct AlertQuery {
+    /// `firing`, `acknowledged`, `snoozed` or `resolved`.
+    status: Option<String>,
+    severity: Option<Severity>,
+    source: Option<String>,
+    search: Option<String>,
+    /// Only alerts that no route matched.
+    #[serde(default)]
+    unrouted: bool,
+    page: Option<u32>,
+    per_page: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SnoozeBody {
+    until: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReassignBody {
+    target: EscalationTarget,
+}
+
+#[derive(Debug, Serialize)]
+struct AlertPage {
+    alerts: Vec<Alert>,
+    total: u64,
+}
+
+async fn list_alerts(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Query(query): Query<AlertQuery>,
+) -> Result<Json<AlertPage>, ApiError> {
+    caller.authorize(Operation::ViewAlerts)?;
+    let status = match query.status.as_deref().map(str::to_lowercase).as_deref() {
+        None => None,
+        Some("firing") => Some(Status::Firing),
+        Some("acknowledged") => Some(Status::Acknowledged),
+        // Filters match by name, whatever the snooze's end.
+        Some("snoozed") => Some(Status::Snoozed { until: Utc::now() }),
+        Some("resolved") => Some(Status::Resolved),
+        Some(other) => return Err(ApiError::bad_request(format!("unknown status `{other}`"))),
+    };
+    let filter = AlertFilter {
+        status,
+        severity: query.severity,
+        source: query.source,
+        search: query.search,
+        unrouted: query.unrouted,
+        page: query.page.unwrap_or(1),
+        per_page: query.per_page.unwrap_or(0).min(MAX_PER_PAGE),
+    };
+    let (alerts, total) = state.alerts.list(&filter).await?;
+    Ok(Json(AlertPage { alerts, total }))
+}
+
+async fn get_alert(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<Alert>, ApiError> {
+    caller.authorize(Operation::ViewAlerts)?;
+    let id = AlertId::parse(&id)?;
+    Ok(Json(state.alerts.get(&id).await?))
+}
+
+async fn acknowledge(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<Alert>, ApiError> {
+    caller.authorize(Operation::AcknowledgeAlert)?;
+    let id = AlertId::parse(&id)?;
+    let by = caller.user_id().clone();
+    state
+        .alerts
+        .acknowledge(&id, by, caller.actor(), Utc::now())
+        .await?;
+    Ok(Json(state.alerts.get(&id).await?))
+}
+
+/// Takes back an acknowledgement or snooze; paging starts over.
+async fn unacknowledge(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<Alert>, ApiError> {
+    caller.authorize(Operation::AcknowledgeAlert)?;
+    let id = AlertId::parse(&id)?;
+    let by = caller.user_id().clone();
+    state
+        .alerts
+        .unacknowledge(&id, by, caller.actor(), Utc::now())
+        .await?;
+    Ok(Json(state.alerts.get(&id).await?))
+}
+
+async fn snooze(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(body): Json<SnoozeBody>,
+) -> Result<Json<Alert>, ApiError> {
+    caller.authorize(Operation::AcknowledgeAlert)?;
+    let id = AlertId::parse(&id)?;
+    let by = caller.user_id().clone();
+    state
+        .alerts
+        .snooze(&id, by, body.until, caller.actor(), Utc::now())
+        .await?;
+    Ok(Json(state.alerts.get(&id).await?))
+}
+
+/// Hands the alert to `target` and pages it now.
+async fn reassign(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(body): Json<ReassignBody>,
+) -> Result<Json<Alert>, ApiError> {
+    caller.authorize(Operation::AcknowledgeAlert)?;
+    let id = AlertId::parse(&id)?;
+    let by = caller.user_id().clone();
+    state
+        .alerts
+        .reassign(&id, body.target, by, caller.actor(), Utc::now())
+        .await?;
+    Ok(Json(state.alerts.get(&id).await?))
+}
+
+/// Pages the next step now instead of waiting for it.
+async fn escalate(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<Alert>, ApiError> {
+    caller.authorize(Operation::AcknowledgeAlert)?;
+    let id = AlertId::parse(&id)?;
+    let by = caller.user_id().clone();
+    state
+        .alerts
+        .escalate_now(&id, by, caller.actor(), Utc::now())
+        .await?;
+    Ok(Json(state.alerts.get(&id).await?))
+}
+
+async fn resolve(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<Alert>, ApiError> {
+    caller.authorize(Operation::ResolveAlert)?;
+    let id = AlertId::parse(&id)?;
+    let by = format!("user:{}", caller.user_id());
+    state
+        .alerts
+        .resolve(&id, by, caller.actor(), Utc::now())
+        .await?;
+    Ok(Json(state.alerts.get(&id).await?))
+}
+
+/// 409 if the same problem has fired since as a newer alert.
+async fn reopen(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<Alert>, ApiError> {
+    caller.authorize(Operation::ResolveAlert)?;
+    let id = AlertId::parse(&id)?;
+    let by = caller.user_id().clone();
+    state
+        .alerts
+        .reopen(&id, by, caller.actor(), Utc::now())
+        .await?;
+    Ok(Json(state.alerts.get(&id).await?))
+}
+
+/// The payloads received for the alert, newest first.
+async fn list_occurrences(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<AlertOccurrence>>, ApiError> {
+    caller.authorize(Operation::ViewAlerts)?;
+    let id = AlertId::parse(&id)?;
+    Ok(Json(state.alerts.occurrences(&id).await?))
+}
+
+/// Prometheus text format, for the caller's tenant.
+async fn metrics(Tenant(state): Tenant, caller: Caller) -> Result<impl IntoResponse, ApiError> {
+    caller.authorize(Operation::ViewAlerts)?;
+    let unrouted = state.alerts.count_unrouted().await?;
+    let body = format!(
+        "# HELP rouse_alerts_unrouted_total Alerts that no route matched.\n\
+         # TYPE rouse_alerts_unrouted_total counter\n\
+         rouse_alerts_unrouted_total {unrouted}\n"
+    );
+    Ok(([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    use rouse_core::ids::PolicyId;
+    use rouse_core::routing::{Matcher, Route};
+    use rouse_ports::outbound::ConfigStore;
+    use rouse_ports::types::{ConfigChanges, RawAlert};
+
+    use crate::api::router;
+    use rouse_core::user::Role;
+    use serde_json::json;
+
+    use crate::api::test_support::{
+        seed_user_with_role, send, send_as, state_with_db, ADMIN_TOKEN,
+    };
+
+    fn raw(service: &str) -> RawAlert {
+        RawAlert {
+            external_id: "ext-1".into(),
+            source: "alertmanager".into(),
+            severity: "critical".into(),
+            labels: BTreeMap::from([("service".into(), service.into())]),
+            summary: "High CPU".into(),
+            status: "firing".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn unrouted_alerts_are_listed_and_counted() {
+        let (state, db) = state_with_db().await;
+        db.commit(&ConfigChanges {
+            routes: vec![Route::new(
+                vec![Matcher::equal("service", "api")],
+                PolicyId::new(),
+            )],
+            default_policy_id: Some(PolicyId::new()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let now = chrono::Utc::now();
+        state.alerts.receive(raw("api"), now).await.unwrap();
+        let lost = state.alerts.receive(raw("apu"), now).await.unwrap();
+
+        let (status, body) = send(&state, "GET", "/api/alerts", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total"], 2);
+
+        let uri = format!("/api/alerts?page={}&per_page=1000", u32::MAX);
+        let (status, body) = send(&state, "GET", &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["alerts"], serde_json::json!([]));
+
+        let (_, body) = send(&state, "GET", "/api/alerts?unrouted=true", None).await;
+        assert_eq!(body["total"], 1);
+        assert_eq!(body["alerts"][0]["id"], lost.to_string());
+        assert_eq!(body["alerts"][0]["labels"]["service"], "apu");
+
+        let request = Request::get("/metrics")
+            .header("authorization", format!("Bearer {ADMIN_TOKEN}"))
+            .body(Body::empty())
+            .unwrap();
+        let response = router(state).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(text.contains("\nrouse_alerts_unrouted_total 1\n"));
+    }
+
+    #[tokio::test]
+    async fn repeat_fires_are_counted_with_their_payloads() {
+        let (state, _db) = state_with_db().await;
+        let start = chrono::Utc::now();
+        let mut id = None;
+        for minute in 0..3 {
+            let at = start + chrono::Duration::minutes(minute);
+            id = Some(state.alerts.receive(raw("api"), at).await.unwrap());
+        }
+        let id = id.unwrap();
+
+        let (_, body) = send(&state, "GET", "/api/alerts?status=firing", None).await;
+        assert_eq!(body["alerts"][0]["occurrence_count"], 3);
+        let (_, body) = send(&state, "GET", "/api/alerts?status=snoozed", None).await;
+        assert_eq!(body["total"], 0);
+
+        let uri = format!("/api/alerts/{id}/occurrences");
+        let (status, history) = send(&state, "GET", &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(history.as_array().unwrap().len(), 3);
+        assert_eq!(history[0]["labels"]["service"], "api");
+    }
+
+    #[tokio::test]
+    async fn users_work_alerts_and_viewers_cannot() {
+        let (state, db) = state_with_db().await;
+        let (_, viewer) = seed_user_with_role(&db, Role::Viewer).await;
+        let (user_id, user) = seed_user_with_role(&db, Role::User).await;
+        let id = state
+            .alerts
+            .receive(raw("api"), chrono::Utc::now())
+            .await
+            .unwrap();
+        let uri = |action: &str| format!("/api/alerts/{id}/{action}");
+        let until = json!({ "until": chrono::Utc::now() + chrono::Duration::hours(1) });
+
+        for (action, body) in [
+            ("acknowledge", None),
+            ("unacknowledge", None),
+            ("snooze", Some(until.clone())),
+            ("resolve", None),
+            ("reopen", None),
+        ] {
+            let (status, _) = send_as(&state, Some(&viewer), "POST", &uri(action), body).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{action}");
+        }
+        let (status, body) = send_as(
+            &state,
+            Some(&viewer),
+            "GET",
+            &format!("/api/alerts/{id}"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "Firing");
+
+        let (status, body) = send_as(&state, Some(&user), "POST", &uri("acknowledge"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "Acknowledged");
+        assert_eq!(body["acknowledged_by"], user_id.to_string());
+
+        let (_, body) = send_as(&state, Some(&user), "POST", &uri("unacknowledge"), None).await;
+        assert_eq!(body["status"], "Firing");
+
+        let (status, body) =
+            send_as(&state, Some(&user), "POST", &uri("snooze"), Some(until)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["status"]["Snoozed"].is_object());
+
+        let (_, body) = send_as(&state, Some(&user), "POST", &uri("resolve"), None).await;
+        assert_eq!(body["status"], "Resolved");
+        let (status, _) = send_as(&state, Some(&user), "POST", &uri("acknowledge"), None).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let (status, body) = send(&state, "POST", &uri("reopen"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "Firing");
+
+        let (_, log) = send(&state, "GET", "/api/audit", None).await;
+        let actions: Vec<&str> = log
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["action"].as_str().unwrap())
+            .collect();
+        assert!(actions.contains(&"alert.acknowledge"));
+        assert!(actions.contains(&"alert.reopen"));
+    }
+
+    #[tokio::test]
+    async fn users_reassign_and_escalate_alerts() {
+        let (state, db) = state_with_db().await;
+        db.commit(&ConfigChanges {
+            routes: vec![Route::new(vec![], PolicyId::new())],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let (_, viewer) = seed_user_with_role(&db, Role::Viewer).await;
+        let (user_id, user) = seed_user_with_role(&db, Role::User).await;
+        let id = state
+            .alerts
+            .receive(raw("api"), chrono::Utc::now())
+            .await
+            .unwrap();
+        let uri = |action: &str| format!("/api/alerts/{id}/{action}");
+        let target = json!({ "target": { "User": user_id.to_string() } });
+
+        let (status, _) = send_as(
+            &state,
+            Some(&viewer),
+            "POST",
+            &uri("reassign"),
+            Some(target.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send_as(&state, Some(&viewer), "POST", &uri("escalate"), None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) =
+            send_as(&state, Some(&user), "POST", &uri("reassign"), Some(target)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["reassigned_to"]["User"], user_id.to_string());
+
+        send_as(&state, Some(&user), "POST", &uri("acknowledge"), None).await;
+        let (status, body) = send_as(&state, Some(&user), "POST", &uri("escalate"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "Firing");
+
+        let (_, log) = send(&state, "GET", "/api/audit", None).await;
+        let actions: Vec<&str> = log
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["action"].as_str().unwrap())
+            .collect();
+        assert!(actions.contains(&"alert.reassign"));
+        assert!(actions.contains(&"alert.escalate"));
+    }
+}
@@ -0,0 +1,113 @@
+use axum::extract::Query;
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use rouse_core::authz::Operation;
+use rouse_core::ids::UserId;
+use rouse_ports::types::{AuditEntry, AuditFilter, AuditSource};
+
+use super::auth::{Caller, Tenant};
+use super::{ApiError, AppState};
+
+const MAX_LIMIT: u32 = 1000;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/audit", get(list_entries))
+}
+
+/// Records what `caller` just did; see `rouse_app::audit::entry`.
+pub(super) async fn record<T: Serialize>(
+    state: &AppState,
+    caller: &Caller,
+    action: &str,
+    target: String,
+    before: Option<&T>,
+    after: Option<&T>,
+) -> Result<(), ApiError> {
+    state
+        .audit
+        .record(caller.actor(), action, target, before, after, Utc::now())
+        .await?;
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct AuditQuery {
+    actor: Option<String>,
+    action: Option<String>,
+    target: Option<String>,
+    source: Option<AuditSource>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    limit: Option<u32>,
+}
+
+async fn list_entries(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Vec<AuditEntry>>, ApiError> {
+    caller.authorize(Operation::ViewAuditLog)?;
+    let filter = AuditFilter {
+        actor: query.actor.as_deref().map(UserId::parse).transpose()?,
+        action: query.action,
+        target: query.target,
+        source: query.source,
+        since: query.since,
+        until: query.until,
+        limit: query.limit.unwrap_or(0).min(MAX_LIMIT),
+    };
+    Ok(Json(state.audit.query(&filter).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test_support::{seed_user_with_role, send, send_as, state_with_db};
+    use axum::http::StatusCode;
+    use rouse_core::user::Role;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn changes_are_listed_with_their_diff() {
+        let (state, _db) = state_with_db().await;
+        let (status, user) = send(
+            &state,
+            "POST",
+            "/api/users",
+            Some(json!({"username": "carol", "email": "carol@example.com"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = user["id"].as_str().unwrap();
+        let uri = format!("/api/users/{id}");
+        send(&state, "PATCH", &uri, Some(json!({"role": "Admin"}))).await;
+        // Unchanged: nothing to record.
+        send(&state, "PATCH", &uri, Some(json!({"role": "Admin"}))).await;
+
+        let (status, entries) = send(&state, "GET", "/api/audit?action=user", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let entries = entries.as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["action"], "user.update");
+        assert_eq!(entries[0]["actor"]["source"], "Api");
+        assert_eq!(entries[0]["target"], format!("user:{id}"));
+        assert_eq!(
+            entries[0]["changes"],
+            json!([{"field": "role", "before": "User", "after": "Admin"}])
+        );
+        assert_eq!(entries[1]["action"], "user.create");
+
+        let (_, none) = send(&state, "GET", "/api/audit?source=Slack", None).await;
+        assert_eq!(none, json!([]));
+    }
+
+    #[tokio::test]
+    async fn only_admins_read_the_audit_log() {
+        let (state, db) = state_with_db().await;
+        let (_, token) = seed_user_with_role(&db, Role::User).await;
+        let (status, _) = send_as(&state, Some(&token), "GET", "/api/audit", None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
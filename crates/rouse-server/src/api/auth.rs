@@ -0,0 +1,473 @@
+use std::net::SocketAddr;
+
+use axum::extract::{ConnectInfo, FromRequestParts, Path, State};
+use axum::http::header::{AUTHORIZATION, COOKIE, SET_COOKIE};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{AppendHeaders, IntoResponse};
+use axum::routing::{delete, get, post, put};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use rouse_app::auth_service::SESSION_TTL;
+use rouse_app::error::AppError;
+use rouse_core::authz::{Operation, Principal};
+use rouse_core::ids::{TenantId, UserId};
+use rouse_core::user::User;
+use rouse_ports::types::{Actor, AuditSource, AuthToken, TokenKind};
+
+use super::audit::record;
+use super::{ApiError, AppState};
+
+const SESSION_COOKIE: &str = "rouse_session";
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/auth/login", post(login))
+        .route("/api/auth/logout", post(logout))
+        .route("/api/auth/me", get(me))
+        .route("/api/users/{id}/password", put(set_password))
+        .route(
+            "/api/users/{id}/tokens",
+            get(list_tokens).post(create_token),
+        )
+        .route("/api/users/{id}/tokens/{token_id}", delete(revoke_token))
+}
+
+/// The authenticated caller. Extracting it rejects requests without a
+/// valid bearer token or session cookie.
+#[derive(Debug, Clone)]
+pub struct Caller(pub Principal, Actor);
+
+impl Caller {
+    pub fn authorize(&self, op: Operation<'_>) -> Result<(), ApiError> {
+        Ok(self.0.authorize(op)?)
+    }
+
+    pub fn user_id(&self) -> &UserId {
+        &self.0.user_id
+    }
+
+    /// Who to record in the audit log: bearer tokens are API calls, session
+    /// cookies the UI. The address is the peer's; proxy headers can be
+    /// forged and are ignored.
+    pub fn actor(&self) -> &Actor {
+        &self.1
+    }
+
+    /// Authorizes `op` on the profile of the user identified by `id`.
+    pub fn authorize_profile(&self, id: &str) -> Result<(), ApiError> {
+        let user_id = UserId::parse(id)?;
+        self.authorize(Operation::ManageProfile { user_id: &user_id })
+    }
+}
+
+impl FromRequestParts<AppState> for Caller {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        // `Tenant` authenticates too; do it once per request.
+        if let Some(caller) = parts.extensions.get::<Self>() {
+            return Ok(caller.clone());
+        }
+        let token = presented_token(&parts.headers).ok_or(AppError::Unauthenticated)?;
+        let principal = state.auth.authenticate(&token, Utc::now()).await?;
+        let actor = Actor {
+            user_id: Some(principal.user_id.clone()),
+            source: if bearer(&parts.headers).is_some() {
+                AuditSource::Api
+            } else {
+                AuditSource::Ui
+            },
+            ip: parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string()),
+        };
+        let caller = Self(principal, actor);
+        parts.extensions.insert(caller.clone());
+        Ok(caller)
+    }
+}
+
+/// Application state confined to the authenticated caller's tenant.
+/// Handlers behind authentication take this instead of `State<AppState>`.
+pub struct Tenant(pub AppState);
+
+impl FromRequestParts<AppState> for Tenant {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let caller = Caller::from_request_parts(parts, state).await?;
+        Ok(Self(state.for_tenant(&caller.0.tenant_id)))
+    }
+}
+
+fn bearer(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+}
+
+/// `Authorization: Bearer <token>` wins over the session cookie.
+fn presented_token(headers: &HeaderMap) -> Option<String> {
+    if let Some(token) = bearer(headers) {
+        return Some(token.trim().to_string());
+    }
+    cookie(headers, SESSION_COOKIE)
+}
+
+pub(super) fn cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .find_map(|pair| {
+            let (key, value) = pair.trim().split_once('=')?;
+            (key == name).then(|| value.to_string())
+        })
+}
+
+pub(super) fn session_cookie(token: &str) -> String {
+    format!(
+        "{SESSION_COOKIE}={token}; HttpOnly; Secure; SameSite=Strict; Path=/; Max-Age={}",
+        SESSION_TTL.num_seconds()
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct LoginBody {
+    username: String,
+    password: String,
+    /// Defaults to the primary tenant.
+    #[serde(default)]
+    tenant: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct LoginResponse {
+    token: String,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PasswordBody {
+    password: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenBody {
+    name: String,
+}
+
+/// What the API shows of a token: never the secret or its digest.
+#[derive(Debug, Serialize)]
+struct TokenView {
+    id: String,
+    name: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<AuthToken> for TokenView {
+    fn from(token: AuthToken) -> Self {
+        let name = match token.kind {
+            TokenKind::Api { name } => name,
+            TokenKind::Session => "session".into(),
+        };
+        Self {
+            id: token.id,
+            name,
+            created_at: token.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CreatedToken {
+    #[serde(flatten)]
+    view: TokenView,
+    /// Shown once; only a digest is kept.
+    token: String,
+}
+
+async fn login(
+    State(state): State<AppState>,
+    Json(body): Json<LoginBody>,
+) -> Result<impl IntoResponse, ApiError> {
+    let tenant = match &body.tenant {
+        Some(id) => TenantId::parse(id)?,
+        None => TenantId::primary(),
+    };
+    let (token, record) = state
+        .auth_for(&tenant)
+        .login(&body.username, &body.password, Utc::now())
+        .await?;
+    Ok((
+        AppendHeaders([(SET_COOKIE, session_cookie(&token))]),
+        Json(LoginResponse {
+            token,
+            expires_at: record.expires_at,
+        }),
+    ))
+}
+
+async fn logout(
+    Tenant(state): Tenant,
+    _caller: Caller,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    if let Some(token) = presented_token(&headers) {
+        state.auth.logout(&token).await?;
+    }
+    let cookie = format!("{SESSION_COOKIE}=; HttpOnly; Secure; SameSite=Strict; Path=/; Max-Age=0");
+    Ok((
+        AppendHeaders([(SET_COOKIE, cookie)]),
+        StatusCode::NO_CONTENT,
+    ))
+}
+
+async fn me(Tenant(state): Tenant, caller: Caller) -> Result<Json<User>, ApiError> {
+    let user = state.users.get_user(&caller.user_id().to_string()).await?;
+    Ok(Json(user))
+}
+
+async fn set_password(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(body): Json<PasswordBody>,
+) -> Result<StatusCode, ApiError> {
+    caller.authorize_profile(&id)?;
+    state.auth.set_password(&id, &body.password).await?;
+    record::<()>(
+        &state,
+        &caller,
+        "user.password.set",
+        format!("user:{id}"),
+        None,
+        None,
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_tokens(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<TokenView>>, ApiError> {
+    caller.authorize_profile(&id)?;
+    let tokens = state.auth.list_api_tokens(&id).await?;
+    Ok(Json(tokens.into_iter().map(TokenView::from).collect()))
+}
+
+async fn create_token(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(body): Json<TokenBody>,
+) -> Result<(StatusCode, Json<CreatedToken>), ApiError> {
+    caller.authorize_profile(&id)?;
+    let (token, created) = state
+        .auth
+        .create_api_token(&id, body.name, Utc::now())
+        .await?;
+    let view = TokenView::from(created);
+    record(
+        &state,
+        &caller,
+        "user.token.create",
+        format!("user:{id}"),
+        None,
+        Some(&view),
+    )
+    .await?;
+    Ok((StatusCode::CREATED, Json(CreatedToken { view, token })))
+}
+
+async fn revoke_token(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path((id, token_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    caller.authorize_profile(&id)?;
+    state.auth.revoke_api_token(&id, &token_id).await?;
+    record::<()>(
+        &state,
+        &caller,
+        "user.token.revoke",
+        format!("token:{token_id}"),
+        None,
+        None,
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{seed_user, seed_user_with_role, send, send_as, state_with_db};
+    use rouse_core::user::Role;
+    use rouse_ports::outbound::TenantScoped;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn requests_without_credentials_are_unauthorized() {
+        let (state, _) = state_with_db().await;
+        let (status, _) = send_as(&state, None, "GET", "/api/users", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send_as(&state, Some("rouse_bogus"), "GET", "/api/users", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn password_login_and_logout() {
+        let (state, db) = state_with_db().await;
+        let (id, token) = seed_user_with_role(&db, Role::User).await;
+
+        let uri = format!("/api/users/{id}/password");
+        let body = json!({ "password": "a long passphrase" });
+        let (status, _) = send_as(&state, Some(&token), "PUT", &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (_, me) = send_as(&state, Some(&token), "GET", "/api/auth/me", None).await;
+        let body = json!({ "username": me["username"], "password": "a long passphrase" });
+        let (status, body) = send_as(&state, None, "POST", "/api/auth/login", Some(body)).await;
+        assert_eq!(status, StatusCode::OK);
+        let session = body["token"].as_str().unwrap().to_string();
+
+        let (status, _) = send_as(&state, Some(&session), "GET", "/api/auth/me", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send_as(&state, Some(&session), "POST", "/api/auth/logout", None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = send_as(&state, Some(&session), "GET", "/api/auth/me", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let body = json!({ "username": me["username"], "password": "wrong" });
+        let (status, _) = send_as(&state, None, "POST", "/api/auth/login", Some(body)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn session_cookie_is_read() {
+        let mut headers = HeaderMap::new();
+        headers.insert(COOKIE, "theme=dark; rouse_session=abc".parse().unwrap());
+        assert_eq!(presented_token(&headers).as_deref(), Some("abc"));
+        headers.insert(AUTHORIZATION, "Bearer xyz".parse().unwrap());
+        assert_eq!(presented_token(&headers).as_deref(), Some("xyz"));
+    }
+
+    #[tokio::test]
+    async fn tokens_are_private_to_their_owner() {
+        let (state, db) = state_with_db().await;
+        let (alice, alice_token) = seed_user_with_role(&db, Role::User).await;
+        let (bob, _) = seed_user_with_role(&db, Role::User).await;
+
+        let uri = format!("/api/users/{alice}/tokens");
+        let body = json!({ "name": "ci" });
+        let (status, body) = send_as(&state, Some(&alice_token), "POST", &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(body["token"].as_str().unwrap().starts_with("rouse_"));
+        assert!(body.get("digest").is_none());
+
+        let uri = format!("/api/users/{bob}/tokens");
+        let (status, _) = send_as(&state, Some(&alice_token), "GET", &uri, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Admins may manage anyone's tokens.
+        let (status, _) = send(&state, "GET", &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn callers_only_reach_their_own_tenant() {
+        let (state, db) = state_with_db().await;
+        let acme_user = seed_user(&db).await;
+        let globex = db.for_tenant(&TenantId::new());
+        let (globex_admin, token) = seed_user_with_role(&globex, Role::Admin).await;
+
+        let (status, users) = send_as(&state, Some(&token), "GET", "/api/users", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(users.as_array().unwrap().len(), 1);
+        assert_eq!(users[0]["id"], globex_admin.to_string());
+
+        let uri = format!("/api/users/{acme_user}");
+        let (status, _) = send_as(&state, Some(&token), "GET", &uri, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send_as(&state, Some(&token), "DELETE", &uri, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(&state, "GET", &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Team names only need to be unique within a tenant.
+        let team = json!({ "name": "platform", "members": [acme_user.to_string()] });
+        let (status, _) = send(&state, "POST", "/api/teams", Some(team)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = send_as(
+            &state,
+            Some(&token),
+            "POST",
+            "/api/teams",
+            Some(json!({ "name": "platform", "members": [globex_admin.to_string()] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (_, teams) = send_as(&state, Some(&token), "GET", "/api/teams", None).await;
+        assert_eq!(teams.as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn login_targets_the_requested_tenant() {
+        let (state, db) = state_with_db().await;
+        let tenant = TenantId::new();
+        let scoped = state.for_tenant(&tenant);
+        let (user_id, _) = seed_user_with_role(&db.for_tenant(&tenant), Role::User).await;
+        let user = scoped.users.get_user(&user_id.to_string()).await.unwrap();
+        scoped
+            .auth
+            .set_password(&user_id.to_string(), "a long passphrase")
+            .await
+            .unwrap();
+
+        let body = json!({ "username": user.username(), "password": "a long passphrase" });
+        let (status, _) =
+            send_as(&state, None, "POST", "/api/auth/login", Some(body.clone())).await;
+        assert_eq!(
+            status,
+            StatusCode::UNAUTHORIZED,
+            "not in the primary tenant"
+        );
+
+        let cached = state.tenants.lock().unwrap().len();
+        let stranger = json!({
+            "username": user.username(),
+            "password": "a long passphrase",
+            "tenant": TenantId::new().to_string(),
+        });
+        let (status, _) = send_as(&state, None, "POST", "/api/auth/login", Some(stranger)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            state.tenants.lock().unwrap().len(),
+            cached,
+            "failed logins cache nothing"
+        );
+
+        let mut body = body;
+        body["tenant"] = json!(tenant.to_string());
+        let (status, session) = send_as(&state, None, "POST", "/api/auth/login", Some(body)).await;
+        assert_eq!(status, StatusCode::OK);
+        let token = session["token"].as_str().unwrap();
+        let (_, me) = send_as(&state, Some(token), "GET", "/api/auth/me", None).await;
+        assert_eq!(me["id"], user_id.to_string());
+    }
+}
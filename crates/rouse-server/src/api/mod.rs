@@ -0,0 +1,391 @@
+pub mod alerts;
+pub mod audit;
+pub mod auth;
+pub mod routes;
+pub mod schedules;
+pub mod sso;
+pub mod teams;
+pub mod users;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use serde_json::json;
+
+use rouse_adapters::crypto::Argon2Hasher;
+use rouse_adapters::oidc::OidcProvider;
+use rouse_adapters::persistence::SqliteDb;
+use rouse_app::alert_service::AlertService;
+use rouse_app::audit::AuditService;
+use rouse_app::auth_service::AuthService;
+use rouse_app::error::AppError;
+use rouse_app::escalation_service::EscalationService;
+use rouse_app::notification_service::NotificationService;
+use rouse_app::routing_service::RoutingService;
+use rouse_app::schedule_service::ScheduleService;
+use rouse_app::sso_service::SsoService;
+use rouse_app::team_service::TeamService;
+use rouse_app::user_service::UserService;
+use rouse_app::verification_service::VerificationService;
+use rouse_core::error::DomainError;
+use rouse_core::ids::TenantId;
+use rouse_ports::error::{IdentityError, NotifyError, PortError};
+use rouse_ports::outbound::{Notifier, TenantScoped};
+
+pub type Schedules = ScheduleService<SqliteDb, SqliteDb, SqliteDb, SqliteDb>;
+pub type Users = UserService<SqliteDb, SqliteDb, SqliteDb, SqliteDb>;
+pub type Teams = TeamService<SqliteDb, SqliteDb>;
+pub type Verifications = VerificationService<SqliteDb, SqliteDb>;
+pub type Auth = AuthService<SqliteDb, SqliteDb, Argon2Hasher>;
+pub type Sso = SsoService<SqliteDb, SqliteDb, OidcProvider, SqliteDb>;
+pub type Audit = AuditService<SqliteDb>;
+pub type Routing = RoutingService<SqliteDb>;
+pub type Alerts =
+    AlertService<SqliteDb, SqliteDb, SqliteDb, SqliteDb, SqliteDb, SqliteDb, SqliteDb>;
+pub type Escalations = EscalationService<
+    SqliteDb,
+    SqliteDb,
+    SqliteDb,
+    SqliteDb,
+    SqliteDb,
+    SqliteDb,
+    SqliteDb,
+    SqliteDb,
+>;
+pub type Deliveries = NotificationService<SqliteDb, SqliteDb>;
+
+/// Where notifications link back to unless `with_base_url` says otherwise.
+pub const DEFAULT_BASE_URL: &str = "http://localhost:8080";
+
+/// Services over one tenant's data; `with_notifiers` starts on the primary
+/// tenant.
+#[derive(Clone)]
+pub struct AppState {
+    pub schedules: Arc<Schedules>,
+    pub users: Arc<Users>,
+    pub teams: Arc<Teams>,
+    pub verifications: Arc<Verifications>,
+    pub auth: Arc<Auth>,
+    pub audit: Arc<Audit>,
+    pub routing: Arc<Routing>,
+    pub alerts: Arc<Alerts>,
+    /// Fires due escalation steps into the notification queue.
+    pub escalations: Arc<Escalations>,
+    /// Sends queued notifications through `notifiers`.
+    pub deliveries: Arc<Deliveries>,
+    /// `None` unless single sign-on is configured. Provisions users into
+    /// the tenant it was built for.
+    pub sso: Option<Arc<Sso>>,
+    db: SqliteDb,
+    notifiers: Vec<Arc<dyn Notifier>>,
+    base_url: String,
+    /// Built once per tenant, so what services cache outlives a request.
+    tenants: Arc<Mutex<HashMap<TenantId, AppState>>>,
+}
+
+impl AppState {
+    /// `notifiers` deliver verification codes and alerts; channels without
+    /// one cannot be verified.
+    pub fn with_notifiers(db: SqliteDb, notifiers: Vec<Arc<dyn Notifier>>) -> Self {
+        Self {
+            schedules: Arc::new(ScheduleService::new(
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db.clone(),
+            )),
+            users: Arc::new(UserService::new(
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db.clone(),
+            )),
+            teams: Arc::new(TeamService::new(db.clone(), db.clone())),
+            verifications: Arc::new(VerificationService::new(
+                db.clone(),
+                db.clone(),
+                notifiers.clone(),
+            )),
+            auth: Arc::new(AuthService::new(
+                db.clone(),
+                db.clone(),
+                Argon2Hasher::default(),
+            )),
+            audit: Arc::new(AuditService::new(db.clone())),
+            routing: Arc::new(RoutingService::new(db.clone())),
+            alerts: Arc::new(AlertService::new(
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db.clone(),
+            )),
+            escalations: Arc::new(EscalationService::new(
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db.clone(),
+                db.clone(),
+            )),
+            deliveries: Arc::new(NotificationService::new(
+                db.clone(),
+                db.clone(),
+                notifiers.clone(),
+                DEFAULT_BASE_URL.into(),
+            )),
+            sso: None,
+            db,
+            notifiers,
+            base_url: DEFAULT_BASE_URL.into(),
+            tenants: Arc::default(),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: String) -> Self {
+        self.deliveries = Arc::new(NotificationService::new(
+            self.db.clone(),
+            self.db.clone(),
+            self.notifiers.clone(),
+            base_url.clone(),
+        ));
+        self.base_url = base_url;
+        self
+    }
+
+    /// The same services over `tenant`'s data, built on first use.
+    pub fn for_tenant(&self, tenant: &TenantId) -> Self {
+        if self.db.tenant() == tenant {
+            return self.clone();
+        }
+        let mut tenants = self.tenants.lock().unwrap();
+        if let Some(state) = tenants.get(tenant) {
+            return state.clone();
+        }
+        let state = Self::with_notifiers(self.db.for_tenant(tenant), self.notifiers.clone())
+            .with_base_url(self.base_url.clone());
+        let state = Self {
+            sso: self.sso.clone(),
+            tenants: self.tenants.clone(),
+            ..state
+        };
+        tenants.insert(tenant.clone(), state.clone());
+        state
+    }
+
+    /// Authentication over `tenant`'s users. Nothing is cached for a tenant
+    /// until someone has signed in to it, so logins naming made-up tenants
+    /// leave no trace.
+    pub fn auth_for(&self, tenant: &TenantId) -> Arc<Auth> {
+        if self.db.tenant() == tenant {
+            return self.auth.clone();
+        }
+        if let Some(state) = self.tenants.lock().unwrap().get(tenant) {
+            return state.auth.clone();
+        }
+        let db = self.db.for_tenant(tenant);
+        Arc::new(AuthService::new(db.clone(), db, Argon2Hasher::default()))
+    }
+
+    pub fn with_sso(mut self, sso: Sso) -> Self {
+        self.sso = Some(Arc::new(sso));
+        self
+    }
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .merge(alerts::routes())
+        .merge(auth::routes())
+        .merge(audit::routes())
+        .merge(routes::routes())
+        .merge(sso::routes())
+        .merge(schedules::routes())
+        .merge(users::routes())
+        .merge(teams::routes())
+        .with_state(state)
+}
+
+/// Maps application errors onto HTTP status codes with a JSON body.
+#[derive(Debug)]
+pub struct ApiError(AppError);
+
+impl ApiError {
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self(AppError::Parse(
+            rouse_ports::error::ParseError::InvalidPayload(message.into()),
+        ))
+    }
+
+    fn status(&self) -> StatusCode {
+        match &self.0 {
+            AppError::Port(PortError::NotFound)
+            | AppError::Domain(DomainError::OverrideNotFound) => StatusCode::NOT_FOUND,
+            AppError::Port(PortError::Conflict(_)) => StatusCode::CONFLICT,
+            AppError::Port(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::Domain(DomainError::InvalidId(_)) => StatusCode::BAD_REQUEST,
+            AppError::Domain(
+                DomainError::SwapNotPending
+                | DomainError::OverlappingOverride
+                | DomainError::ManagedByConfig
+                | DomainError::AlertSuperseded
+                | DomainError::UserInUse(_),
+            ) => StatusCode::CONFLICT,
+            AppError::Domain(
+                DomainError::NotSwapCounterpart
+                | DomainError::NotSwapRequester
+                | DomainError::Forbidden,
+            ) => StatusCode::FORBIDDEN,
+            AppError::Domain(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Notify(NotifyError::ChannelUnavailable) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Notify(_) => StatusCode::BAD_GATEWAY,
+            AppError::Identity(IdentityError::Unavailable(_)) => StatusCode::BAD_GATEWAY,
+            AppError::Identity(IdentityError::Rejected(_)) | AppError::Unauthenticated => {
+                StatusCode::UNAUTHORIZED
+            }
+            AppError::Parse(_) | AppError::Routing(_) | AppError::Config(_) => {
+                StatusCode::BAD_REQUEST
+            }
+        }
+    }
+}
+
+impl From<AppError> for ApiError {
+    fn from(e: AppError) -> Self {
+        Self(e)
+    }
+}
+
+impl From<DomainError> for ApiError {
+    fn from(e: DomainError) -> Self {
+        Self(AppError::Domain(e))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        if status == StatusCode::INTERNAL_SERVER_ERROR {
+            tracing::error!(error = %self.0, "request failed");
+        }
+        (status, Json(json!({ "error": self.0.to_string() }))).into_response()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use http_body_util::BodyExt;
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use rouse_core::ids::UserId;
+    use rouse_core::user::{Role, User};
+    use rouse_ports::outbound::{CredentialHasher, CredentialRepository, UserRepository};
+    use rouse_ports::types::{AuthToken, TokenKind};
+
+    use super::*;
+
+    pub async fn state() -> AppState {
+        state_with_db().await.0
+    }
+
+    /// Bearer token of the admin every test state starts with.
+    pub const ADMIN_TOKEN: &str = "rouse_test_admin";
+
+    /// Test state plus the underlying database for seeding fixtures.
+    pub async fn state_with_db() -> (AppState, SqliteDb) {
+        let db = SqliteDb::new("sqlite::memory:").await.unwrap();
+        seed_admin(&db).await;
+        (AppState::with_notifiers(db.clone(), vec![]), db)
+    }
+
+    /// Stores the admin that `send` authenticates as.
+    pub async fn seed_admin(db: &SqliteDb) {
+        seed_token(db, Role::Admin, ADMIN_TOKEN).await;
+    }
+
+    /// Stores a user that can be put on call and returns its id.
+    pub async fn seed_user(db: &SqliteDb) -> UserId {
+        seed_user_with_role(db, Role::User).await.0
+    }
+
+    /// Stores a reachable user with `role` in `db`'s tenant and returns its
+    /// id and an API token.
+    pub async fn seed_user_with_role(db: &SqliteDb, role: Role) -> (UserId, String) {
+        let token = format!("rouse_test_{}", UserId::new());
+        let id = seed_token(db, role, &token).await;
+        (id, token)
+    }
+
+    async fn seed_token(db: &SqliteDb, role: Role, token: &str) -> UserId {
+        let tag = UserId::new();
+        let mut user = User::new(format!("user-{tag}"), format!("{tag}@example.com"), role);
+        user.set_slack_id("U123".into());
+        user.mark_verified(rouse_core::channel::Channel::Slack, chrono::Utc::now())
+            .unwrap();
+        UserRepository::save(db, &user).await.unwrap();
+
+        let record = AuthToken {
+            id: UserId::new().to_string(),
+            tenant_id: db.tenant().clone(),
+            user_id: user.id().clone(),
+            kind: TokenKind::Api {
+                name: "test".into(),
+            },
+            digest: Argon2Hasher::default().digest_token(token),
+            created_at: chrono::Utc::now(),
+            expires_at: None,
+        };
+        db.save_token(&record).await.unwrap();
+        user.id().clone()
+    }
+
+    /// Sends a request as the seeded admin.
+    pub async fn send(
+        state: &AppState,
+        method: &str,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        send_as(state, Some(ADMIN_TOKEN), method, uri, body).await
+    }
+
+    pub async fn send_as(
+        state: &AppState,
+        token: Option<&str>,
+        method: &str,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {token}"));
+        }
+        let request = match body {
+            Some(body) => request.body(Body::from(body.to_string())).unwrap(),
+            None => request.body(Body::empty()).unwrap(),
+        };
+
+        let response = router(state.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let value = if bytes.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&bytes).unwrap()
+        };
+        (status, value)
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use rouse_app::router::{RouteMatch, RoutingInput};
+use rouse_core::alert::Severity;
+use rouse_core::authz::Operation;
+use rouse_core::routing::parse_severity;
+
+use super::auth::{Caller, Tenant};
+use super::{ApiError, AppState};
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/routes/test", post(test_routes))
+}
+
+/// An alert to try against the routing tree. `severity` falls back to the
+/// `severity` label, then to info, as for received alerts.
+#[derive(Debug, Deserialize)]
+struct TestRoutesBody {
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+    severity: Option<String>,
+    #[serde(default)]
+    source: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TestRoutesResponse {
+    matches: Vec<RouteMatch>,
+}
+
+async fn test_routes(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Json(body): Json<TestRoutesBody>,
+) -> Result<Json<TestRoutesResponse>, ApiError> {
+    caller.authorize(Operation::ViewAlerts)?;
+    let severity = match body
+        .severity
+        .as_deref()
+        .or(body.labels.get("severity").map(String::as_str))
+    {
+        Some(name) => parse_severity(name)
+            .ok_or_else(|| ApiError::bad_request(format!("unknown severity `{name}`")))?,
+        None => Severity::Info,
+    };
+    let input = RoutingInput {
+        severity,
+        source: &body.source,
+        labels: &body.labels,
+    };
+    let matches = state.routing.test(input).await?;
+    Ok(Json(TestRoutesResponse { matches }))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    use rouse_core::ids::PolicyId;
+    use rouse_core::routing::{Matcher, Route};
+    use rouse_ports::outbound::ConfigStore;
+    use rouse_ports::types::ConfigChanges;
+
+    use crate::api::test_support::{send, state_with_db};
+
+    #[tokio::test]
+    async fn test_shows_every_route_a_label_set_reaches() {
+        let (state, db) = state_with_db().await;
+        let (payments, fallback) = (PolicyId::new(), PolicyId::new());
+        let mut api = Route::new(vec![Matcher::equal("service", "payments")], payments);
+        api.continue_matching = true;
+        api.routes = vec![Route {
+            policy_id: None,
+            ..Route::new(vec!["env=~\"stag.*\"".parse().unwrap()], PolicyId::new())
+        }];
+        api.routes[0].options.suppress = Some(true);
+        db.commit(&ConfigChanges {
+            routes: vec![api, Route::new(vec![], fallback.clone())],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let (status, body) = send(
+            &state,
+            "POST",
+            "/api/routes/test",
+            Some(json!({"labels": {"service": "payments", "env": "staging"}, "severity": "critical"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let matches = body["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0]["path"], "1.1");
+        assert_eq!(matches[0]["options"]["suppress"], true);
+        assert_eq!(matches[1]["path"], "2");
+        assert_eq!(matches[1]["policy_id"], fallback.to_string());
+
+        let (status, _) = send(
+            &state,
+            "POST",
+            "/api/routes/test",
+            Some(json!({"labels": {}, "severity": "loud"})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
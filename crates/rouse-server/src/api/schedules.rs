@@ -0,0 +1,802 @@
+use axum::body::Bytes;
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use rouse_core::ids::UserId;
+use rouse_core::schedule::lint::{LintOptions, LintReport};
+use rouse_core::schedule::{
+    OverlapPolicy, ScheduleOverride, SwapDecision, SwapKind, SwapProposal, SwapRequest,
+};
+
+use rouse_core::authz::Operation;
+
+use super::audit::record;
+use super::auth::{Caller, Tenant};
+use super::{ApiError, AppState};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/schedules/{id}/overrides",
+            get(list_overrides).post(add_override),
+        )
+        .route(
+            "/api/schedules/{id}/overrides/{override_id}",
+            put(update_override).delete(remove_override),
+        )
+        .route(
+            "/api/schedules/{id}/overlap-policy",
+            put(set_overlap_policy),
+        )
+        .route("/api/schedules/{id}/lint", get(lint_schedule))
+        .route(
+            "/api/schedules/{id}/swaps",
+            get(list_swaps).post(request_swap),
+        )
+        // Answered through the API only; chat messages carry no buttons.
+        .route("/api/swaps/{id}/accept", post(accept_swap))
+        .route("/api/swaps/{id}/decline", post(decline_swap))
+        .route("/api/swaps/{id}/cancel", post(cancel_swap))
+}
+
+#[derive(Debug, Deserialize)]
+struct OverrideBody {
+    user_id: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RangeQuery {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolicyBody {
+    policy: OverlapPolicy,
+}
+
+async fn list_overrides(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(schedule_id): Path<String>,
+    Query(range): Query<RangeQuery>,
+) -> Result<Json<Vec<ScheduleOverride>>, ApiError> {
+    caller.authorize(Operation::ViewSchedules)?;
+    let overrides = state
+        .schedules
+        .list_overrides(&schedule_id, range.from, range.to)
+        .await?;
+    Ok(Json(overrides))
+}
+
+async fn add_override(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(schedule_id): Path<String>,
+    Json(body): Json<OverrideBody>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    let user_id = UserId::parse(&body.user_id)?;
+    caller.authorize(Operation::ManageOverride { user_id: &user_id })?;
+    let ovr = ScheduleOverride::new(user_id, body.start, body.end);
+    let id = ovr.id().clone();
+    state
+        .schedules
+        .add_override(&schedule_id, ovr.clone(), Utc::now())
+        .await?;
+    let target = format!("schedule:{schedule_id}/override:{id}");
+    record(
+        &state,
+        &caller,
+        "schedule.override.add",
+        target,
+        None,
+        Some(&ovr),
+    )
+    .await?;
+    Ok((StatusCode::CREATED, Json(json!({ "id": id }))))
+}
+
+/// Callers must be allowed to manage both the current and the new holder.
+async fn update_override(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path((schedule_id, override_id)): Path<(String, String)>,
+    Json(body): Json<OverrideBody>,
+) -> Result<StatusCode, ApiError> {
+    let current = state
+        .schedules
+        .get_override(&schedule_id, &override_id)
+        .await?;
+    caller.authorize(Operation::ManageOverride {
+        user_id: current.user_id(),
+    })?;
+    let user_id = UserId::parse(&body.user_id)?;
+    caller.authorize(Operation::ManageOverride { user_id: &user_id })?;
+    state
+        .schedules
+        .update_override(
+            &schedule_id,
+            &override_id,
+            user_id,
+            body.start,
+            body.end,
+            Utc::now(),
+        )
+        .await?;
+    let updated = state
+        .schedules
+        .get_override(&schedule_id, &override_id)
+        .await?;
+    let target = format!("schedule:{schedule_id}/override:{override_id}");
+    record(
+        &state,
+        &caller,
+        "schedule.override.update",
+        target,
+        Some(&current),
+        Some(&updated),
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn remove_override(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path((schedule_id, override_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let current = state
+        .schedules
+        .get_override(&schedule_id, &override_id)
+        .await?;
+    caller.authorize(Operation::ManageOverride {
+        user_id: current.user_id(),
+    })?;
+    state
+        .schedules
+        .remove_override(&schedule_id, &override_id, Utc::now())
+        .await?;
+    let target = format!("schedule:{schedule_id}/override:{override_id}");
+    record(
+        &state,
+        &caller,
+        "schedule.override.remove",
+        target,
+        Some(&current),
+        None,
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn set_overlap_policy(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(schedule_id): Path<String>,
+    Json(body): Json<PolicyBody>,
+) -> Result<StatusCode, ApiError> {
+    caller.authorize(Operation::ConfigureSchedule)?;
+    let before = state.schedules.get_schedule(&schedule_id).await?;
+    state
+        .schedules
+        .set_overlap_policy(&schedule_id, body.policy)
+        .await?;
+    let after = state.schedules.get_schedule(&schedule_id).await?;
+    let target = format!("schedule:{schedule_id}");
+    record(
+        &state,
+        &caller,
+        "schedule.overlap_policy.set",
+        target,
+        Some(&before),
+        Some(&after),
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+const MAX_LINT_DAYS: i64 = 366;
+
+#[derive(Debug, Deserialize)]
+struct LintQuery {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    max_hours: Option<i64>,
+    min_rest_hours: Option<i64>,
+}
+
+/// Lints the next four weeks unless a range is given.
+async fn lint_schedule(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(schedule_id): Path<String>,
+    Query(query): Query<LintQuery>,
+) -> Result<Json<LintReport>, ApiError> {
+    caller.authorize(Operation::ViewSchedules)?;
+    let out_of_range = || ApiError::bad_request("range out of bounds");
+    let from = query.from.unwrap_or_else(Utc::now);
+    let to = match query.to {
+        Some(to) => to,
+        None => from
+            .checked_add_signed(Duration::weeks(4))
+            .ok_or_else(out_of_range)?,
+    };
+    if to <= from {
+        return Err(ApiError::bad_request("to must be after from"));
+    }
+    // Every shift in the range is expanded, so keep it bounded.
+    if to - from > Duration::days(MAX_LINT_DAYS) {
+        return Err(ApiError::bad_request(format!(
+            "range must be at most {MAX_LINT_DAYS} days"
+        )));
+    }
+
+    let hours = |h: i64| Duration::try_hours(h).ok_or_else(out_of_range);
+    let mut options = LintOptions::default();
+    if let Some(h) = query.max_hours {
+        options.max_consecutive = hours(h)?;
+    }
+    if let Some(h) = query.min_rest_hours {
+        options.min_rest = hours(h)?;
+    }
+
+    let report = state
+        .schedules
+        .lint_schedule(&schedule_id, from, to, options)
+        .await?;
+    Ok(Json(report))
+}
+
+#[derive(Debug, Deserialize)]
+struct SwapBody {
+    requester: String,
+    counterpart: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    return_start: Option<DateTime<Utc>>,
+    return_end: Option<DateTime<Utc>>,
+    reason: Option<String>,
+}
+
+impl SwapBody {
+    fn into_proposal(self) -> Result<SwapProposal, ApiError> {
+        let kind = match (self.return_start, self.return_end) {
+            (None, None) => SwapKind::Cover,
+            (Some(return_start), Some(return_end)) => SwapKind::Swap {
+                return_start,
+                return_end,
+            },
+            _ => {
+                return Err(ApiError::bad_request(
+                    "return_start and return_end must be given together",
+                ))
+            }
+        };
+        Ok(SwapProposal {
+            requester: UserId::parse(&self.requester)?,
+            counterpart: UserId::parse(&self.counterpart)?,
+            kind,
+            start: self.start,
+            end: self.end,
+            reason: self.reason,
+        })
+    }
+}
+
+/// Swaps are answered as the caller. Admins may name someone else in
+/// `on_behalf_of`; for anyone else that is forbidden.
+#[derive(Debug, Default, Deserialize)]
+struct ActorBody {
+    on_behalf_of: Option<String>,
+}
+
+impl ActorBody {
+    /// An empty body answers as the caller.
+    fn actor(body: &[u8], caller: &Caller) -> Result<UserId, ApiError> {
+        let body: Self = if body.is_empty() {
+            Self::default()
+        } else {
+            serde_json::from_slice(body).map_err(|e| ApiError::bad_request(e.to_string()))?
+        };
+        let user_id = match body.on_behalf_of {
+            Some(id) => UserId::parse(&id)?,
+            None => caller.user_id().clone(),
+        };
+        caller.authorize(Operation::SwapAs { user_id: &user_id })?;
+        Ok(user_id)
+    }
+}
+
+async fn request_swap(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(schedule_id): Path<String>,
+    Json(body): Json<SwapBody>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    let proposal = body.into_proposal()?;
+    caller.authorize(Operation::SwapAs {
+        user_id: &proposal.requester,
+    })?;
+    let swap_id = state
+        .schedules
+        .request_swap(&schedule_id, proposal, Utc::now())
+        .await?;
+    let swap = state.schedules.get_swap(&swap_id.to_string()).await?;
+    let target = format!("swap:{swap_id}");
+    record(&state, &caller, "swap.request", target, None, Some(&swap)).await?;
+    Ok((StatusCode::CREATED, Json(json!({ "id": swap_id }))))
+}
+
+async fn list_swaps(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(schedule_id): Path<String>,
+) -> Result<Json<Vec<SwapRequest>>, ApiError> {
+    caller.authorize(Operation::ViewSchedules)?;
+    Ok(Json(state.schedules.list_swaps(&schedule_id).await?))
+}
+
+async fn accept_swap(
+    state: Tenant,
+    caller: Caller,
+    path: Path<String>,
+    body: Bytes,
+) -> Result<Json<Value>, ApiError> {
+    respond(state, caller, path, body, SwapDecision::Accept).await
+}
+
+async fn decline_swap(
+    state: Tenant,
+    caller: Caller,
+    path: Path<String>,
+    body: Bytes,
+) -> Result<Json<Value>, ApiError> {
+    respond(state, caller, path, body, SwapDecision::Decline).await
+}
+
+async fn respond(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(swap_id): Path<String>,
+    body: Bytes,
+    decision: SwapDecision,
+) -> Result<Json<Value>, ApiError> {
+    let responder = ActorBody::actor(&body, &caller)?;
+    let before = state.schedules.get_swap(&swap_id).await?;
+    let status = state
+        .schedules
+        .respond_to_swap(&swap_id, &responder, decision, Utc::now())
+        .await?;
+    let action = match decision {
+        SwapDecision::Accept => "swap.accept",
+        SwapDecision::Decline => "swap.decline",
+    };
+    audit_swap(&state, &caller, action, &before).await?;
+    Ok(Json(json!({ "status": status })))
+}
+
+async fn cancel_swap(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(swap_id): Path<String>,
+    body: Bytes,
+) -> Result<StatusCode, ApiError> {
+    let by = ActorBody::actor(&body, &caller)?;
+    let before = state.schedules.get_swap(&swap_id).await?;
+    state
+        .schedules
+        .cancel_swap(&swap_id, &by, Utc::now())
+        .await?;
+    audit_swap(&state, &caller, "swap.cancel", &before).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn audit_swap(
+    state: &AppState,
+    caller: &Caller,
+    action: &str,
+    before: &SwapRequest,
+) -> Result<(), ApiError> {
+    let after = state.schedules.get_swap(&before.id().to_string()).await?;
+    let target = format!("swap:{}", before.id());
+    record(state, caller, action, target, Some(before), Some(&after)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{
+        seed_user, seed_user_with_role, send, send_as, state, state_with_db,
+    };
+    use rouse_core::ids::ScheduleId;
+    use rouse_core::schedule::{HandoffTime, Rotation, Schedule};
+    use rouse_core::user::Role;
+
+    async fn seed_schedule(state: &AppState, users: &[UserId]) -> ScheduleId {
+        let schedule = Schedule::new(
+            "platform".into(),
+            "Europe/Zurich".parse().unwrap(),
+            Rotation::Weekly,
+            users.to_vec(),
+            HandoffTime {
+                day: chrono::Weekday::Mon,
+                hour: 9,
+                minute: 0,
+            },
+        )
+        .unwrap();
+        state.schedules.create_schedule(schedule).await.unwrap()
+    }
+
+    fn swap_body(requester: &UserId, counterpart: &UserId) -> Value {
+        json!({
+            "requester": requester.to_string(),
+            "counterpart": counterpart.to_string(),
+            "start": "2030-01-14T00:00:00Z",
+            "end": "2030-01-15T00:00:00Z",
+            "reason": "conference",
+        })
+    }
+
+    #[tokio::test]
+    async fn request_and_accept_swap() {
+        let (state, db) = state_with_db().await;
+        let (counterpart, token) = seed_user_with_role(&db, Role::User).await;
+        let users = [seed_user(&db).await, counterpart];
+        let schedule_id = seed_schedule(&state, &users).await;
+
+        let (status, body) = send(
+            &state,
+            "POST",
+            &format!("/api/schedules/{schedule_id}/swaps"),
+            Some(swap_body(&users[0], &users[1])),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let swap_id = body["id"].as_str().unwrap().to_string();
+
+        let (status, body) = send_as(
+            &state,
+            Some(&token),
+            "POST",
+            &format!("/api/swaps/{swap_id}/accept"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "Accepted");
+
+        let on_call = state
+            .schedules
+            .who_is_on_call(
+                &schedule_id.to_string(),
+                "2030-01-14T12:00:00Z".parse().unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(on_call, users[1]);
+    }
+
+    #[tokio::test]
+    async fn accept_by_requester_is_forbidden() {
+        let (state, db) = state_with_db().await;
+        let (requester, token) = seed_user_with_role(&db, Role::User).await;
+        let users = [requester, seed_user(&db).await];
+        let schedule_id = seed_schedule(&state, &users).await;
+
+        let (_, body) = send(
+            &state,
+            "POST",
+            &format!("/api/schedules/{schedule_id}/swaps"),
+            Some(swap_body(&users[0], &users[1])),
+        )
+        .await;
+        let swap_id = body["id"].as_str().unwrap().to_string();
+
+        let uri = format!("/api/swaps/{swap_id}/accept");
+        let (status, _) = send_as(&state, Some(&token), "POST", &uri, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Only admins may answer for someone else.
+        let for_counterpart = json!({ "on_behalf_of": users[1].to_string() });
+        let (status, _) = send_as(&state, Some(&token), "POST", &uri, Some(for_counterpart)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn declined_swap_is_listed() {
+        let (state, db) = state_with_db().await;
+        let users = [seed_user(&db).await, seed_user(&db).await];
+        let schedule_id = seed_schedule(&state, &users).await;
+
+        let (_, body) = send(
+            &state,
+            "POST",
+            &format!("/api/schedules/{schedule_id}/swaps"),
+            Some(swap_body(&users[0], &users[1])),
+        )
+        .await;
+        let swap_id = body["id"].as_str().unwrap().to_string();
+
+        let (status, _) = send(
+            &state,
+            "POST",
+            &format!("/api/swaps/{swap_id}/decline"),
+            Some(json!({ "on_behalf_of": users[1].to_string() })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = send(
+            &state,
+            "GET",
+            &format!("/api/schedules/{schedule_id}/swaps"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body[0]["status"], "Declined");
+    }
+
+    #[tokio::test]
+    async fn request_swap_unknown_schedule_is_not_found() {
+        let state = state().await;
+        let (status, _) = send(
+            &state,
+            "POST",
+            &format!("/api/schedules/{}/swaps", ScheduleId::new()),
+            Some(swap_body(&UserId::new(), &UserId::new())),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    fn override_body(user: &UserId, start: &str, end: &str) -> Value {
+        json!({ "user_id": user.to_string(), "start": start, "end": end })
+    }
+
+    #[tokio::test]
+    async fn override_crud() {
+        let (state, db) = state_with_db().await;
+        let users = [seed_user(&db).await, seed_user(&db).await];
+        let schedule_id = seed_schedule(&state, &users).await;
+        let base = format!("/api/schedules/{schedule_id}/overrides");
+
+        let (status, body) = send(
+            &state,
+            "POST",
+            &base,
+            Some(override_body(
+                &users[1],
+                "2030-01-14T00:00:00Z",
+                "2030-01-15T00:00:00Z",
+            )),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let override_id = body["id"].as_str().unwrap().to_string();
+
+        let (status, _) = send(
+            &state,
+            "PUT",
+            &format!("{base}/{override_id}"),
+            Some(override_body(
+                &users[0],
+                "2030-01-14T00:00:00Z",
+                "2030-01-16T00:00:00Z",
+            )),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let range = "from=2030-01-13T00:00:00Z&to=2030-01-20T00:00:00Z";
+        let (status, body) = send(&state, "GET", &format!("{base}?{range}"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        assert_eq!(body[0]["end"], "2030-01-16T00:00:00Z");
+
+        let (status, _) = send(&state, "DELETE", &format!("{base}/{override_id}"), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (_, body) = send(&state, "GET", &format!("{base}?{range}"), None).await;
+        assert!(body.as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn overlapping_override_conflicts() {
+        let (state, db) = state_with_db().await;
+        let users = [seed_user(&db).await, seed_user(&db).await];
+        let schedule_id = seed_schedule(&state, &users).await;
+        let base = format!("/api/schedules/{schedule_id}/overrides");
+
+        let (status, _) = send(
+            &state,
+            "POST",
+            &base,
+            Some(override_body(
+                &users[0],
+                "2030-01-14T00:00:00Z",
+                "2030-01-16T00:00:00Z",
+            )),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let second = override_body(&users[1], "2030-01-15T00:00:00Z", "2030-01-17T00:00:00Z");
+        let (status, _) = send(&state, "POST", &base, Some(second.clone())).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, _) = send(
+            &state,
+            "PUT",
+            &format!("/api/schedules/{schedule_id}/overlap-policy"),
+            Some(json!({ "policy": "Split" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (status, _) = send(&state, "POST", &base, Some(second)).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn override_for_unknown_user_is_rejected() {
+        let (state, db) = state_with_db().await;
+        let users = [seed_user(&db).await];
+        let schedule_id = seed_schedule(&state, &users).await;
+
+        let (status, _) = send(
+            &state,
+            "POST",
+            &format!("/api/schedules/{schedule_id}/overrides"),
+            Some(override_body(
+                &UserId::new(),
+                "2030-01-14T00:00:00Z",
+                "2030-01-15T00:00:00Z",
+            )),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn lint_reports_unknown_participant() {
+        let (state, db) = state_with_db().await;
+        let users = [seed_user(&db).await, UserId::new()];
+        let schedule_id = seed_schedule(&state, &users).await;
+
+        let (status, body) = send(
+            &state,
+            "GET",
+            &format!(
+                "/api/schedules/{schedule_id}/lint?from=2030-01-07T00:00:00Z&to=2030-02-04T00:00:00Z"
+            ),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let findings = body["findings"].as_array().unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0]["severity"], "Error");
+        assert_eq!(
+            findings[0]["issue"]["UnreachableParticipant"]["user_id"],
+            users[1].to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn lint_rejects_ranges_it_cannot_expand() {
+        let (state, db) = state_with_db().await;
+        let schedule_id = seed_schedule(&state, &[seed_user(&db).await]).await;
+        let base = format!("/api/schedules/{schedule_id}/lint");
+
+        for query in [
+            "from=0001-01-01T00:00:00Z&to=9999-01-01T00:00:00Z",
+            "from=2030-01-01T00:00:00Z&max_hours=9223372036854775807",
+            "from=2030-01-01T00:00:00Z&min_rest_hours=-9223372036854775807",
+        ] {
+            let (status, _) = send(&state, "GET", &format!("{base}?{query}"), None).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{query}");
+        }
+    }
+
+    #[tokio::test]
+    async fn users_manage_only_their_own_overrides() {
+        let (state, db) = state_with_db().await;
+        let (me, token) = seed_user_with_role(&db, Role::User).await;
+        let other = seed_user(&db).await;
+        let schedule_id = seed_schedule(&state, &[me.clone(), other.clone()]).await;
+        let base = format!("/api/schedules/{schedule_id}/overrides");
+        let (start, end) = ("2030-01-14T00:00:00Z", "2030-01-15T00:00:00Z");
+
+        let body = Some(override_body(&other, start, end));
+        let (status, _) = send_as(&state, Some(&token), "POST", &base, body).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let body = Some(override_body(&me, start, end));
+        let (status, body) = send_as(&state, Some(&token), "POST", &base, body).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let mine = format!("{base}/{}", body["id"].as_str().unwrap());
+
+        // Handing my override to someone else needs rights over them too.
+        let body = Some(override_body(&other, start, end));
+        let (status, _) = send_as(&state, Some(&token), "PUT", &mine, body).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) = send(
+            &state,
+            "POST",
+            &base,
+            Some(override_body(
+                &other,
+                "2030-02-01T00:00:00Z",
+                "2030-02-02T00:00:00Z",
+            )),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let theirs = format!("{base}/{}", body["id"].as_str().unwrap());
+        let (status, _) = send_as(&state, Some(&token), "DELETE", &theirs, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = send_as(&state, Some(&token), "DELETE", &mine, None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn viewers_read_but_cannot_change_schedules() {
+        let (state, db) = state_with_db().await;
+        let (viewer, token) = seed_user_with_role(&db, Role::Viewer).await;
+        let (_, user_token) = seed_user_with_role(&db, Role::User).await;
+        let schedule_id = seed_schedule(&state, std::slice::from_ref(&viewer)).await;
+
+        let uri = format!(
+            "/api/schedules/{schedule_id}/overrides?from=2030-01-01T00:00:00Z&to=2030-02-01T00:00:00Z"
+        );
+        let (status, _) = send_as(&state, Some(&token), "GET", &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let uri = format!("/api/schedules/{schedule_id}/overrides");
+        let body = override_body(&viewer, "2030-01-14T00:00:00Z", "2030-01-15T00:00:00Z");
+        let (status, _) = send_as(&state, Some(&token), "POST", &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let uri = format!("/api/schedules/{schedule_id}/overlap-policy");
+        for token in [&token, &user_token] {
+            let body = json!({ "policy": "Split" });
+            let (status, _) = send_as(&state, Some(token), "PUT", &uri, Some(body)).await;
+            assert_eq!(status, StatusCode::FORBIDDEN);
+        }
+    }
+
+    #[tokio::test]
+    async fn swaps_can_only_be_made_as_yourself() {
+        let (state, db) = state_with_db().await;
+        let (me, token) = seed_user_with_role(&db, Role::User).await;
+        let (other, other_token) = seed_user_with_role(&db, Role::User).await;
+        let schedule_id = seed_schedule(&state, &[me.clone(), other.clone()]).await;
+        let uri = format!("/api/schedules/{schedule_id}/swaps");
+
+        let body = Some(swap_body(&other, &me));
+        let (status, _) = send_as(&state, Some(&token), "POST", &uri, body).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let body = Some(swap_body(&me, &other));
+        let (status, body) = send_as(&state, Some(&token), "POST", &uri, body).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let accept = format!("/api/swaps/{}/accept", body["id"].as_str().unwrap());
+
+        let body = Some(json!({ "on_behalf_of": other.to_string() }));
+        let (status, _) = send_as(&state, Some(&token), "POST", &accept, body.clone()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send_as(&state, Some(&other_token), "POST", &accept, body).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
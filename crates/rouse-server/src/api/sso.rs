@@ -0,0 +1,183 @@
+use axum::extract::{Query, State};
+use axum::http::header::SET_COOKIE;
+use axum::http::HeaderMap;
+use axum::response::{AppendHeaders, IntoResponse, Redirect};
+use axum::routing::get;
+use axum::Router;
+use chrono::Utc;
+use serde::Deserialize;
+
+use rouse_app::error::AppError;
+use rouse_ports::error::PortError;
+use rouse_ports::types::AuthorizationRequest;
+
+use super::auth::{cookie, session_cookie};
+use super::{ApiError, AppState};
+
+/// Carries state, nonce and PKCE verifier from the redirect to the callback.
+const FLOW_COOKIE: &str = "rouse_oidc";
+const FLOW_PATH: &str = "/api/auth/oidc";
+const FLOW_TTL_SECS: i64 = 600;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/auth/oidc/login", get(login))
+        .route("/api/auth/oidc/callback", get(callback))
+}
+
+#[derive(Debug, Deserialize)]
+struct CallbackQuery {
+    code: String,
+    state: String,
+}
+
+// Lax, not Strict: the callback is a top-level navigation from the provider.
+fn flow_cookie(value: &str, max_age: i64) -> String {
+    format!("{FLOW_COOKIE}={value}; HttpOnly; Secure; SameSite=Lax; Path={FLOW_PATH}; Max-Age={max_age}")
+}
+
+fn encode_flow(request: &AuthorizationRequest) -> String {
+    format!(
+        "{}.{}.{}",
+        request.state, request.nonce, request.pkce_verifier
+    )
+}
+
+fn decode_flow(value: &str) -> Option<AuthorizationRequest> {
+    let mut parts = value.splitn(3, '.');
+    Some(AuthorizationRequest {
+        state: parts.next()?.to_string(),
+        nonce: parts.next()?.to_string(),
+        pkce_verifier: parts.next()?.to_string(),
+    })
+}
+
+async fn login(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let sso = state
+        .sso
+        .as_ref()
+        .ok_or(AppError::Port(PortError::NotFound))?;
+    let (url, request) = sso.begin().await?;
+    Ok((
+        AppendHeaders([(
+            SET_COOKIE,
+            flow_cookie(&encode_flow(&request), FLOW_TTL_SECS),
+        )]),
+        Redirect::to(&url),
+    ))
+}
+
+async fn callback(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<CallbackQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let sso = state
+        .sso
+        .as_ref()
+        .ok_or(AppError::Port(PortError::NotFound))?;
+    let request = cookie(&headers, FLOW_COOKIE)
+        .as_deref()
+        .and_then(decode_flow)
+        .filter(|request| request.state == query.state)
+        .ok_or(AppError::Unauthenticated)?;
+
+    let user = sso.complete(&query.code, &request, Utc::now()).await?;
+    let (token, _) = state.auth.start_session(user.id(), Utc::now()).await?;
+    tracing::info!(username = user.username(), "signed in via SSO");
+    Ok((
+        AppendHeaders([
+            (SET_COOKIE, session_cookie(&token)),
+            (SET_COOKIE, flow_cookie("", 0)),
+        ]),
+        Redirect::to("/"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    use rouse_adapters::oidc::{OidcConfig, OidcProvider};
+    use rouse_adapters::persistence::SqliteDb;
+    use rouse_app::sso_service::SsoService;
+    use rouse_core::user::Role;
+
+    use super::*;
+    use crate::api::router;
+    use crate::api::test_support::{send_as, state, state_with_db};
+
+    /// SSO pointed at a provider that is never reached.
+    async fn sso_state() -> AppState {
+        let (state, db): (AppState, SqliteDb) = state_with_db().await;
+        let provider = OidcProvider::new(OidcConfig::new(
+            "http://127.0.0.1:1",
+            "rouse",
+            "secret",
+            "http://rouse.test/api/auth/oidc/callback",
+        ));
+        state.with_sso(SsoService::new(
+            db.clone(),
+            db.clone(),
+            provider,
+            db,
+            vec![],
+            Role::Viewer,
+        ))
+    }
+
+    async fn callback_with(state: &AppState, cookie: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri("/api/auth/oidc/callback?code=c&state=expected");
+        if let Some(cookie) = cookie {
+            request = request.header("cookie", cookie);
+        }
+        let request = request.body(Body::empty()).unwrap();
+        router(state.clone())
+            .oneshot(request)
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn routes_are_absent_without_configuration() {
+        let state = state().await;
+        let (status, _) = send_as(&state, None, "GET", "/api/auth/oidc/login", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn unreachable_provider_is_a_bad_gateway() {
+        let state = sso_state().await;
+        let (status, _) = send_as(&state, None, "GET", "/api/auth/oidc/login", None).await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn callback_requires_matching_state() {
+        let state = sso_state().await;
+        assert_eq!(callback_with(&state, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            callback_with(&state, Some("rouse_oidc=forged.n.v")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        // Matching state gets as far as the (unreachable) provider.
+        assert_eq!(
+            callback_with(&state, Some("rouse_oidc=expected.n.v")).await,
+            StatusCode::BAD_GATEWAY
+        );
+    }
+
+    #[test]
+    fn flow_cookie_round_trips() {
+        let request = AuthorizationRequest {
+            state: "s".into(),
+            nonce: "n".into(),
+            pkce_verifier: "v".into(),
+        };
+        assert_eq!(decode_flow(&encode_flow(&request)), Some(request));
+        assert_eq!(decode_flow("only.two"), None);
+    }
+}
@@ -0,0 +1,222 @@
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+
+use rouse_core::ids::UserId;
+use rouse_core::user::Team;
+
+use rouse_core::authz::Operation;
+
+use super::audit::record;
+use super::auth::{Caller, Tenant};
+use super::{ApiError, AppState};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/teams", get(list_teams).post(create_team))
+        .route(
+            "/api/teams/{id}",
+            get(get_team).patch(rename_team).delete(delete_team),
+        )
+        .route("/api/teams/{id}/members", post(add_member))
+        .route("/api/teams/{id}/members/{user_id}", delete(remove_member))
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateTeamBody {
+    name: String,
+    members: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RenameTeamBody {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MemberBody {
+    user_id: String,
+}
+
+async fn create_team(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Json(body): Json<CreateTeamBody>,
+) -> Result<(StatusCode, Json<Team>), ApiError> {
+    caller.authorize(Operation::ManageTeams)?;
+    let members = body
+        .members
+        .iter()
+        .map(|m| UserId::parse(m))
+        .collect::<Result<Vec<_>, _>>()?;
+    let team = state.teams.create_team(body.name, members).await?;
+    let target = format!("team:{}", team.id());
+    record(&state, &caller, "team.create", target, None, Some(&team)).await?;
+    Ok((StatusCode::CREATED, Json(team)))
+}
+
+async fn list_teams(Tenant(state): Tenant, caller: Caller) -> Result<Json<Vec<Team>>, ApiError> {
+    caller.authorize(Operation::ViewTeams)?;
+    Ok(Json(state.teams.list_teams().await?))
+}
+
+async fn get_team(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<Team>, ApiError> {
+    caller.authorize(Operation::ViewTeams)?;
+    Ok(Json(state.teams.get_team(&id).await?))
+}
+
+async fn rename_team(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(body): Json<RenameTeamBody>,
+) -> Result<Json<Team>, ApiError> {
+    caller.authorize(Operation::ManageTeams)?;
+    let before = state.teams.get_team(&id).await?;
+    let team = state.teams.rename_team(&id, body.name).await?;
+    audit_change(&state, &caller, "team.rename", &before, &team).await?;
+    Ok(Json(team))
+}
+
+async fn delete_team(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    caller.authorize(Operation::ManageTeams)?;
+    let before = state.teams.get_team(&id).await?;
+    state.teams.delete_team(&id).await?;
+    record(
+        &state,
+        &caller,
+        "team.delete",
+        format!("team:{id}"),
+        Some(&before),
+        None,
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn add_member(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(body): Json<MemberBody>,
+) -> Result<Json<Team>, ApiError> {
+    caller.authorize(Operation::ManageTeams)?;
+    let user_id = UserId::parse(&body.user_id)?;
+    let before = state.teams.get_team(&id).await?;
+    let team = state.teams.add_member(&id, user_id).await?;
+    audit_change(&state, &caller, "team.member.add", &before, &team).await?;
+    Ok(Json(team))
+}
+
+async fn remove_member(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path((id, user_id)): Path<(String, String)>,
+) -> Result<Json<Team>, ApiError> {
+    caller.authorize(Operation::ManageTeams)?;
+    let user_id = UserId::parse(&user_id)?;
+    let before = state.teams.get_team(&id).await?;
+    let team = state.teams.remove_member(&id, &user_id).await?;
+    audit_change(&state, &caller, "team.member.remove", &before, &team).await?;
+    Ok(Json(team))
+}
+
+async fn audit_change(
+    state: &AppState,
+    caller: &Caller,
+    action: &str,
+    before: &Team,
+    after: &Team,
+) -> Result<(), ApiError> {
+    let target = format!("team:{}", after.id());
+    record(state, caller, action, target, Some(before), Some(after)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{seed_user, seed_user_with_role, send, send_as, state_with_db};
+    use rouse_core::user::Role;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn team_membership_lifecycle() {
+        let (state, db) = state_with_db().await;
+        let (alice, bob) = (seed_user(&db).await, seed_user(&db).await);
+
+        let (status, body) = send(
+            &state,
+            "POST",
+            "/api/teams",
+            Some(json!({ "name": "backend", "members": [alice.to_string()] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = body["id"].as_str().unwrap().to_string();
+
+        let (status, body) = send(
+            &state,
+            "POST",
+            &format!("/api/teams/{id}/members"),
+            Some(json!({ "user_id": bob.to_string() })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["members"].as_array().unwrap().len(), 2);
+
+        let (status, body) = send(
+            &state,
+            "DELETE",
+            &format!("/api/teams/{id}/members/{alice}"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["members"], json!([bob.to_string()]));
+
+        let (status, _) = send(
+            &state,
+            "DELETE",
+            &format!("/api/teams/{id}/members/{bob}"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn unknown_member_is_not_found() {
+        let (state, _) = state_with_db().await;
+        let (status, _) = send(
+            &state,
+            "POST",
+            "/api/teams",
+            Some(json!({ "name": "backend", "members": [UserId::new().to_string()] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn only_admins_manage_teams() {
+        let (state, db) = state_with_db().await;
+        let (me, token) = seed_user_with_role(&db, Role::User).await;
+
+        let body = json!({ "name": "backend", "members": [me.to_string()] });
+        let (status, _) = send_as(&state, Some(&token), "POST", "/api/teams", Some(body)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = send_as(&state, Some(&token), "GET", "/api/teams", None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
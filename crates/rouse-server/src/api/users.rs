@@ -0,0 +1,565 @@
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use rouse_core::channel::Channel;
+use rouse_core::user::{DndWindow, NotificationRule, QuietHours, Role, User};
+
+use rouse_core::authz::Operation;
+
+use super::audit::record;
+use super::auth::{Caller, Tenant};
+use super::{ApiError, AppState};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/users", get(list_users).post(create_user))
+        .route(
+            "/api/users/{id}",
+            get(get_user).patch(update_user).delete(delete_user),
+        )
+        .route("/api/users/{id}/contacts", put(set_contact))
+        .route(
+            "/api/users/{id}/quiet-hours",
+            put(set_quiet_hours).delete(clear_quiet_hours),
+        )
+        .route("/api/users/{id}/dnd", post(add_dnd).delete(clear_dnd))
+        .route(
+            "/api/users/{id}/notification-rules",
+            put(set_notification_rules),
+        )
+        .route(
+            "/api/users/{id}/contacts/{channel}/verify",
+            post(start_verification),
+        )
+        .route(
+            "/api/users/{id}/contacts/{channel}/confirm",
+            post(confirm_verification),
+        )
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateUserBody {
+    username: String,
+    email: String,
+    #[serde(default = "default_role")]
+    role: Role,
+}
+
+fn default_role() -> Role {
+    Role::User
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateUserBody {
+    email: Option<String>,
+    role: Option<Role>,
+}
+
+/// `value: null` clears the contact.
+#[derive(Debug, Deserialize)]
+struct ContactBody {
+    channel: Channel,
+    value: Option<String>,
+}
+
+/// Times are local to `timezone`; `fallback` receives non-critical
+/// notifications during quiet time instead of deferring them.
+#[derive(Debug, Deserialize)]
+struct QuietHoursBody {
+    timezone: String,
+    start: NaiveTime,
+    end: NaiveTime,
+    fallback: Option<Channel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DndBody {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct VerificationStarted {
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfirmBody {
+    code: String,
+}
+
+async fn create_user(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Json(body): Json<CreateUserBody>,
+) -> Result<(StatusCode, Json<User>), ApiError> {
+    caller.authorize(Operation::ManageUsers)?;
+    let user = state
+        .users
+        .create_user(body.username, body.email, body.role)
+        .await?;
+    let target = format!("user:{}", user.id());
+    record(&state, &caller, "user.create", target, None, Some(&user)).await?;
+    Ok((StatusCode::CREATED, Json(user)))
+}
+
+async fn list_users(Tenant(state): Tenant, caller: Caller) -> Result<Json<Vec<User>>, ApiError> {
+    caller.authorize(Operation::ViewUsers)?;
+    Ok(Json(state.users.list_users().await?))
+}
+
+async fn get_user(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<User>, ApiError> {
+    caller.authorize(Operation::ViewUsers)?;
+    Ok(Json(state.users.get_user(&id).await?))
+}
+
+async fn update_user(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(body): Json<UpdateUserBody>,
+) -> Result<Json<User>, ApiError> {
+    if body.role.is_some() {
+        caller.authorize(Operation::ManageUsers)?;
+    } else {
+        caller.authorize_profile(&id)?;
+    }
+    let before = state.users.get_user(&id).await?;
+    let user = state.users.update_user(&id, body.email, body.role).await?;
+    audit_change(&state, &caller, "user.update", &before, &user).await?;
+    Ok(Json(user))
+}
+
+async fn set_contact(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(body): Json<ContactBody>,
+) -> Result<Json<User>, ApiError> {
+    caller.authorize_profile(&id)?;
+    let before = state.users.get_user(&id).await?;
+    let user = state
+        .users
+        .set_contact(&id, body.channel, body.value)
+        .await?;
+    audit_change(&state, &caller, "user.contact.set", &before, &user).await?;
+    Ok(Json(user))
+}
+
+async fn set_quiet_hours(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(body): Json<QuietHoursBody>,
+) -> Result<Json<User>, ApiError> {
+    caller.authorize_profile(&id)?;
+    let timezone: Tz = body
+        .timezone
+        .parse()
+        .map_err(|_| ApiError::bad_request(format!("unknown timezone: {}", body.timezone)))?;
+    let quiet_hours = QuietHours::new(timezone, body.start, body.end)?;
+    let before = state.users.get_user(&id).await?;
+    let user = state
+        .users
+        .set_quiet_hours(&id, Some(quiet_hours), body.fallback)
+        .await?;
+    audit_change(&state, &caller, "user.quiet_hours.set", &before, &user).await?;
+    Ok(Json(user))
+}
+
+async fn clear_quiet_hours(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<User>, ApiError> {
+    caller.authorize_profile(&id)?;
+    let before = state.users.get_user(&id).await?;
+    let user = state.users.set_quiet_hours(&id, None, None).await?;
+    audit_change(&state, &caller, "user.quiet_hours.clear", &before, &user).await?;
+    Ok(Json(user))
+}
+
+/// The body is the full list of rules; `[]` pages on every step channel
+/// right away.
+async fn set_notification_rules(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(rules): Json<Vec<NotificationRule>>,
+) -> Result<Json<User>, ApiError> {
+    caller.authorize_profile(&id)?;
+    let before = state.users.get_user(&id).await?;
+    let user = state.users.set_notification_rules(&id, rules).await?;
+    audit_change(
+        &state,
+        &caller,
+        "user.notification_rules.set",
+        &before,
+        &user,
+    )
+    .await?;
+    Ok(Json(user))
+}
+
+async fn add_dnd(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+    Json(body): Json<DndBody>,
+) -> Result<Json<User>, ApiError> {
+    caller.authorize_profile(&id)?;
+    let window = DndWindow::new(body.start, body.end)?;
+    let before = state.users.get_user(&id).await?;
+    let user = state.users.add_dnd(&id, window, Utc::now()).await?;
+    audit_change(&state, &caller, "user.dnd.add", &before, &user).await?;
+    Ok(Json(user))
+}
+
+async fn clear_dnd(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<Json<User>, ApiError> {
+    caller.authorize_profile(&id)?;
+    let before = state.users.get_user(&id).await?;
+    let user = state.users.clear_dnd(&id).await?;
+    audit_change(&state, &caller, "user.dnd.clear", &before, &user).await?;
+    Ok(Json(user))
+}
+
+async fn start_verification(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path((id, channel)): Path<(String, Channel)>,
+) -> Result<(StatusCode, Json<VerificationStarted>), ApiError> {
+    caller.authorize_profile(&id)?;
+    let expires_at = state.verifications.start(&id, channel, Utc::now()).await?;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(VerificationStarted { expires_at }),
+    ))
+}
+
+async fn confirm_verification(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path((id, channel)): Path<(String, Channel)>,
+    Json(body): Json<ConfirmBody>,
+) -> Result<Json<User>, ApiError> {
+    caller.authorize_profile(&id)?;
+    let before = state.users.get_user(&id).await?;
+    let user = state
+        .verifications
+        .confirm(&id, channel, &body.code, Utc::now())
+        .await?;
+    audit_change(&state, &caller, "user.contact.verify", &before, &user).await?;
+    Ok(Json(user))
+}
+
+async fn delete_user(
+    Tenant(state): Tenant,
+    caller: Caller,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    caller.authorize(Operation::ManageUsers)?;
+    let before = state.users.get_user(&id).await?;
+    state.users.delete_user(&id).await?;
+    record(
+        &state,
+        &caller,
+        "user.delete",
+        format!("user:{id}"),
+        Some(&before),
+        None,
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn audit_change(
+    state: &AppState,
+    caller: &Caller,
+    action: &str,
+    before: &User,
+    after: &User,
+) -> Result<(), ApiError> {
+    let target = format!("user:{}", after.id());
+    record(state, caller, action, target, Some(before), Some(after)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{
+        seed_admin, seed_user, seed_user_with_role, send, send_as, state, state_with_db,
+    };
+    use async_trait::async_trait;
+    use rouse_adapters::persistence::SqliteDb;
+    use rouse_ports::error::NotifyError;
+    use rouse_ports::outbound::{Notifier, UserRepository};
+    use rouse_ports::types::{Notification, NotifyResult};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        texts: Mutex<Vec<String>>,
+    }
+
+    impl RecordingNotifier {
+        fn last_code(&self) -> String {
+            let texts = self.texts.lock().unwrap();
+            texts
+                .last()
+                .unwrap()
+                .rsplit(' ')
+                .next()
+                .unwrap()
+                .to_string()
+        }
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn notify(&self, _notification: &Notification) -> Result<NotifyResult, NotifyError> {
+            Ok(NotifyResult::default())
+        }
+        async fn send_text(&self, _target: &str, text: &str) -> Result<NotifyResult, NotifyError> {
+            self.texts.lock().unwrap().push(text.to_string());
+            Ok(NotifyResult::default())
+        }
+        fn channel(&self) -> Channel {
+            Channel::Slack
+        }
+    }
+
+    #[tokio::test]
+    async fn user_lifecycle() {
+        let state = state().await;
+
+        let (status, body) = send(
+            &state,
+            "POST",
+            "/api/users",
+            Some(json!({ "username": "alice", "email": "alice@test.com" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["role"], "User");
+        let id = body["id"].as_str().unwrap().to_string();
+
+        let (status, body) = send(
+            &state,
+            "PUT",
+            &format!("/api/users/{id}/contacts"),
+            Some(json!({ "channel": "Slack", "value": "U123" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["slack_id"], "U123");
+
+        let (status, body) = send(
+            &state,
+            "PATCH",
+            &format!("/api/users/{id}"),
+            Some(json!({ "role": "Admin" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["role"], "Admin");
+
+        // Alice and the admin the test is authenticated as.
+        let (_, body) = send(&state, "GET", "/api/users", None).await;
+        assert_eq!(body.as_array().unwrap().len(), 2);
+
+        let (status, _) = send(&state, "DELETE", &format!("/api/users/{id}"), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (status, _) = send(&state, "GET", &format!("/api/users/{id}"), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn team_member_cannot_be_deleted() {
+        let (state, db) = state_with_db().await;
+        let alice = seed_user(&db).await;
+        let (status, _) = send(
+            &state,
+            "POST",
+            "/api/teams",
+            Some(json!({ "name": "backend", "members": [alice.to_string()] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, body) = send(&state, "DELETE", &format!("/api/users/{alice}"), None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body["error"].as_str().unwrap().contains("team `backend`"));
+    }
+
+    #[tokio::test]
+    async fn contact_verification_flow() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let db = SqliteDb::new("sqlite::memory:").await.unwrap();
+        seed_admin(&db).await;
+        let state = AppState::with_notifiers(db, vec![notifier.clone()]);
+
+        let body = json!({ "username": "alice", "email": "alice@test.com" });
+        let (_, body) = send(&state, "POST", "/api/users", Some(body)).await;
+        let id = body["id"].as_str().unwrap().to_string();
+        let body = json!({ "channel": "Slack", "value": "U123" });
+        send(
+            &state,
+            "PUT",
+            &format!("/api/users/{id}/contacts"),
+            Some(body),
+        )
+        .await;
+
+        let uri = format!("/api/users/{id}/contacts/Slack/verify");
+        let (status, _) = send(&state, "POST", &uri, None).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let code = notifier.last_code();
+
+        let uri = format!("/api/users/{id}/contacts/Slack/confirm");
+        let (status, _) = send(&state, "POST", &uri, Some(json!({ "code": "x" }))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let (status, body) = send(&state, "POST", &uri, Some(json!({ "code": code }))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["verifications"][0]["address"], "U123");
+
+        let uri = format!("/api/users/{id}/contacts/Phone/verify");
+        let (status, _) = send(&state, "POST", &uri, None).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn quiet_hours_and_dnd() {
+        let state = state().await;
+        let body = json!({ "username": "alice", "email": "alice@test.com" });
+        let (_, body) = send(&state, "POST", "/api/users", Some(body)).await;
+        let id = body["id"].as_str().unwrap().to_string();
+
+        let uri = format!("/api/users/{id}/quiet-hours");
+        let body = json!({
+            "timezone": "Europe/Zurich",
+            "start": "22:00:00",
+            "end": "07:00:00",
+            "fallback": "Email",
+        });
+        let (status, body) = send(&state, "PUT", &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["quiet_hours"]["timezone"], "Europe/Zurich");
+        assert_eq!(body["quiet_fallback"], "Email");
+
+        let body = json!({ "timezone": "Mars/Olympus", "start": "22:00:00", "end": "07:00:00" });
+        let (status, _) = send(&state, "PUT", &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let dnd = format!("/api/users/{id}/dnd");
+        let body = json!({ "start": "2099-01-01T00:00:00Z", "end": "2099-01-02T00:00:00Z" });
+        let (status, body) = send(&state, "POST", &dnd, Some(body)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["dnd"].as_array().unwrap().len(), 1);
+
+        let (_, body) = send(&state, "DELETE", &dnd, None).await;
+        assert!(body["dnd"].as_array().unwrap().is_empty());
+        let (_, body) = send(&state, "DELETE", &uri, None).await;
+        assert!(body["quiet_hours"].is_null());
+    }
+
+    #[tokio::test]
+    async fn users_edit_their_profile_but_not_roles_or_others() {
+        let (state, db) = state_with_db().await;
+        let (me, token) = seed_user_with_role(&db, Role::User).await;
+        let (other, _) = seed_user_with_role(&db, Role::User).await;
+
+        let body = json!({ "channel": "Telegram", "value": "42" });
+        let uri = format!("/api/users/{me}/contacts");
+        let (status, _) = send_as(&state, Some(&token), "PUT", &uri, Some(body.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        let uri = format!("/api/users/{other}/contacts");
+        let (status, _) = send_as(&state, Some(&token), "PUT", &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let uri = format!("/api/users/{me}");
+        let body = json!({ "role": "Admin" });
+        let (status, _) = send_as(&state, Some(&token), "PATCH", &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let body = json!({ "username": "mallory", "email": "m@test.com" });
+        let (status, _) = send_as(&state, Some(&token), "POST", "/api/users", Some(body)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send_as(&state, Some(&token), "DELETE", &uri, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = send_as(&state, Some(&token), "GET", "/api/users", None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn users_set_their_own_notification_rules() {
+        let (state, db) = state_with_db().await;
+        let (me, token) = seed_user_with_role(&db, Role::User).await;
+        let (other, _) = seed_user_with_role(&db, Role::User).await;
+
+        let rules = json!([
+            { "channel": "Slack", "delay_secs": 0 },
+            { "channel": "Phone", "delay_secs": 300, "severities": ["Critical"] },
+        ]);
+        let uri = format!("/api/users/{me}/notification-rules");
+        let (status, body) = send_as(&state, Some(&token), "PUT", &uri, Some(rules.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["notification_rules"][1]["delay_secs"], 300);
+
+        let other_uri = format!("/api/users/{other}/notification-rules");
+        let (status, _) = send_as(&state, Some(&token), "PUT", &other_uri, Some(rules)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let webhook = json!([{ "channel": "Webhook", "delay_secs": 0 }]);
+        let (status, _) = send_as(&state, Some(&token), "PUT", &uri, Some(webhook)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let late = json!([{ "channel": "Slack", "delay_secs": 10_000_000 }]);
+        let (status, _) = send_as(&state, Some(&token), "PUT", &uri, Some(late)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn duplicate_email_is_conflict() {
+        let state = state().await;
+        let body = json!({ "username": "alice", "email": "alice@test.com" });
+        let (status, _) = send(&state, "POST", "/api/users", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let body = json!({ "username": "alice2", "email": "alice@test.com" });
+        let (status, _) = send(&state, "POST", "/api/users", Some(body)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn users_from_the_config_file_are_read_only() {
+        let (state, db) = state_with_db().await;
+        let mut user = User::new("alice".into(), "alice@test.com".into(), Role::User);
+        user.set_managed(true);
+        UserRepository::save(&db, &user).await.unwrap();
+        let uri = format!("/api/users/{}", user.id());
+
+        let (status, body) = send(&state, "GET", &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["managed"], true);
+
+        let (status, _) = send(&state, "PATCH", &uri, Some(json!({ "role": "Admin" }))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = send(&state, "DELETE", &uri, None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+}
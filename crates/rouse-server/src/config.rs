@@ -0,0 +1,1111 @@
+//! `rouse.yaml`, environment variables and CLI flags, merged in that order
+//! (last wins).
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{NaiveTime, Weekday};
+use chrono_tz::Tz;
+use clap::{Parser, Subcommand};
+use serde::Deserialize;
+use serde_json::Value;
+
+use rouse_adapters::slack::SlackNotifier;
+use rouse_app::config_service::{
+    Action, ConfigSpec, DefaultPolicySpec, Plan, PolicySpec, RouteSpec, ScheduleSpec, StepSpec,
+    TargetSpec, UserSpec,
+};
+use rouse_core::channel::Channel;
+use rouse_core::escalation::{OnCallModifier, MAX_ACK_TIMEOUT_SECS, MAX_STEP_WAIT_SECS};
+use rouse_core::routing::{AfterSuppression, Matcher, RouteOptions, SuppressionWindow};
+use rouse_core::schedule::rotation::MAX_SHIFT_SECS;
+use rouse_core::schedule::{HandoffTime, Rotation};
+use rouse_core::user::Role;
+use rouse_ports::outbound::Notifier;
+
+const DEFAULT_CONFIG: &str = "rouse.yaml";
+const DEFAULT_DATABASE_URL: &str = "sqlite://rouse.db?mode=rwc";
+const DEFAULT_HOST: &str = "0.0.0.0";
+const DEFAULT_PORT: u16 = 8080;
+
+#[derive(Debug, Default, Parser)]
+#[command(name = "rouse", about = "Self-hosted on-call and alerting")]
+pub struct Args {
+    /// Defaults to `rouse.yaml` in the working directory, if present.
+    #[arg(long, env = "ROUSE_CONFIG")]
+    pub config: Option<PathBuf>,
+    #[arg(long, env = "ROUSE_DATABASE_URL")]
+    pub database_url: Option<String>,
+    #[arg(long, env = "ROUSE_HOST")]
+    pub host: Option<String>,
+    #[arg(long, env = "ROUSE_PORT")]
+    pub port: Option<u16>,
+    /// `host:port`; wins over `--host` and `--port`.
+    #[arg(long, env = "ROUSE_LISTEN")]
+    pub listen: Option<String>,
+    /// Runs the server when omitted.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Compare the config file with the database, or bring the database in
+    /// line with it.
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Subcommand)]
+pub enum ConfigAction {
+    /// Show what `apply` would change. Exits with 2 if the database has
+    /// drifted from the file.
+    Plan,
+    /// Write the changes, all or nothing.
+    Apply,
+}
+
+/// Debug prints like Display so that `main` shows readable, line-numbered
+/// messages when startup fails.
+pub struct ConfigError(String);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Debug for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl ConfigError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+pub struct Settings {
+    /// Where the config came from, for messages.
+    pub source: Option<PathBuf>,
+    pub database_url: String,
+    pub listen: String,
+    /// Where notifications link back to.
+    pub base_url: String,
+    pub spec: ConfigSpec,
+    /// Per-integration settings, e.g. `slack.bot_token`.
+    pub integrations: BTreeMap<String, BTreeMap<String, serde_yaml::Value>>,
+}
+
+impl Settings {
+    pub fn load(args: Args) -> Result<Self, ConfigError> {
+        let source = args.config.clone().or_else(|| {
+            let default = PathBuf::from(DEFAULT_CONFIG);
+            default.exists().then_some(default)
+        });
+        let file = match &source {
+            Some(path) => {
+                let text = std::fs::read_to_string(path)
+                    .map_err(|e| ConfigError(format!("{}: {e}", path.display())))?;
+                parse(&text, |name| std::env::var(name).ok())
+                    .map_err(|e| ConfigError(format!("{}: {e}", path.display())))?
+            }
+            None => Parsed::default(),
+        };
+        Ok(Self::merge(args, source, file))
+    }
+
+    /// One notifier per configured integration rouse can deliver through;
+    /// others are skipped with a warning.
+    pub fn notifiers(&self) -> Result<Vec<Arc<dyn Notifier>>, ConfigError> {
+        let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+        for (name, options) in &self.integrations {
+            let option = |key: &str| options.get(key).and_then(serde_yaml::Value::as_str);
+            match name.as_str() {
+                "slack" => {
+                    let token = option("bot_token")
+                        .filter(|token| !token.is_empty())
+                        .ok_or_else(|| {
+                            ConfigError::new("integrations.slack: bot_token must be set")
+                        })?;
+                    let mut slack = SlackNotifier::new(token);
+                    if let Some(url) = option("api_url") {
+                        slack = slack.with_api_url(url);
+                    }
+                    notifiers.push(Arc::new(slack));
+                }
+                other => tracing::warn!(integration = other, "no notifier for this integration"),
+            }
+        }
+        Ok(notifiers)
+    }
+
+    fn merge(args: Args, source: Option<PathBuf>, file: Parsed) -> Self {
+        let listen = args.listen.unwrap_or_else(|| {
+            let host = args
+                .host
+                .or(file.server.host)
+                .unwrap_or_else(|| DEFAULT_HOST.into());
+            let port = args.port.or(file.server.port).unwrap_or(DEFAULT_PORT);
+            format!("{host}:{port}")
+        });
+        let base_url = file
+            .server
+            .base_url
+            .unwrap_or_else(|| format!("http://{listen}"));
+        Self {
+            source,
+            database_url: args
+                .database_url
+                .or(file.database.url)
+                .unwrap_or_else(|| DEFAULT_DATABASE_URL.into()),
+            listen,
+            base_url,
+            spec: file.spec,
+            integrations: file.integrations,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+struct FileConfig {
+    server: ServerSection,
+    database: DatabaseSection,
+    users: BTreeMap<String, UserEntry>,
+    schedules: BTreeMap<String, ScheduleEntry>,
+    escalation_policies: BTreeMap<String, PolicyEntry>,
+    routes: Vec<RouteEntry>,
+    /// Takes alerts that no route matched.
+    default_policy: Option<String>,
+    integrations: BTreeMap<String, BTreeMap<String, serde_yaml::Value>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+struct ServerSection {
+    host: Option<String>,
+    port: Option<u16>,
+    /// Defaults to `http://` and the listen address.
+    base_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+struct DatabaseSection {
+    url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UserEntry {
+    email: String,
+    #[serde(default)]
+    role: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScheduleEntry {
+    rotation: RotationEntry,
+    #[serde(default)]
+    timezone: Option<String>,
+    participants: Vec<String>,
+    handoff: String,
+}
+
+/// `daily`, `weekly`, a shift length like `12h`, or any rotation spelled
+/// out, e.g. `{ Monthly: { day: 1 } }`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RotationEntry {
+    Named(String),
+    Full(Rotation),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyEntry {
+    steps: Vec<StepEntry>,
+    #[serde(default)]
+    repeat: u32,
+    #[serde(default)]
+    ack_timeout: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StepEntry {
+    #[serde(default)]
+    wait: Option<String>,
+    notify: OneOrMany,
+    channels: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RouteEntry {
+    /// Exact label matches, the short form.
+    #[serde(rename = "match", default)]
+    equal: BTreeMap<String, String>,
+    /// Alertmanager-style matchers: `env!=staging`, `service=~"api|web"`.
+    #[serde(default)]
+    matchers: Vec<String>,
+    /// Required at the top level; child routes inherit it.
+    policy: Option<String>,
+    #[serde(rename = "continue", default)]
+    continue_matching: bool,
+    suppress: Option<bool>,
+    /// `22:00-08:00` or `sat,sun 00:00-24:00`.
+    suppress_hours: Option<Vec<String>>,
+    /// Of `suppress_hours`; UTC if unset.
+    timezone: Option<String>,
+    /// `drop` (the default) or `escalate` once the window ends.
+    after_suppression: Option<String>,
+    #[serde(default)]
+    routes: Vec<RouteEntry>,
+}
+
+#[derive(Default)]
+struct Parsed {
+    server: ServerSection,
+    database: DatabaseSection,
+    spec: ConfigSpec,
+    integrations: BTreeMap<String, BTreeMap<String, serde_yaml::Value>>,
+}
+
+/// Interpolates, parses and checks everything that can be checked without
+/// the database. Every problem is reported, one per line.
+fn parse(text: &str, env: impl Fn(&str) -> Option<String>) -> Result<Parsed, String> {
+    let text = interpolate(text, env)?;
+    let file: FileConfig = serde_yaml::from_str(&text).map_err(|e| e.to_string())?;
+    let mut errors = Vec::new();
+    let mut spec = ConfigSpec::default();
+
+    for (name, entry) in file.users {
+        let line = key_line(&text, "users", &name);
+        match parse_role(entry.role.as_deref().unwrap_or("user")) {
+            Ok(role) => spec.users.push(UserSpec {
+                name,
+                line,
+                email: entry.email,
+                role,
+            }),
+            Err(e) => errors.push(format!("line {line}: users.{name}: {e}")),
+        }
+    }
+    for (name, entry) in file.schedules {
+        let line = key_line(&text, "schedules", &name);
+        match schedule_spec(&name, entry, line) {
+            Ok(schedule) => spec.schedules.push(schedule),
+            Err(e) => errors.push(format!("line {line}: schedules.{name}: {e}")),
+        }
+    }
+    for (name, entry) in file.escalation_policies {
+        let line = key_line(&text, "escalation_policies", &name);
+        match policy_spec(&name, entry, line) {
+            Ok(policy) => spec.policies.push(policy),
+            Err(e) => errors.push(format!("line {line}: escalation_policies.{name}: {e}")),
+        }
+    }
+    for (index, entry) in file.routes.into_iter().enumerate() {
+        let line = item_line(&text, "routes", index);
+        spec.routes.push(route_spec(
+            entry,
+            &format!("routes[{index}]"),
+            line,
+            &mut errors,
+        ));
+    }
+    spec.default_policy = file.default_policy.map(|name| DefaultPolicySpec {
+        name,
+        line: text
+            .lines()
+            .position(|line| line.starts_with("default_policy:"))
+            .map_or(1, |index| index + 1),
+    });
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    Ok(Parsed {
+        server: file.server,
+        database: file.database,
+        spec,
+        integrations: file.integrations,
+    })
+}
+
+/// Child routes are reported at their top-level route's line.
+fn route_spec(entry: RouteEntry, path: &str, line: usize, errors: &mut Vec<String>) -> RouteSpec {
+    let mut matchers: Vec<Matcher> = entry
+        .equal
+        .iter()
+        .map(|(name, value)| Matcher::equal(name, value))
+        .collect();
+    for text in &entry.matchers {
+        match text.parse() {
+            Ok(matcher) => matchers.push(matcher),
+            Err(e) => errors.push(format!("line {line}: {path}: {e}")),
+        }
+    }
+    if !path.contains('.') && entry.policy.is_none() {
+        errors.push(format!("line {line}: {path}: missing `policy`"));
+    }
+    let timezone: Tz = match &entry.timezone {
+        Some(tz) => tz.parse().unwrap_or_else(|_| {
+            errors.push(format!("line {line}: {path}: unknown timezone `{tz}`"));
+            Tz::UTC
+        }),
+        None => Tz::UTC,
+    };
+    let suppress_hours = entry.suppress_hours.map(|windows| {
+        windows
+            .iter()
+            .filter_map(|w| {
+                parse_window(w, timezone)
+                    .map_err(|e| errors.push(format!("line {line}: {path}: {e}")))
+                    .ok()
+            })
+            .collect()
+    });
+    let after_suppression = match entry.after_suppression.as_deref() {
+        None => None,
+        Some("drop") => Some(AfterSuppression::Drop),
+        Some("escalate") => Some(AfterSuppression::Escalate),
+        Some(other) => {
+            errors.push(format!(
+                "line {line}: {path}: after_suppression must be drop or escalate, not `{other}`"
+            ));
+            None
+        }
+    };
+    let routes = entry
+        .routes
+        .into_iter()
+        .enumerate()
+        .map(|(index, child)| route_spec(child, &format!("{path}.routes[{index}]"), line, errors))
+        .collect();
+    RouteSpec {
+        line,
+        matchers,
+        policy: entry.policy,
+        continue_matching: entry.continue_matching,
+        options: RouteOptions {
+            suppress: entry.suppress,
+            suppress_hours,
+            after_suppression,
+        },
+        routes,
+    }
+}
+
+fn schedule_spec(name: &str, entry: ScheduleEntry, line: usize) -> Result<ScheduleSpec, String> {
+    let timezone: Tz = match &entry.timezone {
+        Some(tz) => tz.parse().map_err(|_| format!("unknown timezone `{tz}`"))?,
+        None => Tz::UTC,
+    };
+    let rotation = match entry.rotation {
+        RotationEntry::Full(rotation) => rotation,
+        RotationEntry::Named(named) => match named.as_str() {
+            "daily" => Rotation::Daily,
+            "weekly" => Rotation::Weekly,
+            other => {
+                let secs = parse_duration(other).map_err(|_| {
+                    format!("rotation must be daily, weekly or a shift length, not `{other}`")
+                })?;
+                Rotation::Custom(i64::try_from(secs).unwrap_or(i64::MAX))
+            }
+        },
+    };
+    let shift_secs = match &rotation {
+        Rotation::Custom(secs)
+        | Rotation::Pattern {
+            shift_secs: secs, ..
+        } => Some(*secs),
+        _ => None,
+    };
+    if shift_secs.is_some_and(|secs| secs > MAX_SHIFT_SECS) {
+        return Err("shift length must be at most 52w".into());
+    }
+    Ok(ScheduleSpec {
+        name: name.to_string(),
+        line,
+        timezone,
+        rotation,
+        participants: entry.participants,
+        handoff: parse_handoff(&entry.handoff)?,
+    })
+}
+
+fn policy_spec(name: &str, entry: PolicyEntry, line: usize) -> Result<PolicySpec, String> {
+    let mut steps = Vec::with_capacity(entry.steps.len());
+    for (index, step) in entry.steps.into_iter().enumerate() {
+        let at = |e: String| format!("step {}: {e}", index + 1);
+        let wait_seconds = match &step.wait {
+            Some(wait) => match parse_duration(wait).map_err(at)? {
+                secs if secs > MAX_STEP_WAIT_SECS => {
+                    return Err(at("wait must be at most 1w".into()))
+                }
+                secs => secs,
+            },
+            None => 0,
+        };
+        let notify = match step.notify {
+            OneOrMany::One(target) => vec![target],
+            OneOrMany::Many(targets) => targets,
+        };
+        let targets = notify
+            .iter()
+            .map(|t| parse_target(t))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(at)?;
+        let channels = step
+            .channels
+            .iter()
+            .map(|c| parse_channel(c))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(at)?;
+        steps.push(StepSpec {
+            wait_seconds,
+            targets,
+            channels,
+        });
+    }
+    let ack_timeout_secs = match &entry.ack_timeout {
+        Some(timeout) => match parse_duration(timeout)? {
+            0 => return Err("ack_timeout must be longer than 0".into()),
+            secs if secs > MAX_ACK_TIMEOUT_SECS => {
+                return Err("ack_timeout must be at most 1w".into())
+            }
+            secs => Some(secs),
+        },
+        None => None,
+    };
+    Ok(PolicySpec {
+        name: name.to_string(),
+        line,
+        steps,
+        repeat: entry.repeat,
+        ack_timeout_secs,
+    })
+}
+
+/// Replaces `${NAME}` with the environment variable, or `${NAME:-default}`
+/// when it may be unset. `$${` is a literal `${`. Values are inserted as
+/// they are, so quote them in the YAML if they may contain `:` or `#`.
+fn interpolate(text: &str, env: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut errors = Vec::new();
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        if line.trim_start().starts_with('#') {
+            out.push_str(line);
+            continue;
+        }
+        let mut rest = line;
+        while let Some(start) = rest.find("${") {
+            if rest[..start].ends_with('$') {
+                out.push_str(&rest[..start - 1]);
+                out.push_str("${");
+                rest = &rest[start + 2..];
+                continue;
+            }
+            out.push_str(&rest[..start]);
+            let Some(len) = rest[start..].find('}') else {
+                errors.push(format!("line {}: unterminated `${{`", index + 1));
+                rest = "";
+                break;
+            };
+            let expr = &rest[start + 2..start + len];
+            let (name, default) = match expr.split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (expr, None),
+            };
+            match env(name).or_else(|| default.map(String::from)) {
+                Some(value) => out.push_str(&value),
+                None => errors.push(format!(
+                    "line {}: environment variable `{name}` is not set",
+                    index + 1
+                )),
+            }
+            rest = &rest[start + len + 1..];
+        }
+        out.push_str(rest);
+    }
+    if errors.is_empty() {
+        Ok(out)
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+/// `30s`, `10m`, `1h30m`, `2d`, `1w`; a bare `0` is allowed.
+fn parse_duration(s: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid duration `{s}`, expected e.g. 30s, 10m or 1h30m");
+    if s.trim() == "0" {
+        return Ok(0);
+    }
+    let mut total: u64 = 0;
+    let mut digits = String::new();
+    for c in s.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            'w' => 604_800,
+            _ => return Err(invalid()),
+        };
+        let n: u64 = digits.parse().map_err(|_| invalid())?;
+        total = n
+            .checked_mul(unit)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(invalid)?;
+        digits.clear();
+    }
+    if !digits.is_empty() || s.trim().is_empty() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+/// `monday 09:00`, or just `09:00` for rotations where the day is moot.
+fn parse_handoff(s: &str) -> Result<HandoffTime, String> {
+    let invalid = || format!("invalid handoff `{s}`, expected e.g. `monday 09:00`");
+    let (day, time) = match s.trim().split_once(' ') {
+        Some((day, time)) => (day.parse::<Weekday>().map_err(|_| invalid())?, time),
+        None => (Weekday::Mon, s.trim()),
+    };
+    let (hour, minute) = time.trim().split_once(':').ok_or_else(invalid)?;
+    let hour: u32 = hour.parse().map_err(|_| invalid())?;
+    let minute: u32 = minute.parse().map_err(|_| invalid())?;
+    if hour > 23 || minute > 59 {
+        return Err(invalid());
+    }
+    Ok(HandoffTime { day, hour, minute })
+}
+
+/// `22:00-08:00`, optionally after days: `mon-fri 18:00-09:00`,
+/// `sat,sun 00:00-24:00`.
+fn parse_window(s: &str, timezone: Tz) -> Result<SuppressionWindow, String> {
+    let invalid =
+        || format!("invalid suppression window `{s}`, expected e.g. `mon-fri 22:00-08:00`");
+    let (days, hours) = match s.trim().rsplit_once(' ') {
+        Some((days, hours)) => (parse_days(days).ok_or_else(invalid)?, hours),
+        None => (Vec::new(), s.trim()),
+    };
+    let (start, end) = hours.split_once('-').ok_or_else(invalid)?;
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").map_err(|_| invalid())?;
+    let end = match end.trim() {
+        "24:00" => NaiveTime::MIN,
+        end => NaiveTime::parse_from_str(end, "%H:%M").map_err(|_| invalid())?,
+    };
+    Ok(SuppressionWindow::new(timezone, days, start, end))
+}
+
+/// `mon,wed`, `mon-fri` or a mix; ranges may wrap, as in `fri-mon`.
+fn parse_days(s: &str) -> Option<Vec<Weekday>> {
+    let mut days: Vec<Weekday> = Vec::new();
+    for part in s.split(',') {
+        match part.split_once('-') {
+            Some((from, to)) => {
+                let (mut day, to) = (from.trim().parse().ok()?, to.trim().parse().ok()?);
+                days.push(day);
+                while day != to {
+                    day = day.succ();
+                    days.push(day);
+                }
+            }
+            None => days.push(part.trim().parse().ok()?),
+        }
+    }
+    Some(days)
+}
+
+/// `on-call(schedule[, current|next|previous|secondary])`, `user(name)`,
+/// `team(name)` or a bare user or team name.
+fn parse_target(s: &str) -> Result<TargetSpec, String> {
+    let call = |prefix: &str| {
+        s.trim()
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(')'))
+            .map(str::trim)
+    };
+    if let Some(args) = call("on-call(") {
+        let (schedule, modifier) = match args.split_once(',') {
+            Some((schedule, modifier)) => (schedule.trim(), modifier.trim()),
+            None => (args, "current"),
+        };
+        let modifier = match modifier {
+            "current" => OnCallModifier::Current,
+            "next" => OnCallModifier::Next,
+            "previous" => OnCallModifier::Previous,
+            "secondary" => OnCallModifier::Secondary,
+            other => return Err(format!("unknown on-call modifier `{other}`")),
+        };
+        return Ok(TargetSpec::OnCall {
+            schedule: schedule.to_string(),
+            modifier,
+        });
+    }
+    if let Some(name) = call("user(") {
+        return Ok(TargetSpec::User(name.to_string()));
+    }
+    if let Some(name) = call("team(") {
+        return Ok(TargetSpec::Team(name.to_string()));
+    }
+    if s.trim().is_empty() || s.contains('(') {
+        return Err(format!("invalid target `{s}`"));
+    }
+    Ok(TargetSpec::Named(s.trim().to_string()))
+}
+
+fn parse_role(s: &str) -> Result<Role, String> {
+    Ok(match s.to_ascii_lowercase().as_str() {
+        "admin" => Role::Admin,
+        "user" => Role::User,
+        "viewer" => Role::Viewer,
+        _ => {
+            return Err(format!(
+                "unknown role `{s}`, expected admin, user or viewer"
+            ))
+        }
+    })
+}
+
+fn parse_channel(s: &str) -> Result<Channel, String> {
+    Ok(match s.to_ascii_lowercase().as_str() {
+        "slack" => Channel::Slack,
+        "discord" => Channel::Discord,
+        "telegram" => Channel::Telegram,
+        "whatsapp" => Channel::WhatsApp,
+        "sms" => Channel::Sms,
+        "phone" => Channel::Phone,
+        "email" => Channel::Email,
+        "webhook" => Channel::Webhook,
+        _ => return Err(format!("unknown channel `{s}`")),
+    })
+}
+
+/// Terraform-style: `+` create, `~` update, `-` delete, then a summary.
+pub fn render_plan(plan: &Plan) -> String {
+    let mut out = String::new();
+    for warning in &plan.warnings {
+        out.push_str(&format!("warning: {warning}\n"));
+    }
+    if plan.is_empty() {
+        out.push_str("No changes. The database matches the config.\n");
+        return out;
+    }
+    if !plan.warnings.is_empty() {
+        out.push('\n');
+    }
+    let show = |value: &Option<Value>| value.as_ref().map_or("(none)".into(), Value::to_string);
+    for change in &plan.changes {
+        let sign = match change.action {
+            Action::Create => '+',
+            Action::Update => '~',
+            Action::Delete => '-',
+        };
+        out.push_str(&format!(
+            "  {sign} {}.{}\n",
+            change.kind.as_str(),
+            change.name
+        ));
+        for field in &change.fields {
+            let line = match change.action {
+                Action::Create => format!("{}: {}", field.field, show(&field.after)),
+                Action::Update => format!(
+                    "{}: {} -> {}",
+                    field.field,
+                    show(&field.before),
+                    show(&field.after)
+                ),
+                Action::Delete => continue,
+            };
+            out.push_str(&format!("      {line}\n"));
+        }
+    }
+    out.push_str(&format!(
+        "\nPlan: {} to create, {} to update, {} to delete.\n",
+        plan.count(Action::Create),
+        plan.count(Action::Update),
+        plan.count(Action::Delete)
+    ));
+    out
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+/// The content lines of a top-level section, numbered from 1, and the
+/// indentation of its direct children.
+fn section<'a>(text: &'a str, name: &str) -> Option<(Vec<(usize, &'a str)>, usize)> {
+    let header = format!("{name}:");
+    let mut lines = text.lines().enumerate();
+    lines.find(|(_, line)| line.starts_with(&header))?;
+    let body: Vec<(usize, &str)> = lines
+        .filter(|(_, line)| is_content(line))
+        .take_while(|(_, line)| indent(line) > 0 || line.starts_with('-'))
+        .map(|(index, line)| (index + 1, line))
+        .collect();
+    let child = body.first().map_or(0, |(_, line)| indent(line));
+    Some((body, child))
+}
+
+/// Line of `key` directly under `section`, or 1 if it cannot be found.
+fn key_line(text: &str, section_name: &str, key: &str) -> usize {
+    let Some((body, child)) = section(text, section_name) else {
+        return 1;
+    };
+    body.iter()
+        .find(|(_, line)| {
+            let trimmed = line.trim_start();
+            indent(line) == child
+                && [
+                    format!("{key}:"),
+                    format!("\"{key}\":"),
+                    format!("'{key}':"),
+                ]
+                .iter()
+                .any(|k| trimmed.starts_with(k.as_str()))
+        })
+        .map_or(1, |(number, _)| *number)
+}
+
+/// Line of the `index`th list item under `section`, or 1.
+fn item_line(text: &str, section_name: &str, index: usize) -> usize {
+    let Some((body, child)) = section(text, section_name) else {
+        return 1;
+    };
+    body.iter()
+        .filter(|(_, line)| indent(line) == child && line.trim_start().starts_with('-'))
+        .nth(index)
+        .map_or(1, |(number, _)| *number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../../config/rouse.example.yaml");
+
+    fn env(name: &str) -> Option<String> {
+        name.starts_with("ROUSE_").then(|| format!("<{name}>"))
+    }
+
+    #[test]
+    fn example_config_parses() {
+        let parsed = parse(EXAMPLE, env).unwrap();
+        assert_eq!(parsed.server.port, Some(8080));
+        assert_eq!(parsed.spec.schedules.len(), 1);
+        assert_eq!(parsed.spec.users.len(), 4);
+        assert_eq!(parsed.spec.users[0].role, Role::Admin);
+        assert_eq!(parsed.spec.users[1].role, Role::User);
+
+        let schedule = &parsed.spec.schedules[0];
+        assert_eq!(schedule.name, "platform-team");
+        assert_eq!(schedule.rotation, Rotation::Weekly);
+        assert_eq!(schedule.participants, ["alice", "bob", "charlie"]);
+        assert_eq!(schedule.handoff.day, Weekday::Mon);
+        assert_eq!(
+            EXAMPLE.lines().nth(schedule.line - 1).unwrap().trim(),
+            "platform-team:"
+        );
+
+        let policy = &parsed.spec.policies[0];
+        assert_eq!(policy.steps[1].wait_seconds, 600);
+        assert_eq!(
+            policy.steps[1].targets,
+            [TargetSpec::OnCall {
+                schedule: "platform-team".into(),
+                modifier: OnCallModifier::Next,
+            }]
+        );
+        assert_eq!(
+            policy.steps[1].channels,
+            [Channel::Slack, Channel::Sms, Channel::Phone]
+        );
+        assert_eq!(policy.repeat, 1);
+        assert_eq!(policy.ack_timeout_secs, Some(1800));
+        assert_eq!(parsed.spec.policies[1].ack_timeout_secs, None);
+
+        assert_eq!(parsed.spec.routes.len(), 2);
+        let staging = &parsed.spec.routes[0].routes[0];
+        assert_eq!(staging.policy, None);
+        assert!(staging.options.suppressed());
+        let low = &parsed.spec.routes[1].options;
+        assert_eq!(low.suppress_hours.as_ref().map(Vec::len), Some(2));
+        assert_eq!(low.after_suppression, Some(AfterSuppression::Escalate));
+        assert!(EXAMPLE
+            .lines()
+            .nth(parsed.spec.routes[1].line - 1)
+            .unwrap()
+            .contains("warning"));
+        let default = parsed.spec.default_policy.as_ref().unwrap();
+        assert_eq!(default.name, "platform-low");
+        assert!(EXAMPLE
+            .lines()
+            .nth(default.line - 1)
+            .unwrap()
+            .starts_with("default_policy:"));
+        assert_eq!(
+            parsed.integrations["slack"]["bot_token"],
+            serde_yaml::Value::from("<ROUSE_SLACK_BOT_TOKEN>")
+        );
+    }
+
+    #[test]
+    fn integrations_become_notifiers() {
+        let file = parse(EXAMPLE, env).unwrap();
+        let settings = Settings::merge(Args::default(), None, file);
+        let notifiers = settings.notifiers().unwrap();
+        assert_eq!(notifiers.len(), 1);
+        assert_eq!(notifiers[0].channel(), Channel::Slack);
+
+        let file = parse("integrations:\n  slack:\n    app_token: x\n", env).unwrap();
+        let settings = Settings::merge(Args::default(), None, file);
+        let err = settings.notifiers().err().unwrap();
+        assert_eq!(err.to_string(), "integrations.slack: bot_token must be set");
+    }
+
+    #[test]
+    fn interpolation_supports_defaults_and_escapes() {
+        let text = "a: ${ROUSE_A}\nb: ${MISSING:-fallback}\nc: $${LITERAL}\n# ${IGNORED}\n";
+        assert_eq!(
+            interpolate(text, env).unwrap(),
+            "a: <ROUSE_A>\nb: fallback\nc: ${LITERAL}\n# ${IGNORED}\n"
+        );
+        assert_eq!(
+            interpolate("a: 1\nb: ${MISSING}\n", env).unwrap_err(),
+            "line 2: environment variable `MISSING` is not set"
+        );
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("0"), Ok(0));
+        assert_eq!(parse_duration("0m"), Ok(0));
+        assert_eq!(parse_duration("10m"), Ok(600));
+        assert_eq!(parse_duration("1h30m"), Ok(5400));
+        assert_eq!(parse_duration("2d"), Ok(172_800));
+        assert!(parse_duration("10").is_err());
+        assert!(parse_duration("ten minutes").is_err());
+        assert!(parse_duration("").is_err());
+    }
+
+    #[test]
+    fn targets_and_handoffs() {
+        assert_eq!(
+            parse_target("on-call(platform-team)"),
+            Ok(TargetSpec::OnCall {
+                schedule: "platform-team".into(),
+                modifier: OnCallModifier::Current,
+            })
+        );
+        assert_eq!(
+            parse_target("team(dba)"),
+            Ok(TargetSpec::Team("dba".into()))
+        );
+        assert_eq!(
+            parse_target("engineering-manager"),
+            Ok(TargetSpec::Named("engineering-manager".into()))
+        );
+        assert!(parse_target("on-call(x, later)").is_err());
+        assert_eq!(
+            parse_handoff("09:30"),
+            Ok(HandoffTime {
+                day: Weekday::Mon,
+                hour: 9,
+                minute: 30,
+            })
+        );
+        assert!(parse_handoff("someday 25:00").is_err());
+
+        let hm = |h| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+        let weekend = parse_window("sat,sun 00:00-24:00", Tz::UTC).unwrap();
+        assert_eq!(weekend.days(), [Weekday::Sat, Weekday::Sun]);
+        assert_eq!((weekend.start(), weekend.end()), (hm(0), hm(0)));
+        let nights = parse_window("fri-mon 22:00-08:00", Tz::UTC).unwrap();
+        assert_eq!(
+            nights.days(),
+            [Weekday::Fri, Weekday::Sat, Weekday::Sun, Weekday::Mon]
+        );
+        assert!(parse_window("22:00-08:00", Tz::UTC)
+            .unwrap()
+            .days()
+            .is_empty());
+        for bad in ["22:00", "someday 22:00-08:00", "22:00-25:00"] {
+            assert!(parse_window(bad, Tz::UTC).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn problems_are_reported_with_line_numbers() {
+        let text = "\
+schedules:
+  ok:
+    rotation: daily
+    participants: [a]
+    handoff: \"09:00\"
+  broken:
+    rotation: fortnightly
+    participants: [a]
+    handoff: monday 09:00
+escalation_policies:
+  p:
+    steps:
+      - wait: 5 minutes
+        notify: a
+        channels: [slack]
+routes:
+  - match: { service: api }
+    matchers: ['env!=staging', 'severity>=loud']
+    policy: p
+    timezone: Mars/Olympus
+    routes:
+      - matchers: ['!team']
+";
+        let errors = parse(text, env).err().unwrap();
+        assert_eq!(
+            errors,
+            "line 6: schedules.broken: rotation must be daily, weekly or a shift length, not `fortnightly`\n\
+             line 11: escalation_policies.p: step 1: invalid duration `5 minutes`, expected e.g. 30s, 10m or 1h30m\n\
+             line 17: routes[0]: invalid matcher `severity>=loud`: expected critical, warning or info\n\
+             line 17: routes[0]: unknown timezone `Mars/Olympus`"
+        );
+
+        let unknown = parse(
+            "schedules:\n  x:\n    rotation: daily\n    colour: red\n",
+            env,
+        );
+        assert!(unknown.err().unwrap().contains("at line 4"));
+
+        let forever = parse(
+            "escalation_policies:\n  p:\n    steps:\n      - notify: a\n        channels: [slack]\n    ack_timeout: 2w\n",
+            env,
+        );
+        assert_eq!(
+            forever.err().unwrap(),
+            "line 2: escalation_policies.p: ack_timeout must be at most 1w"
+        );
+
+        let slow = parse(
+            "escalation_policies:\n  p:\n    steps:\n      - wait: 999999999w\n        notify: a\n        channels: [slack]\n",
+            env,
+        );
+        assert_eq!(
+            slow.err().unwrap(),
+            "line 2: escalation_policies.p: step 1: wait must be at most 1w"
+        );
+
+        let endless = parse(
+            "schedules:\n  x:\n    rotation: 99999999999w\n    participants: [a]\n    handoff: \"09:00\"\n",
+            env,
+        );
+        assert_eq!(
+            endless.err().unwrap(),
+            "line 2: schedules.x: shift length must be at most 52w"
+        );
+    }
+
+    #[test]
+    fn env_and_flags_override_the_file() {
+        let file = parse(
+            "server:\n  host: 127.0.0.1\n  port: 9000\ndatabase:\n  url: sqlite://file.db\n",
+            env,
+        )
+        .unwrap();
+        let args = Args {
+            port: Some(9100),
+            ..Default::default()
+        };
+        let settings = Settings::merge(args, None, file);
+        assert_eq!(settings.listen, "127.0.0.1:9100");
+        assert_eq!(settings.base_url, "http://127.0.0.1:9100");
+        assert_eq!(settings.database_url, "sqlite://file.db");
+
+        let settings = Settings::merge(Args::default(), None, Parsed::default());
+        assert_eq!(settings.listen, "0.0.0.0:8080");
+        assert_eq!(settings.database_url, DEFAULT_DATABASE_URL);
+    }
+
+    #[tokio::test]
+    async fn plan_shows_drift_until_applied() {
+        use rouse_adapters::persistence::SqliteDb;
+        use rouse_app::config_service::ConfigService;
+
+        let text = "\
+users:
+  alice:
+    email: alice@example.com
+    role: admin
+escalation_policies:
+  fallback:
+    steps:
+      - notify: user(alice)
+        channels: [email]
+routes:
+  - match: { severity: critical }
+    policy: fallback
+";
+        let spec = parse(text, env).unwrap().spec;
+        let db = SqliteDb::new("sqlite::memory:").await.unwrap();
+        let service = ConfigService::new(db.clone(), db);
+        let now = chrono::Utc::now();
+
+        let plan = service.plan(&spec, now).await.unwrap();
+        let rendered = render_plan(&plan);
+        assert!(rendered.contains("  + user.alice\n      email: \"alice@example.com\"\n"));
+        assert!(rendered.contains("  + route.1\n"));
+        assert!(rendered.ends_with("Plan: 3 to create, 0 to update, 0 to delete.\n"));
+
+        service.apply(&spec, now).await.unwrap();
+        let plan = service.plan(&spec, now).await.unwrap();
+        assert_eq!(
+            render_plan(&plan),
+            "No changes. The database matches the config.\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn example_config_applies_to_an_empty_database() {
+        use rouse_adapters::persistence::SqliteDb;
+        use rouse_app::config_service::ConfigService;
+
+        let spec = parse(EXAMPLE, env).unwrap().spec;
+        let db = SqliteDb::new("sqlite::memory:").await.unwrap();
+        let service = ConfigService::new(db.clone(), db);
+        let now = chrono::Utc::now();
+
+        let plan = service.apply(&spec, now).await.unwrap();
+        assert_eq!(plan.count(Action::Create), 10);
+        assert!(plan.warnings.iter().any(|w| w.contains("`alice`")));
+        assert!(service.plan(&spec, now).await.unwrap().is_empty());
+    }
+}
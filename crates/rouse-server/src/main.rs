@@ -1,3 +1,130 @@
-fn main() {
-    println!("rouse starting");
+mod api;
+mod config;
+mod workers;
+
+use chrono::Utc;
+use clap::Parser;
+use std::net::SocketAddr;
+
+use rouse_adapters::oidc::{OidcConfig, OidcProvider};
+use rouse_adapters::persistence::SqliteDb;
+use rouse_app::config_service::{Action, ConfigService, Plan};
+use rouse_app::sso_service::SsoService;
+use rouse_core::user::{GroupMapping, Role};
+
+use config::{Args, Command, ConfigAction, ConfigError, Settings};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt().init();
+
+    let mut args = Args::parse();
+    let command = args.command.take();
+    let settings = Settings::load(args)?;
+    let db = SqliteDb::new(&settings.database_url).await?;
+
+    if let Some(Command::Config { action }) = command {
+        let plan = reconcile(&settings, db, action).await?;
+        print!("{}", config::render_plan(&plan));
+        if matches!(action, ConfigAction::Plan) && !plan.is_empty() {
+            std::process::exit(2);
+        }
+        return Ok(());
+    }
+
+    let mut state = api::AppState::with_notifiers(db.clone(), settings.notifiers()?)
+        .with_base_url(settings.base_url.clone());
+
+    if let Ok(issuer) = std::env::var("ROUSE_OIDC_ISSUER") {
+        let mut config = OidcConfig::new(
+            issuer,
+            std::env::var("ROUSE_OIDC_CLIENT_ID")?,
+            std::env::var("ROUSE_OIDC_CLIENT_SECRET")?,
+            std::env::var("ROUSE_OIDC_REDIRECT_URL")?,
+        );
+        if let Ok(claim) = std::env::var("ROUSE_OIDC_GROUPS_CLAIM") {
+            config.groups_claim = claim;
+        }
+        // JSON list of `{"group": ..., "role": ..., "team": ...}`.
+        let mappings: Vec<GroupMapping> = match std::env::var("ROUSE_OIDC_GROUP_MAPPINGS") {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(_) => Vec::new(),
+        };
+        state = state.with_sso(SsoService::new(
+            db.clone(),
+            db.clone(),
+            OidcProvider::new(config),
+            db.clone(),
+            mappings,
+            Role::Viewer,
+        ));
+        tracing::info!("single sign-on enabled");
+    }
+
+    // Without an admin nobody could log in to create the first users.
+    if let Ok(password) = std::env::var("ROUSE_ADMIN_PASSWORD") {
+        let username = std::env::var("ROUSE_ADMIN_USERNAME").unwrap_or_else(|_| "admin".into());
+        let email = std::env::var("ROUSE_ADMIN_EMAIL").unwrap_or_else(|_| "admin@localhost".into());
+        if let Some(admin) = state
+            .auth
+            .bootstrap_admin(username, email, &password)
+            .await?
+        {
+            tracing::info!(username = admin.username(), "created initial admin");
+        }
+    }
+
+    // After the admin bootstrap, so the file may adopt the admin.
+    if settings.source.is_some() {
+        let plan = reconcile(&settings, db.clone(), ConfigAction::Apply).await?;
+        if !plan.is_empty() {
+            tracing::info!("config changes:\n{}", config::render_plan(&plan));
+        } else {
+            for warning in &plan.warnings {
+                tracing::warn!("config: {warning}");
+            }
+        }
+        tracing::info!(
+            created = plan.count(Action::Create),
+            updated = plan.count(Action::Update),
+            deleted = plan.count(Action::Delete),
+            unchanged = plan.unchanged,
+            integrations = ?settings.integrations.keys().collect::<Vec<_>>(),
+            "applied configuration"
+        );
+    }
+
+    tokio::spawn(workers::wake_alerts(state.clone(), db.clone()));
+    tokio::spawn(workers::page(state.clone(), db.clone()));
+
+    let app = api::router(state);
+    let listen = settings.listen;
+
+    let listener = tokio::net::TcpListener::bind(&listen).await?;
+    tracing::info!(%listen, "rouse starting");
+    // Peer addresses end up in the audit log.
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn reconcile(
+    settings: &Settings,
+    db: SqliteDb,
+    action: ConfigAction,
+) -> Result<Plan, ConfigError> {
+    let source = settings
+        .source
+        .as_ref()
+        .ok_or_else(|| ConfigError::new("no config file: pass --config or create rouse.yaml"))?;
+    let service = ConfigService::new(db.clone(), db);
+    let result = match action {
+        ConfigAction::Plan => service.plan(&settings.spec, Utc::now()).await,
+        ConfigAction::Apply => service.apply(&settings.spec, Utc::now()).await,
+    };
+    result.map_err(|e| ConfigError::new(format!("{}: {e}", source.display())))
 }
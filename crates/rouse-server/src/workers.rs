@@ -0,0 +1,142 @@
+//! Background loops. Each runs over every tenant in turn.
+
+use std::time::Duration;
+
+use chrono::Utc;
+
+use rouse_adapters::persistence::SqliteDb;
+use rouse_core::ids::TenantId;
+
+use crate::api::AppState;
+
+/// Snoozes and acknowledgements run out on their own; this pages again
+/// for every tenant.
+pub async fn wake_alerts(state: AppState, db: SqliteDb) {
+    let mut tick = tokio::time::interval(Duration::from_secs(30));
+    loop {
+        tick.tick().await;
+        for tenant in tenants(&db).await {
+            let alerts = &state.for_tenant(&tenant).alerts;
+            match alerts.wake_snoozed(Utc::now()).await {
+                Ok(0) => {}
+                Ok(woken) => tracing::info!(%tenant, woken, "snoozed alerts firing again"),
+                Err(e) => tracing::warn!(%tenant, error = %e, "waking snoozed alerts failed"),
+            }
+            match alerts.expire_acks(Utc::now()).await {
+                Ok(0) => {}
+                Ok(expired) => tracing::info!(%tenant, expired, "acknowledgements ran out"),
+                Err(e) => tracing::warn!(%tenant, error = %e, "expiring acknowledgements failed"),
+            }
+        }
+    }
+}
+
+/// Fires due escalation steps and sends the notifications they queue.
+pub async fn page(state: AppState, db: SqliteDb) {
+    let mut tick = tokio::time::interval(Duration::from_secs(2));
+    loop {
+        tick.tick().await;
+        page_once(&state, &db).await;
+    }
+}
+
+async fn page_once(state: &AppState, db: &SqliteDb) {
+    for tenant in tenants(db).await {
+        let state = state.for_tenant(&tenant);
+        match state.escalations.fire_due(Utc::now()).await {
+            Ok(0) => {}
+            Ok(fired) => tracing::info!(%tenant, fired, "escalation steps fired"),
+            Err(e) => tracing::warn!(%tenant, error = %e, "firing escalation steps failed"),
+        }
+        match state.deliveries.deliver_due(Utc::now()).await {
+            Ok(_) => {}
+            Err(e) => tracing::warn!(%tenant, error = %e, "delivering notifications failed"),
+        }
+    }
+}
+
+async fn tenants(db: &SqliteDb) -> Vec<TenantId> {
+    db.tenants().await.unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "listing tenants failed");
+        Vec::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+
+    use rouse_core::channel::Channel;
+    use rouse_core::escalation::{EscalationPolicy, EscalationStep, EscalationTarget};
+    use rouse_ports::error::NotifyError;
+    use rouse_ports::outbound::{ConfigStore, NotificationQueue, Notifier};
+    use rouse_ports::types::{ConfigChanges, Notification, NotifyResult, RawAlert};
+
+    use super::*;
+    use crate::api::test_support::seed_user;
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        sent: Mutex<Vec<Notification>>,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn notify(&self, notification: &Notification) -> Result<NotifyResult, NotifyError> {
+            self.sent.lock().unwrap().push(notification.clone());
+            Ok(NotifyResult::default())
+        }
+        async fn send_text(&self, _target: &str, _text: &str) -> Result<NotifyResult, NotifyError> {
+            Ok(NotifyResult::default())
+        }
+        fn channel(&self) -> Channel {
+            Channel::Slack
+        }
+    }
+
+    #[tokio::test]
+    async fn received_alert_is_paged_and_delivered() {
+        let db = SqliteDb::new("sqlite::memory:").await.unwrap();
+        let user = seed_user(&db).await;
+        let step = EscalationStep::new(
+            0,
+            0,
+            vec![EscalationTarget::User(user)],
+            vec![Channel::Slack],
+        );
+        let policy = EscalationPolicy::new("primary".into(), vec![step], 0).unwrap();
+        db.commit(&ConfigChanges {
+            default_policy_id: Some(policy.id().clone()),
+            policies: vec![policy],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let notifier = Arc::new(RecordingNotifier::default());
+        let state = AppState::with_notifiers(db.clone(), vec![notifier.clone()]);
+
+        let raw = RawAlert {
+            external_id: "ext-1".into(),
+            source: "alertmanager".into(),
+            severity: "critical".into(),
+            labels: BTreeMap::new(),
+            summary: "High CPU".into(),
+            status: "firing".into(),
+        };
+        state.alerts.receive(raw, Utc::now()).await.unwrap();
+
+        state.escalations.fire_due(Utc::now()).await.unwrap();
+        let queued = db.poll_pending().await.unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].target, "U123");
+
+        page_once(&state, &db).await;
+        assert!(db.poll_pending().await.unwrap().is_empty());
+        let sent = notifier.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].summary, "High CPU");
+    }
+}